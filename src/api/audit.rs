@@ -0,0 +1,63 @@
+/*! `/admin/audit` */
+
+use rocket::serde::json::Json;
+
+use crate::{
+    api::infra::*,
+    domain::infra::*,
+    store::TransactionId,
+};
+
+#[derive(Serialize)]
+pub struct Get {
+    pub id: AuditEntryId,
+    pub command: String,
+    pub args: serde_json::Value,
+    pub entities: Vec<String>,
+    pub actor: String,
+    pub recorded_at: Timestamp,
+    pub transaction: TransactionId,
+    pub outcome: AuditOutcome,
+}
+
+/**
+`GET /admin/audit?<entity>&<from>&<to>`
+
+Entries can be narrowed to those that affected a single entity, and to those recorded within a time range.
+*/
+#[rocket::get("/?<entity>&<from>&<to>")]
+pub async fn list(
+    entity: Option<String>,
+    from: Option<Timestamp>,
+    to: Option<Timestamp>,
+    app: AppRequest<'_>,
+) -> Result<Json<Vec<Get>>, Error> {
+    app.transaction(|app| async move {
+        let query = app.get_audit_entries_query();
+
+        let entries = query
+            .execute(GetAuditEntries {
+                entity,
+                from: from.map(|from| from.0),
+                to: to.map(|to| to.0),
+            })
+            .await?;
+
+        Ok(Json(
+            entries
+                .into_iter()
+                .map(|entry| Get {
+                    id: entry.id,
+                    command: entry.command,
+                    args: entry.args,
+                    entities: entry.entities,
+                    actor: entry.actor,
+                    recorded_at: Timestamp(entry.recorded_at),
+                    transaction: entry.transaction,
+                    outcome: entry.outcome,
+                })
+                .collect(),
+        ))
+    })
+    .await
+}
@@ -0,0 +1,183 @@
+/*!
+`/admin/products`
+
+Unlike `/products`, these routes can return archived products and drafts, and can change
+the whole catalog at once through imports.
+*/
+
+use rocket::{
+    data::{
+        Data,
+        ToByteUnit,
+    },
+    http::ContentType,
+    serde::json::Json,
+};
+
+use crate::{
+    api::{
+        infra::*,
+        products::{
+            Get,
+            List,
+        },
+    },
+    domain::{
+        infra::*,
+        products::*,
+    },
+};
+
+/**
+`GET /admin/products/<id>`
+
+Archived products and drafts are always returned.
+The product's version is returned as an `ETag`, and can be passed back in an `If-None-Match` header.
+*/
+#[rocket::get("/<id>")]
+pub async fn get(
+    id: ProductId,
+    if_none_match: IfNoneMatchHeader,
+    app: AppRequest<'_>,
+) -> Result<Tagged<Json<Get>>, Error> {
+    app.transaction(|app| async move {
+        let query = app.get_product_query();
+
+        match query
+            .execute(GetProduct {
+                id,
+                include_archived: true,
+                include_drafts: true,
+            })
+            .await?
+        {
+            Some(product) => {
+                let product = product.into_data();
+
+                Ok(Tagged::new(
+                    product.version,
+                    &if_none_match,
+                    Json(Get::from(product)),
+                ))
+            }
+            None => Err(Error::NotFound(error::msg("product not found"))),
+        }
+    })
+    .await
+}
+
+/**
+`GET /admin/products?<sort>&<descending>&<after>&<limit>&<archived>&<drafts>`
+
+Archived products are only listed if `archived` is `true`, and drafts if `drafts` is `true`.
+Products are sorted and paged the same way as `GET /products`.
+*/
+#[rocket::get("/?<sort>&<descending>&<after>&<limit>&<archived>&<drafts>")]
+pub async fn list(
+    sort: Option<ProductSort>,
+    descending: Option<bool>,
+    after: Option<ProductCursor>,
+    limit: Option<usize>,
+    archived: Option<bool>,
+    drafts: Option<bool>,
+    app: AppRequest<'_>,
+) -> Result<Json<List>, Error> {
+    app.transaction(|app| async move {
+        let query = app.list_products_query();
+
+        let page = query
+            .execute(ListProducts {
+                title: None,
+                min_price: None,
+                max_price: None,
+                category_id: None,
+                tag: None,
+                sort: sort.unwrap_or_default(),
+                descending: descending.unwrap_or(false),
+                after,
+                limit,
+                include_archived: archived.unwrap_or(false),
+                include_drafts: drafts.unwrap_or(false),
+            })
+            .await?;
+
+        Ok(Json(List {
+            products: page.products.into_iter().map(Get::from).collect(),
+            next: page.next,
+        }))
+    })
+    .await
+}
+
+/**
+`POST /admin/products/import?<format>&<batch_size>`
+
+The body is a file of product records in `csv` or `jsonl` format, up to 10MiB.
+Records with the id of an existing product update it, and others create a new product.
+Records that couldn't be imported are returned along with the row of the file they came from.
+
+Records are imported in batches that are each committed in their own transaction, so an import
+that fails partway through keeps the batches committed before it.
+The request's own transaction only holds the import's entry in the audit log.
+*/
+#[rocket::post("/import?<format>&<batch_size>", data = "<data>")]
+pub async fn import(
+    format: ProductFileFormat,
+    batch_size: Option<usize>,
+    data: Data<'_>,
+    app: AppRequest<'_>,
+) -> Result<Json<ImportReport>, Error> {
+    let data = data
+        .open(10.mebibytes())
+        .into_string()
+        .await
+        .map_err(|err| Error::BadRequest(error::msg(err)))?;
+
+    if !data.is_complete() {
+        return Err(Error::BadRequest(error::msg(
+            "the file is larger than 10MiB",
+        )));
+    }
+
+    app.transaction(|app| async move {
+        let command = app.import_products_command();
+
+        let report = command
+            .execute(ImportProducts {
+                format,
+                data: data.into_inner(),
+                batch_size,
+            })
+            .await?;
+
+        Ok(Json(report))
+    })
+    .await
+}
+
+/**
+`GET /admin/products/export?<format>`
+
+All products are exported, including drafts and archived products, in the order they were created.
+The file can be imported again to restore them.
+Variants aren't included.
+*/
+#[rocket::get("/export?<format>")]
+pub async fn export(
+    format: ProductFileFormat,
+    app: AppRequest<'_>,
+) -> Result<(ContentType, String), Error> {
+    app.transaction(|app| async move {
+        let query = app.export_products_query();
+
+        let data = query.execute(ExportProducts { format }).await?;
+
+        let content_type = match format {
+            ProductFileFormat::Csv => ContentType::CSV,
+            ProductFileFormat::JsonLines => ContentType::new("application", "x-ndjson"),
+        };
+
+        Ok((content_type, data))
+    })
+    .await
+}
@@ -0,0 +1,125 @@
+/*! `/categories` */
+
+use rocket::{
+    response::status::Created,
+    serde::json::Json,
+};
+
+use crate::{
+    api::infra::*,
+    domain::{
+        categories::*,
+        infra::*,
+    },
+};
+
+/**
+`GET /categories`
+
+Every category is returned, nested beneath its parent.
+*/
+#[rocket::get("/")]
+pub async fn list(app: AppRequest<'_>) -> Result<Json<Vec<CategoryTree>>, Error> {
+    app.transaction(|app| async move {
+        let query = app.get_category_tree_query();
+
+        let trees = query.execute(GetCategoryTree { id: None }).await?;
+
+        Ok(Json(trees))
+    })
+    .await
+}
+
+/**
+`GET /categories/<id>`
+
+The category is returned along with all of its descendants.
+Products in the category can be listed with `GET /products?category=<id>`.
+*/
+#[rocket::get("/<id>")]
+pub async fn get(id: CategoryId, app: AppRequest<'_>) -> Result<Json<CategoryTree>, Error> {
+    app.transaction(|app| async move {
+        let query = app.get_category_tree_query();
+
+        match query.execute(GetCategoryTree { id: Some(id) }).await?.pop() {
+            Some(tree) => Ok(Json(tree)),
+            None => Err(Error::NotFound(error::msg("category not found"))),
+        }
+    })
+    .await
+}
+
+#[derive(Deserialize)]
+pub struct Create {
+    pub name: String,
+    #[serde(default)]
+    pub parent_id: Option<CategoryId>,
+}
+
+/** `PUT /categories` */
+#[rocket::put("/", format = "application/json", data = "<data>")]
+pub async fn create(
+    data: Json<Create>,
+    app: AppRequest<'_>,
+) -> Result<Created<Json<CategoryId>>, Error> {
+    app.transaction(|app| async move {
+        let id = app.category_id();
+        let command = app.create_category_command();
+
+        let id = id.get()?;
+
+        command
+            .execute(CreateCategory {
+                id,
+                name: data.0.name,
+                parent_id: data.0.parent_id,
+            })
+            .await?;
+
+        let location = format!("/categories/{}", id);
+
+        Ok(Created::new(location).body(Json(id)))
+    })
+    .await
+}
+
+/** `POST /categories/<id>/name/<name>` */
+#[rocket::post("/<id>/name/<name>")]
+pub async fn rename(id: CategoryId, name: String, app: AppRequest<'_>) -> Result<(), Error> {
+    app.transaction(|app| async move {
+        let command = app.rename_category_command();
+
+        command.execute(RenameCategory { id, name }).await?;
+
+        Ok(())
+    })
+    .await
+}
+
+#[derive(Deserialize)]
+pub struct Move {
+    #[serde(default)]
+    pub parent_id: Option<CategoryId>,
+}
+
+/**
+`POST /categories/<id>/move`
+
+If no parent is given then the category is moved to the root.
+*/
+#[rocket::post("/<id>/move", format = "application/json", data = "<data>")]
+pub async fn move_to(id: CategoryId, data: Json<Move>, app: AppRequest<'_>) -> Result<(), Error> {
+    app.transaction(|app| async move {
+        let command = app.move_category_command();
+
+        command
+            .execute(MoveCategory {
+                id,
+                parent_id: data.0.parent_id,
+            })
+            .await?;
+
+        Ok(())
+    })
+    .await
+}
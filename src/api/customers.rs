@@ -13,14 +13,34 @@ use crate::{
     },
 };
 
-/** `GET /customers/<id>` */
-#[rocket::get("/<id>")]
-pub async fn get(id: CustomerId, app: AppRequest<'_>) -> Result<Json<CustomerWithOrders>, Error> {
+/**
+`GET /customers/<id>?<archived>`
+
+Archived customers are only returned if `archived` is `true`.
+The customer's version is returned as an `ETag`, and can be passed back in an `If-None-Match` header.
+*/
+#[rocket::get("/<id>?<archived>")]
+pub async fn get(
+    id: CustomerId,
+    archived: Option<bool>,
+    if_none_match: IfNoneMatchHeader,
+    app: AppRequest<'_>,
+) -> Result<Tagged<Json<CustomerWithOrders>>, Error> {
     app.transaction(|app| async move {
         let query = app.get_customer_with_orders_query();
 
-        match query.execute(GetCustomerWithOrders { id }).await? {
-            Some(customer) => Ok(Json(customer)),
+        match query
+            .execute(GetCustomerWithOrders {
+                id,
+                include_archived: archived.unwrap_or(false),
+            })
+            .await?
+        {
+            Some(customer) => Ok(Tagged::new(
+                customer.version,
+                &if_none_match,
+                Json(customer),
+            )),
             None => Err(Error::NotFound(error::msg("customer not found"))),
         }
     })
@@ -45,3 +65,49 @@ pub async fn create(app: AppRequest<'_>) -> Result<Created<Json<CustomerId>>, Er
     })
     .await
 }
+
+/**
+`POST /customers/<id>/archive`
+
+If an `If-Match` header is sent then the customer is only archived if its version still matches.
+*/
+#[rocket::post("/<id>/archive")]
+pub async fn archive(
+    id: CustomerId,
+    if_match: IfMatchHeader,
+    app: AppRequest<'_>,
+) -> Result<(), Error> {
+    app.transaction(|app| async move {
+        let command = app.archive_customer_command();
+
+        let version = if_match.version()?;
+
+        command.execute(ArchiveCustomer { id, version }).await?;
+
+        Ok(())
+    })
+    .await
+}
+
+/**
+`POST /customers/<id>/restore`
+
+If an `If-Match` header is sent then the customer is only restored if its version still matches.
+*/
+#[rocket::post("/<id>/restore")]
+pub async fn restore(
+    id: CustomerId,
+    if_match: IfMatchHeader,
+    app: AppRequest<'_>,
+) -> Result<(), Error> {
+    app.transaction(|app| async move {
+        let command = app.restore_customer_command();
+
+        let version = if_match.version()?;
+
+        command.execute(RestoreCustomer { id, version }).await?;
+
+        Ok(())
+    })
+    .await
+}
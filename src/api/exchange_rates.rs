@@ -0,0 +1,152 @@
+/*! `/admin/exchange-rates` */
+
+use std::collections::BTreeMap;
+
+use rocket::{
+    Build,
+    Rocket,
+    fairing::{
+        self,
+        Fairing,
+        Info,
+        Kind,
+    },
+    serde::json::Json,
+    tokio,
+};
+
+use crate::{
+    api::infra::*,
+    domain::{
+        App,
+        exchange_rates::*,
+        infra::*,
+    },
+};
+
+#[derive(Serialize)]
+pub struct Get {
+    pub base: CurrencyCode,
+    pub rates: BTreeMap<CurrencyCode, ExchangeRate>,
+}
+
+/** `GET /admin/exchange-rates` */
+#[rocket::get("/")]
+pub async fn get(app: AppRequest<'_>) -> Result<Json<Get>, Error> {
+    app.transaction(|app| async move {
+        let query = app.get_exchange_rates_query();
+
+        match query.execute(GetExchangeRates {}).await? {
+            Some(exchange_rates) => {
+                let exchange_rates = exchange_rates.into_data();
+
+                Ok(Json(Get {
+                    base: exchange_rates.base,
+                    rates: exchange_rates.rates,
+                }))
+            }
+            None => Err(Error::NotFound(error::msg("exchange rates not found"))),
+        }
+    })
+    .await
+}
+
+#[derive(Clone, Deserialize)]
+pub struct Set {
+    pub base: CurrencyCode,
+    pub rates: BTreeMap<CurrencyCode, ExchangeRate>,
+}
+
+/** `PUT /admin/exchange-rates` */
+#[rocket::put("/", format = "application/json", data = "<data>")]
+pub async fn set(data: Json<Set>, app: AppRequest<'_>) -> Result<(), Error> {
+    app.transaction(|app| async move {
+        let command = app.set_exchange_rates_command();
+
+        command
+            .execute(SetExchangeRates {
+                base: data.0.base,
+                rates: data.0.rates,
+            })
+            .await?;
+
+        Ok(())
+    })
+    .await
+}
+
+/**
+A fairing that loads exchange rates from a file when the app starts.
+
+The path to the file is read from the `exchange_rates` configuration value.
+The file uses the same JSON format as `PUT /admin/exchange-rates`.
+The rates are loaded into each tenant hosted by the app.
+If no path is configured then no rates are loaded.
+If the path isn't a string then the app fails to start.
+*/
+pub struct LoadExchangeRatesFairing;
+
+#[rocket::async_trait]
+impl Fairing for LoadExchangeRatesFairing {
+    fn info(&self) -> Info {
+        Info {
+            name: "Exchange rates",
+            kind: Kind::Ignite,
+        }
+    }
+
+    async fn on_ignite(&self, rocket: Rocket<Build>) -> fairing::Result {
+        let path = match rocket.figment().extract_inner::<String>("exchange_rates") {
+            Ok(path) => path,
+            Err(err) if err.missing() => return Ok(rocket),
+            Err(err) => {
+                emit::error!("invalid exchange rates configuration: {reason: err.to_string()}");
+
+                return Err(rocket);
+            }
+        };
+
+        let Some(app) = rocket.state::<App>() else {
+            return Err(rocket);
+        };
+
+        match load(app, &path).await {
+            Ok(()) => {
+                emit::info!("loaded exchange rates from {path}");
+
+                Ok(rocket)
+            }
+            Err(err) => {
+                emit::error!("failed to load exchange rates from {path}: {err}");
+
+                Err(rocket)
+            }
+        }
+    }
+}
+
+async fn load(app: &App, path: &str) -> Result<(), Error> {
+    let data: Set = serde_json::from_slice(&tokio::fs::read(path).await.map_err(error::msg)?)
+        .map_err(|err| Error::BadRequest(error::msg(err)))?;
+
+    for tenant in app.tenants() {
+        let data = data.clone();
+
+        app.for_tenant(tenant)?
+            .transaction(|app| async move {
+                let command = app.set_exchange_rates_command();
+
+                command
+                    .execute(SetExchangeRates {
+                        base: data.base,
+                        rates: data.rates,
+                    })
+                    .await?;
+
+                Ok::<_, Error>(())
+            })
+            .await?;
+    }
+
+    Ok(())
+}
@@ -1,11 +1,18 @@
 pub(in crate::api) mod error;
+pub(in crate::api) mod etag;
+pub(in crate::api) mod idempotency;
 pub(in crate::api) mod request;
 pub(in crate::api) mod span;
+pub(in crate::api) mod timestamp;
 
+mod currency;
 mod id;
 
 pub(in crate::api) use self::{
     error::*,
+    etag::*,
+    idempotency::*,
     request::*,
     span::*,
+    timestamp::*,
 };
@@ -0,0 +1,16 @@
+use rocket::form::{
+    self,
+    FromFormField,
+    ValueField,
+};
+
+use crate::domain::infra::*;
+
+impl<'v> FromFormField<'v> for CurrencyCode {
+    fn from_value(field: ValueField<'v>) -> form::Result<'v, Self> {
+        field
+            .value
+            .parse()
+            .map_err(|err| form::Error::validation(format!("{}", err)).into())
+    }
+}
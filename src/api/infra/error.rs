@@ -10,6 +10,7 @@ use rocket::{
     http::{
         ContentType,
         Status,
+        StatusClass,
     },
     request::Request,
     response::{
@@ -29,6 +30,14 @@ pub enum Error {
     NotFound(#[source] Box<dyn error::Error + Send + Sync>),
     #[error("the user input was invalid")]
     BadRequest(#[source] Box<dyn error::Error + Send + Sync>),
+    #[error("the request conflicts with the current state of an entity")]
+    Conflict(#[source] Box<dyn error::Error + Send + Sync>),
+    #[error("the entity doesn't match the version the request expected")]
+    PreconditionFailed(#[source] Box<dyn error::Error + Send + Sync>),
+    #[error("the request isn't allowed")]
+    Forbidden(#[source] Box<dyn error::Error + Send + Sync>),
+    #[error("the request can't be handled right now")]
+    Unavailable(#[source] Box<dyn error::Error + Send + Sync>),
     #[error("an unexpected error occurred")]
     Other(#[source] Box<dyn error::Error + Send + Sync>),
 }
@@ -38,14 +47,40 @@ impl Error {
         match self {
             Error::NotFound(_) => Status::NotFound,
             Error::BadRequest(_) => Status::BadRequest,
+            Error::Conflict(_) => Status::Conflict,
+            Error::PreconditionFailed(_) => Status::PreconditionFailed,
+            Error::Forbidden(_) => Status::Forbidden,
+            Error::Unavailable(_) => Status::ServiceUnavailable,
             Error::Other(_) => Status::InternalServerError,
         }
     }
 
+    /**
+    A stable, machine-readable code for the error.
+
+    Clients can match on this code instead of the status or message.
+    These values are part of the public API, so they shouldn't change.
+    */
+    pub(in crate::api) fn code(&self) -> &'static str {
+        match self {
+            Error::NotFound(_) => "not_found",
+            Error::BadRequest(_) => "bad_request",
+            Error::Conflict(_) => "conflict",
+            Error::PreconditionFailed(_) => "precondition_failed",
+            Error::Forbidden(_) => "forbidden",
+            Error::Unavailable(_) => "unavailable",
+            Error::Other(_) => "internal",
+        }
+    }
+
     fn into_inner(self) -> Box<dyn error::Error + Send + Sync> {
         match self {
             Error::NotFound(err) => err,
             Error::BadRequest(err) => err,
+            Error::Conflict(err) => err,
+            Error::PreconditionFailed(err) => err,
+            Error::Forbidden(err) => err,
+            Error::Unavailable(err) => err,
             Error::Other(err) => err,
         }
     }
@@ -58,10 +93,12 @@ pub fn msg(err: impl fmt::Display) -> Box<dyn error::Error + Send + Sync> {
 impl<'r, 'o: 'r> Responder<'r, 'o> for Error {
     fn respond_to(self, _: &Request) -> response::Result<'o> {
         let status = self.status();
+        let code = self.code();
 
         let err = self.into_inner();
 
-        let err = serde_json::to_vec(&SerializeError { msg: &err }).unwrap_or_else(|_| Vec::new());
+        let err =
+            serde_json::to_vec(&SerializeError { code, msg: &err }).unwrap_or_else(|_| Vec::new());
 
         Response::build()
             .sized_body(None::<usize>, Cursor::new(err))
@@ -77,7 +114,12 @@ impl From<domain::Error> for Error {
 
         match err.split() {
             (BadInput, err) => Error::BadRequest(err),
-            (_, err) => Error::Other(err),
+            (NotFound, err) => Error::NotFound(err),
+            (Conflict, err) => Error::Conflict(err),
+            (Stale, err) => Error::PreconditionFailed(err),
+            (Forbidden, err) => Error::Forbidden(err),
+            (Unavailable, err) => Error::Unavailable(err),
+            (Other, err) => Error::Other(err),
         }
     }
 }
@@ -90,6 +132,7 @@ impl From<Box<dyn error::Error + Send + Sync>> for Error {
 
 #[derive(Serialize)]
 struct SerializeError<'a> {
+    code: &'a str,
     #[serde(serialize_with = "serialize_msg")]
     msg: &'a dyn fmt::Display,
 }
@@ -104,6 +147,7 @@ where
 #[rocket::catch(500)]
 pub(in crate::api) fn internal_error(_: &Request) -> content::RawJson<Vec<u8>> {
     let err = serde_json::to_vec(&SerializeError {
+        code: "internal",
         msg: &"an internal error occurred",
     })
     .unwrap_or_else(|_| Vec::new());
@@ -111,10 +155,31 @@ pub(in crate::api) fn internal_error(_: &Request) -> content::RawJson<Vec<u8>> {
     content::RawJson(err)
 }
 
-#[rocket::catch(404)]
-pub(in crate::api) fn not_found(_: &Request) -> content::RawJson<Vec<u8>> {
-    let err =
-        serde_json::to_vec(&SerializeError { msg: &"not found" }).unwrap_or_else(|_| Vec::new());
+/**
+Report errors that weren't returned by a route, like a request body that failed to parse.
+
+Statuses without their own code are reported as `bad_request` for client errors, and `internal` otherwise.
+*/
+#[rocket::catch(default)]
+pub(in crate::api) fn default(status: Status, _: &Request) -> content::RawJson<Vec<u8>> {
+    let code = match status.code {
+        400 | 422 => "bad_request",
+        403 => "forbidden",
+        404 => "not_found",
+        409 => "conflict",
+        412 => "precondition_failed",
+        503 => "unavailable",
+        _ => match status.class() {
+            StatusClass::ClientError => "bad_request",
+            _ => "internal",
+        },
+    };
+
+    let err = serde_json::to_vec(&SerializeError {
+        code,
+        msg: &status.reason_lossy(),
+    })
+    .unwrap_or_else(|_| Vec::new());
 
     content::RawJson(err)
 }
@@ -0,0 +1,164 @@
+use std::{
+    fmt,
+    str::FromStr,
+};
+
+use rocket::{
+    Request,
+    http::{
+        Header,
+        Status,
+    },
+    request::{
+        FromRequest,
+        Outcome,
+    },
+    response::{
+        self,
+        Responder,
+        Response,
+    },
+};
+
+use crate::domain::{
+    self,
+    infra::*,
+};
+
+use super::Error;
+
+/** Format a version as a strong entity tag, like `"000000000000000a"`. */
+fn entity_tag<T>(version: Version<T>) -> String {
+    format!("\"{}\"", version)
+}
+
+/**
+The value of an optional `If-None-Match` header.
+
+Endpoints that accept this header will respond with `304 Not Modified` instead of a body
+when the entity's current version matches.
+*/
+pub struct IfNoneMatchHeader(pub Option<String>);
+
+impl IfNoneMatchHeader {
+    fn matches(&self, etag: &str) -> bool {
+        let Some(ref header) = self.0 else {
+            return false;
+        };
+
+        header.split(',').map(str::trim).any(|candidate| {
+            // `If-None-Match` uses weak comparison, so weak tags match too
+            candidate == "*" || candidate.strip_prefix("W/").unwrap_or(candidate) == etag
+        })
+    }
+}
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for IfNoneMatchHeader {
+    type Error = Error;
+
+    async fn from_request(req: &'r Request<'_>) -> Outcome<Self, Error> {
+        Outcome::Success(IfNoneMatchHeader(
+            req.headers()
+                .get_one("If-None-Match")
+                .map(ToOwned::to_owned),
+        ))
+    }
+}
+
+/**
+The value of an optional `If-Match` header.
+
+Endpoints that accept this header pass its version to their command, which will only apply changes
+if the entity's current version still matches. A mismatch is returned as `412 Precondition Failed`.
+Only a single strong entity tag or `*` is supported.
+*/
+pub struct IfMatchHeader(pub Option<String>);
+
+impl IfMatchHeader {
+    /**
+    Get the version the entity is expected to have.
+
+    This will be `None` if the header wasn't sent or is `*`.
+    */
+    pub fn version<T>(&self) -> Result<Option<Version<T>>, Error> {
+        match self.0.as_deref() {
+            None | Some("*") => Ok(None),
+            Some(etag) => {
+                let version = etag
+                    .strip_prefix('"')
+                    .and_then(|etag| etag.strip_suffix('"'))
+                    .ok_or_else(|| {
+                        domain::error::bad_input(format_args!(
+                            "`{}` is not a strong entity tag",
+                            etag
+                        ))
+                    })
+                    .and_then(Version::from_str)?;
+
+                Ok(Some(version))
+            }
+        }
+    }
+}
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for IfMatchHeader {
+    type Error = Error;
+
+    async fn from_request(req: &'r Request<'_>) -> Outcome<Self, Error> {
+        Outcome::Success(IfMatchHeader(
+            req.headers().get_one("If-Match").map(ToOwned::to_owned),
+        ))
+    }
+}
+
+/**
+A response for a single version of an entity.
+
+The version is returned in an `ETag` header.
+If the request's `If-None-Match` header matches the version then the body is replaced by `304 Not Modified`.
+*/
+pub struct Tagged<R> {
+    etag: String,
+    response: Option<R>,
+}
+
+impl<R> Tagged<R> {
+    pub fn new<T>(version: Version<T>, if_none_match: &IfNoneMatchHeader, response: R) -> Self {
+        let etag = entity_tag(version);
+
+        let response = if if_none_match.matches(&etag) {
+            None
+        } else {
+            Some(response)
+        };
+
+        Tagged { etag, response }
+    }
+}
+
+impl<R> fmt::Debug for Tagged<R> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Tagged")
+            .field("etag", &self.etag)
+            .field("modified", &self.response.is_some())
+            .finish()
+    }
+}
+
+impl<'r, 'o: 'r, R> Responder<'r, 'o> for Tagged<R>
+where
+    R: Responder<'r, 'o>,
+{
+    fn respond_to(self, req: &'r Request) -> response::Result<'o> {
+        let mut response = match self.response {
+            Some(response) => response.respond_to(req)?,
+            None => Response::build().status(Status::NotModified).finalize(),
+        };
+
+        response.set_header(Header::new("ETag", self.etag));
+
+        Ok(response)
+    }
+}
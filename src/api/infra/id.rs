@@ -1,4 +1,11 @@
-use rocket::request::FromParam;
+use rocket::{
+    form::{
+        self,
+        FromFormField,
+        ValueField,
+    },
+    request::FromParam,
+};
 use std::convert::TryFrom;
 
 use crate::domain::{
@@ -13,3 +20,12 @@ impl<'r, T> FromParam<'r> for Id<T> {
         Id::try_from(param)
     }
 }
+
+impl<'v, T> FromFormField<'v> for Id<T>
+where
+    T: Send,
+{
+    fn from_value(field: ValueField<'v>) -> form::Result<'v, Self> {
+        Id::try_from(field.value).map_err(|err| form::Error::validation(format!("{}", err)).into())
+    }
+}
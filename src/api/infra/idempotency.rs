@@ -0,0 +1,37 @@
+use std::convert::TryFrom;
+
+use rocket::{
+    Request,
+    http::Status,
+    request::{
+        FromRequest,
+        Outcome,
+    },
+};
+
+use crate::domain::infra::IdempotencyKey;
+
+use super::Error;
+
+/**
+The value of an optional `Idempotency-Key` header.
+
+Endpoints that accept this header will replay their original response when a request
+is retried with the same key. Reusing a key for a request with different input returns `409 Conflict`.
+*/
+pub struct IdempotencyKeyHeader(pub Option<IdempotencyKey>);
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for IdempotencyKeyHeader {
+    type Error = Error;
+
+    async fn from_request(req: &'r Request<'_>) -> Outcome<Self, Error> {
+        match req.headers().get_one("Idempotency-Key") {
+            None => Outcome::Success(IdempotencyKeyHeader(None)),
+            Some(key) => match IdempotencyKey::try_from(key) {
+                Ok(key) => Outcome::Success(IdempotencyKeyHeader(Some(key))),
+                Err(err) => Outcome::Error((Status::BadRequest, err.into())),
+            },
+        }
+    }
+}
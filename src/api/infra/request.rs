@@ -2,7 +2,10 @@ use std::future::Future;
 
 use rocket::{
     Request,
-    http::Status,
+    http::{
+        Status,
+        uri::Host,
+    },
     request::{
         FromRequest,
         Outcome,
@@ -11,7 +14,11 @@ use rocket::{
 
 use crate::domain::{
     App,
-    infra::Resolver,
+    infra::{
+        Actor,
+        Resolver,
+        TenantId,
+    },
 };
 
 use super::{
@@ -19,9 +26,24 @@ use super::{
     RequestSpan,
 };
 
+/**
+The app, as seen by a single request.
+
+The tenant for the request is found using the host the request was made to.
+Apps that only host the default tenant use it for any host. Otherwise, requests made to a host
+that isn't configured for any tenant are treated as missing.
+If the `tenant_header` config value is `true` then an `X-Tenant` header takes precedence over the host.
+Any client can set that header, so it should only be enabled behind a trusted proxy that sets it.
+
+The actor recorded in the audit log for commands executed by the request is anonymous.
+If the `actor_header` config value is `true` then it's read from an `X-Actor` header instead.
+Like `X-Tenant`, it should only be enabled behind a trusted proxy that sets it.
+*/
 pub struct AppRequest<'r> {
     span: RequestSpan,
     app: &'r App,
+    tenant: TenantId,
+    actor: Actor,
 }
 
 impl<'r> AppRequest<'r> {
@@ -31,7 +53,12 @@ impl<'r> AppRequest<'r> {
     {
         self.span
             .trace(async {
-                let r = self.app.transaction(f).await?;
+                let r = self
+                    .app
+                    .for_tenant(self.tenant)?
+                    .as_actor(self.actor)
+                    .transaction(f)
+                    .await?;
 
                 Ok(r)
             })
@@ -52,6 +79,59 @@ impl<'r> FromRequest<'r> for AppRequest<'r> {
             return Outcome::Error((Status::InternalServerError, ()));
         };
 
-        Outcome::Success(AppRequest { span, app })
+        let config_flag = |key: &str| {
+            req.rocket()
+                .figment()
+                .extract_inner::<bool>(key)
+                .unwrap_or(false)
+        };
+        let tenant_header = config_flag("tenant_header");
+        let actor_header = config_flag("actor_header");
+
+        let tenant = match req.headers().get_one("X-Tenant").filter(|_| tenant_header) {
+            Some(tenant) => match tenant.parse() {
+                Ok(tenant) => tenant,
+                Err(_) => return Outcome::Error((Status::BadRequest, ())),
+            },
+            None => {
+                let tenant = req
+                    .host()
+                    .cloned()
+                    .or_else(|| {
+                        req.headers()
+                            .get_one("Host")
+                            .and_then(|host| Host::parse(host).ok())
+                    })
+                    .and_then(|host| app.tenant_for_host(host.domain().as_str()));
+
+                match tenant {
+                    Some(tenant) => tenant,
+                    None if app.tenants() == [TenantId::default_tenant()] => {
+                        TenantId::default_tenant()
+                    }
+                    None => return Outcome::Error((Status::NotFound, ())),
+                }
+            }
+        };
+
+        let actor = match req.headers().get_one("X-Actor").filter(|_| actor_header) {
+            Some(actor) => match actor.parse() {
+                Ok(actor) => actor,
+                Err(_) => return Outcome::Error((Status::BadRequest, ())),
+            },
+            None => Actor::anonymous(),
+        };
+
+        // Requests for tenants that aren't hosted by the app are treated as missing
+        if app.for_tenant(tenant.clone()).is_err() {
+            return Outcome::Error((Status::NotFound, ()));
+        }
+
+        Outcome::Success(AppRequest {
+            span,
+            app,
+            tenant,
+            actor,
+        })
     }
 }
@@ -0,0 +1,103 @@
+use std::time::{
+    SystemTime,
+    UNIX_EPOCH,
+};
+
+use rocket::form::{
+    self,
+    FromFormField,
+    ValueField,
+};
+use serde::{
+    de::{
+        self,
+        Deserialize,
+        Deserializer,
+    },
+    ser::{
+        self,
+        Serialize,
+        Serializer,
+    },
+};
+
+/**
+A point in time formatted as an RFC3339 string, like `2024-01-01T00:00:00Z`.
+
+The domain uses `SystemTime` for points in time, which doesn't have a stable text format of its own.
+*/
+#[derive(Debug, Clone, Copy)]
+pub struct Timestamp(pub SystemTime);
+
+impl Serialize for Timestamp {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let ts = self
+            .0
+            .duration_since(UNIX_EPOCH)
+            .ok()
+            .and_then(emit::Timestamp::from_unix)
+            .ok_or_else(|| ser::Error::custom("timestamp is out of range"))?;
+
+        serializer.collect_str(&ts)
+    }
+}
+
+impl<'de> Deserialize<'de> for Timestamp {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let ts = String::deserialize(deserializer)?;
+
+        parse(&ts)
+            .ok_or_else(|| de::Error::custom(format_args!("`{}` is not a valid timestamp", ts)))
+    }
+}
+
+#[rocket::async_trait]
+impl<'v> FromFormField<'v> for Timestamp {
+    fn from_value(field: ValueField<'v>) -> form::Result<'v, Self> {
+        parse(field.value).ok_or_else(|| {
+            form::Error::validation(format!("`{}` is not a valid timestamp", field.value)).into()
+        })
+    }
+}
+
+fn parse(ts: &str) -> Option<Timestamp> {
+    let parsed = normalize(ts).and_then(|ts| emit::Timestamp::try_from_str(&ts).ok())?;
+
+    Some(Timestamp(parsed.to_system_time()))
+}
+
+/**
+Check a UTC timestamp is in the shape `emit` expects, like `2024-01-01T00:00:00.000Z`.
+
+`emit` panics on some malformed input, and requires fractional seconds, so this is checked up-front.
+Timestamps without fractional seconds are given them.
+*/
+fn normalize(ts: &str) -> Option<String> {
+    let (datetime, fraction) = ts.strip_suffix('Z')?.split_at_checked(19)?;
+
+    let shape_ok = datetime.bytes().enumerate().all(|(i, b)| match i {
+        4 | 7 => b == b'-',
+        10 => b == b'T',
+        13 | 16 => b == b':',
+        _ => b.is_ascii_digit(),
+    });
+
+    let fraction = match fraction.strip_prefix('.') {
+        None if fraction.is_empty() => "0",
+        Some(fraction)
+            if (1..=9).contains(&fraction.len())
+                && fraction.bytes().all(|b| b.is_ascii_digit()) =>
+        {
+            fraction
+        }
+        _ => return None,
+    };
+
+    shape_ok.then(|| format!("{}.{}Z", datetime, fraction))
+}
@@ -0,0 +1,81 @@
+/*! `/inventory` */
+
+use rocket::serde::json::Json;
+
+use crate::{
+    api::infra::*,
+    domain::{
+        infra::*,
+        inventory::*,
+        products::{
+            ProductId,
+            VariantId,
+        },
+    },
+};
+
+/**
+`GET /inventory/<product_id>?<variant>`
+
+Products that aren't tracked don't have a stock level.
+The stock of products with variants is tracked for each variant.
+*/
+#[rocket::get("/<product_id>?<variant>")]
+pub async fn get(
+    product_id: ProductId,
+    variant: Option<VariantId>,
+    app: AppRequest<'_>,
+) -> Result<Json<StockLevel>, Error> {
+    app.transaction(|app| async move {
+        let query = app.get_stock_level_query();
+
+        match query
+            .execute(GetStockLevel {
+                product_id,
+                variant_id: variant,
+            })
+            .await?
+        {
+            Some(stock) => Ok(Json(stock)),
+            None => Err(Error::NotFound(error::msg("stock not found"))),
+        }
+    })
+    .await
+}
+
+#[derive(Deserialize)]
+pub struct Set {
+    pub on_hand: u32,
+}
+
+/**
+`PUT /inventory/<product_id>?<variant>`
+
+Setting the stock level of a product starts tracking its stock.
+*/
+#[rocket::put(
+    "/<product_id>?<variant>",
+    format = "application/json",
+    data = "<data>"
+)]
+pub async fn set(
+    product_id: ProductId,
+    variant: Option<VariantId>,
+    data: Json<Set>,
+    app: AppRequest<'_>,
+) -> Result<(), Error> {
+    app.transaction(|app| async move {
+        let command = app.set_stock_level_command();
+
+        command
+            .execute(SetStockLevel {
+                product_id,
+                variant_id: variant,
+                on_hand: data.0.on_hand,
+            })
+            .await?;
+
+        Ok(())
+    })
+    .await
+}
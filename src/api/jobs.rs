@@ -0,0 +1,284 @@
+/*! `/admin/jobs` */
+
+use std::time::Duration;
+
+use rocket::{
+    Orbit,
+    Rocket,
+    fairing::{
+        Fairing,
+        Info,
+        Kind,
+    },
+    response::status::Created,
+    serde::json::Json,
+};
+
+use crate::{
+    api::infra::*,
+    domain::{
+        App,
+        infra::*,
+        inventory::EXPIRE_RESERVATIONS_JOB,
+        jobs::*,
+    },
+};
+
+#[derive(Serialize)]
+pub struct Get {
+    pub id: JobId,
+    pub version: JobVersion,
+    pub kind: String,
+    pub args: serde_json::Value,
+    pub schedule: JobSchedule,
+    pub status: JobStatus,
+    pub run_at: Timestamp,
+    pub runs: u32,
+    pub last_run_at: Option<Timestamp>,
+    pub last_error: Option<String>,
+}
+
+impl From<JobData> for Get {
+    fn from(job: JobData) -> Self {
+        Get {
+            id: job.id,
+            version: job.version,
+            kind: job.kind,
+            args: job.args,
+            schedule: job.schedule,
+            status: job.status,
+            run_at: Timestamp(job.run_at),
+            runs: job.runs,
+            last_run_at: job.last_run_at.map(Timestamp),
+            last_error: job.last_error,
+        }
+    }
+}
+
+/** `GET /admin/jobs` */
+#[rocket::get("/")]
+pub async fn list(app: AppRequest<'_>) -> Result<Json<Vec<Get>>, Error> {
+    app.transaction(|app| async move {
+        let query = app.get_jobs_query();
+
+        let jobs = query.execute(GetJobs {}).await?;
+
+        Ok(Json(jobs.into_iter().map(Get::from).collect()))
+    })
+    .await
+}
+
+/** `GET /admin/jobs/<id>` */
+#[rocket::get("/<id>")]
+pub async fn get(id: JobId, app: AppRequest<'_>) -> Result<Json<Get>, Error> {
+    app.transaction(|app| async move {
+        let query = app.get_job_query();
+
+        match query.execute(GetJob { id }).await? {
+            Some(job) => Ok(Json(Get::from(job.into_data()))),
+            None => Err(Error::NotFound(error::msg("job not found"))),
+        }
+    })
+    .await
+}
+
+#[derive(Deserialize)]
+pub struct Schedule {
+    pub kind: String,
+    #[serde(default)]
+    pub args: serde_json::Value,
+    pub schedule: JobSchedule,
+}
+
+/**
+`PUT /admin/jobs`
+
+Jobs can only be scheduled for kinds that have a handler. The only built-in kind is
+`expire-reservations`; embedders register others with `AppBuilder::job`.
+*/
+#[rocket::put("/", format = "application/json", data = "<data>")]
+pub async fn schedule(
+    data: Json<Schedule>,
+    app: AppRequest<'_>,
+) -> Result<Created<Json<JobId>>, Error> {
+    app.transaction(|app| async move {
+        let id = app.job_id();
+        let command = app.schedule_job_command();
+
+        let id = id.get()?;
+
+        command
+            .execute(ScheduleJob {
+                id,
+                kind: data.0.kind,
+                args: data.0.args,
+                schedule: data.0.schedule,
+            })
+            .await?;
+
+        let location = format!("/admin/jobs/{}", id);
+
+        Ok(Created::new(location).body(Json(id)))
+    })
+    .await
+}
+
+/**
+Run all jobs that are due.
+
+Each job is run in its own transaction, so changes made by jobs that fail are discarded.
+The outcome is then recorded against the job in a separate transaction.
+Failing to run or complete one job doesn't stop the others from running.
+The number of jobs that were run is returned.
+*/
+pub async fn run_due_jobs(app: &App) -> Result<usize, Error> {
+    let due = app
+        .transaction(|app| async move {
+            let query = app.get_due_jobs_query();
+
+            Ok::<_, Error>(query.execute(GetDueJobs {}).await?)
+        })
+        .await?;
+
+    for &id in &due {
+        let result = app
+            .transaction(|app| async move {
+                let command = app.run_job_command();
+
+                Ok::<_, Error>(command.execute(RunJob { id }).await?)
+            })
+            .await;
+
+        if let Err(ref err) = result {
+            emit::warn!("job {job: id} failed: {err}");
+        }
+
+        let result = app
+            .transaction(|app| async move {
+                let command = app.complete_job_command();
+
+                command
+                    .execute(CompleteJob {
+                        id,
+                        error: result.err().map(|err| err.to_string()),
+                    })
+                    .await?;
+
+                Ok::<_, Error>(())
+            })
+            .await;
+
+        if let Err(err) = result {
+            emit::warn!("job {job: id} couldn't be completed: {err}");
+        }
+    }
+
+    Ok(due.len())
+}
+
+/**
+Schedule the jobs that every tenant runs, unless they've already been scheduled.
+
+Expired stock reservations are cleaned up every `reservation_expiry_interval` seconds.
+*/
+pub async fn schedule_default_jobs(
+    app: &App,
+    reservation_expiry_interval: u64,
+) -> Result<(), Error> {
+    app.transaction(|app| async move {
+        let query = app.get_jobs_query();
+
+        let jobs = query.execute(GetJobs {}).await?;
+
+        if jobs.iter().any(|job| job.kind == EXPIRE_RESERVATIONS_JOB) {
+            return Ok(());
+        }
+
+        let id = app.job_id();
+        let command = app.schedule_job_command();
+
+        command
+            .execute(ScheduleJob {
+                id: id.get()?,
+                kind: EXPIRE_RESERVATIONS_JOB.to_owned(),
+                args: serde_json::json!({}),
+                schedule: JobSchedule::Interval {
+                    seconds: reservation_expiry_interval,
+                },
+            })
+            .await?;
+
+        Ok::<_, Error>(())
+    })
+    .await
+}
+
+/**
+A fairing that runs due jobs in the background while the app is running.
+
+Jobs are run for each tenant hosted by the app. Default jobs are scheduled for each tenant
+when the app starts.
+
+The number of seconds to wait between checking for due jobs is read from the `job_interval`
+configuration value. It defaults to 1 second.
+The number of seconds between cleaning up expired stock reservations is read from the
+`reservation_expiry_interval` configuration value. It defaults to 60 seconds.
+*/
+pub struct RunJobsFairing;
+
+#[rocket::async_trait]
+impl Fairing for RunJobsFairing {
+    fn info(&self) -> Info {
+        Info {
+            name: "Jobs",
+            kind: Kind::Liftoff,
+        }
+    }
+
+    async fn on_liftoff(&self, rocket: &Rocket<Orbit>) {
+        let interval = rocket
+            .figment()
+            .extract_inner::<u64>("job_interval")
+            .map(Duration::from_secs)
+            .unwrap_or(Duration::from_secs(1));
+
+        let Some(app) = rocket.state::<App>() else {
+            return;
+        };
+
+        let app = app.by_ref();
+
+        let reservation_expiry_interval = rocket
+            .figment()
+            .extract_inner::<u64>("reservation_expiry_interval")
+            .unwrap_or(60);
+
+        for tenant in app.tenants() {
+            let result = match app.for_tenant(tenant) {
+                Ok(app) => schedule_default_jobs(&app, reservation_expiry_interval).await,
+                Err(err) => Err(err.into()),
+            };
+
+            if let Err(err) = result {
+                emit::error!("failed to schedule default jobs: {err}");
+            }
+        }
+
+        rocket::tokio::spawn(async move {
+            loop {
+                rocket::tokio::time::sleep(interval).await;
+
+                for tenant in app.tenants() {
+                    let result = match app.for_tenant(tenant) {
+                        Ok(app) => run_due_jobs(&app).await,
+                        Err(err) => Err(err.into()),
+                    };
+
+                    if let Err(err) = result {
+                        emit::error!("failed to run jobs: {err}");
+                    }
+                }
+            }
+        });
+    }
+}
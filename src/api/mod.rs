@@ -8,33 +8,115 @@ use crate::domain::App;
 
 mod infra;
 
+pub mod audit;
+pub mod catalog;
+pub mod categories;
 pub mod customers;
+pub mod exchange_rates;
+pub mod inventory;
+pub mod jobs;
 pub mod orders;
 pub mod products;
+pub mod promotions;
+pub mod query_cache;
+pub mod sagas;
+pub mod taxes;
 
 /**
-Create a `Rocket` that will host the app.
+Create a `Rocket` that will host the given app.
 
 The rocket can either be launched or passed to a local client for testing.
 */
-pub fn init() -> rocket::Rocket<Build> {
+pub fn init(app: App) -> rocket::Rocket<Build> {
     rocket::build()
-        .manage(App::default())
+        .manage(app)
         .mount(
             "/products",
-            rocket::routes![products::get, products::create, products::set_title],
+            rocket::routes![
+                products::get,
+                products::list,
+                products::search,
+                products::get_by_sku,
+                products::create,
+                products::set_title,
+                products::set_price,
+                products::price_history,
+                products::set_category,
+                products::remove_category,
+                products::set_tags,
+                products::add_variant,
+                products::remove_variant,
+                products::publish,
+                products::discontinue,
+                products::archive,
+                products::restore
+            ],
+        )
+        .mount(
+            "/categories",
+            rocket::routes![
+                categories::list,
+                categories::get,
+                categories::create,
+                categories::rename,
+                categories::move_to
+            ],
         )
         .mount(
             "/orders",
-            rocket::routes![orders::get, orders::create, orders::add_or_update_product],
+            rocket::routes![
+                orders::get,
+                orders::create,
+                orders::add_or_update_product,
+                orders::remove_product,
+                orders::set_tax_region,
+                orders::apply_promotion
+            ],
         )
         .mount(
             "/customers",
-            rocket::routes![customers::get, customers::create],
+            rocket::routes![
+                customers::get,
+                customers::create,
+                customers::archive,
+                customers::restore
+            ],
+        )
+        .mount(
+            "/inventory",
+            rocket::routes![inventory::get, inventory::set],
+        )
+        .mount(
+            "/admin/exchange-rates",
+            rocket::routes![exchange_rates::get, exchange_rates::set],
+        )
+        .mount(
+            "/admin/products",
+            rocket::routes![
+                catalog::get,
+                catalog::list,
+                catalog::import,
+                catalog::export
+            ],
+        )
+        .mount("/admin/tax-rates", rocket::routes![taxes::get, taxes::set])
+        .mount(
+            "/admin/promotions",
+            rocket::routes![promotions::get, promotions::create],
+        )
+        .mount(
+            "/admin/jobs",
+            rocket::routes![jobs::list, jobs::get, jobs::schedule],
         )
+        .mount("/admin/query-cache", rocket::routes![query_cache::metrics])
+        .mount("/admin/sagas", rocket::routes![sagas::get])
+        .mount("/admin/audit", rocket::routes![audit::list])
         .attach(infra::span::SpanFairing)
+        .attach(exchange_rates::LoadExchangeRatesFairing)
+        .attach(jobs::RunJobsFairing)
+        .attach(sagas::ResumeSagasFairing)
         .register(
             "/",
-            rocket::catchers![infra::error::not_found, infra::error::internal_error],
+            rocket::catchers![infra::error::internal_error, infra::error::default],
         )
 }
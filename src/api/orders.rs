@@ -15,14 +15,23 @@ use crate::{
     },
 };
 
-/** `GET /orders/<id>` */
+/**
+`GET /orders/<id>`
+
+The order's version is returned as an `ETag`, and can be passed back in an `If-None-Match` header.
+Changing any of its line items also changes the order's version.
+*/
 #[rocket::get("/<id>")]
-pub async fn get(id: OrderId, app: AppRequest<'_>) -> Result<Json<OrderWithProducts>, Error> {
+pub async fn get(
+    id: OrderId,
+    if_none_match: IfNoneMatchHeader,
+    app: AppRequest<'_>,
+) -> Result<Tagged<Json<OrderWithProducts>>, Error> {
     app.transaction(|app| async move {
         let query = app.get_order_with_products_query();
 
         match query.execute(GetOrderWithProducts { id }).await? {
-            Some(order) => Ok(Json(order)),
+            Some(order) => Ok(Tagged::new(order.version, &if_none_match, Json(order))),
             None => Err(Error::NotFound(error::msg("order not found"))),
         }
     })
@@ -55,22 +64,30 @@ pub struct Create {
     pub customer: CustomerId,
 }
 
-/** `PUT /orders` */
+/**
+`PUT /orders`
+
+Retrying with the same `Idempotency-Key` header returns the originally created order.
+*/
 #[rocket::put("/", format = "application/json", data = "<data>")]
 pub async fn create(
     data: Json<Create>,
+    key: IdempotencyKeyHeader,
     app: AppRequest<'_>,
 ) -> Result<Created<Json<OrderId>>, Error> {
     app.transaction(|app| async move {
         let id = app.order_id();
-        let command = app.create_order_command();
+        let command = app.idempotent_command(app.create_order_command());
 
         let id = id.get()?;
 
-        command
-            .execute(CreateOrder {
-                id,
-                customer_id: data.customer,
+        let id = command
+            .execute(Idempotent {
+                key: key.0,
+                args: CreateOrder {
+                    id,
+                    customer_id: data.customer,
+                },
             })
             .await?;
 
@@ -83,10 +100,17 @@ pub async fn create(
 
 #[derive(Deserialize)]
 pub struct ProductQuantity {
+    #[serde(default)]
+    variant_id: Option<VariantId>,
     quantity: u32,
 }
 
-/** `POST /orders/<id>/products/<product_id>` */
+/**
+`POST /orders/<id>/products/<product_id>`
+
+Retrying with the same `Idempotency-Key` header returns the original line item without applying the change again.
+If an `If-Match` header is sent then the product is only added or updated if the order's version still matches.
+*/
 #[rocket::post(
     "/<id>/products/<product_id>",
     format = "application/json",
@@ -96,20 +120,118 @@ pub async fn add_or_update_product(
     id: OrderId,
     product_id: ProductId,
     data: Json<ProductQuantity>,
+    key: IdempotencyKeyHeader,
+    if_match: IfMatchHeader,
     app: AppRequest<'_>,
 ) -> Result<Json<LineItemId>, Error> {
     app.transaction(|app| async move {
-        let command = app.add_or_update_product_command();
+        let command = app.idempotent_command(app.add_or_update_product_command());
+
+        let version = if_match.version()?;
 
         let line_item_id = command
-            .execute(AddOrUpdateProduct {
+            .execute(Idempotent {
+                key: key.0,
+                args: AddOrUpdateProduct {
+                    id,
+                    product_id,
+                    variant_id: data.0.variant_id,
+                    quantity: data.0.quantity,
+                    version,
+                },
+            })
+            .await?;
+
+        Ok(Json(line_item_id))
+    })
+    .await
+}
+
+/**
+`DELETE /orders/<id>/products/<product_id>?<variant>`
+
+Any stock reserved for the product is released.
+If an `If-Match` header is sent then the product is only removed if the order's version still matches.
+*/
+#[rocket::delete("/<id>/products/<product_id>?<variant>")]
+pub async fn remove_product(
+    id: OrderId,
+    product_id: ProductId,
+    variant: Option<VariantId>,
+    if_match: IfMatchHeader,
+    app: AppRequest<'_>,
+) -> Result<(), Error> {
+    app.transaction(|app| async move {
+        let command = app.remove_product_command();
+
+        let version = if_match.version()?;
+
+        command
+            .execute(RemoveProduct {
                 id,
                 product_id,
-                quantity: data.0.quantity,
+                variant_id: variant,
+                version,
             })
             .await?;
 
-        Ok(Json(line_item_id))
+        Ok(())
+    })
+    .await
+}
+
+/**
+`POST /orders/<id>/tax-region/<region>`
+
+If an `If-Match` header is sent then the region is only set if the order's version still matches.
+*/
+#[rocket::post("/<id>/tax-region/<region>")]
+pub async fn set_tax_region(
+    id: OrderId,
+    region: String,
+    if_match: IfMatchHeader,
+    app: AppRequest<'_>,
+) -> Result<(), Error> {
+    app.transaction(|app| async move {
+        let command = app.set_order_tax_region_command();
+
+        let version = if_match.version()?;
+
+        command
+            .execute(SetOrderTaxRegion {
+                id,
+                region,
+                version,
+            })
+            .await?;
+
+        Ok(())
+    })
+    .await
+}
+
+/**
+`POST /orders/<id>/promotions/<code>`
+
+If an `If-Match` header is sent then the promotion is only applied if the order's version still matches.
+*/
+#[rocket::post("/<id>/promotions/<code>")]
+pub async fn apply_promotion(
+    id: OrderId,
+    code: String,
+    if_match: IfMatchHeader,
+    app: AppRequest<'_>,
+) -> Result<Json<DiscountLineId>, Error> {
+    app.transaction(|app| async move {
+        let command = app.apply_promotion_command();
+
+        let version = if_match.version()?;
+
+        let discount_line_id = command
+            .execute(ApplyPromotion { id, code, version })
+            .await?;
+
+        Ok(Json(discount_line_id))
     })
     .await
 }
@@ -1,6 +1,13 @@
 /*! `/products` */
 
+use std::collections::BTreeMap;
+
 use rocket::{
+    form::{
+        self,
+        FromFormField,
+        ValueField,
+    },
     response::status::Created,
     serde::json::Json,
 };
@@ -8,6 +15,8 @@ use rocket::{
 use crate::{
     api::infra::*,
     domain::{
+        categories::CategoryId,
+        exchange_rates::*,
         infra::*,
         products::*,
     },
@@ -16,25 +25,107 @@ use crate::{
 #[derive(Serialize)]
 pub struct Get {
     pub id: ProductId,
+    pub version: ProductVersion,
     pub title: String,
     pub price: Currency,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub converted_price: Option<Currency>,
+    pub tax_category: String,
+    pub status: ProductStatus,
+    pub archived: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub category_id: Option<CategoryId>,
+    pub tags: Vec<String>,
+    pub variants: Vec<Variant>,
+}
+
+impl From<ProductData> for Get {
+    fn from(product: ProductData) -> Self {
+        Get {
+            id: product.id,
+            version: product.version,
+            title: product.title,
+            price: product.price,
+            converted_price: None,
+            tax_category: product.tax_category,
+            status: product.status,
+            archived: product.archived,
+            category_id: product.category_id,
+            tags: product.tags,
+            variants: product.variants.into_iter().map(Variant::from).collect(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct Variant {
+    pub id: VariantId,
+    pub sku: String,
+    pub options: BTreeMap<String, String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub price: Option<Currency>,
+}
+
+impl From<VariantData> for Variant {
+    fn from(variant: VariantData) -> Self {
+        Variant {
+            id: variant.id,
+            sku: variant.sku,
+            options: variant.options,
+            price: variant.price,
+        }
+    }
 }
 
-/** `GET /products/<id>` */
-#[rocket::get("/<id>")]
-pub async fn get(id: ProductId, app: AppRequest<'_>) -> Result<Json<Get>, Error> {
+/**
+`GET /products/<id>?<currency>`
+
+If a currency is given then the product's price is also converted into it.
+Archived products and drafts aren't returned. They can be fetched from `/admin/products` instead.
+The product's version is returned as an `ETag`, and can be passed back in an `If-None-Match` header.
+*/
+#[rocket::get("/<id>?<currency>")]
+pub async fn get(
+    id: ProductId,
+    currency: Option<CurrencyCode>,
+    if_none_match: IfNoneMatchHeader,
+    app: AppRequest<'_>,
+) -> Result<Tagged<Json<Get>>, Error> {
     app.transaction(|app| async move {
-        let query = app.get_product_query();
+        let query = app.cached_query(app.get_product_query());
+        let convert_query = app.convert_currency_query();
 
-        match query.execute(GetProduct { id }).await? {
+        match query
+            .execute(GetProduct {
+                id,
+                include_archived: false,
+                include_drafts: false,
+            })
+            .await?
+        {
             Some(product) => {
                 let product = product.into_data();
 
-                Ok(Json(Get {
-                    id: product.id,
-                    title: product.title,
-                    price: product.price,
-                }))
+                let converted_price = match currency {
+                    Some(to) => Some(
+                        convert_query
+                            .execute(ConvertCurrency {
+                                amount: product.price,
+                                to,
+                            })
+                            .await?,
+                    ),
+                    None => None,
+                };
+
+                Ok(Tagged::new(
+                    product.version,
+                    &if_none_match,
+                    Json(Get {
+                        converted_price,
+                        ..Get::from(product)
+                    }),
+                ))
             }
             None => Err(Error::NotFound(error::msg("product not found"))),
         }
@@ -42,13 +133,200 @@ pub async fn get(id: ProductId, app: AppRequest<'_>) -> Result<Json<Get>, Error>
     .await
 }
 
+impl<'v> FromFormField<'v> for ProductSort {
+    fn from_value(field: ValueField<'v>) -> form::Result<'v, Self> {
+        field
+            .value
+            .parse()
+            .map_err(|err| form::Error::validation(format!("{}", err)).into())
+    }
+}
+
+impl<'v> FromFormField<'v> for ProductFileFormat {
+    fn from_value(field: ValueField<'v>) -> form::Result<'v, Self> {
+        field
+            .value
+            .parse()
+            .map_err(|err| form::Error::validation(format!("{}", err)).into())
+    }
+}
+
+impl<'v> FromFormField<'v> for ProductCursor {
+    fn from_value(field: ValueField<'v>) -> form::Result<'v, Self> {
+        field
+            .value
+            .parse()
+            .map_err(|err| form::Error::validation(format!("{}", err)).into())
+    }
+}
+
+#[derive(Serialize)]
+pub struct List {
+    pub products: Vec<Get>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next: Option<ProductCursor>,
+}
+
+/**
+`GET /products?<title>&<currency>&<min_price>&<max_price>&<category>&<tag>&<sort>&<descending>&<after>&<limit>`
+
+Products can be filtered by a substring of their title, and by a price range in minor units of the given currency.
+Filtering by a category also includes products in any of its descendants.
+They're sorted by `title`, `price` or `created`, which is the default.
+If there are more products then a cursor is returned, which can be passed as `after` to get the next page.
+Archived products and drafts aren't listed.
+*/
+#[rocket::get(
+    "/?<title>&<currency>&<min_price>&<max_price>&<category>&<tag>&<sort>&<descending>&<after>&<limit>"
+)]
+#[allow(clippy::too_many_arguments)]
+pub async fn list(
+    title: Option<String>,
+    currency: Option<CurrencyCode>,
+    min_price: Option<u64>,
+    max_price: Option<u64>,
+    category: Option<CategoryId>,
+    tag: Option<String>,
+    sort: Option<ProductSort>,
+    descending: Option<bool>,
+    after: Option<ProductCursor>,
+    limit: Option<usize>,
+    app: AppRequest<'_>,
+) -> Result<Json<List>, Error> {
+    app.transaction(|app| async move {
+        let query = app.list_products_query();
+
+        let price = |units: Option<u64>| match (units, currency) {
+            (Some(units), Some(currency)) => Ok(Some(Currency::from_minor_units(currency, units))),
+            (Some(_), None) => Err(Error::BadRequest(error::msg(
+                "a currency is needed to filter by price",
+            ))),
+            (None, _) => Ok(None),
+        };
+
+        let page = query
+            .execute(ListProducts {
+                title,
+                min_price: price(min_price)?,
+                max_price: price(max_price)?,
+                category_id: category,
+                tag,
+                sort: sort.unwrap_or_default(),
+                descending: descending.unwrap_or(false),
+                after,
+                limit,
+                include_archived: false,
+                include_drafts: false,
+            })
+            .await?;
+
+        Ok(Json(List {
+            products: page.products.into_iter().map(Get::from).collect(),
+            next: page.next,
+        }))
+    })
+    .await
+}
+
+#[derive(Serialize)]
+pub struct SearchResult {
+    #[serde(flatten)]
+    pub product: Get,
+    pub score: f64,
+}
+
+/**
+`GET /products/search?<q>&<prefix>&<limit>`
+
+Products are returned with the most relevant first.
+If `prefix` is `true` then the last word in the query also matches words it's the start of,
+which is useful for autocomplete.
+Archived products and drafts aren't returned.
+*/
+#[rocket::get("/search?<q>&<prefix>&<limit>")]
+pub async fn search(
+    q: String,
+    prefix: Option<bool>,
+    limit: Option<usize>,
+    app: AppRequest<'_>,
+) -> Result<Json<Vec<SearchResult>>, Error> {
+    app.transaction(|app| async move {
+        let query = app.search_products_query();
+
+        let results = query
+            .execute(SearchProducts {
+                query: q,
+                prefix: prefix.unwrap_or(false),
+                limit,
+                include_archived: false,
+                include_drafts: false,
+            })
+            .await?;
+
+        Ok(Json(
+            results
+                .into_iter()
+                .map(|result| SearchResult {
+                    product: Get::from(result.product),
+                    score: result.score,
+                })
+                .collect(),
+        ))
+    })
+    .await
+}
+
+#[derive(Serialize)]
+pub struct GetBySku {
+    pub product: Get,
+    pub variant: Variant,
+}
+
+/**
+`GET /products/skus/<sku>`
+
+SKUs are matched without regard to case.
+Variants of archived products and drafts aren't returned.
+*/
+// Ranked after `/<id>/prices`, which forwards here because `skus` isn't a product id
+#[rocket::get("/skus/<sku>", rank = 1)]
+pub async fn get_by_sku(sku: String, app: AppRequest<'_>) -> Result<Json<GetBySku>, Error> {
+    app.transaction(|app| async move {
+        let query = app.get_product_variant_by_sku_query();
+
+        match query
+            .execute(GetProductVariantBySku {
+                sku,
+                include_archived: false,
+                include_drafts: false,
+            })
+            .await?
+        {
+            Some(result) => Ok(Json(GetBySku {
+                product: Get::from(result.product),
+                variant: Variant::from(result.variant),
+            })),
+            None => Err(Error::NotFound(error::msg("variant not found"))),
+        }
+    })
+    .await
+}
+
 #[derive(Deserialize)]
 pub struct Create {
     pub title: String,
     pub price: Currency,
+    #[serde(default)]
+    pub tax_category: Option<String>,
+    #[serde(default)]
+    pub draft: bool,
 }
 
-/** `PUT /products` */
+/**
+`PUT /products`
+
+Draft products are hidden until they're published.
+*/
 #[rocket::put("/", format = "application/json", data = "<data>")]
 pub async fn create(
     data: Json<Create>,
@@ -65,6 +343,8 @@ pub async fn create(
                 id,
                 title: data.0.title,
                 price: data.0.price,
+                tax_category: data.0.tax_category,
+                draft: data.0.draft,
             })
             .await?;
 
@@ -75,13 +355,357 @@ pub async fn create(
     .await
 }
 
-/** `POST /products/<id>/title/<title>` */
+/**
+`POST /products/<id>/title/<title>`
+
+If an `If-Match` header is sent then the title is only set if the product's version still matches.
+*/
 #[rocket::post("/<id>/title/<title>")]
-pub async fn set_title(id: ProductId, title: String, app: AppRequest<'_>) -> Result<(), Error> {
+pub async fn set_title(
+    id: ProductId,
+    title: String,
+    if_match: IfMatchHeader,
+    app: AppRequest<'_>,
+) -> Result<(), Error> {
     app.transaction(|app| async move {
         let command = app.set_product_title_command();
 
-        command.execute(SetProductTitle { id, title }).await?;
+        let version = if_match.version()?;
+
+        command
+            .execute(SetProductTitle { id, title, version })
+            .await?;
+
+        Ok(())
+    })
+    .await
+}
+
+/**
+`PUT /products/<id>/price`
+
+The change is recorded in the product's price history.
+Orders that already contain the product keep the price it had when it was added.
+If an `If-Match` header is sent then the price is only set if the product's version still matches.
+*/
+#[rocket::put("/<id>/price", format = "application/json", data = "<data>")]
+pub async fn set_price(
+    id: ProductId,
+    data: Json<Currency>,
+    if_match: IfMatchHeader,
+    app: AppRequest<'_>,
+) -> Result<(), Error> {
+    app.transaction(|app| async move {
+        let command = app.set_product_price_command();
+
+        let version = if_match.version()?;
+
+        command
+            .execute(SetProductPrice {
+                id,
+                price: data.0,
+                version,
+            })
+            .await?;
+
+        Ok(())
+    })
+    .await
+}
+
+#[derive(Serialize)]
+pub struct PriceHistory {
+    pub price: Currency,
+    pub effective_from: Timestamp,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub effective_to: Option<Timestamp>,
+}
+
+/**
+`GET /products/<id>/prices`
+
+Prices are returned from oldest to newest. The current price doesn't have an `effective_to`.
+*/
+#[rocket::get("/<id>/prices")]
+pub async fn price_history(
+    id: ProductId,
+    app: AppRequest<'_>,
+) -> Result<Json<Vec<PriceHistory>>, Error> {
+    app.transaction(|app| async move {
+        let query = app.get_product_price_history_query();
+
+        match query.execute(GetProductPriceHistory { id }).await? {
+            Some(history) => Ok(Json(
+                history
+                    .into_iter()
+                    .map(|entry| PriceHistory {
+                        price: entry.price,
+                        effective_from: Timestamp(entry.effective_from),
+                        effective_to: entry.effective_to.map(Timestamp),
+                    })
+                    .collect(),
+            )),
+            None => Err(Error::NotFound(error::msg("product not found"))),
+        }
+    })
+    .await
+}
+
+/**
+`PUT /products/<id>/category/<category>`
+
+If an `If-Match` header is sent then the category is only set if the product's version still matches.
+*/
+#[rocket::put("/<id>/category/<category>")]
+pub async fn set_category(
+    id: ProductId,
+    category: CategoryId,
+    if_match: IfMatchHeader,
+    app: AppRequest<'_>,
+) -> Result<(), Error> {
+    app.transaction(|app| async move {
+        let command = app.set_product_category_command();
+
+        let version = if_match.version()?;
+
+        command
+            .execute(SetProductCategory {
+                id,
+                category_id: Some(category),
+                version,
+            })
+            .await?;
+
+        Ok(())
+    })
+    .await
+}
+
+/**
+`DELETE /products/<id>/category`
+
+If an `If-Match` header is sent then the category is only removed if the product's version still matches.
+*/
+#[rocket::delete("/<id>/category")]
+pub async fn remove_category(
+    id: ProductId,
+    if_match: IfMatchHeader,
+    app: AppRequest<'_>,
+) -> Result<(), Error> {
+    app.transaction(|app| async move {
+        let command = app.set_product_category_command();
+
+        let version = if_match.version()?;
+
+        command
+            .execute(SetProductCategory {
+                id,
+                category_id: None,
+                version,
+            })
+            .await?;
+
+        Ok(())
+    })
+    .await
+}
+
+/**
+`PUT /products/<id>/tags`
+
+The given tags replace any the product already has.
+If an `If-Match` header is sent then the tags are only set if the product's version still matches.
+*/
+#[rocket::put("/<id>/tags", format = "application/json", data = "<data>")]
+pub async fn set_tags(
+    id: ProductId,
+    data: Json<Vec<String>>,
+    if_match: IfMatchHeader,
+    app: AppRequest<'_>,
+) -> Result<(), Error> {
+    app.transaction(|app| async move {
+        let command = app.set_product_tags_command();
+
+        let version = if_match.version()?;
+
+        command
+            .execute(SetProductTags {
+                id,
+                tags: data.0,
+                version,
+            })
+            .await?;
+
+        Ok(())
+    })
+    .await
+}
+
+#[derive(Deserialize)]
+pub struct AddVariant {
+    pub sku: String,
+    #[serde(default)]
+    pub options: BTreeMap<String, String>,
+    #[serde(default)]
+    pub price: Option<Currency>,
+}
+
+/**
+`PUT /products/<id>/variants`
+
+If an `If-Match` header is sent then the variant is only added if the product's version still matches.
+*/
+#[rocket::put("/<id>/variants", format = "application/json", data = "<data>")]
+pub async fn add_variant(
+    id: ProductId,
+    data: Json<AddVariant>,
+    if_match: IfMatchHeader,
+    app: AppRequest<'_>,
+) -> Result<Created<Json<VariantId>>, Error> {
+    app.transaction(|app| async move {
+        let variant_id = app.variant_id();
+        let command = app.add_product_variant_command();
+
+        let version = if_match.version()?;
+
+        let variant_id = variant_id.get()?;
+
+        command
+            .execute(AddProductVariant {
+                id,
+                variant_id,
+                sku: data.0.sku,
+                options: data.0.options,
+                price: data.0.price,
+                version,
+            })
+            .await?;
+
+        let location = format!("/products/{}", id);
+
+        Ok(Created::new(location).body(Json(variant_id)))
+    })
+    .await
+}
+
+/**
+`DELETE /products/<id>/variants/<variant_id>`
+
+If an `If-Match` header is sent then the variant is only removed if the product's version still matches.
+*/
+#[rocket::delete("/<id>/variants/<variant_id>")]
+pub async fn remove_variant(
+    id: ProductId,
+    variant_id: VariantId,
+    if_match: IfMatchHeader,
+    app: AppRequest<'_>,
+) -> Result<(), Error> {
+    app.transaction(|app| async move {
+        let command = app.remove_product_variant_command();
+
+        let version = if_match.version()?;
+
+        command
+            .execute(RemoveProductVariant {
+                id,
+                variant_id,
+                version,
+            })
+            .await?;
+
+        Ok(())
+    })
+    .await
+}
+
+/**
+`POST /products/<id>/publish`
+
+Draft and discontinued products can be published so they can be ordered.
+If an `If-Match` header is sent then the product is only published if its version still matches.
+*/
+#[rocket::post("/<id>/publish")]
+pub async fn publish(
+    id: ProductId,
+    if_match: IfMatchHeader,
+    app: AppRequest<'_>,
+) -> Result<(), Error> {
+    app.transaction(|app| async move {
+        let command = app.publish_product_command();
+
+        let version = if_match.version()?;
+
+        command.execute(PublishProduct { id, version }).await?;
+
+        Ok(())
+    })
+    .await
+}
+
+/**
+`POST /products/<id>/discontinue`
+
+Discontinued products can no longer be ordered.
+If an `If-Match` header is sent then the product is only discontinued if its version still matches.
+*/
+#[rocket::post("/<id>/discontinue")]
+pub async fn discontinue(
+    id: ProductId,
+    if_match: IfMatchHeader,
+    app: AppRequest<'_>,
+) -> Result<(), Error> {
+    app.transaction(|app| async move {
+        let command = app.discontinue_product_command();
+
+        let version = if_match.version()?;
+
+        command.execute(DiscontinueProduct { id, version }).await?;
+
+        Ok(())
+    })
+    .await
+}
+
+/**
+`POST /products/<id>/archive`
+
+If an `If-Match` header is sent then the product is only archived if its version still matches.
+*/
+#[rocket::post("/<id>/archive")]
+pub async fn archive(
+    id: ProductId,
+    if_match: IfMatchHeader,
+    app: AppRequest<'_>,
+) -> Result<(), Error> {
+    app.transaction(|app| async move {
+        let command = app.archive_product_command();
+
+        let version = if_match.version()?;
+
+        command.execute(ArchiveProduct { id, version }).await?;
+
+        Ok(())
+    })
+    .await
+}
+
+/**
+`POST /products/<id>/restore`
+
+If an `If-Match` header is sent then the product is only restored if its version still matches.
+*/
+#[rocket::post("/<id>/restore")]
+pub async fn restore(
+    id: ProductId,
+    if_match: IfMatchHeader,
+    app: AppRequest<'_>,
+) -> Result<(), Error> {
+    app.transaction(|app| async move {
+        let command = app.restore_product_command();
+
+        let version = if_match.version()?;
+
+        command.execute(RestoreProduct { id, version }).await?;
 
         Ok(())
     })
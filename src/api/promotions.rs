@@ -0,0 +1,107 @@
+/*! `/admin/promotions` */
+
+use rocket::{
+    response::status::Created,
+    serde::json::Json,
+};
+
+use crate::{
+    api::infra::*,
+    domain::{
+        infra::*,
+        promotions::*,
+    },
+};
+
+#[derive(Serialize)]
+pub struct Get {
+    pub id: PromotionId,
+    pub version: PromotionVersion,
+    pub code: String,
+    pub discount: Discount,
+    pub min_spend: Option<Currency>,
+    pub expires_at: Option<Timestamp>,
+    pub usage_limit: Option<u32>,
+    pub uses: u32,
+}
+
+/**
+`GET /admin/promotions/<id>`
+
+The promotion's version is returned as an `ETag`, and can be passed back in an `If-None-Match` header.
+*/
+#[rocket::get("/<id>")]
+pub async fn get(
+    id: PromotionId,
+    if_none_match: IfNoneMatchHeader,
+    app: AppRequest<'_>,
+) -> Result<Tagged<Json<Get>>, Error> {
+    app.transaction(|app| async move {
+        let query = app.get_promotion_query();
+
+        match query.execute(GetPromotion { id }).await? {
+            Some(promotion) => {
+                let promotion = promotion.into_data();
+
+                Ok(Tagged::new(
+                    promotion.version,
+                    &if_none_match,
+                    Json(Get {
+                        id: promotion.id,
+                        version: promotion.version,
+                        code: promotion.code,
+                        discount: promotion.discount,
+                        min_spend: promotion.min_spend,
+                        expires_at: promotion.expires_at.map(Timestamp),
+                        usage_limit: promotion.usage_limit,
+                        uses: promotion.uses,
+                    }),
+                ))
+            }
+            None => Err(Error::NotFound(error::msg("promotion not found"))),
+        }
+    })
+    .await
+}
+
+#[derive(Deserialize)]
+pub struct Create {
+    pub code: String,
+    pub discount: Discount,
+    #[serde(default)]
+    pub min_spend: Option<Currency>,
+    #[serde(default)]
+    pub expires_at: Option<Timestamp>,
+    #[serde(default)]
+    pub usage_limit: Option<u32>,
+}
+
+/** `PUT /admin/promotions` */
+#[rocket::put("/", format = "application/json", data = "<data>")]
+pub async fn create(
+    data: Json<Create>,
+    app: AppRequest<'_>,
+) -> Result<Created<Json<PromotionId>>, Error> {
+    app.transaction(|app| async move {
+        let id = app.promotion_id();
+        let command = app.create_promotion_command();
+
+        let id = id.get()?;
+
+        command
+            .execute(CreatePromotion {
+                id,
+                code: data.0.code,
+                discount: data.0.discount,
+                min_spend: data.0.min_spend,
+                expires_at: data.0.expires_at.map(|Timestamp(ts)| ts),
+                usage_limit: data.0.usage_limit,
+            })
+            .await?;
+
+        let location = format!("/admin/promotions/{}", id);
+
+        Ok(Created::new(location).body(Json(id)))
+    })
+    .await
+}
@@ -0,0 +1,15 @@
+/*! `/admin/query-cache` */
+
+use rocket::serde::json::Json;
+
+use crate::{
+    api::infra::*,
+    domain::infra::*,
+};
+
+/** `GET /admin/query-cache/metrics` */
+#[rocket::get("/metrics")]
+pub async fn metrics(app: AppRequest<'_>) -> Result<Json<Vec<QueryCacheMetrics>>, Error> {
+    app.transaction(|app| async move { Ok(Json(app.query_cache_metrics())) })
+        .await
+}
@@ -0,0 +1,91 @@
+/*! `/admin/sagas` */
+
+use rocket::{
+    Orbit,
+    Rocket,
+    fairing::{
+        Fairing,
+        Info,
+        Kind,
+    },
+    serde::json::Json,
+};
+
+use crate::{
+    api::infra::*,
+    domain::{
+        App,
+        infra::*,
+    },
+};
+
+#[derive(Serialize)]
+pub struct Get {
+    pub id: SagaId,
+    pub version: SagaVersion,
+    pub saga: String,
+    pub status: SagaStatus,
+    pub step: usize,
+    pub error: Option<String>,
+}
+
+/** `GET /admin/sagas/<id>` */
+#[rocket::get("/<id>")]
+pub async fn get(id: SagaId, app: AppRequest<'_>) -> Result<Json<Get>, Error> {
+    app.transaction(|app| async move {
+        let query = app.get_saga_query();
+
+        match query.execute(GetSaga { id }).await? {
+            Some(saga) => Ok(Json(Get {
+                id: saga.id,
+                version: saga.version,
+                saga: saga.saga,
+                status: saga.status,
+                step: saga.step,
+                error: saga.error,
+            })),
+            None => Err(Error::NotFound(error::msg("saga not found"))),
+        }
+    })
+    .await
+}
+
+/**
+A fairing that resumes any sagas that didn't finish when the app starts.
+
+Sagas are resumed for each tenant hosted by the app.
+*/
+pub struct ResumeSagasFairing;
+
+#[rocket::async_trait]
+impl Fairing for ResumeSagasFairing {
+    fn info(&self) -> Info {
+        Info {
+            name: "Sagas",
+            kind: Kind::Liftoff,
+        }
+    }
+
+    async fn on_liftoff(&self, rocket: &Rocket<Orbit>) {
+        let Some(app) = rocket.state::<App>() else {
+            return;
+        };
+
+        let app = app.by_ref();
+
+        rocket::tokio::spawn(async move {
+            for tenant in app.tenants() {
+                let result = match app.for_tenant(tenant) {
+                    Ok(app) => app.resume_sagas().await,
+                    Err(err) => Err(err),
+                };
+
+                match result {
+                    Ok(0) => (),
+                    Ok(resumed) => emit::info!("resumed {resumed} sagas"),
+                    Err(err) => emit::error!("failed to resume sagas: {reason: err.to_string()}"),
+                }
+            }
+        });
+    }
+}
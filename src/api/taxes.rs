@@ -0,0 +1,67 @@
+/*! `/admin/tax-rates` */
+
+use std::collections::BTreeMap;
+
+use rocket::serde::json::Json;
+
+use crate::{
+    api::infra::*,
+    domain::{
+        infra::*,
+        taxes::*,
+    },
+};
+
+#[derive(Serialize)]
+pub struct Get {
+    pub region: String,
+    pub pricing: PricingMode,
+    pub rates: BTreeMap<String, TaxRate>,
+}
+
+/** `GET /admin/tax-rates/<region>` */
+#[rocket::get("/<region>")]
+pub async fn get(region: String, app: AppRequest<'_>) -> Result<Json<Get>, Error> {
+    app.transaction(|app| async move {
+        let query = app.get_tax_rates_query();
+
+        match query.execute(GetTaxRates { region }).await? {
+            Some(tax_rates) => {
+                let tax_rates = tax_rates.into_data();
+
+                Ok(Json(Get {
+                    region: tax_rates.region,
+                    pricing: tax_rates.pricing,
+                    rates: tax_rates.rates,
+                }))
+            }
+            None => Err(Error::NotFound(error::msg("tax rates not found"))),
+        }
+    })
+    .await
+}
+
+#[derive(Deserialize)]
+pub struct Set {
+    pub pricing: PricingMode,
+    pub rates: BTreeMap<String, TaxRate>,
+}
+
+/** `PUT /admin/tax-rates/<region>` */
+#[rocket::put("/<region>", format = "application/json", data = "<data>")]
+pub async fn set(region: String, data: Json<Set>, app: AppRequest<'_>) -> Result<(), Error> {
+    app.transaction(|app| async move {
+        let command = app.set_tax_rates_command();
+
+        command
+            .execute(SetTaxRates {
+                region,
+                pricing: data.0.pricing,
+                rates: data.0.rates,
+            })
+            .await?;
+
+        Ok(())
+    })
+    .await
+}
@@ -0,0 +1,118 @@
+/*! Contains the `CreateCategoryCommand` type. */
+
+use crate::domain::{
+    Error,
+    ErrorKind,
+    categories::*,
+    error,
+    infra::*,
+};
+
+/**
+Input for a `CreateCategoryCommand`.
+
+If a parent is given then the category is created beneath it.
+Otherwise the category is created at the root of the tree.
+*/
+#[derive(Clone, Serialize, Deserialize)]
+pub struct CreateCategory {
+    pub id: CategoryId,
+    pub name: String,
+    #[serde(default)]
+    pub parent_id: Option<CategoryId>,
+}
+
+impl CommandArgs for CreateCategory {
+    type Output = Result<(), Error>;
+}
+
+/** Default implementation for a `CreateCategoryCommand`. */
+async fn execute(
+    command: CreateCategory,
+    transaction: ActiveTransaction,
+    store: impl CategoryStore,
+) -> Result<(), Error> {
+    let category = {
+        if store.get_category(command.id)?.is_some() {
+            return Err(
+                error::emit(emit::evt!("category {id: command.id} already exists"))
+                    .with_kind(ErrorKind::Conflict),
+            );
+        }
+
+        let parent = match command.parent_id {
+            Some(parent_id) => Some(
+                store
+                    .get_category(parent_id)?
+                    .ok_or_else(|| error::bad_input("parent category not found"))?,
+            ),
+            None => None,
+        };
+
+        Category::new(command.id, command.name, parent.as_ref())?
+    };
+
+    store.set_category(transaction.get(), category)?;
+
+    Ok(())
+}
+
+impl Resolver {
+    /** Create a category. */
+    pub fn create_category_command(&self) -> impl Command<CreateCategory> {
+        self.command(|resolver, command: CreateCategory| async move {
+            let store = resolver.category_store();
+            let active_transaction = resolver.active_transaction();
+
+            execute(command, active_transaction, store).await
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::domain::categories::model::store::in_memory_store;
+
+    #[tokio::test]
+    async fn err_if_already_exists() {
+        let store = in_memory_store(Default::default());
+
+        let create = CreateCategory {
+            id: CategoryId::new(),
+            name: "Clothing".to_owned(),
+            parent_id: None,
+        };
+
+        execute(create.clone(), ActiveTransaction::none(), &store)
+            .await
+            .unwrap();
+
+        assert!(
+            execute(create, ActiveTransaction::none(), &store)
+                .await
+                .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn err_if_parent_not_found() {
+        let store = in_memory_store(Default::default());
+
+        let err = execute(
+            CreateCategory {
+                id: CategoryId::new(),
+                name: "Shirts".to_owned(),
+                parent_id: Some(CategoryId::new()),
+            },
+            ActiveTransaction::none(),
+            &store,
+        )
+        .await
+        .err()
+        .unwrap();
+
+        assert_eq!(ErrorKind::BadInput, err.kind());
+    }
+}
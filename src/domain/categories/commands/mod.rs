@@ -0,0 +1,11 @@
+/*! Commands for modifying category state. */
+
+mod create_category;
+mod move_category;
+mod rename_category;
+
+pub use self::{
+    create_category::*,
+    move_category::*,
+    rename_category::*,
+};
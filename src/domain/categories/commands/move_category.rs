@@ -0,0 +1,146 @@
+/*! Contains the `MoveCategoryCommand` type. */
+
+use crate::domain::{
+    Error,
+    categories::*,
+    error,
+    infra::*,
+};
+
+/**
+Input for a `MoveCategoryCommand`.
+
+If no parent is given then the category is moved to the root of the tree.
+A category can't be moved beneath itself or any of its descendants.
+*/
+#[derive(Clone, Serialize, Deserialize)]
+pub struct MoveCategory {
+    pub id: CategoryId,
+    #[serde(default)]
+    pub parent_id: Option<CategoryId>,
+}
+
+impl CommandArgs for MoveCategory {
+    type Output = Result<(), Error>;
+}
+
+/** Default implementation for a `MoveCategoryCommand`. */
+async fn execute(
+    command: MoveCategory,
+    transaction: ActiveTransaction,
+    store: impl CategoryStore,
+) -> Result<(), Error> {
+    let Some(mut category) = store.get_category(command.id)? else {
+        return Err(error::not_found("category not found"));
+    };
+
+    let parent = match command.parent_id {
+        Some(parent_id) => Some(
+            store
+                .get_category(parent_id)?
+                .ok_or_else(|| error::bad_input("parent category not found"))?,
+        ),
+        None => None,
+    };
+
+    // Walk up from the new parent so the category can check it isn't becoming its own ancestor
+    let mut ancestors = Vec::new();
+    let mut next = parent
+        .as_ref()
+        .and_then(|parent| parent.to_data().parent_id);
+    while let Some(id) = next {
+        if ancestors.contains(&id) {
+            break;
+        }
+
+        ancestors.push(id);
+        next = store
+            .get_category(id)?
+            .and_then(|ancestor| ancestor.to_data().parent_id);
+    }
+
+    category.set_parent(parent.as_ref(), &ancestors)?;
+
+    store.set_category(transaction.get(), category)?;
+
+    Ok(())
+}
+
+impl Resolver {
+    /** Move an existing category beneath a different parent. */
+    pub fn move_category_command(&self) -> impl Command<MoveCategory> {
+        self.command(|resolver, command: MoveCategory| async move {
+            let store = resolver.category_store();
+            let active_transaction = resolver.active_transaction();
+
+            execute(command, active_transaction, store).await
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::domain::{
+        ErrorKind,
+        categories::model::{
+            store::in_memory_store,
+            test_data::CategoryBuilder,
+        },
+    };
+
+    #[tokio::test]
+    async fn err_if_moved_beneath_descendant() {
+        let store = in_memory_store(Default::default());
+
+        let root = CategoryId::new();
+        let child = CategoryId::new();
+        let grandchild = CategoryId::new();
+
+        for category in [
+            CategoryBuilder::new().id(root).build(),
+            CategoryBuilder::new().id(child).parent(root).build(),
+            CategoryBuilder::new().id(grandchild).parent(child).build(),
+        ] {
+            store
+                .set_category(ActiveTransaction::none().get(), category)
+                .unwrap();
+        }
+
+        let err = execute(
+            MoveCategory {
+                id: root,
+                parent_id: Some(grandchild),
+            },
+            ActiveTransaction::none(),
+            &store,
+        )
+        .await
+        .err()
+        .unwrap();
+
+        assert_eq!(ErrorKind::BadInput, err.kind());
+
+        execute(
+            MoveCategory {
+                id: grandchild,
+                parent_id: None,
+            },
+            ActiveTransaction::none(),
+            &store,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            None,
+            store
+                .get_category(grandchild)
+                .unwrap()
+                .unwrap()
+                .to_data()
+                .parent_id
+        );
+    }
+}
@@ -0,0 +1,48 @@
+/*! Contains the `RenameCategoryCommand` type. */
+
+use crate::domain::{
+    Error,
+    categories::*,
+    error,
+    infra::*,
+};
+
+/** Input for a `RenameCategoryCommand`. */
+#[derive(Clone, Serialize, Deserialize)]
+pub struct RenameCategory {
+    pub id: CategoryId,
+    pub name: String,
+}
+
+impl CommandArgs for RenameCategory {
+    type Output = Result<(), Error>;
+}
+
+/** Default implementation for a `RenameCategoryCommand`. */
+async fn execute(
+    command: RenameCategory,
+    transaction: ActiveTransaction,
+    store: impl CategoryStore,
+) -> Result<(), Error> {
+    let Some(mut category) = store.get_category(command.id)? else {
+        return Err(error::not_found("category not found"));
+    };
+
+    category.set_name(command.name)?;
+
+    store.set_category(transaction.get(), category)?;
+
+    Ok(())
+}
+
+impl Resolver {
+    /** Rename an existing category. */
+    pub fn rename_category_command(&self) -> impl Command<RenameCategory> {
+        self.command(|resolver, command: RenameCategory| async move {
+            let store = resolver.category_store();
+            let active_transaction = resolver.active_transaction();
+
+            execute(command, active_transaction, store).await
+        })
+    }
+}
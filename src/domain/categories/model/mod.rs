@@ -0,0 +1,176 @@
+/*! Contains the `Category` entity. */
+
+use std::convert::{
+    TryFrom,
+    TryInto,
+};
+
+use crate::domain::{
+    Error,
+    error,
+    infra::*,
+};
+
+pub mod store;
+
+#[cfg(test)]
+pub mod test_data;
+
+pub type CategoryId = Id<CategoryData>;
+pub type NextCategoryId = NextId<CategoryData>;
+pub type CategoryVersion = Version<CategoryData>;
+
+/**
+A category name.
+
+The name must not be empty or longer than 64 characters.
+*/
+pub struct CategoryName(String);
+
+impl TryFrom<String> for CategoryName {
+    type Error = Error;
+
+    fn try_from(name: String) -> Result<Self, Self::Error> {
+        if name.is_empty() || name.chars().count() > 64 {
+            return Err(error::bad_input(
+                "category name must be between 1 and 64 characters",
+            ));
+        }
+
+        Ok(CategoryName(name))
+    }
+}
+
+impl<'a> TryFrom<&'a str> for CategoryName {
+    type Error = Error;
+
+    fn try_from(name: &'a str) -> Result<Self, Self::Error> {
+        Self::try_from(name.to_owned())
+    }
+}
+
+/** Data for a category. */
+#[derive(Clone, Serialize, Deserialize)]
+pub struct CategoryData {
+    pub id: CategoryId,
+    pub version: CategoryVersion,
+    pub name: String,
+    pub parent_id: Option<CategoryId>,
+    _private: (),
+}
+
+/**
+A category of products.
+
+Categories form a tree, where each category can have a single parent.
+A category without a parent is at the root of the tree.
+*/
+#[derive(Clone)]
+pub struct Category {
+    data: CategoryData,
+}
+
+impl Category {
+    pub(self) fn from_data(data: CategoryData) -> Self {
+        Category { data }
+    }
+
+    pub fn to_data(&self) -> &CategoryData {
+        &self.data
+    }
+
+    pub fn into_data(self) -> CategoryData {
+        self.data
+    }
+
+    pub fn new(
+        id: impl IdProvider<CategoryData>,
+        name: impl TryInto<CategoryName, Error = Error>,
+        parent: Option<&Category>,
+    ) -> Result<Self, Error> {
+        let id = id.get()?;
+
+        Ok(Category::from_data(CategoryData {
+            id,
+            version: CategoryVersion::default(),
+            name: name.try_into()?.0,
+            parent_id: parent.map(|parent| parent.data.id),
+            _private: (),
+        }))
+    }
+
+    pub fn set_name(
+        &mut self,
+        name: impl TryInto<CategoryName, Error = Error>,
+    ) -> Result<(), Error> {
+        self.data.name = name.try_into()?.0;
+
+        Ok(())
+    }
+
+    /**
+    Move the category beneath a new parent.
+
+    The ids of the parent's ancestors are needed to make sure the category isn't moved beneath
+    one of its own descendants.
+    */
+    pub fn set_parent(
+        &mut self,
+        parent: Option<&Category>,
+        parent_ancestors: &[CategoryId],
+    ) -> Result<(), Error> {
+        if let Some(parent) = parent
+            && (parent.data.id == self.data.id || parent_ancestors.contains(&self.data.id))
+        {
+            return Err(error::bad_input(
+                "a category can't be moved beneath itself or its descendants",
+            ));
+        }
+
+        self.data.parent_id = parent.map(|parent| parent.data.id);
+
+        Ok(())
+    }
+}
+
+impl Entity for Category {
+    type Id = CategoryId;
+    type Version = CategoryVersion;
+    type Data = CategoryData;
+    type Error = Error;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn name_must_be_valid() {
+        assert!(Category::new(CategoryId::new(), "", None).is_err());
+        assert!(Category::new(CategoryId::new(), "a".repeat(65), None).is_err());
+
+        let mut category = Category::new(CategoryId::new(), "Shirts", None).unwrap();
+
+        assert!(category.set_name("").is_err());
+    }
+
+    #[test]
+    fn parent_must_not_be_a_descendant() {
+        let mut root = Category::new(CategoryId::new(), "Clothing", None).unwrap();
+        let child = Category::new(CategoryId::new(), "Shirts", Some(&root)).unwrap();
+        let grandchild = Category::new(CategoryId::new(), "T-shirts", Some(&child)).unwrap();
+
+        assert!(root.set_parent(Some(&root.clone()), &[]).is_err());
+        assert!(
+            root.set_parent(Some(&grandchild), &[child.data.id, root.data.id])
+                .is_err()
+        );
+
+        let mut grandchild = grandchild;
+        grandchild.set_parent(Some(&root), &[]).unwrap();
+        assert_eq!(Some(root.data.id), grandchild.data.parent_id);
+
+        grandchild.set_parent(None, &[]).unwrap();
+        assert_eq!(None, grandchild.data.parent_id);
+    }
+}
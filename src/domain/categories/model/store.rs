@@ -0,0 +1,113 @@
+/*! Persistent category storage. */
+
+use crate::{
+    domain::{
+        Error,
+        categories::*,
+    },
+    store::*,
+};
+
+/** A place to persist and fetch categories. */
+#[auto_impl(&, Arc)]
+pub(in crate::domain) trait CategoryStore {
+    fn get_category(&self, id: CategoryId) -> Result<Option<Category>, Error>;
+    fn set_category(&self, transaction: &Transaction, category: Category) -> Result<(), Error>;
+}
+
+/**
+An additional store for fetching multiple category records at a time.
+
+Like `ProductStoreFilter`, this trait is an implementation detail that will probably need to be
+refactored when we add a proper database.
+*/
+#[auto_impl(&, Arc)]
+pub(in crate::domain) trait CategoryStoreFilter {
+    fn filter<F>(&self, predicate: F) -> Result<Vec<CategoryData>, Error>
+    where
+        F: Fn(&CategoryData) -> bool;
+
+    fn get_categories(&self, ids: &[CategoryId]) -> Result<Vec<Category>, Error>;
+}
+
+/** A test in-memory category store. */
+pub(in crate::domain) struct InMemoryStore(TransactionValueStore<CategoryData>);
+
+impl CategoryStore for InMemoryStore {
+    fn get_category(&self, id: CategoryId) -> Result<Option<Category>, Error> {
+        if let Some((version, data)) = self.0.get(id) {
+            assert_eq!(version, data.version.into());
+
+            Ok(Some(Category::from_data(data)))
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn set_category(&self, transaction: &Transaction, category: Category) -> Result<(), Error> {
+        let mut data = category.into_data();
+        let id = data.id;
+
+        self.0.set(
+            transaction,
+            id,
+            Some(data.version),
+            data.version.next()?,
+            data,
+        )?;
+
+        Ok(())
+    }
+}
+
+impl CategoryStoreFilter for InMemoryStore {
+    fn filter<F>(&self, predicate: F) -> Result<Vec<CategoryData>, Error>
+    where
+        F: Fn(&CategoryData) -> bool,
+    {
+        Ok(self.0.get_all(predicate).map(|(_, data)| data).collect())
+    }
+
+    fn get_categories(&self, ids: &[CategoryId]) -> Result<Vec<Category>, Error> {
+        let categories = self
+            .0
+            .get_all(|c| ids.contains(&c.id))
+            .map(|(_, data)| Category::from_data(data))
+            .collect();
+
+        Ok(categories)
+    }
+}
+
+/**
+Create an in-memory category store.
+
+The store will participate in transactions tracked by the given transaction store.
+*/
+pub(in crate::domain) fn in_memory_store(transaction_store: TransactionStore) -> InMemoryStore {
+    InMemoryStore(TransactionValueStore::new(transaction_store))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::domain::categories::model::test_data::CategoryBuilder;
+
+    #[test]
+    fn add_category_twice_fails_concurrency_check() {
+        let store = in_memory_store(Default::default());
+
+        let id = CategoryId::new();
+
+        store
+            .set_category(&Transaction::none(), CategoryBuilder::new().id(id).build())
+            .unwrap();
+
+        assert!(
+            store
+                .set_category(&Transaction::none(), CategoryBuilder::new().id(id).build())
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,46 @@
+use crate::domain::categories::*;
+
+pub fn default_name() -> String {
+    "A test category".to_owned()
+}
+
+pub fn default_category() -> Category {
+    Category::new(NextCategoryId::new(), default_name(), None).unwrap()
+}
+
+pub struct CategoryBuilder {
+    category: Category,
+}
+
+impl Default for CategoryBuilder {
+    fn default() -> Self {
+        CategoryBuilder {
+            category: default_category(),
+        }
+    }
+}
+
+impl CategoryBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn id(mut self, id: CategoryId) -> Self {
+        self.category.data.id = id;
+        self
+    }
+
+    pub fn name(mut self, name: &str) -> Self {
+        self.category.set_name(name).unwrap();
+        self
+    }
+
+    pub fn parent(mut self, parent_id: CategoryId) -> Self {
+        self.category.data.parent_id = Some(parent_id);
+        self
+    }
+
+    pub fn build(self) -> Category {
+        self.category
+    }
+}
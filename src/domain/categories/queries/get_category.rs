@@ -0,0 +1,48 @@
+/*! Contains the `GetCategoryQuery` type. */
+
+use crate::domain::{
+    Error,
+    categories::*,
+    infra::*,
+};
+
+/** Input for a `GetCategoryQuery`. */
+#[derive(Serialize, Deserialize)]
+pub struct GetCategory {
+    pub id: CategoryId,
+}
+
+impl QueryArgs for GetCategory {
+    type Output = Result<Option<Category>, Error>;
+}
+
+/**
+Default implementation for a `GetCategoryQuery`.
+
+Categories fetched concurrently within the same transaction are loaded in a single batch.
+*/
+async fn execute(
+    query: GetCategory,
+    loader: BatchLoader<CategoryId, Category>,
+    store: impl CategoryStoreFilter,
+) -> Result<Option<Category>, Error> {
+    loader
+        .load(query.id, |ids| {
+            let categories = store.get_categories(ids)?;
+
+            Ok(categories.into_iter().map(|c| (c.to_data().id, c)))
+        })
+        .await
+}
+
+impl Resolver {
+    /** Get a category. */
+    pub fn get_category_query(&self) -> impl Query<GetCategory> {
+        self.query(|resolver, query: GetCategory| async move {
+            let loader = resolver.batch_loader();
+            let store = resolver.category_store_filter();
+
+            execute(query, loader, store).await
+        })
+    }
+}
@@ -0,0 +1,182 @@
+/*! Contains the `GetCategoryTreeQuery` type. */
+
+use std::collections::HashMap;
+
+use crate::domain::{
+    Error,
+    categories::*,
+    infra::*,
+};
+
+/**
+Input for a `GetCategoryTreeQuery`.
+
+If an id is given then only the tree beneath that category is returned.
+Otherwise every tree starting from a root category is returned.
+*/
+#[derive(Serialize, Deserialize)]
+pub struct GetCategoryTree {
+    #[serde(default)]
+    pub id: Option<CategoryId>,
+}
+
+/**
+A category along with all of its descendants.
+
+Children are sorted by their name.
+*/
+#[derive(Serialize)]
+pub struct CategoryTree {
+    #[serde(flatten)]
+    pub category: CategoryData,
+    pub children: Vec<CategoryTree>,
+}
+
+impl CategoryTree {
+    /** Get the ids of the category and all of its descendants. */
+    pub fn ids(&self) -> Vec<CategoryId> {
+        let mut ids = vec![self.category.id];
+
+        for child in &self.children {
+            ids.extend(child.ids());
+        }
+
+        ids
+    }
+}
+
+impl QueryArgs for GetCategoryTree {
+    type Output = Result<Vec<CategoryTree>, Error>;
+}
+
+/**
+Default implementation for a `GetCategoryTreeQuery`.
+
+The result is empty if the requested category doesn't exist.
+*/
+async fn execute(
+    query: GetCategoryTree,
+    store: impl CategoryStoreFilter,
+) -> Result<Vec<CategoryTree>, Error> {
+    let mut categories = store.filter(|_| true)?;
+    categories.sort_by(|a, b| (&a.name, a.id).cmp(&(&b.name, b.id)));
+
+    let mut children = HashMap::<_, Vec<_>>::new();
+    let mut roots = Vec::new();
+    for category in categories {
+        let is_root = match query.id {
+            Some(id) => category.id == id,
+            None => category.parent_id.is_none(),
+        };
+
+        match (is_root, category.parent_id) {
+            (true, _) => roots.push(category),
+            (false, Some(parent_id)) => children.entry(parent_id).or_default().push(category),
+            (false, None) => (),
+        }
+    }
+
+    // Categories are only visited beneath their parent, so a category is never visited twice
+    fn build(
+        category: CategoryData,
+        children: &mut HashMap<CategoryId, Vec<CategoryData>>,
+    ) -> CategoryTree {
+        let descendants = children
+            .remove(&category.id)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|child| build(child, children))
+            .collect();
+
+        CategoryTree {
+            category,
+            children: descendants,
+        }
+    }
+
+    Ok(roots
+        .into_iter()
+        .map(|root| build(root, &mut children))
+        .collect())
+}
+
+impl Resolver {
+    /** Get a tree of categories. */
+    pub fn get_category_tree_query(&self) -> impl Query<GetCategoryTree> {
+        self.query(|resolver, query: GetCategoryTree| async move {
+            let store = resolver.category_store_filter();
+
+            execute(query, store).await
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::{
+        domain::categories::model::{
+            store::{
+                CategoryStore,
+                in_memory_store,
+            },
+            test_data::CategoryBuilder,
+        },
+        store::Transaction,
+    };
+
+    fn names(trees: &[CategoryTree]) -> Vec<(&str, usize)> {
+        trees
+            .iter()
+            .map(|tree| (tree.category.name.as_str(), tree.ids().len()))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn get_trees() {
+        let store = in_memory_store(Default::default());
+
+        let clothing = CategoryId::new();
+        let shirts = CategoryId::new();
+
+        for category in [
+            CategoryBuilder::new().id(clothing).name("Clothing").build(),
+            CategoryBuilder::new()
+                .id(shirts)
+                .name("Shirts")
+                .parent(clothing)
+                .build(),
+            CategoryBuilder::new()
+                .name("T-shirts")
+                .parent(shirts)
+                .build(),
+            CategoryBuilder::new().name("Hats").parent(clothing).build(),
+            CategoryBuilder::new().name("Books").build(),
+        ] {
+            store.set_category(&Transaction::none(), category).unwrap();
+        }
+
+        let all = execute(GetCategoryTree { id: None }, &store).await.unwrap();
+
+        assert_eq!(vec![("Books", 1), ("Clothing", 4)], names(&all));
+        assert_eq!(vec![("Hats", 1), ("Shirts", 2)], names(&all[1].children));
+
+        let shirts = execute(GetCategoryTree { id: Some(shirts) }, &store)
+            .await
+            .unwrap();
+
+        assert_eq!(vec![("Shirts", 2)], names(&shirts));
+
+        let missing = execute(
+            GetCategoryTree {
+                id: Some(CategoryId::new()),
+            },
+            &store,
+        )
+        .await
+        .unwrap();
+
+        assert!(missing.is_empty());
+    }
+}
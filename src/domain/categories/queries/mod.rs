@@ -0,0 +1,9 @@
+/*! Queries for fetching category state. */
+
+mod get_category;
+mod get_category_tree;
+
+pub use self::{
+    get_category::*,
+    get_category_tree::*,
+};
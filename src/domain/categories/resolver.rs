@@ -0,0 +1,66 @@
+/*! Contains the `CategoriesResolver` type. */
+
+use std::sync::Arc;
+
+use crate::domain::{
+    categories::{
+        CategoryData,
+        model::store::{
+            self,
+            CategoryStore,
+            CategoryStoreFilter,
+            InMemoryStore,
+        },
+    },
+    infra::*,
+};
+
+/**
+Resolver for categories.
+
+The `CategoriesResolver` type wraps private implementation details and exposes them as traits within the `categories` module.
+*/
+#[derive(Clone)]
+pub(in crate::domain) struct CategoriesResolver {
+    category_store: Register<Arc<InMemoryStore>>,
+    category_id: Register<Arc<dyn IdProvider<CategoryData> + Send + Sync>>,
+}
+
+impl Default for CategoriesResolver {
+    fn default() -> Self {
+        CategoriesResolver {
+            category_store: Register::per_tenant(|resolver| {
+                Arc::new(store::in_memory_store(resolver.transaction_store()))
+            }),
+            category_id: Register::once(|_| {
+                Arc::new(NextId::<CategoryData>::new())
+                    as Arc<dyn IdProvider<CategoryData> + Send + Sync>
+            }),
+        }
+    }
+}
+
+impl AppBuilder {
+    /** Use a different source of ids for new categories. */
+    pub fn category_id(
+        mut self,
+        category_id: Register<Arc<dyn IdProvider<CategoryData> + Send + Sync>>,
+    ) -> Self {
+        self.root_resolver.categories_resolver.category_id = category_id;
+        self
+    }
+}
+
+impl Resolver {
+    pub fn category_id(&self) -> impl IdProvider<CategoryData> {
+        self.resolve(&self.categories_resolver.category_id)
+    }
+
+    pub(in crate::domain::categories) fn category_store(&self) -> impl CategoryStore {
+        self.resolve(&self.categories_resolver.category_store)
+    }
+
+    pub(in crate::domain::categories) fn category_store_filter(&self) -> impl CategoryStoreFilter {
+        self.resolve(&self.categories_resolver.category_store)
+    }
+}
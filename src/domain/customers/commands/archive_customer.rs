@@ -0,0 +1,54 @@
+/*! Contains the `ArchiveCustomerCommand` type. */
+
+use crate::domain::{
+    Error,
+    customers::*,
+    error,
+    infra::*,
+};
+
+/**
+Input for an `ArchiveCustomerCommand`.
+
+If a version is given then the customer must still have that version to be archived.
+*/
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ArchiveCustomer {
+    pub id: CustomerId,
+    #[serde(default)]
+    pub version: Option<CustomerVersion>,
+}
+
+impl CommandArgs for ArchiveCustomer {
+    type Output = Result<(), Error>;
+}
+
+async fn execute(
+    command: ArchiveCustomer,
+    transaction: ActiveTransaction,
+    store: impl CustomerStore,
+) -> Result<(), Error> {
+    let Some(mut customer) = store.get_customer(command.id)? else {
+        return Err(error::not_found("customer not found"));
+    };
+
+    customer.check_version(command.version)?;
+
+    customer.archive();
+
+    store.set_customer(transaction.get(), customer)?;
+
+    Ok(())
+}
+
+impl Resolver {
+    /** Archive a customer. */
+    pub fn archive_customer_command(&self) -> impl Command<ArchiveCustomer> {
+        self.command(|resolver, command: ArchiveCustomer| async move {
+            let store = resolver.customer_store();
+            let active_transaction = resolver.active_transaction();
+
+            execute(command, active_transaction, store).await
+        })
+    }
+}
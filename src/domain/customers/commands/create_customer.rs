@@ -2,6 +2,7 @@
 
 use crate::domain::{
     Error,
+    ErrorKind,
     customers::*,
     error,
     infra::*,
@@ -24,9 +25,10 @@ async fn execute(
 ) -> Result<(), Error> {
     let customer = {
         if store.get_customer(command.id)?.is_some() {
-            return Err(error::emit(emit::evt!(
-                "customer {id: command.id} already exists"
-            )));
+            return Err(
+                error::emit(emit::evt!("customer {id: command.id} already exists"))
+                    .with_kind(ErrorKind::Conflict),
+            );
         } else {
             Customer::new(command.id)?
         }
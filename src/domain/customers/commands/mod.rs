@@ -1,5 +1,11 @@
 /*! Commands for modifying customer state. */
 
+mod archive_customer;
 mod create_customer;
+mod restore_customer;
 
-pub use self::create_customer::*;
+pub use self::{
+    archive_customer::*,
+    create_customer::*,
+    restore_customer::*,
+};
@@ -0,0 +1,88 @@
+/*! Contains the `RestoreCustomerCommand` type. */
+
+use crate::domain::{
+    Error,
+    customers::*,
+    error,
+    infra::*,
+};
+
+/**
+Input for a `RestoreCustomerCommand`.
+
+If a version is given then the customer must still have that version to be restored.
+*/
+#[derive(Clone, Serialize, Deserialize)]
+pub struct RestoreCustomer {
+    pub id: CustomerId,
+    #[serde(default)]
+    pub version: Option<CustomerVersion>,
+}
+
+impl CommandArgs for RestoreCustomer {
+    type Output = Result<(), Error>;
+}
+
+async fn execute(
+    command: RestoreCustomer,
+    transaction: ActiveTransaction,
+    store: impl CustomerStore,
+) -> Result<(), Error> {
+    let Some(mut customer) = store.get_customer(command.id)? else {
+        return Err(error::not_found("customer not found"));
+    };
+
+    customer.check_version(command.version)?;
+
+    customer.restore();
+
+    store.set_customer(transaction.get(), customer)?;
+
+    Ok(())
+}
+
+impl Resolver {
+    /** Restore an archived customer. */
+    pub fn restore_customer_command(&self) -> impl Command<RestoreCustomer> {
+        self.command(|resolver, command: RestoreCustomer| async move {
+            let store = resolver.customer_store();
+            let active_transaction = resolver.active_transaction();
+
+            execute(command, active_transaction, store).await
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::domain::customers::model::{
+        store::in_memory_store,
+        test_data::CustomerBuilder,
+    };
+
+    use super::*;
+
+    #[tokio::test]
+    async fn restore_archived_customer() {
+        let store = in_memory_store(Default::default());
+
+        let id = CustomerId::new();
+
+        store
+            .set_customer(
+                ActiveTransaction::none().get(),
+                CustomerBuilder::new().id(id).archived().build(),
+            )
+            .unwrap();
+
+        execute(
+            RestoreCustomer { id, version: None },
+            ActiveTransaction::none(),
+            &store,
+        )
+        .await
+        .unwrap();
+
+        assert!(!store.get_customer(id).unwrap().unwrap().to_data().archived);
+    }
+}
@@ -11,4 +11,7 @@ pub use self::{
     queries::*,
 };
 
-pub(self) use self::model::store::CustomerStore;
+use self::model::store::{
+    CustomerStore,
+    CustomerStoreFilter,
+};
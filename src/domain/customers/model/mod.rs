@@ -1,7 +1,10 @@
 /*! Contains the `Customer` entity. */
 
 use crate::domain::{
-    Error,
+    error::{
+        self,
+        Error,
+    },
     infra::*,
 };
 
@@ -19,10 +22,13 @@ pub mod test_data;
 pub struct CustomerData {
     pub id: CustomerId,
     pub version: CustomerVersion,
+    #[serde(default)]
+    pub archived: bool,
     _private: (),
 }
 
 /** A customer. */
+#[derive(Clone)]
 pub struct Customer {
     data: CustomerData,
 }
@@ -46,9 +52,39 @@ impl Customer {
         Ok(Customer::from_data(CustomerData {
             id,
             version: CustomerVersion::default(),
+            archived: false,
             _private: (),
         }))
     }
+
+    /**
+    Archive the customer.
+
+    Archived customers are hidden from queries by default and can't place new orders.
+    Their existing orders are kept.
+    */
+    pub fn archive(&mut self) {
+        self.data.archived = true;
+    }
+
+    /** Restore an archived customer. */
+    pub fn restore(&mut self) {
+        self.data.archived = false;
+    }
+
+    /**
+    Check the customer still has the version a command expects.
+
+    If no version is expected then any version is accepted.
+    */
+    pub fn check_version(&self, expected: Option<CustomerVersion>) -> Result<(), Error> {
+        match expected {
+            Some(expected) if expected != self.data.version => {
+                Err(error::stale("customer has been changed since it was read"))
+            }
+            _ => Ok(()),
+        }
+    }
 }
 
 impl Entity for Customer {
@@ -57,9 +93,3 @@ impl Entity for Customer {
     type Data = CustomerData;
     type Error = Error;
 }
-
-impl Resolver {
-    pub fn customer_id(&self) -> impl IdProvider<CustomerData> {
-        NextId::<CustomerData>::new()
-    }
-}
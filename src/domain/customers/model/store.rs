@@ -15,6 +15,18 @@ pub(in crate::domain) trait CustomerStore {
     fn set_customer(&self, transaction: &Transaction, customer: Customer) -> Result<(), Error>;
 }
 
+/**
+An additional store for fetching multiple customer records at a time.
+
+Like `ProductStoreFilter`, this trait is an implementation detail that will probably need to be
+refactored when we add a proper database.
+*/
+#[auto_impl(&, Arc)]
+pub(in crate::domain) trait CustomerStoreFilter {
+    fn get_customers(&self, ids: &[CustomerId]) -> Result<Vec<Customer>, Error>;
+}
+
+/** A test in-memory customer store. */
 pub(in crate::domain) struct InMemoryStore(TransactionValueStore<CustomerData>);
 
 impl CustomerStore for InMemoryStore {
@@ -36,7 +48,7 @@ impl CustomerStore for InMemoryStore {
             transaction,
             id,
             Some(data.version),
-            data.version.next(),
+            data.version.next()?,
             data,
         )?;
 
@@ -44,6 +56,23 @@ impl CustomerStore for InMemoryStore {
     }
 }
 
+impl CustomerStoreFilter for InMemoryStore {
+    fn get_customers(&self, ids: &[CustomerId]) -> Result<Vec<Customer>, Error> {
+        let customers = self
+            .0
+            .get_all(|c| ids.contains(&c.id))
+            .map(|(_, data)| Customer::from_data(data))
+            .collect();
+
+        Ok(customers)
+    }
+}
+
+/**
+Create an in-memory customer store.
+
+The store will participate in transactions tracked by the given transaction store.
+*/
 pub(in crate::domain) fn in_memory_store(transaction_store: TransactionStore) -> InMemoryStore {
     InMemoryStore(TransactionValueStore::new(transaction_store))
 }
@@ -26,6 +26,11 @@ impl CustomerBuilder {
         self
     }
 
+    pub fn archived(mut self) -> Self {
+        self.customer.archive();
+        self
+    }
+
     pub fn build(self) -> Customer {
         self.customer
     }
@@ -6,29 +6,51 @@ use crate::domain::{
     infra::*,
 };
 
-/** Input for a `GetCustomerQuery`. */
+/**
+Input for a `GetCustomerQuery`.
+
+Archived customers aren't returned unless `include_archived` is set.
+*/
 #[derive(Serialize, Deserialize)]
 pub struct GetCustomer {
     pub id: CustomerId,
+    #[serde(default)]
+    pub include_archived: bool,
 }
 
 impl QueryArgs for GetCustomer {
     type Output = Result<Option<Customer>, Error>;
 }
 
-async fn execute(query: GetCustomer, store: impl CustomerStore) -> Result<Option<Customer>, Error> {
-    let customer = store.get_customer(query.id)?;
+/**
+Default implementation for a `GetCustomerQuery`.
+
+Customers fetched concurrently within the same transaction are loaded in a single batch.
+*/
+async fn execute(
+    query: GetCustomer,
+    loader: BatchLoader<CustomerId, Customer>,
+    store: impl CustomerStoreFilter,
+) -> Result<Option<Customer>, Error> {
+    let customer = loader
+        .load(query.id, |ids| {
+            let customers = store.get_customers(ids)?;
+
+            Ok(customers.into_iter().map(|c| (c.to_data().id, c)))
+        })
+        .await?;
 
-    Ok(customer)
+    Ok(customer.filter(|c| query.include_archived || !c.to_data().archived))
 }
 
 impl Resolver {
     /** Get a customer. */
     pub fn get_customer_query(&self) -> impl Query<GetCustomer> {
         self.query(|resolver, query: GetCustomer| async move {
-            let store = resolver.customer_store();
+            let loader = resolver.batch_loader();
+            let store = resolver.customer_store_filter();
 
-            execute(query, store).await
+            execute(query, loader, store).await
         })
     }
 }
@@ -1,5 +1,7 @@
 /*! Contains the `GetCustomerWithOrdersQuery` type. */
 
+use futures::future;
+
 use crate::domain::{
     Error,
     customers::*,
@@ -7,23 +9,40 @@ use crate::domain::{
     orders::*,
 };
 
-/** Input for a `GetCustomerWithOrdersQuery`. */
+/**
+Input for a `GetCustomerWithOrdersQuery`.
+
+Archived customers aren't returned unless `include_archived` is set.
+*/
 #[derive(Serialize, Deserialize)]
 pub struct GetCustomerWithOrders {
     pub id: CustomerId,
+    #[serde(default)]
+    pub include_archived: bool,
 }
 
 /** An order with a order summary for each of its line items. */
 #[derive(Serialize)]
 pub struct CustomerWithOrders {
     pub id: CustomerId,
+    pub version: CustomerVersion,
+    pub archived: bool,
     pub orders: Vec<CustomerOrder>,
 }
 
-/** An individual order. */
+/**
+An individual order.
+
+The subtotal, discount and total will be `None` if the order doesn't have any line items.
+The tax will be `None` if the order also doesn't have a tax region, or there are no tax rates for it.
+*/
 #[derive(Serialize)]
 pub struct CustomerOrder {
     pub id: OrderId,
+    pub subtotal: Option<Currency>,
+    pub discount: Option<Currency>,
+    pub tax: Option<Currency>,
+    pub total: Option<Currency>,
 }
 
 impl QueryArgs for GetCustomerWithOrders {
@@ -32,23 +51,37 @@ impl QueryArgs for GetCustomerWithOrders {
 
 async fn execute(
     query: GetCustomerWithOrders,
-    store: impl CustomerStore,
+    customer_query: impl Query<GetCustomer>,
     orders_query: impl Query<GetOrderSummariesForCustomer>,
 ) -> Result<Option<CustomerWithOrders>, Error> {
-    let customer = match store.get_customer(query.id)? {
-        Some(customer) => customer.into_data(),
-        None => return Ok(None),
+    let (customer, orders) = future::try_join(
+        customer_query.execute(GetCustomer {
+            id: query.id,
+            include_archived: query.include_archived,
+        }),
+        orders_query.execute(GetOrderSummariesForCustomer { id: query.id }),
+    )
+    .await?;
+
+    let Some(customer) = customer else {
+        return Ok(None);
     };
 
-    let orders = orders_query
-        .execute(GetOrderSummariesForCustomer { id: query.id })
-        .await?;
+    let customer = customer.into_data();
 
     Ok(Some(CustomerWithOrders {
         id: customer.id,
+        version: customer.version,
+        archived: customer.archived,
         orders: orders
             .into_iter()
-            .map(|order| CustomerOrder { id: order.id })
+            .map(|order| CustomerOrder {
+                id: order.id,
+                subtotal: order.subtotal,
+                discount: order.discount,
+                tax: order.tax,
+                total: order.total,
+            })
             .collect(),
     }))
 }
@@ -57,10 +90,10 @@ impl Resolver {
     /** Get a customer along with all of their orders. */
     pub fn get_customer_with_orders_query(&self) -> impl Query<GetCustomerWithOrders> {
         self.query(|resolver, query: GetCustomerWithOrders| async move {
-            let store = resolver.customer_store();
+            let customer_query = resolver.get_customer_query();
             let orders_query = resolver.get_order_summaries_for_customer_query();
 
-            execute(query, store, orders_query).await
+            execute(query, customer_query, orders_query).await
         })
     }
 }
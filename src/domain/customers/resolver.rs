@@ -3,10 +3,14 @@
 use std::sync::Arc;
 
 use crate::domain::{
-    customers::model::store::{
-        self,
-        CustomerStore,
-        InMemoryStore,
+    customers::{
+        CustomerData,
+        model::store::{
+            self,
+            CustomerStore,
+            CustomerStoreFilter,
+            InMemoryStore,
+        },
     },
     infra::*,
 };
@@ -19,20 +23,44 @@ The `CustomersResolver` type wraps private implementation details and exposes th
 #[derive(Clone)]
 pub(in crate::domain) struct CustomersResolver {
     customer_store: Register<Arc<InMemoryStore>>,
+    customer_id: Register<Arc<dyn IdProvider<CustomerData> + Send + Sync>>,
 }
 
 impl Default for CustomersResolver {
     fn default() -> Self {
         CustomersResolver {
-            customer_store: Register::once(|resolver| {
+            customer_store: Register::per_tenant(|resolver| {
                 Arc::new(store::in_memory_store(resolver.transaction_store()))
             }),
+            customer_id: Register::once(|_| {
+                Arc::new(NextId::<CustomerData>::new())
+                    as Arc<dyn IdProvider<CustomerData> + Send + Sync>
+            }),
         }
     }
 }
 
+impl AppBuilder {
+    /** Use a different source of ids for new customers. */
+    pub fn customer_id(
+        mut self,
+        customer_id: Register<Arc<dyn IdProvider<CustomerData> + Send + Sync>>,
+    ) -> Self {
+        self.root_resolver.customers_resolver.customer_id = customer_id;
+        self
+    }
+}
+
 impl Resolver {
+    pub fn customer_id(&self) -> impl IdProvider<CustomerData> {
+        self.resolve(&self.customers_resolver.customer_id)
+    }
+
     pub(in crate::domain::customers) fn customer_store(&self) -> impl CustomerStore {
         self.resolve(&self.customers_resolver.customer_store)
     }
+
+    pub(in crate::domain::customers) fn customer_store_filter(&self) -> impl CustomerStoreFilter {
+        self.resolve(&self.customers_resolver.customer_store)
+    }
 }
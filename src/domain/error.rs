@@ -3,6 +3,8 @@ use std::{
     fmt,
 };
 
+use crate::store;
+
 /**
 The main error type.
 
@@ -28,10 +30,20 @@ impl fmt::Display for Error {
 /**
 The kind of an error captured.
 */
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ErrorKind {
     /** A command or query was given bad input. */
     BadInput,
+    /** An entity a command or query depends on doesn't exist. */
+    NotFound,
+    /** A command conflicts with the current state of an entity. */
+    Conflict,
+    /** A command expected a version of an entity that's since been changed. */
+    Stale,
+    /** A command or query isn't allowed. */
+    Forbidden,
+    /** A command or query can't be executed right now, but may succeed if retried. */
+    Unavailable,
     /** Some other kind of error. */
     Other,
 }
@@ -73,7 +85,81 @@ pub fn bad_input(msg: impl fmt::Display) -> Error {
     }
 }
 
+/**
+Create an error for a missing entity.
+
+This message may make its way to end-users so it should be friendly.
+*/
+pub fn not_found(msg: impl fmt::Display) -> Error {
+    Error {
+        kind: ErrorKind::NotFound,
+        inner: msg.to_string().into(),
+    }
+}
+
+/**
+Create an error for a change that conflicts with the current state of an entity.
+
+This message may make its way to end-users so it should be friendly.
+*/
+pub fn conflict(msg: impl fmt::Display) -> Error {
+    Error {
+        kind: ErrorKind::Conflict,
+        inner: msg.to_string().into(),
+    }
+}
+
+/**
+Create an error for a command that expected a version of an entity that's since been changed.
+
+This message may make its way to end-users so it should be friendly.
+*/
+pub fn stale(msg: impl fmt::Display) -> Error {
+    Error {
+        kind: ErrorKind::Stale,
+        inner: msg.to_string().into(),
+    }
+}
+
+/**
+Create an error for a command or query that isn't allowed.
+
+This message may make its way to end-users so it should be friendly.
+*/
+pub fn forbidden(msg: impl fmt::Display) -> Error {
+    Error {
+        kind: ErrorKind::Forbidden,
+        inner: msg.to_string().into(),
+    }
+}
+
+/**
+Create an error for a command or query that can't be executed right now.
+
+This message may make its way to end-users so it should be friendly.
+*/
+pub fn unavailable(msg: impl fmt::Display) -> Error {
+    Error {
+        kind: ErrorKind::Unavailable,
+        inner: msg.to_string().into(),
+    }
+}
+
 impl Error {
+    /**
+    Get the kind of error this is.
+    */
+    pub fn kind(&self) -> ErrorKind {
+        self.kind
+    }
+
+    /**
+    Change the kind of error this is.
+    */
+    pub(crate) fn with_kind(self, kind: ErrorKind) -> Self {
+        Error { kind, ..self }
+    }
+
     /**
     Split an error into its kind and value.
     */
@@ -87,9 +173,15 @@ where
     E: Into<Box<dyn error::Error + Send + Sync>>,
 {
     fn from(err: E) -> Error {
-        Error {
-            kind: ErrorKind::Other,
-            inner: err.into(),
-        }
+        let inner = err.into();
+
+        // Failed optimistic concurrency checks mean the entity was changed by someone else
+        let kind = if inner.is::<store::VersionMismatch>() {
+            ErrorKind::Conflict
+        } else {
+            ErrorKind::Other
+        };
+
+        Error { kind, inner }
     }
 }
@@ -0,0 +1,5 @@
+/*! Commands for modifying exchange rate state. */
+
+mod set_exchange_rates;
+
+pub use self::set_exchange_rates::*;
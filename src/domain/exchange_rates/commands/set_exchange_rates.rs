@@ -0,0 +1,102 @@
+/*! Contains the `SetExchangeRatesCommand` type. */
+
+use std::collections::BTreeMap;
+
+use crate::domain::{
+    Error,
+    exchange_rates::*,
+    infra::*,
+};
+
+/**
+Input for a `SetExchangeRatesCommand`.
+
+Rates are the value of one unit of the base currency in each other currency.
+*/
+#[derive(Clone, Serialize, Deserialize)]
+pub struct SetExchangeRates {
+    pub base: CurrencyCode,
+    pub rates: BTreeMap<CurrencyCode, ExchangeRate>,
+}
+
+impl CommandArgs for SetExchangeRates {
+    type Output = Result<(), Error>;
+}
+
+/** Default implementation for a `SetExchangeRatesCommand`. */
+async fn execute(
+    command: SetExchangeRates,
+    transaction: ActiveTransaction,
+    store: impl ExchangeRatesStore,
+) -> Result<(), Error> {
+    let exchange_rates = {
+        if let Some(mut exchange_rates) = store.get_exchange_rates()? {
+            exchange_rates.set_rates(command.base, command.rates)?;
+
+            exchange_rates
+        } else {
+            ExchangeRates::new(command.base, command.rates)?
+        }
+    };
+
+    store.set_exchange_rates(transaction.get(), exchange_rates)?;
+
+    Ok(())
+}
+
+impl Resolver {
+    /** Replace the exchange rate table. */
+    pub fn set_exchange_rates_command(&self) -> impl Command<SetExchangeRates> {
+        self.command(|resolver, command: SetExchangeRates| async move {
+            let store = resolver.exchange_rates_store();
+            let active_transaction = resolver.active_transaction();
+
+            execute(command, active_transaction, store).await
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::domain::exchange_rates::model::store::in_memory_store;
+
+    fn set(base: CurrencyCode, rates: &[(CurrencyCode, &str)]) -> SetExchangeRates {
+        SetExchangeRates {
+            base,
+            rates: rates
+                .iter()
+                .map(|(code, rate)| (*code, rate.parse().unwrap()))
+                .collect(),
+        }
+    }
+
+    #[tokio::test]
+    async fn set_exchange_rates() {
+        let store = in_memory_store(Default::default());
+
+        execute(
+            set(CurrencyCode::USD, &[(CurrencyCode::EUR, "0.9")]),
+            ActiveTransaction::none(),
+            &store,
+        )
+        .await
+        .unwrap();
+
+        // Setting the rates again replaces the existing table
+        execute(
+            set(CurrencyCode::EUR, &[(CurrencyCode::GBP, "0.85")]),
+            ActiveTransaction::none(),
+            &store,
+        )
+        .await
+        .unwrap();
+
+        let table = store.get_exchange_rates().unwrap().unwrap();
+
+        assert_eq!(CurrencyCode::EUR, table.to_data().base);
+        assert!(table.rate(CurrencyCode::USD).is_none());
+        assert_eq!(Some("0.85".parse().unwrap()), table.rate(CurrencyCode::GBP));
+    }
+}
@@ -0,0 +1,422 @@
+/*! Contains the `ExchangeRates` entity. */
+
+use std::{
+    collections::BTreeMap,
+    convert::TryFrom,
+    fmt,
+    str::FromStr,
+};
+
+use serde::{
+    de::{
+        self,
+        Deserialize,
+        Deserializer,
+    },
+    ser::{
+        Serialize,
+        Serializer,
+    },
+};
+
+pub mod store;
+
+use crate::domain::{
+    Error,
+    error,
+    infra::*,
+};
+
+pub type ExchangeRatesVersion = Version<ExchangeRatesData>;
+
+// The most decimal places a rate can have
+const MAX_SCALE: u32 = 9;
+
+/**
+An exchange rate.
+
+Rates are exact decimal numbers, like `0.9215`, rather than floating point numbers so
+conversions are repeatable. Rates serialize as strings to keep that precision in JSON.
+A rate must be greater than zero and have no more than 9 decimal places.
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExchangeRate {
+    value: u64,
+    scale: u32,
+}
+
+impl ExchangeRate {
+    /** The rate between a currency and itself. */
+    pub const ONE: ExchangeRate = ExchangeRate { value: 1, scale: 0 };
+
+    /**
+    Create a rate from a whole number and a number of decimal places.
+
+    `ExchangeRate::new(9215, 4)` is the rate `0.9215`.
+    */
+    pub fn new(value: u64, scale: u32) -> Result<Self, Error> {
+        if value == 0 {
+            return Err(error::bad_input("exchange rates must be greater than zero"));
+        }
+
+        if scale > MAX_SCALE {
+            return Err(error::bad_input(format_args!(
+                "exchange rates can't have more than {} decimal places",
+                MAX_SCALE
+            )));
+        }
+
+        Ok(ExchangeRate { value, scale })
+    }
+
+    fn is_one(&self) -> bool {
+        10u64.checked_pow(self.scale) == Some(self.value)
+    }
+}
+
+impl fmt::Display for ExchangeRate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let scale = self.scale as usize;
+        let digits = format!("{:0>width$}", self.value, width = scale + 1);
+        let (whole, fraction) = digits.split_at(digits.len() - scale);
+
+        if fraction.is_empty() {
+            f.write_str(whole)
+        } else {
+            write!(f, "{}.{}", whole, fraction)
+        }
+    }
+}
+
+impl FromStr for ExchangeRate {
+    type Err = Error;
+
+    fn from_str(rate: &str) -> Result<Self, Self::Err> {
+        let invalid = || error::bad_input(format_args!("`{}` is not a valid exchange rate", rate));
+
+        let (whole, fraction) = match rate.split_once('.') {
+            Some((_, "")) => return Err(invalid()),
+            Some(parts) => parts,
+            None => (rate, ""),
+        };
+
+        if whole.is_empty()
+            || !whole.bytes().all(|b| b.is_ascii_digit())
+            || !fraction.bytes().all(|b| b.is_ascii_digit())
+        {
+            return Err(invalid());
+        }
+
+        let value = format!("{}{}", whole, fraction)
+            .parse()
+            .map_err(|_| invalid())?;
+
+        ExchangeRate::new(value, fraction.len() as u32)
+    }
+}
+
+impl<'a> TryFrom<&'a str> for ExchangeRate {
+    type Error = Error;
+
+    fn try_from(rate: &'a str) -> Result<Self, Self::Error> {
+        rate.parse()
+    }
+}
+
+impl Serialize for ExchangeRate {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for ExchangeRate {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let rate = String::deserialize(deserializer)?;
+
+        rate.parse().map_err(de::Error::custom)
+    }
+}
+
+/**
+Data for an exchange rate table.
+
+Rates are the value of one unit of the base currency in some other currency.
+The base currency always has a rate of `1`.
+*/
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ExchangeRatesData {
+    pub version: ExchangeRatesVersion,
+    pub base: CurrencyCode,
+    pub rates: BTreeMap<CurrencyCode, ExchangeRate>,
+    _private: (),
+}
+
+/**
+A table of exchange rates.
+
+There's only a single exchange rate table, so it doesn't have an id.
+*/
+pub struct ExchangeRates {
+    data: ExchangeRatesData,
+}
+
+impl ExchangeRates {
+    pub(self) fn from_data(data: ExchangeRatesData) -> Self {
+        ExchangeRates { data }
+    }
+
+    pub fn into_data(self) -> ExchangeRatesData {
+        self.data
+    }
+
+    pub fn to_data(&self) -> &ExchangeRatesData {
+        &self.data
+    }
+
+    pub fn new(
+        base: CurrencyCode,
+        rates: impl IntoIterator<Item = (CurrencyCode, ExchangeRate)>,
+    ) -> Result<Self, Error> {
+        let mut table = ExchangeRates::from_data(ExchangeRatesData {
+            version: ExchangeRatesVersion::default(),
+            base,
+            rates: BTreeMap::new(),
+            _private: (),
+        });
+
+        table.set_rates(base, rates)?;
+
+        Ok(table)
+    }
+
+    /** Replace all rates in the table. */
+    pub fn set_rates(
+        &mut self,
+        base: CurrencyCode,
+        rates: impl IntoIterator<Item = (CurrencyCode, ExchangeRate)>,
+    ) -> Result<(), Error> {
+        let mut rates: BTreeMap<_, _> = rates.into_iter().collect();
+
+        match rates.get(&base) {
+            Some(rate) if !rate.is_one() => {
+                return Err(error::bad_input(format_args!(
+                    "the base currency {} must have a rate of 1",
+                    base
+                )));
+            }
+            _ => {
+                rates.insert(base, ExchangeRate::ONE);
+            }
+        }
+
+        self.data.base = base;
+        self.data.rates = rates;
+
+        Ok(())
+    }
+
+    /** Get the rate for a currency relative to the base currency. */
+    pub fn rate(&self, code: CurrencyCode) -> Option<ExchangeRate> {
+        self.data.rates.get(&code).copied()
+    }
+
+    /**
+    Convert a currency value into another currency.
+
+    The value is converted through the base currency in a single step and only rounded once
+    at the end, into the minor units of the target currency.
+    */
+    pub fn convert(
+        &self,
+        amount: Currency,
+        to: CurrencyCode,
+        rounding: Rounding,
+    ) -> Result<Currency, Error> {
+        let from = amount.code();
+
+        if from == to {
+            return Ok(amount);
+        }
+
+        let rate = |code| {
+            self.rate(code).ok_or_else(|| {
+                error::unavailable(format_args!("there's no exchange rate for {}", code))
+            })
+        };
+
+        let from_rate = rate(from)?;
+        let to_rate = rate(to)?;
+
+        // units_to = units_from * (to_rate / from_rate) * 10^(to_exp - from_exp)
+        let too_large = || error::bad_input("converted currency value is too large");
+
+        let numerator = u128::from(amount.minor_units())
+            .checked_mul(u128::from(to_rate.value))
+            .and_then(|n| n.checked_mul(10u128.checked_pow(from_rate.scale + to.exponent())?))
+            .ok_or_else(too_large)?;
+
+        let denominator = 10u128
+            .checked_pow(to_rate.scale + from.exponent())
+            .and_then(|d| d.checked_mul(u128::from(from_rate.value)))
+            .ok_or_else(too_large)?;
+
+        let units =
+            u64::try_from(rounding.divide(numerator, denominator)).map_err(|_| too_large())?;
+
+        Ok(Currency::from_minor_units(to, units))
+    }
+}
+
+impl Entity for ExchangeRates {
+    type Id = ();
+    type Version = ExchangeRatesVersion;
+    type Data = ExchangeRatesData;
+    type Error = Error;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::domain::ErrorKind;
+
+    fn rate(rate: &str) -> ExchangeRate {
+        rate.parse().unwrap()
+    }
+
+    fn table() -> ExchangeRates {
+        ExchangeRates::new(
+            CurrencyCode::USD,
+            [
+                (CurrencyCode::EUR, rate("0.9")),
+                (CurrencyCode::JPY, rate("150.25")),
+                (CurrencyCode::GBP, rate("0.8")),
+            ],
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn parse_rate() {
+        for (input, value, scale) in [("1", 1, 0), ("0.9215", 9215, 4), ("150.25", 15025, 2)] {
+            let parsed = rate(input);
+
+            assert_eq!(ExchangeRate::new(value, scale).unwrap(), parsed);
+            assert_eq!(input, parsed.to_string());
+        }
+
+        for input in [
+            "",
+            "0",
+            "0.000",
+            "-1",
+            "1.",
+            ".5",
+            "1.2.3",
+            "1e5",
+            "0.0000000001",
+        ] {
+            assert!(input.parse::<ExchangeRate>().is_err(), "{}", input);
+        }
+    }
+
+    #[test]
+    fn rate_serde_roundtrip() {
+        let json = serde_json::to_string(&rate("0.0925")).unwrap();
+
+        assert_eq!(r#""0.0925""#, json);
+        assert_eq!(rate("0.0925"), serde_json::from_str(&json).unwrap());
+    }
+
+    #[test]
+    fn base_rate_must_be_one() {
+        assert!(ExchangeRates::new(CurrencyCode::USD, [(CurrencyCode::USD, rate("2"))]).is_err());
+
+        let table = ExchangeRates::new(CurrencyCode::USD, []).unwrap();
+
+        assert_eq!(Some(ExchangeRate::ONE), table.rate(CurrencyCode::USD));
+    }
+
+    #[test]
+    fn convert() {
+        let table = table();
+
+        for (amount, to, expected) in [
+            // From the base currency
+            (Currency::usd(1000), CurrencyCode::EUR, Currency::eur(900)),
+            // To the base currency
+            (Currency::eur(900), CurrencyCode::USD, Currency::usd(1000)),
+            // Between two non-base currencies
+            (Currency::eur(900), CurrencyCode::GBP, Currency::gbp(800)),
+            // Between currencies with different exponents
+            (Currency::usd(1000), CurrencyCode::JPY, Currency::jpy(1502)),
+            (
+                Currency::jpy(15025),
+                CurrencyCode::USD,
+                Currency::usd(10000),
+            ),
+            // To the same currency
+            (Currency::aud(123), CurrencyCode::AUD, Currency::aud(123)),
+        ] {
+            assert_eq!(
+                expected,
+                table.convert(amount, to, Rounding::HalfEven).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn convert_rounding() {
+        let table =
+            ExchangeRates::new(CurrencyCode::USD, [(CurrencyCode::EUR, rate("0.5"))]).unwrap();
+
+        for (cents, rounding, expected) in [
+            (1, Rounding::HalfEven, 0),
+            (3, Rounding::HalfEven, 2),
+            (1, Rounding::HalfUp, 1),
+            (3, Rounding::HalfUp, 2),
+            (3, Rounding::Down, 1),
+            (3, Rounding::Up, 2),
+            (4, Rounding::Up, 2),
+        ] {
+            assert_eq!(
+                Currency::eur(expected),
+                table
+                    .convert(Currency::usd(cents), CurrencyCode::EUR, rounding)
+                    .unwrap(),
+                "{} {:?}",
+                cents,
+                rounding
+            );
+        }
+    }
+
+    #[test]
+    fn convert_missing_rate() {
+        let err = table()
+            .convert(Currency::usd(100), CurrencyCode::AUD, Rounding::HalfEven)
+            .err()
+            .unwrap();
+
+        assert_eq!(ErrorKind::Unavailable, err.kind());
+    }
+
+    #[test]
+    fn convert_overflow() {
+        assert!(
+            table()
+                .convert(
+                    Currency::usd(u64::MAX),
+                    CurrencyCode::JPY,
+                    Rounding::HalfEven
+                )
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,123 @@
+/*! Persistent storage for exchange rates. */
+
+use uuid::Uuid;
+
+use crate::{
+    domain::{
+        Error,
+        exchange_rates::*,
+    },
+    store::{
+        self,
+        Transaction,
+        TransactionStore,
+        TransactionValueStore,
+    },
+};
+
+/* A place to persist and fetch the exchange rate table. */
+#[auto_impl(&, Arc)]
+pub(in crate::domain) trait ExchangeRatesStore {
+    fn get_exchange_rates(&self) -> Result<Option<ExchangeRates>, Error>;
+    fn set_exchange_rates(
+        &self,
+        transaction: &Transaction,
+        exchange_rates: ExchangeRates,
+    ) -> Result<(), Error>;
+}
+
+/** A test in-memory exchange rate store. */
+pub(in crate::domain) struct InMemoryStore(TransactionValueStore<ExchangeRatesData>);
+
+// The storage id of the single exchange rate table
+const TABLE_ID: Uuid = Uuid::from_u128(0x8e2b_61d4_07a3_4c9f_b5e1_3d70_9c2a_f6b8);
+
+impl ExchangeRatesStore for InMemoryStore {
+    fn get_exchange_rates(&self) -> Result<Option<ExchangeRates>, Error> {
+        if let Some((version, data)) = self.0.get(store::Id::from_raw(TABLE_ID)) {
+            assert_eq!(version, data.version.into());
+
+            Ok(Some(ExchangeRates::from_data(data)))
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn set_exchange_rates(
+        &self,
+        transaction: &Transaction,
+        exchange_rates: ExchangeRates,
+    ) -> Result<(), Error> {
+        let mut data = exchange_rates.into_data();
+
+        self.0.set(
+            transaction,
+            store::Id::from_raw(TABLE_ID),
+            Some(data.version),
+            data.version.next()?,
+            data,
+        )?;
+
+        Ok(())
+    }
+}
+
+/**
+Create an in-memory exchange rate store.
+
+The store will participate in transactions tracked by the given transaction store.
+*/
+pub(in crate::domain) fn in_memory_store(transaction_store: TransactionStore) -> InMemoryStore {
+    InMemoryStore(TransactionValueStore::new(transaction_store))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::domain::{
+        ErrorKind,
+        infra::*,
+    };
+
+    #[test]
+    fn test_in_memory_store() {
+        let store = in_memory_store(Default::default());
+
+        assert!(store.get_exchange_rates().unwrap().is_none());
+
+        let table = ExchangeRates::new(
+            CurrencyCode::USD,
+            [(CurrencyCode::EUR, "0.9".parse().unwrap())],
+        )
+        .unwrap();
+        store
+            .set_exchange_rates(&Transaction::none(), table)
+            .unwrap();
+
+        let found = store.get_exchange_rates().unwrap().unwrap();
+        assert_eq!(CurrencyCode::USD, found.to_data().base);
+    }
+
+    #[test]
+    fn set_new_table_twice_fails_concurrency_check() {
+        let store = in_memory_store(Default::default());
+
+        store
+            .set_exchange_rates(
+                &Transaction::none(),
+                ExchangeRates::new(CurrencyCode::USD, []).unwrap(),
+            )
+            .unwrap();
+
+        let err = store
+            .set_exchange_rates(
+                &Transaction::none(),
+                ExchangeRates::new(CurrencyCode::EUR, []).unwrap(),
+            )
+            .err()
+            .unwrap();
+
+        assert_eq!(ErrorKind::Conflict, err.kind());
+    }
+}
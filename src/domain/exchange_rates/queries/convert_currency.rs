@@ -0,0 +1,130 @@
+/*! Contains the `ConvertCurrencyQuery` type. */
+
+use crate::domain::{
+    Error,
+    error,
+    exchange_rates::*,
+    infra::*,
+};
+
+/**
+Input for a `ConvertCurrencyQuery`.
+
+Values are converted using the current exchange rate table and rounded using the
+rounding rule registered with the app.
+*/
+#[derive(Serialize, Deserialize)]
+pub struct ConvertCurrency {
+    pub amount: Currency,
+    pub to: CurrencyCode,
+}
+
+impl QueryArgs for ConvertCurrency {
+    type Output = Result<Currency, Error>;
+}
+
+/** Default implementation for a `ConvertCurrencyQuery`. */
+async fn execute(
+    query: ConvertCurrency,
+    store: impl ExchangeRatesStore,
+    rounding: Rounding,
+) -> Result<Currency, Error> {
+    if query.amount.code() == query.to {
+        return Ok(query.amount);
+    }
+
+    let Some(exchange_rates) = store.get_exchange_rates()? else {
+        return Err(error::unavailable("exchange rates haven't been set"));
+    };
+
+    exchange_rates.convert(query.amount, query.to, rounding)
+}
+
+impl Resolver {
+    /** Convert a currency value into another currency. */
+    pub fn convert_currency_query(&self) -> impl Query<ConvertCurrency> {
+        self.query(|resolver, query: ConvertCurrency| async move {
+            let store = resolver.exchange_rates_store();
+            let rounding = resolver.currency_rounding();
+
+            execute(query, store, rounding).await
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::{
+        domain::{
+            ErrorKind,
+            exchange_rates::model::store::in_memory_store,
+        },
+        store::Transaction,
+    };
+
+    #[tokio::test]
+    async fn convert_without_rates() {
+        let store = in_memory_store(Default::default());
+
+        // Converting to the same currency doesn't need any rates
+        let same = execute(
+            ConvertCurrency {
+                amount: Currency::usd(100),
+                to: CurrencyCode::USD,
+            },
+            &store,
+            Rounding::HalfEven,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(Currency::usd(100), same);
+
+        let err = execute(
+            ConvertCurrency {
+                amount: Currency::usd(100),
+                to: CurrencyCode::EUR,
+            },
+            &store,
+            Rounding::HalfEven,
+        )
+        .await
+        .err()
+        .unwrap();
+
+        assert_eq!(ErrorKind::Unavailable, err.kind());
+    }
+
+    #[tokio::test]
+    async fn convert_with_rounding() {
+        let store = in_memory_store(Default::default());
+
+        store
+            .set_exchange_rates(
+                &Transaction::none(),
+                ExchangeRates::new(
+                    CurrencyCode::USD,
+                    [(CurrencyCode::EUR, "0.925".parse().unwrap())],
+                )
+                .unwrap(),
+            )
+            .unwrap();
+
+        for (rounding, expected) in [(Rounding::Down, 92), (Rounding::Up, 93)] {
+            let converted = execute(
+                ConvertCurrency {
+                    amount: Currency::usd(100),
+                    to: CurrencyCode::EUR,
+                },
+                &store,
+                rounding,
+            )
+            .await
+            .unwrap();
+
+            assert_eq!(Currency::eur(expected), converted);
+        }
+    }
+}
@@ -0,0 +1,34 @@
+/*! Contains the `GetExchangeRatesQuery` type. */
+
+use crate::domain::{
+    Error,
+    exchange_rates::*,
+    infra::*,
+};
+
+/** Input for a `GetExchangeRatesQuery`. */
+#[derive(Serialize, Deserialize)]
+pub struct GetExchangeRates {}
+
+impl QueryArgs for GetExchangeRates {
+    type Output = Result<Option<ExchangeRates>, Error>;
+}
+
+/** Default implementation for a `GetExchangeRatesQuery`. */
+async fn execute(
+    _: GetExchangeRates,
+    store: impl ExchangeRatesStore,
+) -> Result<Option<ExchangeRates>, Error> {
+    store.get_exchange_rates()
+}
+
+impl Resolver {
+    /** Get the exchange rate table. */
+    pub fn get_exchange_rates_query(&self) -> impl Query<GetExchangeRates> {
+        self.query(|resolver, query: GetExchangeRates| async move {
+            let store = resolver.exchange_rates_store();
+
+            execute(query, store).await
+        })
+    }
+}
@@ -0,0 +1,9 @@
+/*! Queries for fetching exchange rate state. */
+
+mod convert_currency;
+mod get_exchange_rates;
+
+pub use self::{
+    convert_currency::*,
+    get_exchange_rates::*,
+};
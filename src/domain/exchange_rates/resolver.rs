@@ -0,0 +1,54 @@
+/*! Contains the `ExchangeRatesResolver` type. */
+
+use std::sync::Arc;
+
+use crate::domain::{
+    exchange_rates::model::store::{
+        self,
+        ExchangeRatesStore,
+        InMemoryStore,
+    },
+    infra::*,
+};
+
+/**
+Resolver for exchange rates.
+
+The `ExchangeRatesResolver` type wraps private implementation details and exposes them as traits within the `exchange_rates` module.
+*/
+#[derive(Clone)]
+pub(in crate::domain) struct ExchangeRatesResolver {
+    exchange_rates_store: Register<Arc<InMemoryStore>>,
+    rounding: Register<Rounding>,
+}
+
+impl Default for ExchangeRatesResolver {
+    fn default() -> Self {
+        ExchangeRatesResolver {
+            exchange_rates_store: Register::per_tenant(|resolver| {
+                Arc::new(store::in_memory_store(resolver.transaction_store()))
+            }),
+            rounding: Register::once(|_| Rounding::default()),
+        }
+    }
+}
+
+impl AppBuilder {
+    /** Use a different rule for rounding currency values converted using exchange rates. */
+    pub fn currency_rounding(mut self, rounding: Register<Rounding>) -> Self {
+        self.root_resolver.exchange_rates_resolver.rounding = rounding;
+        self
+    }
+}
+
+impl Resolver {
+    pub(in crate::domain::exchange_rates) fn exchange_rates_store(
+        &self,
+    ) -> impl ExchangeRatesStore {
+        self.resolve(&self.exchange_rates_resolver.exchange_rates_store)
+    }
+
+    pub(in crate::domain::exchange_rates) fn currency_rounding(&self) -> Rounding {
+        self.resolve(&self.exchange_rates_resolver.rounding)
+    }
+}
@@ -0,0 +1,83 @@
+/*! Contains the `Actor` type. */
+
+use std::{
+    convert::TryFrom,
+    fmt,
+    str::FromStr,
+};
+
+use crate::domain::{
+    Error,
+    error,
+};
+
+/**
+Whoever is executing commands, like a user name or a service.
+
+Actors are between 1 and 128 characters, and can't contain control characters.
+*/
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+pub struct Actor(String);
+
+impl Actor {
+    /** The actor for commands the app executes by itself, like running jobs or resuming sagas. */
+    pub fn system() -> Self {
+        Actor("system".to_owned())
+    }
+
+    /** The actor for commands executed by callers that didn't identify themselves. */
+    pub fn anonymous() -> Self {
+        Actor("anonymous".to_owned())
+    }
+}
+
+impl fmt::Display for Actor {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl TryFrom<String> for Actor {
+    type Error = Error;
+
+    fn try_from(actor: String) -> Result<Self, Self::Error> {
+        if actor.is_empty() || actor.chars().count() > 128 || actor.chars().any(char::is_control) {
+            return Err(error::bad_input("actor is not valid"));
+        }
+
+        Ok(Actor(actor))
+    }
+}
+
+impl<'a> TryFrom<&'a str> for Actor {
+    type Error = Error;
+
+    fn try_from(actor: &'a str) -> Result<Self, Self::Error> {
+        Self::try_from(actor.to_owned())
+    }
+}
+
+impl FromStr for Actor {
+    type Err = Error;
+
+    fn from_str(actor: &str) -> Result<Self, Self::Err> {
+        Self::try_from(actor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn actor_must_be_valid() {
+        for actor in ["", "an\nactor", &"a".repeat(129)] {
+            assert!(Actor::try_from(actor).is_err());
+        }
+
+        assert_eq!(
+            "jo@example.com",
+            Actor::try_from("jo@example.com").unwrap().to_string()
+        );
+    }
+}
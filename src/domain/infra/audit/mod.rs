@@ -0,0 +1,27 @@
+/*!
+A durable log of executed commands.
+
+Each command executed through the `Resolver` is recorded along with its serialized input (or a
+summary of it for commands with large inputs), the actor that executed it, when it was executed,
+the transaction it ran in, and whether it succeeded.
+Entries for successful commands are written in the same transaction as the changes made by the
+command, so they're only observable if those changes are too. Entries for failed commands are
+committed on their own, so they survive the command's transaction being cancelled.
+*/
+
+mod actor;
+mod query;
+mod record;
+pub(in crate::domain) mod resolver;
+pub(in crate::domain) mod store;
+
+pub use self::{
+    actor::*,
+    query::*,
+    record::*,
+    store::{
+        AuditEntryData,
+        AuditEntryId,
+        AuditOutcome,
+    },
+};
@@ -0,0 +1,138 @@
+/*! Contains the `GetAuditEntriesQuery` type. */
+
+use std::time::SystemTime;
+
+use crate::domain::{
+    Error,
+    infra::{
+        audit::store::{
+            AuditEntryData,
+            AuditStoreFilter,
+        },
+        *,
+    },
+};
+
+/**
+Input for a `GetAuditEntriesQuery`.
+
+Entries can be narrowed to a single entity, and to a time range.
+The range includes its start but not its end.
+*/
+#[derive(Serialize, Deserialize)]
+pub struct GetAuditEntries {
+    #[serde(default)]
+    pub entity: Option<String>,
+    #[serde(default)]
+    pub from: Option<SystemTime>,
+    #[serde(default)]
+    pub to: Option<SystemTime>,
+}
+
+impl QueryArgs for GetAuditEntries {
+    type Output = Result<Vec<AuditEntryData>, Error>;
+}
+
+/**
+Default implementation for a `GetAuditEntriesQuery`.
+
+Entries are returned in the order they were recorded.
+*/
+async fn execute(
+    query: GetAuditEntries,
+    store: impl AuditStoreFilter,
+) -> Result<Vec<AuditEntryData>, Error> {
+    let mut entries: Vec<_> = store
+        .filter(|entry| {
+            query
+                .entity
+                .as_ref()
+                .map(|entity| entry.entities.contains(entity))
+                .unwrap_or(true)
+                && query
+                    .from
+                    .map(|from| entry.recorded_at >= from)
+                    .unwrap_or(true)
+                && query.to.map(|to| entry.recorded_at < to).unwrap_or(true)
+        })?
+        .collect();
+
+    entries.sort_by_key(|entry| entry.recorded_at);
+
+    Ok(entries)
+}
+
+impl Resolver {
+    /** Get entries from the audit log. */
+    pub fn get_audit_entries_query(&self) -> impl Query<GetAuditEntries> {
+        self.query(|resolver, query: GetAuditEntries| async move {
+            let store = resolver.audit_store_filter();
+
+            execute(query, store).await
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    use crate::domain::infra::audit::store::{
+        AuditEntryId,
+        AuditOutcome,
+        AuditStore,
+        in_memory_store,
+    };
+
+    fn entry(entity: &str, recorded_at: SystemTime) -> AuditEntryData {
+        AuditEntryData {
+            id: AuditEntryId::new(),
+            command: "SetProductTitle".to_owned(),
+            args: serde_json::Value::Null,
+            entities: vec![entity.to_owned()],
+            actor: Actor::system().to_string(),
+            recorded_at,
+            transaction: ActiveTransaction::none().get().id(),
+            outcome: AuditOutcome::Succeeded,
+        }
+    }
+
+    #[tokio::test]
+    async fn filter_by_entity_and_time() {
+        let store = in_memory_store(Default::default());
+
+        let start = SystemTime::UNIX_EPOCH;
+        for (entity, secs) in [("a", 2), ("a", 1), ("b", 1), ("a", 3)] {
+            store
+                .add_entry(
+                    ActiveTransaction::none().get(),
+                    entry(entity, start + Duration::from_secs(secs)),
+                )
+                .unwrap();
+        }
+
+        let entries = execute(
+            GetAuditEntries {
+                entity: Some("a".to_owned()),
+                from: Some(start + Duration::from_secs(1)),
+                to: Some(start + Duration::from_secs(3)),
+            },
+            &store,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            vec![
+                start + Duration::from_secs(1),
+                start + Duration::from_secs(2)
+            ],
+            entries
+                .iter()
+                .map(|entry| entry.recorded_at)
+                .collect::<Vec<_>>()
+        );
+    }
+}
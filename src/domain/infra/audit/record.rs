@@ -0,0 +1,242 @@
+/*! Recording executed commands in the audit log. */
+
+use std::any;
+
+use crate::{
+    domain::{
+        Error,
+        infra::{
+            audit::store::{
+                AuditEntryData,
+                AuditEntryId,
+                AuditOutcome,
+                AuditStore,
+            },
+            *,
+        },
+    },
+    store::{
+        Transaction,
+        TransactionId,
+    },
+};
+
+/**
+The output of a command that can be recorded in the audit log.
+
+This is implemented for the `Result` returned by commands in the domain.
+*/
+pub trait CommandOutput {
+    fn outcome(&self) -> AuditOutcome;
+    fn from_error(err: Error) -> Self;
+}
+
+impl<T> CommandOutput for Result<T, Error> {
+    fn outcome(&self) -> AuditOutcome {
+        match self {
+            Ok(_) => AuditOutcome::Succeeded,
+            Err(err) => AuditOutcome::Failed {
+                reason: err.to_string(),
+            },
+        }
+    }
+
+    fn from_error(err: Error) -> Self {
+        Err(err)
+    }
+}
+
+/**
+Get a readable name for a command from the type of its input.
+
+Module paths are stripped, so `a::CreateProduct` becomes `CreateProduct` and
+`a::Idempotent<b::CreateOrder>` becomes `Idempotent<CreateOrder>`.
+*/
+fn command_name<TArgs>() -> String {
+    let mut name = String::new();
+
+    for part in any::type_name::<TArgs>().split_inclusive(['<', '>', ',', ' ']) {
+        name.push_str(part.rsplit("::").next().unwrap_or(part));
+    }
+
+    name
+}
+
+/**
+Find the ids of entities a command affected.
+
+Any strings in the command's input under an `id` field or a field ending in `_id` are considered entity ids.
+*/
+fn entity_ids(args: &serde_json::Value, ids: &mut Vec<String>) {
+    match args {
+        serde_json::Value::Object(fields) => {
+            for (field, value) in fields {
+                match value {
+                    serde_json::Value::String(id) if field == "id" || field.ends_with("_id") => {
+                        if !ids.contains(id) {
+                            ids.push(id.clone());
+                        }
+                    }
+                    value => entity_ids(value, ids),
+                }
+            }
+        }
+        serde_json::Value::Array(values) => {
+            for value in values {
+                entity_ids(value, ids);
+            }
+        }
+        _ => (),
+    }
+}
+
+/**
+Record the execution of a command in the audit log.
+
+The entry is written in the given transaction, which may not be the one the command ran in.
+*/
+fn record<TArgs>(
+    args: serde_json::Value,
+    outcome: AuditOutcome,
+    command_transaction: TransactionId,
+    transaction: &Transaction,
+    store: impl AuditStore,
+    clock: impl Clock,
+    actor: Actor,
+) -> Result<(), Error> {
+    let mut entities = Vec::new();
+    entity_ids(&args, &mut entities);
+
+    store.add_entry(
+        transaction,
+        AuditEntryData {
+            id: AuditEntryId::new(),
+            command: command_name::<TArgs>(),
+            args,
+            entities,
+            actor: actor.to_string(),
+            recorded_at: clock.now(),
+            transaction: command_transaction,
+            outcome,
+        },
+    )
+}
+
+impl Resolver {
+    /**
+    Record the execution of a command in the audit log.
+
+    Successful commands are recorded in the active transaction, so they're only observable if the
+    changes made by the command are too. Failed commands are likely to take the active transaction
+    down with them, so they're recorded and committed in a transaction of their own.
+    */
+    pub(in crate::domain) fn audit_command<TArgs>(
+        &self,
+        args: serde_json::Value,
+        output: &impl CommandOutput,
+    ) -> Result<(), Error> {
+        let store = self.audit_store();
+        let active_transaction = self.active_transaction();
+        let clock = self.clock();
+        let actor = self.actor();
+
+        let command_transaction = active_transaction.get().id();
+
+        match output.outcome() {
+            outcome @ AuditOutcome::Succeeded => record::<TArgs>(
+                args,
+                outcome,
+                command_transaction,
+                active_transaction.get(),
+                store,
+                clock,
+                actor,
+            ),
+            outcome @ AuditOutcome::Failed { .. } => {
+                let transactions = self.transaction_store();
+                let transaction = transactions.begin();
+
+                match record::<TArgs>(
+                    args,
+                    outcome,
+                    command_transaction,
+                    &transaction,
+                    store,
+                    clock,
+                    actor,
+                ) {
+                    Ok(()) => {
+                        transactions.commit(transaction);
+
+                        Ok(())
+                    }
+                    Err(err) => {
+                        transactions.cancel(transaction);
+
+                        Err(err)
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::SystemTime;
+
+    use super::*;
+
+    use crate::domain::{
+        infra::{
+            audit::store::{
+                AuditStoreFilter,
+                in_memory_store,
+            },
+            idempotency::Idempotent,
+        },
+        products::SetProductTitle,
+    };
+
+    #[test]
+    fn command_names_are_readable() {
+        assert_eq!("SetProductTitle", command_name::<SetProductTitle>());
+        assert_eq!(
+            "Idempotent<SetProductTitle>",
+            command_name::<Idempotent<SetProductTitle>>()
+        );
+    }
+
+    #[test]
+    fn record_entity_ids() {
+        let store = in_memory_store(Default::default());
+
+        let args = serde_json::json!({
+            "key": "a-key",
+            "args": {
+                "id": "an-order",
+                "product_id": "a-product",
+                "quantity": 1
+            }
+        });
+
+        let transaction = ActiveTransaction::none();
+
+        record::<SetProductTitle>(
+            args,
+            AuditOutcome::Succeeded,
+            transaction.get().id(),
+            transaction.get(),
+            &store,
+            SystemTime::UNIX_EPOCH,
+            Actor::system(),
+        )
+        .unwrap();
+
+        let entries: Vec<_> = store.filter(|_| true).unwrap().collect();
+
+        assert_eq!(1, entries.len());
+        assert_eq!(vec!["an-order", "a-product"], entries[0].entities);
+        assert_eq!("system", entries[0].actor);
+    }
+}
@@ -0,0 +1,69 @@
+use std::sync::Arc;
+
+use crate::domain::infra::{
+    audit::store::{
+        self,
+        AuditStore,
+        AuditStoreFilter,
+        InMemoryStore,
+    },
+    *,
+};
+
+/**
+Resolver for the audit log.
+*/
+#[derive(Clone)]
+pub(in crate::domain) struct AuditResolver {
+    audit_store: Register<Arc<InMemoryStore>>,
+    actor: Register<Actor>,
+}
+
+impl Default for AuditResolver {
+    fn default() -> Self {
+        AuditResolver {
+            audit_store: Register::per_tenant(|resolver| {
+                Arc::new(store::in_memory_store(resolver.transaction_store()))
+            }),
+            actor: Register::factory(|_| Actor::system()),
+        }
+    }
+}
+
+impl App {
+    /**
+    Get a handle to the app for an actor.
+
+    Commands executed through the returned handle are recorded in the audit log against that actor.
+    */
+    pub fn as_actor(&self, actor: Actor) -> App {
+        App {
+            root_resolver: self.root_resolver.with_actor(actor),
+        }
+    }
+}
+
+impl Resolver {
+    /** Get the actor that commands are being executed by. */
+    pub fn actor(&self) -> Actor {
+        self.resolve(&self.audit_resolver.actor)
+    }
+
+    pub(in crate::domain::infra::audit) fn audit_store(&self) -> impl AuditStore {
+        self.resolve(&self.audit_resolver.audit_store)
+    }
+
+    pub(in crate::domain::infra::audit) fn audit_store_filter(&self) -> impl AuditStoreFilter {
+        self.resolve(&self.audit_resolver.audit_store)
+    }
+
+    pub(in crate::domain) fn with_actor(&self, actor: Actor) -> Resolver {
+        Resolver {
+            audit_resolver: AuditResolver {
+                audit_store: self.audit_resolver.audit_store.clone(),
+                actor: Register::factory(move |_| actor.clone()),
+            },
+            ..self.by_ref()
+        }
+    }
+}
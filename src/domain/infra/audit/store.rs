@@ -0,0 +1,102 @@
+/*! Persistent storage for audit log entries. */
+
+use std::{
+    time::SystemTime,
+    vec::IntoIter,
+};
+
+use crate::{
+    domain::{
+        Error,
+        infra::*,
+    },
+    store::{
+        Transaction,
+        TransactionId,
+        TransactionStore,
+        TransactionValueStore,
+    },
+};
+
+pub type AuditEntryId = Id<AuditEntryData>;
+pub(in crate::domain) type AuditEntryVersion = Version<AuditEntryData>;
+
+/** Whether or not an audited command succeeded. */
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "status")]
+pub enum AuditOutcome {
+    Succeeded,
+    Failed { reason: String },
+}
+
+/**
+An entry in the audit log for a single executed command.
+
+The entities are the ids found in the command's input, so entries can be found by the entities they affected.
+*/
+#[derive(Clone, Serialize, Deserialize)]
+pub struct AuditEntryData {
+    pub id: AuditEntryId,
+    pub command: String,
+    pub args: serde_json::Value,
+    pub entities: Vec<String>,
+    pub actor: String,
+    pub recorded_at: SystemTime,
+    pub transaction: TransactionId,
+    pub outcome: AuditOutcome,
+}
+
+/**
+A place to persist audit log entries.
+
+Entries are only ever added, never changed.
+*/
+#[auto_impl(&, Arc)]
+pub(in crate::domain) trait AuditStore {
+    fn add_entry(&self, transaction: &Transaction, entry: AuditEntryData) -> Result<(), Error>;
+}
+
+/** An additional store for fetching multiple audit log entries at a time. */
+#[auto_impl(&, Arc)]
+pub(in crate::domain) trait AuditStoreFilter {
+    fn filter<F>(&self, predicate: F) -> Result<Iter, Error>
+    where
+        F: Fn(&AuditEntryData) -> bool;
+}
+
+pub(in crate::domain) type Iter = IntoIter<AuditEntryData>;
+
+/** A test in-memory audit store. */
+pub(in crate::domain) struct InMemoryStore(TransactionValueStore<AuditEntryData>);
+
+impl AuditStore for InMemoryStore {
+    fn add_entry(&self, transaction: &Transaction, entry: AuditEntryData) -> Result<(), Error> {
+        let id = entry.id;
+
+        self.0.set(
+            transaction,
+            id,
+            None::<AuditEntryVersion>,
+            AuditEntryVersion::default().next()?,
+            entry,
+        )?;
+
+        Ok(())
+    }
+}
+
+impl AuditStoreFilter for InMemoryStore {
+    #[allow(clippy::needless_collect)]
+    fn filter<F>(&self, predicate: F) -> Result<Iter, Error>
+    where
+        F: Fn(&AuditEntryData) -> bool,
+    {
+        let entries: Vec<_> = self.0.get_all(predicate).map(|(_, data)| data).collect();
+
+        Ok(entries.into_iter())
+    }
+}
+
+pub(in crate::domain) fn in_memory_store(transaction_store: TransactionStore) -> InMemoryStore {
+    InMemoryStore(TransactionValueStore::new(transaction_store))
+}
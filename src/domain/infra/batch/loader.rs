@@ -0,0 +1,229 @@
+/*! Contains the `BatchLoader` type. */
+
+use std::{
+    any::{
+        Any,
+        TypeId,
+    },
+    collections::HashMap,
+    future::Future,
+    hash::Hash,
+    pin::Pin,
+    sync::{
+        Arc,
+        Mutex,
+    },
+    task::{
+        Context,
+        Poll,
+    },
+};
+
+use crate::domain::Error;
+
+/**
+A loader that coalesces concurrent loads into batches.
+
+Keys are queued when they're loaded, and the first caller to resume after giving the others a
+chance to queue theirs fetches all of them at once.
+Loaded values are only shared with the callers whose keys were in the same batch, so a load made
+after a write in the same transaction always sees the write.
+*/
+pub(in crate::domain) struct BatchLoader<K, V>(Arc<Mutex<Batch<K, V>>>);
+
+struct Batch<K, V> {
+    pending: Vec<K>,
+    loaded: Arc<Mutex<Option<HashMap<K, V>>>>,
+}
+
+impl<K, V> Clone for BatchLoader<K, V> {
+    fn clone(&self) -> Self {
+        BatchLoader(self.0.clone())
+    }
+}
+
+impl<K, V> Default for BatchLoader<K, V> {
+    fn default() -> Self {
+        BatchLoader(Arc::new(Mutex::new(Batch {
+            pending: Vec::new(),
+            loaded: Arc::new(Mutex::new(None)),
+        })))
+    }
+}
+
+impl<K, V> BatchLoader<K, V>
+where
+    K: Eq + Hash + Copy,
+    V: Clone,
+{
+    /**
+    Load a value by its key.
+
+    The `fetch` function is given all the keys queued at the time the batch is run.
+    Any keys it doesn't return a value for are treated as missing.
+    If fetching fails then the other callers in the batch queue their keys again and retry them.
+    */
+    pub(in crate::domain) async fn load<I>(
+        &self,
+        key: K,
+        fetch: impl FnOnce(&[K]) -> Result<I, Error>,
+    ) -> Result<Option<V>, Error>
+    where
+        I: IntoIterator<Item = (K, V)>,
+    {
+        loop {
+            let loaded = {
+                let mut batch = self.0.lock().unwrap();
+
+                if !batch.pending.contains(&key) {
+                    batch.pending.push(key);
+                }
+
+                batch.loaded.clone()
+            };
+
+            // Give other concurrent callers a chance to queue their keys
+            YieldNow(false).await;
+
+            let mut batch = self.0.lock().unwrap();
+
+            // Another caller already fetched the batch
+            if let Some(ref values) = *loaded.lock().unwrap() {
+                return Ok(values.get(&key).cloned());
+            }
+
+            // This caller is the first to resume, so it fetches the batch
+            if Arc::ptr_eq(&loaded, &batch.loaded) {
+                let keys = std::mem::take(&mut batch.pending);
+                batch.loaded = Arc::new(Mutex::new(None));
+
+                let values: HashMap<_, _> = fetch(&keys)?.into_iter().collect();
+                let value = values.get(&key).cloned();
+
+                *loaded.lock().unwrap() = Some(values);
+
+                return Ok(value);
+            }
+
+            // The batch failed to fetch, so queue the key again in the next one
+        }
+    }
+}
+
+/**
+A set of batch loaders for different types of values.
+
+All loaders for the same key and value types share a single batch.
+*/
+#[derive(Clone, Default)]
+pub(in crate::domain) struct BatchLoaders(Arc<Mutex<HashMap<TypeId, Box<dyn Any + Send>>>>);
+
+impl BatchLoaders {
+    pub(in crate::domain) fn get<K, V>(&self) -> BatchLoader<K, V>
+    where
+        K: Send + 'static,
+        V: Send + 'static,
+    {
+        let mut loaders = self.0.lock().unwrap();
+
+        loaders
+            .entry(TypeId::of::<BatchLoader<K, V>>())
+            .or_insert_with(|| Box::new(BatchLoader::<K, V>::default()))
+            .downcast_ref::<BatchLoader<K, V>>()
+            .expect("invalid batch loader")
+            .clone()
+    }
+}
+
+struct YieldNow(bool);
+
+impl Future for YieldNow {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        if self.0 {
+            Poll::Ready(())
+        } else {
+            self.0 = true;
+            cx.waker().wake_by_ref();
+
+            Poll::Pending
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{
+        AtomicUsize,
+        Ordering,
+    };
+
+    use futures::future;
+
+    use super::*;
+
+    use crate::domain::error;
+
+    #[tokio::test]
+    async fn coalesce_concurrent_loads() {
+        let loader = BatchLoader::<u32, u32>::default();
+        let fetches = AtomicUsize::new(0);
+
+        let fetch = |keys: &[u32]| {
+            fetches.fetch_add(1, Ordering::SeqCst);
+
+            Ok(keys
+                .iter()
+                .filter(|key| **key != 3)
+                .map(|key| (*key, key * 10))
+                .collect::<Vec<_>>())
+        };
+
+        let values =
+            future::try_join_all([1, 2, 3, 1].into_iter().map(|key| loader.load(key, fetch)))
+                .await
+                .unwrap();
+
+        assert_eq!(vec![Some(10), Some(20), None, Some(10)], values);
+        assert_eq!(1, fetches.load(Ordering::SeqCst));
+
+        // Later loads are fetched again, so they see any changes made since
+        let value = loader.load(2, fetch).await.unwrap();
+
+        assert_eq!(Some(20), value);
+        assert_eq!(2, fetches.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn retry_after_failed_fetch() {
+        let loader = BatchLoader::<u32, u32>::default();
+        let fetches = AtomicUsize::new(0);
+
+        let fetch = |keys: &[u32]| {
+            if fetches.fetch_add(1, Ordering::SeqCst) == 0 {
+                Err(error::msg("failed"))
+            } else {
+                Ok(keys.iter().map(|key| (*key, key * 10)).collect::<Vec<_>>())
+            }
+        };
+
+        let (first, second) = future::join(loader.load(1, fetch), loader.load(2, fetch)).await;
+
+        assert!(first.is_err());
+        assert_eq!(Some(20), second.unwrap());
+        assert_eq!(2, fetches.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn share_loaders_by_type() {
+        let loaders = BatchLoaders::default();
+
+        let a = loaders.get::<u32, u32>();
+        let b = loaders.get::<u32, u32>();
+        let _ = loaders.get::<u32, String>();
+
+        assert!(Arc::ptr_eq(&a.0, &b.0));
+        assert_eq!(2, loaders.0.lock().unwrap().len());
+    }
+}
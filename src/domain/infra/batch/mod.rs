@@ -0,0 +1,14 @@
+/*!
+Batched loading for queries.
+
+Queries that fetch a single entity by id can load it through a `BatchLoader`.
+Loads made concurrently within the same transaction are coalesced into a single lookup
+against the underlying store, so composite queries can fetch related entities one at a time
+without making a separate round-trip for each of them.
+Loaded values aren't cached beyond their batch, so later loads see changes made since.
+*/
+
+mod loader;
+pub(in crate::domain) mod resolver;
+
+pub(in crate::domain) use self::loader::*;
@@ -0,0 +1,47 @@
+use crate::domain::infra::{
+    batch::{
+        BatchLoader,
+        BatchLoaders,
+    },
+    *,
+};
+
+/**
+Resolver for batch loaders.
+*/
+#[derive(Clone)]
+pub(in crate::domain) struct BatchResolver {
+    batch_loaders: Register<BatchLoaders>,
+}
+
+impl Default for BatchResolver {
+    fn default() -> Self {
+        BatchResolver {
+            batch_loaders: Register::factory(|_| {
+                // By default, each call to get a batch loader will receive a fresh one
+                // so loads are only batched within a transaction
+                BatchLoaders::default()
+            }),
+        }
+    }
+}
+
+impl Resolver {
+    pub(in crate::domain) fn batch_loader<K, V>(&self) -> BatchLoader<K, V>
+    where
+        K: Send + 'static,
+        V: Send + 'static,
+    {
+        self.resolve(&self.batch_resolver.batch_loaders).get()
+    }
+
+    pub(in crate::domain) fn with_batch_loaders(
+        &self,
+        batch_loaders: Register<BatchLoaders>,
+    ) -> Resolver {
+        Resolver {
+            batch_resolver: BatchResolver { batch_loaders },
+            ..self.by_ref()
+        }
+    }
+}
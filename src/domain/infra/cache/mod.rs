@@ -0,0 +1,13 @@
+/*!
+Opt-in caching for query results.
+
+Queries wrapped with `Resolver::cached_query` keep their results keyed on their serialized arguments.
+Cached results are discarded when any changes to stores are committed, or when they've been kept
+for longer than the query's time-to-live.
+*/
+
+mod query;
+pub(in crate::domain) mod resolver;
+pub(in crate::domain) mod store;
+
+pub use self::store::QueryCacheMetrics;
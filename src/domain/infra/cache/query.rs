@@ -0,0 +1,250 @@
+/*! Contains the cached query wrapper. */
+
+use std::{
+    any,
+    sync::Arc,
+    time::Duration,
+};
+
+use serde::Serialize;
+
+use crate::{
+    domain::{
+        Error,
+        infra::{
+            cache::store::QueryCache,
+            *,
+        },
+    },
+    store::TransactionStore,
+};
+
+/** Default implementation for a cached query. */
+async fn execute<TArgs, T>(
+    query: TArgs,
+    cache: QueryCache,
+    transactions: TransactionStore,
+    clock: impl Clock,
+    ttl: Duration,
+    inner: &impl Query<TArgs>,
+) -> Result<T, Error>
+where
+    TArgs: QueryArgs<Output = Result<T, Error>> + Serialize,
+    T: Clone + Send + Sync + 'static,
+{
+    if ttl.is_zero() {
+        return inner.execute(query).await;
+    }
+
+    let name = any::type_name::<TArgs>();
+    let key = serde_json::to_string(&query)?;
+    let now = clock.now();
+
+    // The generation is read before executing the query so any changes committed while
+    // it's running will invalidate its result
+    let generation = transactions.generation();
+
+    if let Some(result) = cache.get::<T>(name, &key, generation, now) {
+        emit::debug!("using cached result for {query: name}");
+
+        return Ok(result);
+    }
+
+    // Errors aren't cached, so a failed query will be executed again
+    let result = inner.execute(query).await?;
+
+    if let Some(expires_at) = now.checked_add(ttl) {
+        cache.set(name, key, generation, expires_at, result.clone());
+    }
+
+    Ok(result)
+}
+
+impl Resolver {
+    /**
+    Cache the results of a query.
+
+    Results are keyed on the serialized query arguments.
+    They're kept until any changes are committed to stores, or until the query's time-to-live passes.
+    */
+    pub fn cached_query<TArgs, T>(
+        &self,
+        query: impl Query<TArgs> + Send + Sync,
+    ) -> impl Query<TArgs>
+    where
+        TArgs: QueryArgs<Output = Result<T, Error>> + Serialize + Send + 'static,
+        T: Clone + Send + Sync + 'static,
+    {
+        let query = Arc::new(query);
+
+        self.query(move |resolver, args: TArgs| {
+            let query = query.clone();
+
+            async move {
+                let cache = resolver.query_cache();
+                let transactions = resolver.transaction_store();
+                let clock = resolver.clock();
+                let ttl = resolver.query_cache_ttl::<TArgs>();
+
+                execute(args, cache, transactions, clock, ttl, &*query).await
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::atomic::{
+            AtomicUsize,
+            Ordering,
+        },
+        time::SystemTime,
+    };
+
+    use super::*;
+
+    use crate::domain::error;
+
+    #[derive(Serialize)]
+    struct Count {
+        key: &'static str,
+    }
+
+    impl QueryArgs for Count {
+        type Output = Result<usize, Error>;
+    }
+
+    fn count(counter: &AtomicUsize) -> impl Query<Count> + '_ {
+        move |_: Count| async move { Ok(counter.fetch_add(1, Ordering::SeqCst) + 1) }
+    }
+
+    const TTL: Duration = Duration::from_secs(60);
+
+    #[tokio::test]
+    async fn cache_result_for_same_args() {
+        let cache = QueryCache::default();
+        let transactions = TransactionStore::new();
+        let counter = AtomicUsize::new(0);
+        let query = count(&counter);
+        let now = SystemTime::now();
+
+        for (key, expected) in [("a", 1), ("a", 1), ("b", 2)] {
+            let result = execute(
+                Count { key },
+                cache.clone(),
+                transactions.clone(),
+                now,
+                TTL,
+                &query,
+            )
+            .await
+            .unwrap();
+
+            assert_eq!(expected, result);
+        }
+
+        assert_eq!(2, counter.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn execute_again_after_changes() {
+        let cache = QueryCache::default();
+        let transactions = TransactionStore::new();
+        let counter = AtomicUsize::new(0);
+        let query = count(&counter);
+        let now = SystemTime::now();
+
+        execute(
+            Count { key: "a" },
+            cache.clone(),
+            transactions.clone(),
+            now,
+            TTL,
+            &query,
+        )
+        .await
+        .unwrap();
+
+        // Changes in an active transaction don't invalidate results
+        let transaction = transactions.begin();
+        transactions.changed(transaction.id());
+
+        let result = execute(
+            Count { key: "a" },
+            cache.clone(),
+            transactions.clone(),
+            now,
+            TTL,
+            &query,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(1, result);
+
+        transactions.commit(transaction);
+
+        let result = execute(
+            Count { key: "a" },
+            cache.clone(),
+            transactions.clone(),
+            now,
+            TTL,
+            &query,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(2, result);
+    }
+
+    #[tokio::test]
+    async fn execute_again_after_ttl() {
+        let cache = QueryCache::default();
+        let transactions = TransactionStore::new();
+        let counter = AtomicUsize::new(0);
+        let query = count(&counter);
+        let now = SystemTime::now();
+
+        for now in [now, now + TTL] {
+            execute(
+                Count { key: "a" },
+                cache.clone(),
+                transactions.clone(),
+                now,
+                TTL,
+                &query,
+            )
+            .await
+            .unwrap();
+        }
+
+        assert_eq!(2, counter.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn errors_are_not_cached() {
+        let cache = QueryCache::default();
+        let transactions = TransactionStore::new();
+        let now = SystemTime::now();
+
+        let result = execute(
+            Count { key: "a" },
+            cache.clone(),
+            transactions.clone(),
+            now,
+            TTL,
+            &|_: Count| async { Err::<usize, _>(error::msg("failed")) },
+        )
+        .await;
+
+        assert!(result.is_err());
+        assert!(
+            cache
+                .metrics()
+                .iter()
+                .all(|metrics| metrics.hits == 0 && metrics.misses == 1)
+        );
+    }
+}
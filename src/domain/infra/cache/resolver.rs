@@ -0,0 +1,82 @@
+use std::{
+    any,
+    collections::HashMap,
+    time::Duration,
+};
+
+use crate::domain::infra::{
+    cache::store::QueryCache,
+    *,
+};
+
+/**
+The default length of time query results are cached for.
+
+Results are also discarded as soon as any changes are committed, so this is a backstop.
+*/
+const DEFAULT_TTL: Duration = Duration::from_secs(60);
+
+/**
+Resolver for cached queries.
+*/
+#[derive(Clone)]
+pub(in crate::domain) struct CacheResolver {
+    query_cache: Register<QueryCache>,
+    default_ttl: Register<Duration>,
+    ttls: HashMap<&'static str, Register<Duration>>,
+}
+
+impl Default for CacheResolver {
+    fn default() -> Self {
+        CacheResolver {
+            query_cache: Register::per_tenant(|_| QueryCache::default()),
+            default_ttl: Register::once(|_| DEFAULT_TTL),
+            ttls: HashMap::new(),
+        }
+    }
+}
+
+impl AppBuilder {
+    /** Use a different length of time to cache query results for. */
+    pub fn query_cache_ttl(mut self, ttl: Register<Duration>) -> Self {
+        self.root_resolver.cache_resolver.default_ttl = ttl;
+        self
+    }
+
+    /**
+    Use a different length of time to cache the results of a specific query for.
+
+    A time-to-live of zero disables caching for the query.
+    */
+    pub fn query_cache_ttl_for<TArgs>(mut self, ttl: Register<Duration>) -> Self
+    where
+        TArgs: QueryArgs,
+    {
+        self.root_resolver
+            .cache_resolver
+            .ttls
+            .insert(any::type_name::<TArgs>(), ttl);
+        self
+    }
+}
+
+impl Resolver {
+    /** Get the hit and miss counts for each cached query. */
+    pub fn query_cache_metrics(&self) -> Vec<QueryCacheMetrics> {
+        self.query_cache().metrics()
+    }
+
+    pub(in crate::domain::infra::cache) fn query_cache(&self) -> QueryCache {
+        self.resolve(&self.cache_resolver.query_cache)
+    }
+
+    pub(in crate::domain::infra::cache) fn query_cache_ttl<TArgs>(&self) -> Duration
+    where
+        TArgs: QueryArgs,
+    {
+        match self.cache_resolver.ttls.get(any::type_name::<TArgs>()) {
+            Some(ttl) => self.resolve(ttl),
+            None => self.resolve(&self.cache_resolver.default_ttl),
+        }
+    }
+}
@@ -0,0 +1,174 @@
+/*! In-memory storage for cached query results. */
+
+use std::{
+    any::Any,
+    collections::{
+        BTreeMap,
+        HashMap,
+    },
+    sync::{
+        Arc,
+        Mutex,
+    },
+    time::SystemTime,
+};
+
+/** Hit and miss counts for a single cached query. */
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct QueryCacheMetrics {
+    pub query: String,
+    pub hits: u64,
+    pub misses: u64,
+}
+
+struct CachedResult {
+    generation: u64,
+    expires_at: SystemTime,
+    result: Arc<dyn Any + Send + Sync>,
+}
+
+#[derive(Default)]
+struct Inner {
+    generation: u64,
+    results: HashMap<(&'static str, String), CachedResult>,
+    metrics: BTreeMap<&'static str, (u64, u64)>,
+}
+
+/**
+A cache of query results.
+
+Results are tagged with the generation of the transaction store they were read at.
+A result is only returned while that generation is still current and it hasn't expired.
+*/
+#[derive(Clone, Default)]
+pub struct QueryCache(Arc<Mutex<Inner>>);
+
+impl QueryCache {
+    pub(in crate::domain::infra::cache) fn get<T>(
+        &self,
+        query: &'static str,
+        key: &str,
+        generation: u64,
+        now: SystemTime,
+    ) -> Option<T>
+    where
+        T: Clone + Send + Sync + 'static,
+    {
+        let mut inner = self.0.lock().unwrap();
+
+        // Once a new generation is seen, results from older ones can never be used again
+        if inner.generation < generation {
+            inner.generation = generation;
+            inner
+                .results
+                .retain(|_, cached| cached.generation >= generation);
+        }
+
+        let hit = inner
+            .results
+            .get(&(query, key.to_owned()))
+            .filter(|cached| cached.generation == generation && cached.expires_at > now)
+            .and_then(|cached| cached.result.downcast_ref::<T>())
+            .cloned();
+
+        let (hits, misses) = inner.metrics.entry(query).or_default();
+        if hit.is_some() {
+            *hits += 1;
+        } else {
+            *misses += 1;
+        }
+
+        hit
+    }
+
+    pub(in crate::domain::infra::cache) fn set<T>(
+        &self,
+        query: &'static str,
+        key: String,
+        generation: u64,
+        expires_at: SystemTime,
+        result: T,
+    ) where
+        T: Send + Sync + 'static,
+    {
+        let mut inner = self.0.lock().unwrap();
+
+        // Don't keep results that were read from a generation that's already stale
+        if generation < inner.generation {
+            return;
+        }
+
+        inner.results.insert(
+            (query, key),
+            CachedResult {
+                generation,
+                expires_at,
+                result: Arc::new(result),
+            },
+        );
+    }
+
+    /** Get the hit and miss counts for each cached query. */
+    pub fn metrics(&self) -> Vec<QueryCacheMetrics> {
+        let inner = self.0.lock().unwrap();
+
+        inner
+            .metrics
+            .iter()
+            .map(|(query, (hits, misses))| QueryCacheMetrics {
+                query: (*query).to_owned(),
+                hits: *hits,
+                misses: *misses,
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[test]
+    fn get_set() {
+        let cache = QueryCache::default();
+        let now = SystemTime::now();
+        let expires_at = now + Duration::from_secs(1);
+
+        assert_eq!(None, cache.get::<i32>("query", "a", 0, now));
+
+        cache.set("query", "a".to_owned(), 0, expires_at, 1);
+
+        assert_eq!(Some(1), cache.get::<i32>("query", "a", 0, now));
+        assert_eq!(None, cache.get::<i32>("query", "b", 0, now));
+
+        // The result has expired
+        assert_eq!(None, cache.get::<i32>("query", "a", 0, expires_at));
+
+        assert_eq!(
+            vec![QueryCacheMetrics {
+                query: "query".to_owned(),
+                hits: 1,
+                misses: 3,
+            }],
+            cache.metrics()
+        );
+    }
+
+    #[test]
+    fn new_generation_invalidates() {
+        let cache = QueryCache::default();
+        let now = SystemTime::now();
+        let expires_at = now + Duration::from_secs(1);
+
+        cache.set("query", "a".to_owned(), 0, expires_at, 1);
+
+        assert_eq!(None, cache.get::<i32>("query", "a", 1, now));
+
+        // Results from stale generations aren't kept
+        cache.set("query", "a".to_owned(), 0, expires_at, 1);
+
+        assert_eq!(None, cache.get::<i32>("query", "a", 1, now));
+    }
+}
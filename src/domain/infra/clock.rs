@@ -0,0 +1,65 @@
+/*! Contains the shared `Clock` type. */
+
+use std::{
+    sync::Arc,
+    time::SystemTime,
+};
+
+use crate::domain::infra::*;
+
+/**
+A source of the current time.
+
+Items that need to know what time it is should depend on a `Clock` rather than calling
+`SystemTime::now` directly so the time can be controlled.
+*/
+#[auto_impl(&, Arc)]
+pub trait Clock {
+    fn now(&self) -> SystemTime;
+}
+
+/** A clock that reads the current system time. */
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> SystemTime {
+        SystemTime::now()
+    }
+}
+
+impl Clock for SystemTime {
+    fn now(&self) -> SystemTime {
+        *self
+    }
+}
+
+/**
+Resolver for the clock.
+*/
+#[derive(Clone)]
+pub(in crate::domain) struct ClockResolver {
+    clock: Register<Arc<dyn Clock + Send + Sync>>,
+}
+
+impl Default for ClockResolver {
+    fn default() -> Self {
+        ClockResolver {
+            clock: Register::once(|_| Arc::new(SystemClock) as Arc<dyn Clock + Send + Sync>),
+        }
+    }
+}
+
+impl AppBuilder {
+    /** Use a different clock. */
+    pub fn clock(mut self, clock: Register<Arc<dyn Clock + Send + Sync>>) -> Self {
+        self.root_resolver.clock_resolver.clock = clock;
+        self
+    }
+}
+
+impl Resolver {
+    pub(in crate::domain) fn clock(&self) -> impl Clock {
+        self.resolve(&self.clock_resolver.clock)
+    }
+}
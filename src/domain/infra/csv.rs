@@ -0,0 +1,185 @@
+/*!
+Reading and writing comma-separated values.
+
+This is a small implementation of RFC 4180 that's enough for files exported from spreadsheets.
+Fields can be quoted with `"`, and quotes within quoted fields are escaped by doubling them.
+Records can end with either `\n` or `\r\n`.
+*/
+
+use crate::domain::{
+    Error,
+    error,
+};
+
+/**
+Read records from comma-separated values.
+
+Each record is returned with the line it started on, counting from 1.
+Blank lines aren't returned as records.
+*/
+pub(in crate::domain) fn read(data: &str) -> Result<Vec<(usize, Vec<String>)>, Error> {
+    let mut records = Vec::new();
+
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut in_quotes = false;
+
+    let mut line = 1;
+    let mut record_line = 1;
+
+    let mut chars = data.chars().peekable();
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    field.push('"');
+                }
+                '"' => in_quotes = false,
+                c => {
+                    if c == '\n' {
+                        line += 1;
+                    }
+
+                    field.push(c);
+                }
+            }
+
+            continue;
+        }
+
+        match c {
+            '"' if field.is_empty() && !quoted => {
+                quoted = true;
+                in_quotes = true;
+            }
+            '"' => {
+                return Err(error::bad_input(format_args!(
+                    "unexpected quote on line {}",
+                    line
+                )));
+            }
+            ',' => {
+                record.push(std::mem::take(&mut field));
+                quoted = false;
+            }
+            '\r' if chars.peek() == Some(&'\n') => (),
+            '\n' => {
+                end_record(&mut records, &mut record, &mut field, quoted, record_line);
+                quoted = false;
+
+                line += 1;
+                record_line = line;
+            }
+            c if quoted => {
+                return Err(error::bad_input(format_args!(
+                    "unexpected `{}` after a quoted field on line {}",
+                    c, line
+                )));
+            }
+            c => field.push(c),
+        }
+    }
+
+    if in_quotes {
+        return Err(error::bad_input(format_args!(
+            "unterminated quote starting on line {}",
+            record_line
+        )));
+    }
+
+    end_record(&mut records, &mut record, &mut field, quoted, record_line);
+
+    Ok(records)
+}
+
+fn end_record(
+    records: &mut Vec<(usize, Vec<String>)>,
+    record: &mut Vec<String>,
+    field: &mut String,
+    quoted: bool,
+    line: usize,
+) {
+    if record.is_empty() && field.is_empty() && !quoted {
+        return;
+    }
+
+    record.push(std::mem::take(field));
+    records.push((line, std::mem::take(record)));
+}
+
+/**
+Write a record as comma-separated values.
+
+Fields are only quoted if they need to be.
+*/
+pub(in crate::domain) fn write<T: AsRef<str>>(
+    out: &mut String,
+    record: impl IntoIterator<Item = T>,
+) {
+    for (i, field) in record.into_iter().enumerate() {
+        let field = field.as_ref();
+
+        if i > 0 {
+            out.push(',');
+        }
+
+        if field.contains([',', '"', '\n', '\r']) {
+            out.push('"');
+            out.push_str(&field.replace('"', "\"\""));
+            out.push('"');
+        } else {
+            out.push_str(field);
+        }
+    }
+
+    out.push_str("\r\n");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read_quoted_fields() {
+        let records = read(
+            "title,price\r\n\"A \"\"quoted\"\" title\",100\n\n\"Two\nlines\",\"1,000\"\nlast,",
+        )
+        .unwrap();
+
+        assert_eq!(
+            vec![
+                (1, vec!["title".to_owned(), "price".to_owned()]),
+                (2, vec!["A \"quoted\" title".to_owned(), "100".to_owned()]),
+                (4, vec!["Two\nlines".to_owned(), "1,000".to_owned()]),
+                (6, vec!["last".to_owned(), "".to_owned()]),
+            ],
+            records
+        );
+    }
+
+    #[test]
+    fn err_if_malformed() {
+        assert!(read("\"unterminated").is_err());
+        assert!(read("\"quoted\"trailing").is_err());
+        assert!(read("a\"b").is_err());
+    }
+
+    #[test]
+    fn write_round_trips() {
+        let record = ["plain", "with, comma", "with \"quotes\"", "two\nlines", ""];
+
+        let mut out = String::new();
+        write(&mut out, record);
+
+        assert_eq!(
+            "plain,\"with, comma\",\"with \"\"quotes\"\"\",\"two\nlines\",\r\n",
+            out
+        );
+        assert_eq!(
+            vec![(1, record.map(String::from).to_vec())],
+            read(&out).unwrap()
+        );
+    }
+}
@@ -1,19 +1,256 @@
+/*! Contains the shared `Currency` type. */
+
+use std::{
+    convert::TryFrom,
+    fmt,
+    str::FromStr,
+};
+
+use crate::domain::{
+    Error,
+    error,
+};
+
 /**
 A lossless representation of currency.
 
 This type encodes the currency using its smallest possible unit. This is a better approach
 than floating point numbers where imprecision can change the results of calculations.
+
+Each variant serializes using its lowercase ISO 4217 code along with the name of its minor unit,
+like `{"usd":{"cents":100}}` or `{"jpy":{"yen":100}}`.
 */
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "lowercase")]
 pub enum Currency {
     USD(USD),
+    EUR(EUR),
+    GBP(GBP),
+    JPY(JPY),
+    AUD(AUD),
 }
 
 impl Currency {
     pub fn usd(cents: u64) -> Self {
         Currency::USD(USD::new(cents))
     }
+
+    pub fn eur(cents: u64) -> Self {
+        Currency::EUR(EUR::new(cents))
+    }
+
+    pub fn gbp(pence: u64) -> Self {
+        Currency::GBP(GBP::new(pence))
+    }
+
+    pub fn jpy(yen: u64) -> Self {
+        Currency::JPY(JPY::new(yen))
+    }
+
+    pub fn aud(cents: u64) -> Self {
+        Currency::AUD(AUD::new(cents))
+    }
+
+    /**
+    Create a currency value from a number of its minor units.
+
+    The minor unit for a currency depends on its exponent, so `100` is
+    one dollar for `USD` but one hundred yen for `JPY`.
+    */
+    pub fn from_minor_units(code: CurrencyCode, units: u64) -> Self {
+        match code {
+            CurrencyCode::USD => Currency::usd(units),
+            CurrencyCode::EUR => Currency::eur(units),
+            CurrencyCode::GBP => Currency::gbp(units),
+            CurrencyCode::JPY => Currency::jpy(units),
+            CurrencyCode::AUD => Currency::aud(units),
+        }
+    }
+
+    /** The ISO 4217 code for this currency. */
+    pub fn code(&self) -> CurrencyCode {
+        match self {
+            Currency::USD(_) => CurrencyCode::USD,
+            Currency::EUR(_) => CurrencyCode::EUR,
+            Currency::GBP(_) => CurrencyCode::GBP,
+            Currency::JPY(_) => CurrencyCode::JPY,
+            Currency::AUD(_) => CurrencyCode::AUD,
+        }
+    }
+
+    /** The value of this currency in its minor units. */
+    pub fn minor_units(&self) -> u64 {
+        match self {
+            Currency::USD(value) => value.cents,
+            Currency::EUR(value) => value.cents,
+            Currency::GBP(value) => value.pence,
+            Currency::JPY(value) => value.yen,
+            Currency::AUD(value) => value.cents,
+        }
+    }
+
+    /** A zero value in the given currency. */
+    pub fn zero(code: CurrencyCode) -> Self {
+        Currency::from_minor_units(code, 0)
+    }
+
+    /**
+    Add two currency values together.
+
+    The values must use the same currency.
+    This method will return an error instead of overflowing.
+    */
+    pub fn checked_add(self, other: Currency) -> Result<Currency, Error> {
+        if self.code() != other.code() {
+            return Err(error::bad_input(format_args!(
+                "can't add {} to {}",
+                other.code(),
+                self.code()
+            )));
+        }
+
+        let units = self
+            .minor_units()
+            .checked_add(other.minor_units())
+            .ok_or_else(|| error::bad_input("currency value is too large"))?;
+
+        Ok(Currency::from_minor_units(self.code(), units))
+    }
+
+    /**
+    Multiply a currency value by a quantity.
+
+    This method will return an error instead of overflowing.
+    */
+    pub fn checked_mul(self, quantity: u32) -> Result<Currency, Error> {
+        let units = self
+            .minor_units()
+            .checked_mul(u64::from(quantity))
+            .ok_or_else(|| error::bad_input("currency value is too large"))?;
+
+        Ok(Currency::from_minor_units(self.code(), units))
+    }
+
+    /**
+    Sum a set of currency values in the given currency.
+
+    All values must use the given currency. If there are no values then the result is zero.
+    This method will return an error instead of overflowing.
+    */
+    pub fn checked_sum(
+        code: CurrencyCode,
+        values: impl IntoIterator<Item = Currency>,
+    ) -> Result<Currency, Error> {
+        values
+            .into_iter()
+            .try_fold(Currency::zero(code), Currency::checked_add)
+    }
+}
+
+/**
+An ISO 4217 currency code.
+
+Codes serialize as their uppercase three-letter representation, like `"USD"`.
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum CurrencyCode {
+    USD,
+    EUR,
+    GBP,
+    JPY,
+    AUD,
+}
+
+impl CurrencyCode {
+    /** The number of decimal places between the major and minor units of the currency. */
+    pub fn exponent(&self) -> u32 {
+        match self {
+            CurrencyCode::JPY => 0,
+            CurrencyCode::USD | CurrencyCode::EUR | CurrencyCode::GBP | CurrencyCode::AUD => 2,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CurrencyCode::USD => "USD",
+            CurrencyCode::EUR => "EUR",
+            CurrencyCode::GBP => "GBP",
+            CurrencyCode::JPY => "JPY",
+            CurrencyCode::AUD => "AUD",
+        }
+    }
+}
+
+impl fmt::Display for CurrencyCode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for CurrencyCode {
+    type Err = Error;
+
+    fn from_str(code: &str) -> Result<Self, Self::Err> {
+        match code.to_ascii_uppercase().as_str() {
+            "USD" => Ok(CurrencyCode::USD),
+            "EUR" => Ok(CurrencyCode::EUR),
+            "GBP" => Ok(CurrencyCode::GBP),
+            "JPY" => Ok(CurrencyCode::JPY),
+            "AUD" => Ok(CurrencyCode::AUD),
+            _ => Err(error::bad_input(format_args!(
+                "`{}` is not a supported currency",
+                code
+            ))),
+        }
+    }
+}
+
+impl<'a> TryFrom<&'a str> for CurrencyCode {
+    type Error = Error;
+
+    fn try_from(code: &'a str) -> Result<Self, Self::Error> {
+        code.parse()
+    }
+}
+
+/**
+How to round a calculated value that falls between two minor units.
+
+The default is `HalfEven`, which rounds halfway values to the nearest even unit.
+It avoids consistently favouring either the shop or the shopper over many calculations.
+*/
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Rounding {
+    /** Round halfway values to the nearest even unit. */
+    #[default]
+    HalfEven,
+    /** Round halfway values away from zero. */
+    HalfUp,
+    /** Always round towards zero. */
+    Down,
+    /** Always round away from zero. */
+    Up,
+}
+
+impl Rounding {
+    /** Divide two numbers, rounding the result to a whole number. */
+    pub(in crate::domain) fn divide(self, numerator: u128, denominator: u128) -> u128 {
+        let quotient = numerator / denominator;
+        let remainder = numerator % denominator;
+
+        // Compare the remainder to half the denominator without overflowing
+        let half = remainder.cmp(&(denominator - remainder));
+
+        let round_up = match self {
+            Rounding::Down => false,
+            Rounding::Up => remainder > 0,
+            Rounding::HalfUp => half.is_ge(),
+            Rounding::HalfEven => half.is_gt() || (half.is_eq() && quotient % 2 == 1),
+        };
+
+        if round_up { quotient + 1 } else { quotient }
+    }
 }
 
 /**
@@ -31,3 +268,173 @@ impl USD {
         USD { cents }
     }
 }
+
+/**
+A currency value in EUR.
+
+The value is encoded as whole cents.
+*/
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+pub struct EUR {
+    cents: u64,
+}
+
+impl EUR {
+    pub fn new(cents: u64) -> Self {
+        EUR { cents }
+    }
+}
+
+/**
+A currency value in GBP.
+
+The value is encoded as whole pence.
+*/
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+pub struct GBP {
+    pence: u64,
+}
+
+impl GBP {
+    pub fn new(pence: u64) -> Self {
+        GBP { pence }
+    }
+}
+
+/**
+A currency value in JPY.
+
+The yen has no minor unit, so the value is encoded as whole yen.
+*/
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+pub struct JPY {
+    yen: u64,
+}
+
+impl JPY {
+    pub fn new(yen: u64) -> Self {
+        JPY { yen }
+    }
+}
+
+/**
+A currency value in AUD.
+
+The value is encoded as whole cents.
+*/
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+pub struct AUD {
+    cents: u64,
+}
+
+impl AUD {
+    pub fn new(cents: u64) -> Self {
+        AUD { cents }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serde_roundtrip() {
+        for (currency, json) in [
+            (Currency::usd(123), r#"{"usd":{"cents":123}}"#),
+            (Currency::eur(123), r#"{"eur":{"cents":123}}"#),
+            (Currency::gbp(123), r#"{"gbp":{"pence":123}}"#),
+            (Currency::jpy(123), r#"{"jpy":{"yen":123}}"#),
+            (Currency::aud(123), r#"{"aud":{"cents":123}}"#),
+        ] {
+            assert_eq!(json, serde_json::to_string(&currency).unwrap());
+            assert_eq!(currency, serde_json::from_str::<Currency>(json).unwrap());
+        }
+    }
+
+    #[test]
+    fn minor_units_roundtrip() {
+        for code in [
+            CurrencyCode::USD,
+            CurrencyCode::EUR,
+            CurrencyCode::GBP,
+            CurrencyCode::JPY,
+            CurrencyCode::AUD,
+        ] {
+            let currency = Currency::from_minor_units(code, 500);
+
+            assert_eq!(code, currency.code());
+            assert_eq!(500, currency.minor_units());
+        }
+    }
+
+    #[test]
+    fn checked_arithmetic() {
+        assert_eq!(
+            Currency::usd(300),
+            Currency::usd(100).checked_add(Currency::usd(200)).unwrap()
+        );
+        assert_eq!(
+            Currency::jpy(500),
+            Currency::jpy(100).checked_mul(5).unwrap()
+        );
+        assert_eq!(
+            Currency::eur(600),
+            Currency::checked_sum(
+                CurrencyCode::EUR,
+                [Currency::eur(100), Currency::eur(200), Currency::eur(300)]
+            )
+            .unwrap()
+        );
+        assert_eq!(
+            Currency::gbp(0),
+            Currency::checked_sum(CurrencyCode::GBP, []).unwrap()
+        );
+    }
+
+    #[test]
+    fn checked_arithmetic_errors() {
+        assert!(Currency::usd(100).checked_add(Currency::eur(100)).is_err());
+        assert!(
+            Currency::usd(u64::MAX)
+                .checked_add(Currency::usd(1))
+                .is_err()
+        );
+        assert!(Currency::usd(u64::MAX).checked_mul(2).is_err());
+        assert!(Currency::checked_sum(CurrencyCode::USD, [Currency::aud(1)]).is_err());
+    }
+
+    #[test]
+    fn rounding() {
+        for (rounding, expected) in [
+            (Rounding::HalfEven, [0, 2, 2, 2]),
+            (Rounding::HalfUp, [1, 2, 2, 3]),
+            (Rounding::Down, [0, 1, 2, 2]),
+            (Rounding::Up, [1, 2, 2, 3]),
+        ] {
+            // 1/2, 3/2, 4/2, 5/2
+            for (numerator, expected) in [1, 3, 4, 5].into_iter().zip(expected) {
+                assert_eq!(
+                    expected,
+                    rounding.divide(numerator, 2),
+                    "{:?} {}",
+                    rounding,
+                    numerator
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn exponents() {
+        assert_eq!(2, CurrencyCode::USD.exponent());
+        assert_eq!(0, CurrencyCode::JPY.exponent());
+    }
+
+    #[test]
+    fn parse_code() {
+        assert_eq!(CurrencyCode::EUR, "eur".parse::<CurrencyCode>().unwrap());
+        assert_eq!(CurrencyCode::GBP, "GBP".parse::<CurrencyCode>().unwrap());
+
+        assert!("XYZ".parse::<CurrencyCode>().is_err());
+    }
+}
@@ -4,6 +4,9 @@ Defines the constraints that all entities must satisfy.
 This trait is really just a marker for ensuring all entities follow a basic structure.
 It's a checklist: the first thing to do when creating a new entity is to implement this trait and fill in the blanks.
 Any changes to entities that should be consistent can be added here.
+
+Entities can derive `Clone` so queries can share a loaded entity between callers.
+A stale clone can't overwrite newer changes, because its version won't match when it's stored.
 */
 
 #[allow(dead_code)]
@@ -1,11 +1,41 @@
 use serde::Serialize;
 
-use crate::domain::infra::Resolver;
+use crate::domain::infra::{
+    CommandOutput,
+    Resolver,
+};
 
 use std::future::Future;
 
 pub trait CommandArgs {
     type Output;
+
+    /**
+    The input to record in the audit log.
+
+    This is the serialized input by default.
+    Commands with large inputs can record a summary of them instead.
+    */
+    fn audit_args(&self) -> Result<serde_json::Value, serde_json::Error>
+    where
+        Self: Serialize,
+    {
+        serde_json::to_value(self)
+    }
+
+    /**
+    The input compared when an idempotency key is reused.
+
+    This is the serialized input by default.
+    Commands should leave out input the server generates itself, like the ids of new entities,
+    so a retried request still matches the original one.
+    */
+    fn idempotency_args(&self) -> Result<serde_json::Value, serde_json::Error>
+    where
+        Self: Serialize,
+    {
+        serde_json::to_value(self)
+    }
 }
 
 pub trait Command<TArgs: CommandArgs> {
@@ -45,19 +75,39 @@ where
 }
 
 impl Resolver {
+    /**
+    Resolve a command.
+
+    Each execution of the command is recorded in the audit log. Successful executions are recorded
+    in the same transaction as the changes they make, and failed ones in a transaction of their own.
+    If the entry can't be recorded then the command fails.
+    */
     pub(in crate::domain) fn command<TArgs, TCommand, TFuture>(
         &self,
         command: TCommand,
     ) -> impl Command<TArgs>
     where
         TArgs: CommandArgs + Serialize + Send + 'static,
+        TArgs::Output: CommandOutput,
         TCommand: FnOnce(Resolver, TArgs) -> TFuture + Send,
         TFuture: Future<Output = TArgs::Output> + Send,
     {
         let resolver = self.by_ref();
         move |input: TArgs| {
             let resolver = resolver.by_ref();
-            command(resolver, input)
+            async move {
+                let args = match input.audit_args() {
+                    Ok(args) => args,
+                    Err(err) => return TArgs::Output::from_error(err.into()),
+                };
+
+                let output = command(resolver.by_ref(), input).await;
+
+                match resolver.audit_command::<TArgs>(args, &output) {
+                    Ok(()) => output,
+                    Err(err) => TArgs::Output::from_error(err),
+                }
+            }
         }
     }
 
@@ -23,11 +23,18 @@ use std::{
         Hasher,
     },
     marker::PhantomData,
+    sync::atomic::{
+        AtomicU64,
+        Ordering as AtomicOrdering,
+    },
 };
 use uuid::Uuid;
 
 use crate::{
-    domain::error::Error,
+    domain::error::{
+        self,
+        Error,
+    },
     store,
 };
 
@@ -65,7 +72,7 @@ impl<T> fmt::Display for Id<T> {
 
 impl<T> Clone for Id<T> {
     fn clone(&self) -> Self {
-        Id(self.0, PhantomData)
+        *self
     }
 }
 
@@ -81,7 +88,7 @@ impl<T> Eq for Id<T> {}
 
 impl<T> PartialOrd for Id<T> {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        self.0.partial_cmp(&other.0)
+        Some(self.cmp(other))
     }
 }
 
@@ -108,7 +115,9 @@ impl<'a, T> TryFrom<&'a str> for Id<T> {
     type Error = Error;
 
     fn try_from(id: &'a str) -> Result<Self, Self::Error> {
-        Ok(Id(Uuid::parse_str(id)?, PhantomData))
+        let id = Uuid::parse_str(id).map_err(error::bad_input)?;
+
+        Ok(Id(id, PhantomData))
     }
 }
 
@@ -147,7 +156,12 @@ impl<T> IdProvider<T> for Id<T> {
     }
 }
 
-/** Generate a new `Id` randomly. */
+/**
+Generate a new `Id` randomly.
+
+Random ids don't carry any ordering, so entities created one after the other won't sort together.
+Use `TimeOrderedId` for ids that sort by the time they were created.
+*/
 pub struct NextId<T>(PhantomData<T>);
 
 impl<T> Default for NextId<T> {
@@ -171,3 +185,111 @@ impl<T> IdProvider<T> for NextId<T> {
         Ok(self.next())
     }
 }
+
+/**
+Generate a new `Id` that sorts by the time it was created.
+
+Ids are UUIDv7s, with a millisecond Unix timestamp in their most significant bits.
+Ids generated within the same millisecond by the same process still sort in the order they were generated.
+*/
+pub struct TimeOrderedId<T>(PhantomData<T>);
+
+impl<T> Default for TimeOrderedId<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> TimeOrderedId<T> {
+    pub fn new() -> Self {
+        TimeOrderedId(PhantomData)
+    }
+
+    pub fn next(&self) -> Id<T> {
+        Id(Uuid::now_v7(), PhantomData)
+    }
+}
+
+impl<T> IdProvider<T> for TimeOrderedId<T> {
+    fn get(&self) -> Result<Id<T>, Error> {
+        Ok(self.next())
+    }
+}
+
+/**
+Generate a new `Id` from a counter.
+
+Ids are deterministic, starting from `00000000-0000-0000-0000-000000000001`, so they're useful in tests.
+They aren't unique across instances, so shouldn't be used outside of tests.
+*/
+pub struct SequentialId<T>(AtomicU64, PhantomData<T>);
+
+impl<T> Default for SequentialId<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> SequentialId<T> {
+    pub fn new() -> Self {
+        Self::starting_at(1)
+    }
+
+    /** Start counting from a given value instead of `1`. */
+    pub fn starting_at(start: u64) -> Self {
+        SequentialId(AtomicU64::new(start), PhantomData)
+    }
+
+    pub fn next(&self) -> Result<Id<T>, Error> {
+        let next = self
+            .0
+            .fetch_update(AtomicOrdering::Relaxed, AtomicOrdering::Relaxed, |n| {
+                n.checked_add(1)
+            })
+            .map_err(|_| error::msg("sequential ids have been exhausted"))?;
+
+        Ok(Id(Uuid::from_u128(u128::from(next)), PhantomData))
+    }
+}
+
+impl<T> IdProvider<T> for SequentialId<T> {
+    fn get(&self) -> Result<Id<T>, Error> {
+        self.next()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn time_ordered_ids_sort_by_creation() {
+        let provider = TimeOrderedId::<()>::new();
+
+        let ids: Vec<_> = (0..100).map(|_| provider.get().unwrap()).collect();
+
+        let mut sorted = ids.clone();
+        sorted.sort();
+
+        assert_eq!(ids, sorted);
+        assert_eq!(Some(uuid::Version::SortRand), ids[0].0.get_version());
+    }
+
+    #[test]
+    fn sequential_ids_count_up() {
+        let provider = SequentialId::<()>::new();
+
+        assert_eq!(
+            "00000000-0000-0000-0000-000000000001",
+            provider.get().unwrap().to_string()
+        );
+        assert_eq!(
+            "00000000-0000-0000-0000-000000000002",
+            provider.get().unwrap().to_string()
+        );
+
+        let provider = SequentialId::<()>::starting_at(u64::MAX);
+
+        provider.get().unwrap_err();
+    }
+}
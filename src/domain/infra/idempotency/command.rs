@@ -0,0 +1,340 @@
+/*! Contains the `Idempotent` command wrapper. */
+
+use std::{
+    any,
+    time::Duration,
+};
+
+use serde::{
+    Serialize,
+    de::DeserializeOwned,
+};
+use uuid::Uuid;
+
+use crate::domain::{
+    Error,
+    error,
+    infra::{
+        idempotency::store::{
+            IdempotencyStore,
+            IdempotentResultData,
+            IdempotentResultVersion,
+        },
+        *,
+    },
+};
+
+/**
+Input for a command that may be retried.
+
+If a key is given then the result of the wrapped command is stored alongside it.
+Executing a command again with the same key will return that stored result instead of
+running the command a second time.
+Reusing a key with different args is a conflict.
+*/
+#[derive(Clone, Serialize)]
+pub struct Idempotent<TArgs> {
+    pub key: Option<IdempotencyKey>,
+    pub args: TArgs,
+}
+
+impl<TArgs> CommandArgs for Idempotent<TArgs>
+where
+    TArgs: CommandArgs + Serialize,
+{
+    type Output = TArgs::Output;
+
+    fn audit_args(&self) -> Result<serde_json::Value, serde_json::Error> {
+        Ok(serde_json::json!({
+            "key": self.key,
+            "args": self.args.audit_args()?,
+        }))
+    }
+}
+
+// The namespace used to derive digests of command args
+const NAMESPACE: Uuid = Uuid::from_u128(0x8e41_2b6d_c93f_4a17_a0d5_63e9_1f2c_7b84);
+
+/** Default implementation for an idempotent command. */
+async fn execute<TArgs, T>(
+    command: Idempotent<TArgs>,
+    transaction: ActiveTransaction,
+    store: impl IdempotencyStore,
+    clock: impl Clock,
+    retention: Duration,
+    inner: impl Command<TArgs>,
+) -> Result<T, Error>
+where
+    TArgs: CommandArgs<Output = Result<T, Error>> + Serialize,
+    T: Serialize + DeserializeOwned,
+{
+    let Some(key) = command.key else {
+        return inner.execute(command.args).await;
+    };
+
+    let name = any::type_name::<TArgs>();
+    let args = Uuid::new_v5(
+        &NAMESPACE,
+        &serde_json::to_vec(&command.args.idempotency_args()?)?,
+    );
+    let now = clock.now();
+
+    let existing = store.get_result(name, &key)?;
+
+    // If the command has already been executed within the retention window then
+    // return its original result without executing it again
+    let version = match existing {
+        Some(existing) => {
+            let expired = existing
+                .recorded_at
+                .checked_add(retention)
+                .map(|expires_at| expires_at <= now)
+                .unwrap_or(false);
+
+            if !expired {
+                if existing.args != args {
+                    return Err(error::conflict(
+                        "the idempotency key has already been used with different input",
+                    ));
+                }
+
+                emit::debug!("replaying result for {command: name} with key {key}");
+
+                return Ok(serde_json::from_value(existing.result)?);
+            }
+
+            existing.version
+        }
+        None => IdempotentResultVersion::default(),
+    };
+
+    // Errors aren't stored, so a failed command can be retried with the same key
+    let result = inner.execute(command.args).await?;
+
+    store.set_result(
+        transaction.get(),
+        IdempotentResultData {
+            command: name.to_owned(),
+            key,
+            args,
+            version,
+            recorded_at: now,
+            result: serde_json::to_value(&result)?,
+        },
+    )?;
+
+    Ok(result)
+}
+
+impl Resolver {
+    /**
+    Make a command idempotent.
+
+    The result of the command is stored in the same transaction as any changes it makes,
+    and replayed for duplicate submissions using the same key within the retention window.
+    */
+    pub fn idempotent_command<TArgs, T>(
+        &self,
+        command: impl Command<TArgs> + Send,
+    ) -> impl Command<Idempotent<TArgs>>
+    where
+        TArgs: CommandArgs<Output = Result<T, Error>> + Serialize + Send + 'static,
+        T: Serialize + DeserializeOwned + Send,
+    {
+        self.command(move |resolver, args: Idempotent<TArgs>| async move {
+            let store = resolver.idempotency_store();
+            let active_transaction = resolver.active_transaction();
+            let clock = resolver.clock();
+            let retention = resolver.idempotency_retention();
+
+            execute(args, active_transaction, store, clock, retention, command).await
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        convert::TryFrom,
+        sync::atomic::{
+            AtomicUsize,
+            Ordering,
+        },
+        time::SystemTime,
+    };
+
+    use super::*;
+
+    use crate::domain::{
+        ErrorKind,
+        infra::idempotency::store::in_memory_store,
+    };
+
+    #[derive(Clone, Serialize)]
+    struct Increment {
+        by: usize,
+    }
+
+    impl CommandArgs for Increment {
+        type Output = Result<usize, Error>;
+    }
+
+    fn increment(counter: &AtomicUsize) -> impl Command<Increment> + '_ {
+        move |args: Increment| async move { Ok(counter.fetch_add(args.by, Ordering::SeqCst) + args.by) }
+    }
+
+    fn args(key: &str) -> Idempotent<Increment> {
+        Idempotent {
+            key: Some(IdempotencyKey::try_from(key).unwrap()),
+            args: Increment { by: 1 },
+        }
+    }
+
+    const RETENTION: Duration = Duration::from_secs(60);
+
+    #[tokio::test]
+    async fn replay_result_for_same_key() {
+        let store = in_memory_store(Default::default());
+        let counter = AtomicUsize::new(0);
+        let now = SystemTime::now();
+
+        let first = execute(
+            args("a"),
+            ActiveTransaction::none(),
+            &store,
+            now,
+            RETENTION,
+            increment(&counter),
+        )
+        .await
+        .unwrap();
+
+        let second = execute(
+            args("a"),
+            ActiveTransaction::none(),
+            &store,
+            now + Duration::from_secs(1),
+            RETENTION,
+            increment(&counter),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(1, first);
+        assert_eq!(1, second);
+        assert_eq!(1, counter.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn err_if_same_key_has_different_args() {
+        let store = in_memory_store(Default::default());
+        let counter = AtomicUsize::new(0);
+        let now = SystemTime::now();
+
+        execute(
+            args("a"),
+            ActiveTransaction::none(),
+            &store,
+            now,
+            RETENTION,
+            increment(&counter),
+        )
+        .await
+        .unwrap();
+
+        let err = execute(
+            Idempotent {
+                args: Increment { by: 2 },
+                ..args("a")
+            },
+            ActiveTransaction::none(),
+            &store,
+            now,
+            RETENTION,
+            increment(&counter),
+        )
+        .await
+        .err()
+        .unwrap();
+
+        assert_eq!(ErrorKind::Conflict, err.kind());
+        assert_eq!(1, counter.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn execute_again_for_different_key() {
+        let store = in_memory_store(Default::default());
+        let counter = AtomicUsize::new(0);
+        let now = SystemTime::now();
+
+        for key in ["a", "b"] {
+            execute(
+                args(key),
+                ActiveTransaction::none(),
+                &store,
+                now,
+                RETENTION,
+                increment(&counter),
+            )
+            .await
+            .unwrap();
+        }
+
+        assert_eq!(2, counter.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn execute_again_after_retention() {
+        let store = in_memory_store(Default::default());
+        let counter = AtomicUsize::new(0);
+        let now = SystemTime::now();
+
+        execute(
+            args("a"),
+            ActiveTransaction::none(),
+            &store,
+            now,
+            RETENTION,
+            increment(&counter),
+        )
+        .await
+        .unwrap();
+
+        let executed = execute(
+            args("a"),
+            ActiveTransaction::none(),
+            &store,
+            now + RETENTION,
+            RETENTION,
+            increment(&counter),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(2, executed);
+    }
+
+    #[tokio::test]
+    async fn errors_are_not_stored() {
+        let store = in_memory_store(Default::default());
+        let now = SystemTime::now();
+
+        let result = execute(
+            args("a"),
+            ActiveTransaction::none(),
+            &store,
+            now,
+            RETENTION,
+            |_: Increment| async { Err::<usize, _>(error::msg("failed")) },
+        )
+        .await;
+
+        assert!(result.is_err());
+        assert!(
+            store
+                .get_result(any::type_name::<Increment>(), &args("a").key.unwrap())
+                .unwrap()
+                .is_none()
+        );
+    }
+}
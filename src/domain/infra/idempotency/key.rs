@@ -0,0 +1,89 @@
+use serde::de::{
+    Deserialize,
+    Deserializer,
+    Error as _,
+};
+use std::{
+    convert::TryFrom,
+    fmt,
+};
+
+use crate::domain::{
+    Error,
+    error,
+};
+
+/**
+An idempotency key.
+
+Keys are chosen by clients and identify a single logical request, no matter how many times it's
+retried. The key must be between 1 and 255 visible ASCII characters.
+*/
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+#[serde(transparent)]
+pub struct IdempotencyKey(String);
+
+impl IdempotencyKey {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for IdempotencyKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+impl TryFrom<String> for IdempotencyKey {
+    type Error = Error;
+
+    fn try_from(key: String) -> Result<Self, Self::Error> {
+        if key.is_empty() || key.len() > 255 {
+            return Err(error::bad_input(
+                "idempotency key must be between 1 and 255 characters",
+            ));
+        }
+
+        if !key.bytes().all(|b| b.is_ascii_graphic()) {
+            return Err(error::bad_input(
+                "idempotency key must only contain visible ASCII characters",
+            ));
+        }
+
+        Ok(IdempotencyKey(key))
+    }
+}
+
+impl<'a> TryFrom<&'a str> for IdempotencyKey {
+    type Error = Error;
+
+    fn try_from(key: &'a str) -> Result<Self, Self::Error> {
+        Self::try_from(key.to_owned())
+    }
+}
+
+impl<'de> Deserialize<'de> for IdempotencyKey {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let key = String::deserialize(deserializer)?;
+
+        IdempotencyKey::try_from(key).map_err(D::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn key_must_be_valid() {
+        assert!(IdempotencyKey::try_from("").is_err());
+        assert!(IdempotencyKey::try_from("a key").is_err());
+        assert!(IdempotencyKey::try_from("a".repeat(256)).is_err());
+
+        assert!(IdempotencyKey::try_from("4b4c7b6a-a9b2-4b41-9e43-5b0d0d5b7a61").is_ok());
+    }
+}
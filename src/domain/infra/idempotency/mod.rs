@@ -0,0 +1,18 @@
+/*!
+Idempotent command execution.
+
+Clients that time out waiting for a response can't tell whether their command was applied or not.
+Commands wrapped in `Idempotent` store their result under a client-supplied key in the same transaction
+as the changes they make, so retrying with the same key replays that result instead of applying the
+changes a second time.
+*/
+
+mod command;
+mod key;
+pub(in crate::domain) mod resolver;
+pub(in crate::domain) mod store;
+
+pub use self::{
+    command::*,
+    key::*,
+};
@@ -0,0 +1,58 @@
+use std::{
+    sync::Arc,
+    time::Duration,
+};
+
+use crate::domain::infra::{
+    idempotency::store::{
+        self,
+        IdempotencyStore,
+        InMemoryStore,
+    },
+    *,
+};
+
+/**
+The default length of time results are kept for replaying.
+
+Clients retrying a request after this window has passed will execute the command again.
+*/
+const DEFAULT_RETENTION: Duration = Duration::from_secs(24 * 60 * 60);
+
+/**
+Resolver for idempotent commands.
+*/
+#[derive(Clone)]
+pub(in crate::domain) struct IdempotencyResolver {
+    idempotency_store: Register<Arc<InMemoryStore>>,
+    retention: Register<Duration>,
+}
+
+impl Default for IdempotencyResolver {
+    fn default() -> Self {
+        IdempotencyResolver {
+            idempotency_store: Register::per_tenant(|resolver| {
+                Arc::new(store::in_memory_store(resolver.transaction_store()))
+            }),
+            retention: Register::once(|_| DEFAULT_RETENTION),
+        }
+    }
+}
+
+impl AppBuilder {
+    /** Use a different length of time to keep the results of idempotent commands for. */
+    pub fn idempotency_retention(mut self, retention: Register<Duration>) -> Self {
+        self.root_resolver.idempotency_resolver.retention = retention;
+        self
+    }
+}
+
+impl Resolver {
+    pub(in crate::domain::infra::idempotency) fn idempotency_store(&self) -> impl IdempotencyStore {
+        self.resolve(&self.idempotency_resolver.idempotency_store)
+    }
+
+    pub(in crate::domain::infra::idempotency) fn idempotency_retention(&self) -> Duration {
+        self.resolve(&self.idempotency_resolver.retention)
+    }
+}
@@ -0,0 +1,102 @@
+/*! Persistent storage for idempotent command results. */
+
+use std::time::SystemTime;
+
+use uuid::Uuid;
+
+use crate::{
+    domain::{
+        Error,
+        infra::*,
+    },
+    store::{
+        self,
+        Transaction,
+        TransactionStore,
+        TransactionValueStore,
+    },
+};
+
+pub(in crate::domain) type IdempotentResultVersion = Version<IdempotentResultData>;
+
+/**
+The stored result of a command that was executed with an idempotency key.
+
+Results are scoped to the command that produced them, so the same key can be
+used with different commands without them interfering with each other.
+A digest of the command's input is kept so a key can't be reused with different input.
+*/
+#[derive(Clone, Serialize, Deserialize)]
+pub(in crate::domain) struct IdempotentResultData {
+    pub command: String,
+    pub key: IdempotencyKey,
+    pub args: Uuid,
+    pub version: IdempotentResultVersion,
+    pub recorded_at: SystemTime,
+    pub result: serde_json::Value,
+}
+
+/** A place to persist and fetch the results of idempotent commands. */
+#[auto_impl(&, Arc)]
+pub(in crate::domain) trait IdempotencyStore {
+    fn get_result(
+        &self,
+        command: &str,
+        key: &IdempotencyKey,
+    ) -> Result<Option<IdempotentResultData>, Error>;
+    fn set_result(
+        &self,
+        transaction: &Transaction,
+        result: IdempotentResultData,
+    ) -> Result<(), Error>;
+}
+
+/** A test in-memory idempotency store. */
+pub(in crate::domain) struct InMemoryStore(TransactionValueStore<IdempotentResultData>);
+
+// The namespace used to derive storage ids from command names and idempotency keys
+const NAMESPACE: Uuid = Uuid::from_u128(0x5c1a_3ef4_8d0b_4d5e_9a63_2f7e_1b8c_4a90);
+
+fn result_id(command: &str, key: &IdempotencyKey) -> store::Id {
+    let name = format!("{}:{}", command, key);
+
+    store::Id::from_raw(Uuid::new_v5(&NAMESPACE, name.as_bytes()))
+}
+
+impl IdempotencyStore for InMemoryStore {
+    fn get_result(
+        &self,
+        command: &str,
+        key: &IdempotencyKey,
+    ) -> Result<Option<IdempotentResultData>, Error> {
+        if let Some((version, data)) = self.0.get(result_id(command, key)) {
+            assert_eq!(version, data.version.into());
+
+            Ok(Some(data))
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn set_result(
+        &self,
+        transaction: &Transaction,
+        mut result: IdempotentResultData,
+    ) -> Result<(), Error> {
+        let id = result_id(&result.command, &result.key);
+
+        self.0.set(
+            transaction,
+            id,
+            Some(result.version),
+            result.version.next()?,
+            result,
+        )?;
+
+        Ok(())
+    }
+}
+
+pub(in crate::domain) fn in_memory_store(transaction_store: TransactionStore) -> InMemoryStore {
+    InMemoryStore(TransactionValueStore::new(transaction_store))
+}
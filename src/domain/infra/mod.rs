@@ -5,21 +5,40 @@ This module contains shared types like `Currency` and services like `Resolver` t
 domain modules can use.
 */
 
+pub(in crate::domain) mod audit;
+pub(in crate::domain) mod batch;
+pub(in crate::domain) mod cache;
+pub(in crate::domain) mod clock;
+pub(in crate::domain) mod csv;
 pub(in crate::domain) mod currency;
 pub(in crate::domain) mod entity;
 pub mod func;
 pub(in crate::domain) mod id;
+pub(in crate::domain) mod idempotency;
 pub(in crate::domain) mod resolver;
+pub(in crate::domain) mod saga;
+pub(in crate::domain) mod search;
+pub(in crate::domain) mod tenant;
 pub(in crate::domain) mod transaction;
 pub(in crate::domain) mod version;
 
 pub use self::{
+    audit::*,
+    cache::*,
+    clock::*,
     currency::*,
     func::*,
     id::*,
+    idempotency::*,
     resolver::*,
+    saga::*,
+    tenant::*,
     transaction::*,
     version::*,
 };
 
-pub(in crate::domain) use self::entity::*;
+pub(in crate::domain) use self::{
+    batch::*,
+    entity::*,
+    search::*,
+};
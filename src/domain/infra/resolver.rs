@@ -5,14 +5,32 @@ use std::sync::Arc;
 use once_cell::sync::OnceCell;
 
 use crate::domain::{
+    categories::resolver::CategoriesResolver,
     customers::resolver::CustomersResolver,
-    infra::transaction::resolver::TransactionsResolver,
+    exchange_rates::resolver::ExchangeRatesResolver,
+    infra::{
+        audit::resolver::AuditResolver,
+        batch::resolver::BatchResolver,
+        cache::resolver::CacheResolver,
+        clock::ClockResolver,
+        idempotency::resolver::IdempotencyResolver,
+        saga::resolver::SagaResolver,
+        tenant::TenantResolver,
+        transaction::resolver::TransactionsResolver,
+    },
+    inventory::resolver::InventoryResolver,
+    jobs::resolver::JobsResolver,
     orders::resolver::OrdersResolver,
     products::resolver::ProductsResolver,
+    promotions::resolver::PromotionsResolver,
+    taxes::resolver::TaxesResolver,
 };
 
 /**
 The app.
+
+An app with the default registrations can be created with `App::new`.
+Use `App::builder` to override some of them before creating the app.
 */
 pub struct App {
     pub(in crate::domain) root_resolver: Resolver,
@@ -26,15 +44,73 @@ impl Default for App {
 
 impl App {
     pub fn new() -> Self {
+        App::builder().build()
+    }
+
+    pub fn builder() -> AppBuilder {
+        AppBuilder::new()
+    }
+
+    /**
+    Get another handle to the app.
+
+    The handle shares all of the app's registrations, so it can be moved into background tasks.
+    */
+    pub fn by_ref(&self) -> App {
         App {
+            root_resolver: self.root_resolver.by_ref(),
+        }
+    }
+}
+
+/**
+A builder for an app.
+
+The builder starts with the default registrations for the app.
+Methods for replacing registrations live alongside the resolvers that own them.
+Registrations that depend on other values, like the transaction store, are given the
+resolver of the app being built.
+*/
+pub struct AppBuilder {
+    pub(in crate::domain) root_resolver: Resolver,
+}
+
+impl Default for AppBuilder {
+    fn default() -> Self {
+        AppBuilder::new()
+    }
+}
+
+impl AppBuilder {
+    pub fn new() -> Self {
+        AppBuilder {
             root_resolver: Resolver {
                 transactions_resolver: Default::default(),
+                tenant_resolver: Default::default(),
+                clock_resolver: Default::default(),
+                cache_resolver: Default::default(),
+                batch_resolver: Default::default(),
+                idempotency_resolver: Default::default(),
+                saga_resolver: Default::default(),
+                audit_resolver: Default::default(),
                 products_resolver: Default::default(),
+                categories_resolver: Default::default(),
+                inventory_resolver: Default::default(),
                 orders_resolver: Default::default(),
                 customers_resolver: Default::default(),
+                exchange_rates_resolver: Default::default(),
+                taxes_resolver: Default::default(),
+                promotions_resolver: Default::default(),
+                jobs_resolver: Default::default(),
             },
         }
     }
+
+    pub fn build(self) -> App {
+        App {
+            root_resolver: self.root_resolver,
+        }
+    }
 }
 
 /**
@@ -48,18 +124,44 @@ Commands and queries are resolved from this `Resolver`.
 */
 pub struct Resolver {
     pub(in crate::domain) transactions_resolver: TransactionsResolver,
+    pub(in crate::domain) tenant_resolver: TenantResolver,
+    pub(in crate::domain) clock_resolver: ClockResolver,
+    pub(in crate::domain) cache_resolver: CacheResolver,
+    pub(in crate::domain) batch_resolver: BatchResolver,
+    pub(in crate::domain) idempotency_resolver: IdempotencyResolver,
+    pub(in crate::domain) saga_resolver: SagaResolver,
+    pub(in crate::domain) audit_resolver: AuditResolver,
     pub(in crate::domain) products_resolver: ProductsResolver,
+    pub(in crate::domain) categories_resolver: CategoriesResolver,
+    pub(in crate::domain) inventory_resolver: InventoryResolver,
     pub(in crate::domain) orders_resolver: OrdersResolver,
     pub(in crate::domain) customers_resolver: CustomersResolver,
+    pub(in crate::domain) exchange_rates_resolver: ExchangeRatesResolver,
+    pub(in crate::domain) taxes_resolver: TaxesResolver,
+    pub(in crate::domain) promotions_resolver: PromotionsResolver,
+    pub(in crate::domain) jobs_resolver: JobsResolver,
 }
 
 impl Resolver {
     pub(in crate::domain) fn by_ref(&self) -> Self {
         Resolver {
             transactions_resolver: self.transactions_resolver.clone(),
+            tenant_resolver: self.tenant_resolver.clone(),
+            clock_resolver: self.clock_resolver.clone(),
+            cache_resolver: self.cache_resolver.clone(),
+            batch_resolver: self.batch_resolver.clone(),
+            idempotency_resolver: self.idempotency_resolver.clone(),
+            saga_resolver: self.saga_resolver.clone(),
+            audit_resolver: self.audit_resolver.clone(),
             products_resolver: self.products_resolver.clone(),
+            categories_resolver: self.categories_resolver.clone(),
+            inventory_resolver: self.inventory_resolver.clone(),
             orders_resolver: self.orders_resolver.clone(),
             customers_resolver: self.customers_resolver.clone(),
+            exchange_rates_resolver: self.exchange_rates_resolver.clone(),
+            taxes_resolver: self.taxes_resolver.clone(),
+            promotions_resolver: self.promotions_resolver.clone(),
+            jobs_resolver: self.jobs_resolver.clone(),
         }
     }
 
@@ -0,0 +1,112 @@
+/*! Contains the `SagaDefinition` type. */
+
+use std::{
+    future::Future,
+    marker::PhantomData,
+    sync::Arc,
+};
+
+use futures::{
+    FutureExt,
+    future::{
+        self,
+        BoxFuture,
+    },
+};
+use serde::de::DeserializeOwned;
+
+use crate::domain::{
+    Error,
+    error,
+    infra::*,
+};
+
+/**
+A function that runs a step, or compensates for it.
+
+Handlers are given a resolver that participates in the transaction the step is run in.
+*/
+pub(in crate::domain) type SagaHandler =
+    Arc<dyn Fn(Resolver, serde_json::Value) -> BoxFuture<'static, Result<(), Error>> + Send + Sync>;
+
+/** A single step in a saga. */
+pub(in crate::domain) struct SagaStep {
+    pub name: String,
+    pub action: SagaHandler,
+    pub compensation: Option<SagaHandler>,
+}
+
+/**
+The definition of a saga.
+
+Each step in a saga is given the arguments the saga was started with.
+Steps are identified by their position in the saga, so changing the steps of a saga
+that has instances in progress may resume them at the wrong step.
+*/
+pub struct SagaDefinition<TArgs> {
+    pub(in crate::domain) name: String,
+    pub(in crate::domain) steps: Vec<SagaStep>,
+    _args: PhantomData<fn(TArgs)>,
+}
+
+impl<TArgs> SagaDefinition<TArgs>
+where
+    TArgs: DeserializeOwned + Send + 'static,
+{
+    pub fn new(name: impl Into<String>) -> Self {
+        SagaDefinition {
+            name: name.into(),
+            steps: Vec::new(),
+            _args: PhantomData,
+        }
+    }
+
+    /** Add a step that doesn't need to be undone if a later step fails. */
+    pub fn step<F, O>(mut self, name: impl Into<String>, action: F) -> Self
+    where
+        F: Fn(Resolver, TArgs) -> O + Send + Sync + 'static,
+        O: Future<Output = Result<(), Error>> + Send + 'static,
+    {
+        self.steps.push(SagaStep {
+            name: name.into(),
+            action: handler(action),
+            compensation: None,
+        });
+        self
+    }
+
+    /** Add a step that's undone by running its compensation if a later step fails. */
+    pub fn compensated_step<F, FO, C, CO>(
+        mut self,
+        name: impl Into<String>,
+        action: F,
+        compensation: C,
+    ) -> Self
+    where
+        F: Fn(Resolver, TArgs) -> FO + Send + Sync + 'static,
+        FO: Future<Output = Result<(), Error>> + Send + 'static,
+        C: Fn(Resolver, TArgs) -> CO + Send + Sync + 'static,
+        CO: Future<Output = Result<(), Error>> + Send + 'static,
+    {
+        self.steps.push(SagaStep {
+            name: name.into(),
+            action: handler(action),
+            compensation: Some(handler(compensation)),
+        });
+        self
+    }
+}
+
+fn handler<TArgs, F, O>(f: F) -> SagaHandler
+where
+    TArgs: DeserializeOwned + Send + 'static,
+    F: Fn(Resolver, TArgs) -> O + Send + Sync + 'static,
+    O: Future<Output = Result<(), Error>> + Send + 'static,
+{
+    Arc::new(
+        move |resolver, args| match serde_json::from_value::<TArgs>(args) {
+            Ok(args) => f(resolver, args).boxed(),
+            Err(err) => future::err(error::bad_input(err)).boxed(),
+        },
+    )
+}
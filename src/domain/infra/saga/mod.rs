@@ -0,0 +1,25 @@
+/*!
+Sagas for business processes that span multiple transactions.
+
+A saga runs a sequence of steps, each in its own transaction.
+If a step fails then the steps before it are compensated in reverse order, so their effects can be undone.
+The progress of each saga is persisted in the same transaction as the step that advanced it,
+so sagas that didn't complete, like when the app is restarted, can be resumed where they left off.
+*/
+
+mod definition;
+mod query;
+pub(in crate::domain) mod resolver;
+mod runner;
+pub(in crate::domain) mod store;
+
+pub use self::{
+    definition::*,
+    query::*,
+    store::{
+        SagaData,
+        SagaId,
+        SagaStatus,
+        SagaVersion,
+    },
+};
@@ -0,0 +1,37 @@
+/*! Contains the `GetSagaQuery` type. */
+
+use crate::domain::{
+    Error,
+    infra::{
+        saga::store::SagaStore,
+        *,
+    },
+};
+
+/** Input for a `GetSagaQuery`. */
+#[derive(Serialize, Deserialize)]
+pub struct GetSaga {
+    pub id: SagaId,
+}
+
+impl QueryArgs for GetSaga {
+    type Output = Result<Option<SagaData>, Error>;
+}
+
+/** Default implementation for a `GetSagaQuery`. */
+async fn execute(query: GetSaga, store: impl SagaStore) -> Result<Option<SagaData>, Error> {
+    let saga = store.get_saga(query.id)?;
+
+    Ok(saga)
+}
+
+impl Resolver {
+    /** Get the state of a saga. */
+    pub fn get_saga_query(&self) -> impl Query<GetSaga> {
+        self.query(|resolver, query: GetSaga| async move {
+            let store = resolver.saga_store();
+
+            execute(query, store).await
+        })
+    }
+}
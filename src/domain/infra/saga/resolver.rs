@@ -0,0 +1,68 @@
+use std::{
+    collections::HashMap,
+    sync::Arc,
+};
+
+use serde::de::DeserializeOwned;
+
+use crate::domain::infra::{
+    saga::{
+        SagaStep,
+        store::{
+            self,
+            InMemoryStore,
+            SagaStore,
+            SagaStoreFilter,
+        },
+    },
+    *,
+};
+
+/**
+Resolver for sagas.
+*/
+#[derive(Clone)]
+pub(in crate::domain) struct SagaResolver {
+    saga_store: Register<Arc<InMemoryStore>>,
+    sagas: Arc<HashMap<String, Arc<Vec<SagaStep>>>>,
+}
+
+impl Default for SagaResolver {
+    fn default() -> Self {
+        SagaResolver {
+            saga_store: Register::per_tenant(|resolver| {
+                Arc::new(store::in_memory_store(resolver.transaction_store()))
+            }),
+            sagas: Default::default(),
+        }
+    }
+}
+
+impl AppBuilder {
+    /** Define a saga that can be started by its name. */
+    pub fn saga<TArgs>(mut self, saga: SagaDefinition<TArgs>) -> Self
+    where
+        TArgs: DeserializeOwned + Send + 'static,
+    {
+        Arc::make_mut(&mut self.root_resolver.saga_resolver.sagas)
+            .insert(saga.name, Arc::new(saga.steps));
+        self
+    }
+}
+
+impl Resolver {
+    pub(in crate::domain::infra::saga) fn saga_store(&self) -> impl SagaStore {
+        self.resolve(&self.saga_resolver.saga_store)
+    }
+
+    pub(in crate::domain::infra::saga) fn saga_store_filter(&self) -> impl SagaStoreFilter {
+        self.resolve(&self.saga_resolver.saga_store)
+    }
+
+    pub(in crate::domain::infra::saga) fn saga_steps(
+        &self,
+        saga: &str,
+    ) -> Option<Arc<Vec<SagaStep>>> {
+        self.saga_resolver.sagas.get(saga).cloned()
+    }
+}
@@ -0,0 +1,385 @@
+/*! Runs sagas through their steps. */
+
+use serde::Serialize;
+
+use crate::domain::{
+    Error,
+    error,
+    infra::{
+        saga::{
+            SagaHandler,
+            store::{
+                SagaStore,
+                SagaStoreFilter,
+            },
+        },
+        *,
+    },
+};
+
+impl App {
+    /**
+    Start a saga and run it until it finishes.
+
+    The saga is persisted before its first step runs, so if the app stops before it finishes
+    it can be picked up again with `App::resume_sagas`.
+    The status the saga finished with is returned.
+    */
+    pub async fn start_saga<TArgs>(
+        &self,
+        id: SagaId,
+        saga: &str,
+        args: TArgs,
+    ) -> Result<SagaStatus, Error>
+    where
+        TArgs: Serialize,
+    {
+        let resolver = &self.root_resolver;
+
+        if resolver.saga_steps(saga).is_none() {
+            return Err(error::bad_input(format_args!(
+                "saga `{}` isn't defined",
+                saga
+            )));
+        }
+
+        let args = serde_json::to_value(args)?;
+
+        resolver
+            .transaction(|resolver| async move {
+                let store = resolver.saga_store();
+                let transaction = resolver.active_transaction();
+
+                if store.get_saga(id)?.is_some() {
+                    return Err(error::conflict(format_args!("saga {} already exists", id)));
+                }
+
+                store.set_saga(
+                    transaction.get(),
+                    SagaData {
+                        id,
+                        version: SagaVersion::default(),
+                        saga: saga.to_owned(),
+                        args,
+                        status: SagaStatus::Running,
+                        step: 0,
+                        error: None,
+                    },
+                )?;
+
+                Ok(())
+            })
+            .await?;
+
+        run(resolver, id).await
+    }
+
+    /**
+    Resume all sagas that haven't finished.
+
+    Sagas that fail to resume are logged and skipped.
+    The number of sagas that were resumed is returned.
+    */
+    pub async fn resume_sagas(&self) -> Result<usize, Error> {
+        let resolver = &self.root_resolver;
+
+        let unfinished: Vec<_> = resolver
+            .saga_store_filter()
+            .filter(|saga| !saga.status.is_finished())?
+            .map(|saga| saga.id)
+            .collect();
+
+        let mut resumed = 0;
+        for id in unfinished {
+            match run(resolver, id).await {
+                Ok(_) => resumed += 1,
+                Err(err) => {
+                    emit::error!("failed to resume saga {saga: id}: {reason: err.to_string()}")
+                }
+            }
+        }
+
+        Ok(resumed)
+    }
+}
+
+/** Run a saga from its current step until it finishes. */
+async fn run(resolver: &Resolver, id: SagaId) -> Result<SagaStatus, Error> {
+    loop {
+        let saga = resolver
+            .saga_store()
+            .get_saga(id)?
+            .ok_or_else(|| error::not_found("saga not found"))?;
+
+        let steps = resolver
+            .saga_steps(&saga.saga)
+            .ok_or_else(|| error::msg(format_args!("saga `{}` isn't defined", saga.saga)))?;
+
+        match saga.status {
+            SagaStatus::Running => match steps.get(saga.step) {
+                Some(step) => {
+                    if let Err(err) = advance(resolver, saga.clone(), Some(&step.action), |saga| {
+                        saga.step += 1
+                    })
+                    .await
+                    {
+                        emit::warn!(
+                            "saga {saga: id} failed at step {step: step.name}: {reason: err.to_string()}"
+                        );
+
+                        advance(resolver, saga, None, |saga| {
+                            saga.status = SagaStatus::Compensating;
+                            saga.error = Some(err.to_string());
+                        })
+                        .await?;
+                    }
+                }
+                None => {
+                    advance(resolver, saga, None, |saga| {
+                        saga.status = SagaStatus::Completed
+                    })
+                    .await?
+                }
+            },
+            SagaStatus::Compensating => match saga.step.checked_sub(1) {
+                Some(step) => {
+                    let compensation = steps.get(step).and_then(|step| step.compensation.as_ref());
+
+                    if let Err(err) =
+                        advance(resolver, saga.clone(), compensation, |saga| saga.step -= 1).await
+                    {
+                        emit::error!(
+                            "saga {saga: id} failed to compensate step {step}: {reason: err.to_string()}"
+                        );
+
+                        advance(resolver, saga, None, |saga| {
+                            saga.status = SagaStatus::Failed;
+                            saga.error = Some(err.to_string());
+                        })
+                        .await?;
+                    }
+                }
+                None => {
+                    advance(resolver, saga, None, |saga| {
+                        saga.status = SagaStatus::Compensated
+                    })
+                    .await?
+                }
+            },
+            status => return Ok(status),
+        }
+    }
+}
+
+/**
+Run a handler and update the state of the saga in the same transaction.
+
+If the handler fails then none of its changes, or the changes to the saga, are committed.
+*/
+async fn advance(
+    resolver: &Resolver,
+    mut saga: SagaData,
+    handler: Option<&SagaHandler>,
+    update: impl FnOnce(&mut SagaData),
+) -> Result<(), Error> {
+    resolver
+        .transaction(|resolver| async move {
+            if let Some(handler) = handler {
+                handler(resolver.by_ref(), saga.args.clone()).await?;
+            }
+
+            let store = resolver.saga_store();
+            let transaction = resolver.active_transaction();
+
+            update(&mut saga);
+            store.set_saga(transaction.get(), saga)?;
+
+            Ok(())
+        })
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        Arc,
+        Mutex,
+    };
+
+    use super::*;
+
+    use crate::domain::customers::*;
+
+    #[derive(Clone, Serialize, Deserialize)]
+    struct Args {
+        fail_at: Vec<String>,
+    }
+
+    impl Args {
+        fn fail_at(steps: &[&str]) -> Self {
+            Args {
+                fail_at: steps.iter().map(|step| step.to_string()).collect(),
+            }
+        }
+    }
+
+    type Log = Arc<Mutex<Vec<String>>>;
+
+    fn step(
+        log: &Log,
+        name: &'static str,
+    ) -> impl Fn(Resolver, Args) -> futures::future::Ready<Result<(), Error>> + use<> {
+        let log = log.clone();
+
+        move |_, args: Args| {
+            if args.fail_at.iter().any(|step| step == name) {
+                return futures::future::err(error::msg("failed"));
+            }
+
+            log.lock().unwrap().push(name.to_owned());
+
+            futures::future::ok(())
+        }
+    }
+
+    fn app(log: &Log) -> App {
+        App::builder()
+            .saga(
+                SagaDefinition::<Args>::new("test")
+                    .compensated_step("a", step(log, "a"), step(log, "undo a"))
+                    .step("b", step(log, "b"))
+                    .compensated_step("c", step(log, "c"), step(log, "undo c"))
+                    .compensated_step("d", step(log, "d"), step(log, "undo d")),
+            )
+            .build()
+    }
+
+    #[tokio::test]
+    async fn run_all_steps() {
+        let log = Log::default();
+        let app = app(&log);
+
+        let status = app
+            .start_saga(SagaId::new(), "test", Args::fail_at(&[]))
+            .await
+            .unwrap();
+
+        assert_eq!(SagaStatus::Completed, status);
+        assert_eq!(vec!["a", "b", "c", "d"], *log.lock().unwrap());
+    }
+
+    #[tokio::test]
+    async fn compensate_completed_steps_in_reverse() {
+        let log = Log::default();
+        let app = app(&log);
+
+        let id = SagaId::new();
+
+        let status = app
+            .start_saga(id, "test", Args::fail_at(&["d"]))
+            .await
+            .unwrap();
+
+        assert_eq!(SagaStatus::Compensated, status);
+        assert_eq!(
+            vec!["a", "b", "c", "undo c", "undo a"],
+            *log.lock().unwrap()
+        );
+
+        let saga = app
+            .root_resolver
+            .get_saga_query()
+            .execute(GetSaga { id })
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(0, saga.step);
+        assert_eq!(Some("failed"), saga.error.as_deref());
+    }
+
+    #[tokio::test]
+    async fn fail_if_compensation_fails() {
+        let log = Log::default();
+        let app = app(&log);
+
+        let status = app
+            .start_saga(SagaId::new(), "test", Args::fail_at(&["d", "undo c"]))
+            .await
+            .unwrap();
+
+        assert_eq!(SagaStatus::Failed, status);
+
+        // Steps before the failed compensation are left as they are
+        assert_eq!(vec!["a", "b", "c"], *log.lock().unwrap());
+    }
+
+    #[tokio::test]
+    async fn discard_changes_from_failed_steps() {
+        let customer_id = CustomerId::new();
+
+        let app = App::builder()
+            .saga(SagaDefinition::<CustomerId>::new("create-customer").step(
+                "create",
+                |resolver, id| async move {
+                    resolver
+                        .create_customer_command()
+                        .execute(CreateCustomer { id })
+                        .await?;
+
+                    Err(error::msg("failed after creating the customer"))
+                },
+            ))
+            .build();
+
+        let status = app
+            .start_saga(SagaId::new(), "create-customer", customer_id)
+            .await
+            .unwrap();
+
+        assert_eq!(SagaStatus::Compensated, status);
+
+        let customer = app
+            .root_resolver
+            .get_customer_query()
+            .execute(GetCustomer {
+                id: customer_id,
+                include_archived: true,
+            })
+            .await
+            .unwrap();
+
+        assert!(customer.is_none());
+    }
+
+    #[tokio::test]
+    async fn resume_unfinished_sagas() {
+        let log = Log::default();
+        let app = app(&log);
+
+        let id = SagaId::new();
+
+        // Store a saga that stopped after its first two steps
+        app.root_resolver
+            .saga_store()
+            .set_saga(
+                &crate::store::Transaction::none(),
+                SagaData {
+                    id,
+                    version: SagaVersion::default(),
+                    saga: "test".to_owned(),
+                    args: serde_json::to_value(Args::fail_at(&[])).unwrap(),
+                    status: SagaStatus::Running,
+                    step: 2,
+                    error: None,
+                },
+            )
+            .unwrap();
+
+        assert_eq!(1, app.resume_sagas().await.unwrap());
+        assert_eq!(vec!["c", "d"], *log.lock().unwrap());
+
+        // Finished sagas aren't resumed again
+        assert_eq!(0, app.resume_sagas().await.unwrap());
+    }
+}
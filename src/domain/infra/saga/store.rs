@@ -0,0 +1,129 @@
+/*! Persistent storage for saga state. */
+
+use std::vec::IntoIter;
+
+use crate::{
+    domain::{
+        Error,
+        infra::*,
+    },
+    store::{
+        Transaction,
+        TransactionStore,
+        TransactionValueStore,
+    },
+};
+
+pub type SagaId = Id<SagaData>;
+pub type SagaVersion = Version<SagaData>;
+
+/**
+The status of a saga.
+
+- `Running` sagas are working forwards through their steps.
+- `Compensating` sagas had a step fail and are working backwards through the steps that completed.
+- `Completed` sagas ran all of their steps.
+- `Compensated` sagas undid all of the steps that completed before one failed.
+- `Failed` sagas had a compensation fail, so they need to be looked at manually.
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SagaStatus {
+    Running,
+    Compensating,
+    Completed,
+    Compensated,
+    Failed,
+}
+
+impl SagaStatus {
+    /** Whether the saga has finished and won't run any more steps. */
+    pub fn is_finished(self) -> bool {
+        matches!(
+            self,
+            SagaStatus::Completed | SagaStatus::Compensated | SagaStatus::Failed
+        )
+    }
+}
+
+/**
+The state of a single instance of a saga.
+
+The step is the number of steps that have completed and haven't been compensated.
+*/
+#[derive(Clone, Serialize, Deserialize)]
+pub struct SagaData {
+    pub id: SagaId,
+    pub version: SagaVersion,
+    pub saga: String,
+    pub args: serde_json::Value,
+    pub status: SagaStatus,
+    pub step: usize,
+    pub error: Option<String>,
+}
+
+/** A place to persist and fetch saga state. */
+#[auto_impl(&, Arc)]
+pub(in crate::domain) trait SagaStore {
+    fn get_saga(&self, id: SagaId) -> Result<Option<SagaData>, Error>;
+    fn set_saga(&self, transaction: &Transaction, saga: SagaData) -> Result<(), Error>;
+}
+
+/**
+An additional store for fetching multiple sagas at a time.
+
+This is used to find sagas that haven't finished so they can be resumed.
+*/
+#[auto_impl(&, Arc)]
+pub(in crate::domain) trait SagaStoreFilter {
+    fn filter<F>(&self, predicate: F) -> Result<Iter, Error>
+    where
+        F: Fn(&SagaData) -> bool;
+}
+
+pub(in crate::domain) type Iter = IntoIter<SagaData>;
+
+/** A test in-memory saga store. */
+pub(in crate::domain) struct InMemoryStore(TransactionValueStore<SagaData>);
+
+impl SagaStore for InMemoryStore {
+    fn get_saga(&self, id: SagaId) -> Result<Option<SagaData>, Error> {
+        if let Some((version, data)) = self.0.get(id) {
+            assert_eq!(version, data.version.into());
+
+            Ok(Some(data))
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn set_saga(&self, transaction: &Transaction, mut saga: SagaData) -> Result<(), Error> {
+        let id = saga.id;
+
+        self.0.set(
+            transaction,
+            id,
+            Some(saga.version),
+            saga.version.next()?,
+            saga,
+        )?;
+
+        Ok(())
+    }
+}
+
+impl SagaStoreFilter for InMemoryStore {
+    #[allow(clippy::needless_collect)]
+    fn filter<F>(&self, predicate: F) -> Result<Iter, Error>
+    where
+        F: Fn(&SagaData) -> bool,
+    {
+        let sagas: Vec<_> = self.0.get_all(predicate).map(|(_, data)| data).collect();
+
+        Ok(sagas.into_iter())
+    }
+}
+
+pub(in crate::domain) fn in_memory_store(transaction_store: TransactionStore) -> InMemoryStore {
+    InMemoryStore(TransactionValueStore::new(transaction_store))
+}
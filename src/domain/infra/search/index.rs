@@ -0,0 +1,266 @@
+/*! Contains the `SearchIndex` type. */
+
+use std::{
+    cmp::Ordering,
+    collections::{
+        BTreeMap,
+        HashMap,
+        HashSet,
+    },
+    hash::Hash,
+};
+
+use crate::domain::infra::search::text;
+
+/**
+How much less a term that only matched a prefix of the query counts towards a document's score
+than one that matched a whole word.
+*/
+const PREFIX_WEIGHT: f64 = 0.5;
+
+/** A document that matched a search, along with how relevant it is. */
+#[derive(Debug, Clone, PartialEq)]
+pub(in crate::domain) struct SearchHit<K> {
+    pub key: K,
+    pub score: f64,
+}
+
+struct Document<V> {
+    version: V,
+    terms: HashMap<String, f64>,
+    words: HashSet<String>,
+}
+
+/**
+An inverted index of documents.
+
+Each document is a set of weighted fields, like a title and a description.
+Documents are versioned, so changes that are applied out of order can't replace newer ones.
+
+Documents are ranked using term frequency and inverse document frequency, so terms that appear
+more often in a document, in more heavily weighted fields, and in fewer other documents count for more.
+Frequencies are normalized by the length of the document, so a match in a short title counts for more
+than the same match in a long one.
+*/
+pub(in crate::domain) struct SearchIndex<K, V> {
+    // The stem of each term and the documents that contain it, along with their normalized frequency
+    terms: HashMap<String, HashMap<K, f64>>,
+    // Each word that appears in a document and the stem it's indexed under, used for prefix matching
+    words: BTreeMap<String, String>,
+    documents: HashMap<K, Document<V>>,
+}
+
+impl<K, V> Default for SearchIndex<K, V> {
+    fn default() -> Self {
+        SearchIndex {
+            terms: HashMap::new(),
+            words: BTreeMap::new(),
+            documents: HashMap::new(),
+        }
+    }
+}
+
+impl<K, V> SearchIndex<K, V>
+where
+    K: Clone + Eq + Hash + Ord,
+    V: Ord,
+{
+    /**
+    Add a document to the index, replacing any previous version of it.
+
+    If the index already contains a newer version of the document then this does nothing.
+    */
+    pub(in crate::domain) fn insert<'a>(
+        &mut self,
+        key: K,
+        version: V,
+        fields: impl IntoIterator<Item = (&'a str, f64)>,
+    ) {
+        if let Some(existing) = self.documents.get(&key)
+            && existing.version >= version
+        {
+            return;
+        }
+
+        self.remove_terms(&key);
+
+        let mut terms = HashMap::new();
+        let mut words = HashSet::new();
+        for (field, weight) in fields {
+            for word in text::words(field) {
+                let stem = text::stem(&word);
+
+                self.words.insert(word.clone(), stem.clone());
+                words.insert(word);
+                *terms.entry(stem).or_insert(0.0) += weight;
+            }
+        }
+
+        let length = terms.values().sum::<f64>();
+        for (term, frequency) in &terms {
+            self.terms
+                .entry(term.clone())
+                .or_default()
+                .insert(key.clone(), frequency / length.sqrt());
+        }
+
+        self.documents.insert(
+            key,
+            Document {
+                version,
+                terms,
+                words,
+            },
+        );
+    }
+
+    fn remove_terms(&mut self, key: &K) {
+        let Some(existing) = self.documents.get(key) else {
+            return;
+        };
+
+        for term in existing.terms.keys() {
+            if let Some(documents) = self.terms.get_mut(term) {
+                documents.remove(key);
+
+                if documents.is_empty() {
+                    self.terms.remove(term);
+                }
+            }
+        }
+
+        // Words are only used to find terms, so they're removed once their terms are gone
+        for word in &existing.words {
+            if let Some(stem) = self.words.get(word)
+                && !self.terms.contains_key(stem)
+            {
+                self.words.remove(word);
+            }
+        }
+    }
+
+    /**
+    Search the index for documents that match a query.
+
+    Documents match if they contain any of the terms in the query.
+    If `prefix` is set then the last word in the query also matches any word it's the start of,
+    which is useful for suggesting results while the query is still being typed.
+
+    Hits are returned with the most relevant first.
+    */
+    pub(in crate::domain) fn search(&self, query: &str, prefix: bool) -> Vec<SearchHit<K>> {
+        let words: Vec<_> = text::words(query).collect();
+
+        // Each term in the query, along with how much it counts towards scores
+        let mut query_terms = HashMap::new();
+        for word in &words {
+            query_terms.insert(text::stem(word), 1.0);
+        }
+
+        if prefix && let Some(last) = words.last() {
+            for (_, stem) in self
+                .words
+                .range(last.clone()..)
+                .take_while(|(word, _)| word.starts_with(last.as_str()))
+            {
+                query_terms.entry(stem.clone()).or_insert(PREFIX_WEIGHT);
+            }
+        }
+
+        let total = self.documents.len() as f64;
+
+        let mut scores = HashMap::new();
+        for (term, weight) in query_terms {
+            let Some(documents) = self.terms.get(&term) else {
+                continue;
+            };
+
+            let idf = (1.0 + total / documents.len() as f64).ln();
+
+            for (key, frequency) in documents {
+                *scores.entry(key.clone()).or_insert(0.0) += weight * frequency * idf;
+            }
+        }
+
+        let mut hits: Vec<_> = scores
+            .into_iter()
+            .map(|(key, score)| SearchHit { key, score })
+            .collect();
+
+        hits.sort_by(|a, b| {
+            b.score
+                .partial_cmp(&a.score)
+                .unwrap_or(Ordering::Equal)
+                .then_with(|| a.key.cmp(&b.key))
+        });
+
+        hits
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys(hits: Vec<SearchHit<&'static str>>) -> Vec<&'static str> {
+        hits.into_iter().map(|hit| hit.key).collect()
+    }
+
+    fn index() -> SearchIndex<&'static str, u32> {
+        let mut index = SearchIndex::default();
+
+        index.insert("a", 1, [("Red running shirt", 1.0)]);
+        index.insert("b", 1, [("Blue shirts", 1.0)]);
+        index.insert("c", 1, [("Red hat", 1.0)]);
+
+        index
+    }
+
+    #[test]
+    fn search_stemmed_terms() {
+        let index = index();
+
+        // Shorter documents rank higher for the same match
+        assert_eq!(vec!["b", "a"], keys(index.search("shirts", false)));
+        assert_eq!(vec!["a"], keys(index.search("run", false)));
+        assert!(index.search("green", false).is_empty());
+    }
+
+    #[test]
+    fn rank_by_relevance() {
+        let mut index = index();
+
+        // Documents matching more of the query rank higher
+        assert_eq!(vec!["a", "b", "c"], keys(index.search("red shirt", false)));
+
+        // Terms in more heavily weighted fields count for more
+        index.insert("d", 1, [("Hat", 2.0), ("For sunny days", 1.0)]);
+        assert_eq!(vec!["d", "c"], keys(index.search("hat", false)));
+    }
+
+    #[test]
+    fn search_by_prefix() {
+        let index = index();
+
+        assert!(index.search("shi", false).is_empty());
+        assert_eq!(vec!["b", "a"], keys(index.search("shi", true)));
+        assert_eq!(vec!["a", "c", "b"], keys(index.search("red shi", true)));
+    }
+
+    #[test]
+    fn replace_documents_by_version() {
+        let mut index = index();
+
+        index.insert("a", 2, [("Green running shirt", 1.0)]);
+        assert_eq!(vec!["a"], keys(index.search("green", false)));
+        assert_eq!(vec!["c"], keys(index.search("red", false)));
+
+        // Older versions are ignored
+        index.insert("a", 1, [("Red running shirt", 1.0)]);
+        assert_eq!(vec!["c"], keys(index.search("red", false)));
+
+        // Words from replaced versions are no longer searchable
+        index.insert("a", 3, [("Green shirt", 1.0)]);
+        assert!(index.search("runn", true).is_empty());
+    }
+}
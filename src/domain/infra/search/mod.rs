@@ -0,0 +1,12 @@
+/*!
+In-process full-text search.
+
+Text is split into terms that are lowercased and stemmed, so `Running Shirts` can be found by `shirt` or `run`.
+Documents are kept in an inverted index from each term to the documents that contain it, so searching
+only looks at the documents that could match instead of scanning everything.
+*/
+
+mod index;
+mod text;
+
+pub(in crate::domain) use self::index::*;
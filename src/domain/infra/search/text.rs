@@ -0,0 +1,97 @@
+/*! Tokenizing and stemming text for search. */
+
+/**
+Split some text into lowercase words.
+
+Words are runs of alphanumeric characters, so punctuation and whitespace are discarded.
+*/
+pub(in crate::domain::infra::search) fn words(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| word.to_lowercase())
+}
+
+/**
+Reduce a lowercase word to its stem, so different forms of the same word can match each other.
+
+This is a light stemmer for English that only strips common plural and tense suffixes.
+It doesn't always produce real words, but it produces the same stem for the forms it knows about.
+*/
+pub(in crate::domain::infra::search) fn stem(word: &str) -> String {
+    // Words are only stemmed if they'd still have a few characters left
+    let strip = |suffix: &str, min: usize| {
+        word.strip_suffix(suffix)
+            .filter(|stem| stem.chars().count() >= min)
+    };
+
+    if let Some(stem) = strip("ies", 2) {
+        return format!("{}y", stem);
+    }
+
+    if let Some(stem) = strip("sses", 2) {
+        return format!("{}ss", stem);
+    }
+
+    for suffix in ["ing", "ed"] {
+        if let Some(stem) = strip(suffix, 3) {
+            return undouble(stem);
+        }
+    }
+
+    if let Some(stem) = strip("ly", 3) {
+        return stem.to_owned();
+    }
+
+    if !word.ends_with("ss")
+        && !word.ends_with("us")
+        && !word.ends_with("is")
+        && let Some(stem) = strip("s", 3)
+    {
+        return stem.to_owned();
+    }
+
+    word.to_owned()
+}
+
+/** Remove a doubled final consonant left behind after stripping a suffix, like `runn` from `running`. */
+fn undouble(stem: &str) -> String {
+    let mut chars = stem.chars().rev();
+
+    match (chars.next(), chars.next()) {
+        (Some(a), Some(b)) if a == b && !"aeioulsz".contains(a) => {
+            stem[..stem.len() - a.len_utf8()].to_owned()
+        }
+        _ => stem.to_owned(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_words() {
+        assert_eq!(
+            vec!["a", "red", "t", "shirt", "2024"],
+            words("A red T-shirt (2024)").collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn stem_words() {
+        for (word, expected) in [
+            ("shirts", "shirt"),
+            ("shirt", "shirt"),
+            ("berries", "berry"),
+            ("dresses", "dress"),
+            ("dress", "dress"),
+            ("running", "run"),
+            ("printed", "print"),
+            ("quickly", "quick"),
+            ("bus", "bus"),
+            ("is", "is"),
+        ] {
+            assert_eq!(expected, stem(word), "{}", word);
+        }
+    }
+}
@@ -0,0 +1,298 @@
+/*! Contains the shared `TenantId` type. */
+
+use std::{
+    collections::HashMap,
+    convert::TryFrom,
+    fmt,
+    str::FromStr,
+    sync::{
+        Arc,
+        Mutex,
+    },
+};
+
+use crate::domain::{
+    Error,
+    error,
+    infra::*,
+};
+
+/**
+The id of a tenant, like `my-store`.
+
+Each storefront hosted by the app is a separate tenant.
+Tenant ids are between 1 and 64 ASCII letters, digits, dashes or underscores.
+*/
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize)]
+pub struct TenantId(String);
+
+impl TenantId {
+    /** The tenant that's used when no other tenant is given. */
+    pub fn default_tenant() -> Self {
+        TenantId("default".to_owned())
+    }
+}
+
+impl Default for TenantId {
+    fn default() -> Self {
+        TenantId::default_tenant()
+    }
+}
+
+impl fmt::Display for TenantId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl TryFrom<String> for TenantId {
+    type Error = Error;
+
+    fn try_from(id: String) -> Result<Self, Self::Error> {
+        if id.is_empty()
+            || id.len() > 64
+            || !id
+                .bytes()
+                .all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_')
+        {
+            return Err(error::bad_input(format_args!(
+                "`{}` is not a valid tenant id",
+                id
+            )));
+        }
+
+        Ok(TenantId(id))
+    }
+}
+
+impl<'a> TryFrom<&'a str> for TenantId {
+    type Error = Error;
+
+    fn try_from(id: &'a str) -> Result<Self, Self::Error> {
+        Self::try_from(id.to_owned())
+    }
+}
+
+impl FromStr for TenantId {
+    type Err = Error;
+
+    fn from_str(id: &str) -> Result<Self, Self::Err> {
+        Self::try_from(id)
+    }
+}
+
+/**
+Configuration for a single tenant.
+
+Settings that aren't given use the app-wide registration.
+*/
+#[derive(Debug, Clone, Default)]
+pub struct TenantConfig {
+    pub(in crate::domain) hosts: Vec<String>,
+    pub(in crate::domain) tax_rounding: Option<Rounding>,
+}
+
+impl TenantConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /** Serve requests made to the given host for this tenant. */
+    pub fn host(mut self, host: impl Into<String>) -> Self {
+        self.hosts.push(host.into().to_ascii_lowercase());
+        self
+    }
+
+    /** Use a different rule for rounding calculated tax. */
+    pub fn tax_rounding(mut self, rounding: Rounding) -> Self {
+        self.tax_rounding = Some(rounding);
+        self
+    }
+}
+
+/**
+Resolver for the tenant.
+*/
+#[derive(Clone)]
+pub(in crate::domain) struct TenantResolver {
+    tenant: Register<TenantId>,
+    tenants: Arc<HashMap<TenantId, TenantConfig>>,
+}
+
+impl Default for TenantResolver {
+    fn default() -> Self {
+        TenantResolver {
+            tenant: Register::factory(|_| TenantId::default_tenant()),
+            tenants: Default::default(),
+        }
+    }
+}
+
+impl<T> Register<T> {
+    /**
+    Create a register that returns the same instance of a value for each tenant.
+
+    Stores are registered this way so data belonging to one tenant is never visible to another.
+    */
+    pub fn per_tenant(f: impl Fn(&Resolver) -> T + Send + Sync + 'static) -> Self
+    where
+        T: Send + Sync + Clone + 'static,
+    {
+        let instances = Mutex::new(HashMap::new());
+        Register::factory(move |resolver| {
+            let mut instances = instances.lock().unwrap();
+
+            instances
+                .entry(resolver.tenant())
+                .or_insert_with(|| f(resolver))
+                .clone()
+        })
+    }
+}
+
+impl AppBuilder {
+    /**
+    Host a tenant.
+
+    The default tenant is always hosted, and doesn't need to be added.
+    */
+    pub fn tenant(mut self, tenant: TenantId, config: TenantConfig) -> Self {
+        Arc::make_mut(&mut self.root_resolver.tenant_resolver.tenants).insert(tenant, config);
+        self
+    }
+}
+
+impl App {
+    /**
+    Get a handle to the app for a tenant.
+
+    Commands and queries resolved from the handle can only see data belonging to that tenant.
+    */
+    pub fn for_tenant(&self, tenant: TenantId) -> Result<App, Error> {
+        let tenants = &self.root_resolver.tenant_resolver.tenants;
+
+        if tenant != TenantId::default_tenant() && !tenants.contains_key(&tenant) {
+            return Err(error::not_found(format_args!(
+                "tenant `{}` not found",
+                tenant
+            )));
+        }
+
+        Ok(App {
+            root_resolver: self.root_resolver.with_tenant(tenant),
+        })
+    }
+
+    /** Find the tenant that serves requests made to the given host. */
+    pub fn tenant_for_host(&self, host: &str) -> Option<TenantId> {
+        let host = host.to_ascii_lowercase();
+
+        self.root_resolver
+            .tenant_resolver
+            .tenants
+            .iter()
+            .find(|(_, config)| config.hosts.contains(&host))
+            .map(|(tenant, _)| tenant.clone())
+    }
+
+    /** Get all of the tenants hosted by the app, including the default tenant. */
+    pub fn tenants(&self) -> Vec<TenantId> {
+        let mut tenants: Vec<_> = self
+            .root_resolver
+            .tenant_resolver
+            .tenants
+            .keys()
+            .cloned()
+            .collect();
+
+        if !tenants.contains(&TenantId::default_tenant()) {
+            tenants.push(TenantId::default_tenant());
+        }
+
+        tenants.sort();
+        tenants
+    }
+}
+
+impl Resolver {
+    /** Get the tenant that commands and queries are resolved for. */
+    pub fn tenant(&self) -> TenantId {
+        self.resolve(&self.tenant_resolver.tenant)
+    }
+
+    pub(in crate::domain) fn tenant_config(&self) -> Option<&TenantConfig> {
+        self.tenant_resolver.tenants.get(&self.tenant())
+    }
+
+    pub(in crate::domain) fn with_tenant(&self, tenant: TenantId) -> Resolver {
+        Resolver {
+            tenant_resolver: TenantResolver {
+                tenant: Register::factory(move |_| tenant.clone()),
+                tenants: self.tenant_resolver.tenants.clone(),
+            },
+            ..self.by_ref()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tenant_id_must_be_valid() {
+        for id in ["", "not a tenant", "tenant/1"] {
+            assert!(TenantId::try_from(id).is_err());
+        }
+
+        assert_eq!(
+            "my-store",
+            TenantId::try_from("my-store").unwrap().to_string()
+        );
+    }
+
+    #[test]
+    fn per_tenant_register() {
+        let app = App::builder()
+            .tenant(TenantId::try_from("a").unwrap(), TenantConfig::new())
+            .build();
+
+        let register = Register::per_tenant(|resolver| Arc::new(resolver.tenant()));
+
+        let default = app.root_resolver.resolve(&register);
+        let a = app
+            .for_tenant(TenantId::try_from("a").unwrap())
+            .unwrap()
+            .root_resolver
+            .resolve(&register);
+
+        assert_eq!(TenantId::default_tenant(), *default);
+        assert_eq!(TenantId::try_from("a").unwrap(), *a);
+
+        assert!(Arc::ptr_eq(
+            &a,
+            &app.for_tenant(TenantId::try_from("a").unwrap())
+                .unwrap()
+                .root_resolver
+                .resolve(&register)
+        ));
+    }
+
+    #[test]
+    fn find_tenant_by_host() {
+        let app = App::builder()
+            .tenant(
+                TenantId::try_from("a").unwrap(),
+                TenantConfig::new().host("A.example.com"),
+            )
+            .build();
+
+        assert_eq!(
+            Some(TenantId::try_from("a").unwrap()),
+            app.tenant_for_host("a.example.com")
+        );
+        assert_eq!(None, app.tenant_for_host("b.example.com"));
+
+        assert!(app.for_tenant(TenantId::try_from("b").unwrap()).is_err());
+    }
+}
@@ -60,10 +60,10 @@ impl ActiveTransaction {
     If there are it will return an error instead of cancelling.
     */
     pub fn cancel(mut self) {
-        if let Ok(transaction) = Arc::try_unwrap(self.transaction) {
-            if let Some(store) = self.store.take() {
-                store.cancel(transaction);
-            }
+        if let Ok(transaction) = Arc::try_unwrap(self.transaction)
+            && let Some(store) = self.store.take()
+        {
+            store.cancel(transaction);
         }
     }
 
@@ -30,6 +30,7 @@ impl App {
     Begin a transaction and return a resolver that uses it.
 
     Any commands that are resolved within the closure will participate in the returned transaction.
+    Any queries that are resolved within the closure will share batch loaders.
     The transaction will need to be completed before it will commit.
     */
     #[emit::span(
@@ -43,22 +44,25 @@ impl App {
         O: ::std::future::Future<Output = Result<T, E>>,
         E: ::std::error::Error + Send + Sync + From<Error> + 'static,
     {
-        let resolver = self
-            .root_resolver
-            .with_active_transaction(Register::once(|resolver| {
-                ActiveTransaction::begin(resolver.transaction_store())
-            }));
-
-        let transaction = resolver.active_transaction();
-        let r = f(resolver).await?;
-        transaction.commit()?;
+        self.root_resolver.transaction(f).await
+    }
+}
 
-        Ok(r)
+impl AppBuilder {
+    /** Use a different store for tracking transactions. */
+    pub fn transaction_store(mut self, transaction_store: Register<TransactionStore>) -> Self {
+        self.root_resolver.transactions_resolver.transaction_store = transaction_store;
+        self
     }
 }
 
 impl Resolver {
-    pub(in crate::domain) fn transaction_store(&self) -> TransactionStore {
+    /**
+    Get the store that tracks transactions.
+
+    Stores that participate in transactions need to share this store.
+    */
+    pub fn transaction_store(&self) -> TransactionStore {
         self.resolve(&self.transactions_resolver.transaction_store)
     }
 
@@ -66,6 +70,30 @@ impl Resolver {
         self.resolve(&self.transactions_resolver.active_transaction)
     }
 
+    /**
+    Begin a transaction and pass a resolver that uses it to the given closure.
+
+    This is the same as `App::transaction`, but can be used with the domain's own `Error` type.
+    */
+    pub(in crate::domain) async fn transaction<F, O, T, E>(&self, f: F) -> Result<T, E>
+    where
+        F: FnOnce(Resolver) -> O,
+        O: ::std::future::Future<Output = Result<T, E>>,
+        E: From<Error>,
+    {
+        let resolver = self
+            .with_active_transaction(Register::once(|resolver| {
+                ActiveTransaction::begin(resolver.transaction_store())
+            }))
+            .with_batch_loaders(Register::once(|_| BatchLoaders::default()));
+
+        let transaction = resolver.active_transaction();
+        let r = f(resolver).await?;
+        transaction.commit()?;
+
+        Ok(r)
+    }
+
     pub(in crate::domain) fn with_active_transaction(
         &self,
         active_transaction: Register<ActiveTransaction>,
@@ -2,6 +2,7 @@
 
 use serde::{
     de::{
+        self,
         Deserialize,
         Deserializer,
     },
@@ -12,6 +13,7 @@ use serde::{
 };
 use std::{
     cmp::Ordering,
+    convert::TryFrom,
     fmt::{
         self,
         Formatter,
@@ -22,54 +24,86 @@ use std::{
         Hasher,
     },
     marker::PhantomData,
+    str::FromStr,
 };
 use uuid::Uuid;
 
-use crate::store;
+use crate::{
+    domain::error::{
+        self,
+        Error,
+    },
+    store,
+};
 
 /**
 A version.
 
 The version provides optimistic concurrency.
 Versions have a phantom generic type so you can't compare `Version<T>` to `Version<U>`.
+
+Versions increase each time an entity is stored, so a greater version is always newer.
+They're formatted as an opaque string of hex digits, like `"000000000000000a"`, which
+makes them suitable for use as strong ETags.
+Clients shouldn't try to interpret them.
 */
-pub struct Version<T>(Uuid, PhantomData<T>);
+pub struct Version<T>(u64, PhantomData<T>);
 
 impl<T> From<Version<T>> for store::Version {
-    fn from(id: Version<T>) -> store::Version {
-        store::Version::from_raw(id.0)
-    }
-}
-
-impl<T> From<store::Version> for Version<T> {
-    fn from(id: store::Version) -> Version<T> {
-        Version(id.into_raw(), PhantomData)
+    fn from(version: Version<T>) -> store::Version {
+        store::Version::from_raw(Uuid::from_u128(u128::from(version.0)))
     }
 }
 
 impl<T> fmt::Debug for Version<T> {
     fn fmt(&self, f: &mut Formatter) -> FmtResult {
-        self.0.fmt(f)
+        fmt::Display::fmt(self, f)
     }
 }
 
 impl<T> fmt::Display for Version<T> {
     fn fmt(&self, f: &mut Formatter) -> FmtResult {
-        self.0.fmt(f)
+        write!(f, "{:016x}", self.0)
+    }
+}
+
+impl<T> FromStr for Version<T> {
+    type Err = Error;
+
+    fn from_str(version: &str) -> Result<Self, Self::Err> {
+        if version.len() != 16 || !version.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Err(error::bad_input(format_args!(
+                "`{}` is not a valid version",
+                version
+            )));
+        }
+
+        let version = u64::from_str_radix(version, 16).map_err(error::bad_input)?;
+
+        Ok(Version(version, PhantomData))
+    }
+}
+
+impl<'a, T> TryFrom<&'a str> for Version<T> {
+    type Error = Error;
+
+    fn try_from(version: &'a str) -> Result<Self, Self::Error> {
+        version.parse()
     }
 }
 
 impl<T> Clone for Version<T> {
     fn clone(&self) -> Self {
-        Version(self.0, PhantomData)
+        *self
     }
 }
 
 impl<T> Copy for Version<T> {}
 
+/** The version of an entity that hasn't been stored yet. */
 impl<T> Default for Version<T> {
     fn default() -> Self {
-        Version(Uuid::default(), PhantomData)
+        Version(0, PhantomData)
     }
 }
 
@@ -83,7 +117,7 @@ impl<T> Eq for Version<T> {}
 
 impl<T> PartialOrd for Version<T> {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        self.0.partial_cmp(&other.0)
+        Some(self.cmp(other))
     }
 }
 
@@ -104,7 +138,7 @@ impl<T> Serialize for Version<T> {
     where
         S: Serializer,
     {
-        self.0.serialize(serializer)
+        serializer.collect_str(self)
     }
 }
 
@@ -113,20 +147,63 @@ impl<'de, T> Deserialize<'de> for Version<T> {
     where
         D: Deserializer<'de>,
     {
-        let id = Uuid::deserialize(deserializer)?;
-        Ok(Version(id, PhantomData))
+        let version = String::deserialize(deserializer)?;
+
+        version.parse().map_err(de::Error::custom)
     }
 }
 
 impl<T> Version<T> {
-    pub fn new() -> Self {
-        Version(Uuid::new_v4(), PhantomData)
+    /**
+    Move to the next version.
+
+    The next version is always greater than the current one.
+    */
+    pub(in crate::domain) fn next(&mut self) -> Result<Version<T>, Error> {
+        self.0 = self
+            .0
+            .checked_add(1)
+            .ok_or_else(|| error::conflict("the version is too large to increment"))?;
+
+        Ok(*self)
     }
 }
 
-impl<T> Version<T> {
-    pub(in crate::domain) fn next(&mut self) -> Version<T> {
-        *self = Version(Uuid::new_v4(), PhantomData);
-        *self
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn next_is_greater() {
+        let mut version = Version::<()>::default();
+
+        let first = version.next().unwrap();
+        let second = version.next().unwrap();
+
+        assert!(second > first);
+        assert!(first > Version::default());
+    }
+
+    #[test]
+    fn next_err_if_too_large() {
+        let mut version = Version::<()>(u64::MAX, PhantomData);
+
+        assert!(version.next().is_err());
+        assert_eq!(Version(u64::MAX, PhantomData), version);
+    }
+
+    #[test]
+    fn serde_roundtrip() {
+        let mut version = Version::<()>::default();
+        version.next().unwrap();
+
+        let json = serde_json::to_string(&version).unwrap();
+
+        assert_eq!(r#""0000000000000001""#, json);
+        assert_eq!(version, serde_json::from_str(&json).unwrap());
+
+        for invalid in ["", "1", "not a version", "+000000000000001"] {
+            assert!(invalid.parse::<Version<()>>().is_err(), "{}", invalid);
+        }
     }
 }
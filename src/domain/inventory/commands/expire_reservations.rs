@@ -0,0 +1,132 @@
+/*! Contains the `ExpireReservationsCommand` type. */
+
+use crate::domain::{
+    Error,
+    infra::*,
+    inventory::*,
+};
+
+/**
+Input for an `ExpireReservationsCommand`.
+
+Expired reservations already don't count against available stock, so this command only cleans
+them up. It's run by jobs of the `EXPIRE_RESERVATIONS_JOB` kind, which the API schedules on an
+interval for each tenant.
+*/
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct ExpireReservations {}
+
+/** The kind of job that runs an `ExpireReservationsCommand`. */
+pub const EXPIRE_RESERVATIONS_JOB: &str = "expire-reservations";
+
+impl CommandArgs for ExpireReservations {
+    type Output = Result<usize, Error>;
+}
+
+/** Default implementation for an `ExpireReservationsCommand`. */
+async fn execute(
+    _: ExpireReservations,
+    transaction: ActiveTransaction,
+    store: impl StockStore,
+    filter: impl StockStoreFilter,
+    clock: impl Clock,
+) -> Result<usize, Error> {
+    let now = clock.now();
+
+    let mut expired = 0;
+    for mut stock in filter.filter(|stock| {
+        stock
+            .reservations
+            .iter()
+            .any(|reservation| reservation.expires_at <= now)
+    })? {
+        expired += stock.release_expired(now);
+
+        store.set_stock(transaction.get(), stock)?;
+    }
+
+    Ok(expired)
+}
+
+impl Resolver {
+    /** Release reservations that have expired, returning how many there were. */
+    pub fn expire_reservations_command(&self) -> impl Command<ExpireReservations> {
+        self.command(|resolver, command: ExpireReservations| async move {
+            let store = resolver.stock_store();
+            let filter = resolver.stock_store_filter();
+            let active_transaction = resolver.active_transaction();
+            let clock = resolver.clock();
+
+            execute(command, active_transaction, store, filter, clock).await
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{
+        Duration,
+        SystemTime,
+    };
+
+    use super::*;
+
+    use crate::domain::{
+        inventory::model::{
+            store::in_memory_store,
+            test_data::StockBuilder,
+        },
+        orders::{
+            LineItemId,
+            OrderId,
+        },
+        products::ProductId,
+    };
+
+    #[tokio::test]
+    async fn release_expired_reservations() {
+        let store = in_memory_store(Default::default());
+
+        let product_id = ProductId::new();
+        let now = SystemTime::UNIX_EPOCH;
+
+        let mut stock = StockBuilder::new().product_id(product_id).build();
+        for secs in [10, 20] {
+            stock
+                .reserve(
+                    OrderId::new(),
+                    LineItemId::new(),
+                    1,
+                    now + Duration::from_secs(secs),
+                    now,
+                )
+                .unwrap();
+        }
+
+        store
+            .set_stock(ActiveTransaction::none().get(), stock)
+            .unwrap();
+
+        let expired = execute(
+            ExpireReservations {},
+            ActiveTransaction::none(),
+            &store,
+            &store,
+            now + Duration::from_secs(15),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(1, expired);
+        assert_eq!(
+            1,
+            store
+                .get_stock(product_id, None)
+                .unwrap()
+                .unwrap()
+                .to_data()
+                .reservations
+                .len()
+        );
+    }
+}
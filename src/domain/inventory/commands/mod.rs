@@ -0,0 +1,13 @@
+/*! Commands for modifying inventory state. */
+
+mod expire_reservations;
+mod release_stock;
+mod reserve_stock;
+mod set_stock_level;
+
+pub use self::{
+    expire_reservations::*,
+    release_stock::*,
+    reserve_stock::*,
+    set_stock_level::*,
+};
@@ -0,0 +1,58 @@
+/*! Contains the `ReleaseStockCommand` type. */
+
+use crate::domain::{
+    Error,
+    infra::*,
+    inventory::*,
+    orders::LineItemId,
+    products::{
+        ProductId,
+        VariantId,
+    },
+};
+
+/**
+Input for a `ReleaseStockCommand`.
+
+Releasing stock for a line item without a reservation does nothing.
+*/
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ReleaseStock {
+    pub product_id: ProductId,
+    #[serde(default)]
+    pub variant_id: Option<VariantId>,
+    pub line_item_id: LineItemId,
+}
+
+impl CommandArgs for ReleaseStock {
+    type Output = Result<(), Error>;
+}
+
+/** Default implementation for a `ReleaseStockCommand`. */
+async fn execute(
+    command: ReleaseStock,
+    transaction: ActiveTransaction,
+    store: impl StockStore,
+) -> Result<(), Error> {
+    let Some(mut stock) = store.get_stock(command.product_id, command.variant_id)? else {
+        return Ok(());
+    };
+
+    if stock.release(command.line_item_id).is_some() {
+        store.set_stock(transaction.get(), stock)?;
+    }
+
+    Ok(())
+}
+
+impl Resolver {
+    /** Release the stock reserved for a line item in an order. */
+    pub fn release_stock_command(&self) -> impl Command<ReleaseStock> {
+        self.command(|resolver, command: ReleaseStock| async move {
+            let store = resolver.stock_store();
+            let active_transaction = resolver.active_transaction();
+
+            execute(command, active_transaction, store).await
+        })
+    }
+}
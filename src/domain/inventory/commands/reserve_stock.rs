@@ -0,0 +1,204 @@
+/*! Contains the `ReserveStockCommand` type. */
+
+use crate::domain::{
+    Error,
+    error,
+    infra::*,
+    inventory::*,
+    orders::{
+        LineItemId,
+        OrderId,
+    },
+    products::{
+        ProductId,
+        VariantId,
+    },
+};
+
+/**
+Input for a `ReserveStockCommand`.
+
+Any existing reservation for the line item is replaced.
+Reserving stock for a product that isn't tracked always succeeds.
+*/
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ReserveStock {
+    pub product_id: ProductId,
+    #[serde(default)]
+    pub variant_id: Option<VariantId>,
+    pub order_id: OrderId,
+    pub line_item_id: LineItemId,
+    pub quantity: u32,
+}
+
+impl CommandArgs for ReserveStock {
+    type Output = Result<(), Error>;
+}
+
+/** Default implementation for a `ReserveStockCommand`. */
+async fn execute(
+    command: ReserveStock,
+    transaction: ActiveTransaction,
+    store: impl StockStore,
+    clock: impl Clock,
+    ttl: std::time::Duration,
+) -> Result<(), Error> {
+    let Some(mut stock) = store.get_stock(command.product_id, command.variant_id)? else {
+        return Ok(());
+    };
+
+    let now = clock.now();
+    let expires_at = now
+        .checked_add(ttl)
+        .ok_or_else(|| error::msg("the reservation ttl is too large"))?;
+
+    stock.reserve(
+        command.order_id,
+        command.line_item_id,
+        command.quantity,
+        expires_at,
+        now,
+    )?;
+
+    store.set_stock(transaction.get(), stock)?;
+
+    Ok(())
+}
+
+impl Resolver {
+    /** Reserve stock of a product for a line item in an order. */
+    pub fn reserve_stock_command(&self) -> impl Command<ReserveStock> {
+        self.command(|resolver, command: ReserveStock| async move {
+            let store = resolver.stock_store();
+            let active_transaction = resolver.active_transaction();
+            let clock = resolver.clock();
+            let ttl = resolver.reservation_ttl();
+
+            execute(command, active_transaction, store, clock, ttl).await
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{
+        Duration,
+        SystemTime,
+    };
+
+    use super::*;
+
+    use crate::domain::{
+        ErrorKind,
+        inventory::model::{
+            store::in_memory_store,
+            test_data::StockBuilder,
+        },
+    };
+
+    #[tokio::test]
+    async fn err_if_not_enough_stock() {
+        let store = in_memory_store(Default::default());
+
+        let product_id = ProductId::new();
+        let now = SystemTime::UNIX_EPOCH;
+        let ttl = Duration::from_secs(60);
+
+        store
+            .set_stock(
+                ActiveTransaction::none().get(),
+                StockBuilder::new()
+                    .product_id(product_id)
+                    .on_hand(3)
+                    .build(),
+            )
+            .unwrap();
+
+        let reserve = |quantity| ReserveStock {
+            product_id,
+            variant_id: None,
+            order_id: OrderId::new(),
+            line_item_id: LineItemId::new(),
+            quantity,
+        };
+
+        execute(reserve(2), ActiveTransaction::none(), &store, now, ttl)
+            .await
+            .unwrap();
+
+        let err = execute(reserve(2), ActiveTransaction::none(), &store, now, ttl)
+            .await
+            .err()
+            .unwrap();
+
+        assert_eq!(ErrorKind::Conflict, err.kind());
+
+        // Once the first reservation expires its stock is available again
+        execute(
+            reserve(2),
+            ActiveTransaction::none(),
+            &store,
+            now + ttl,
+            ttl,
+        )
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn untracked_products_are_not_reserved() {
+        let store = in_memory_store(Default::default());
+
+        execute(
+            ReserveStock {
+                product_id: ProductId::new(),
+                variant_id: None,
+                order_id: OrderId::new(),
+                line_item_id: LineItemId::new(),
+                quantity: 100,
+            },
+            ActiveTransaction::none(),
+            &store,
+            SystemTime::UNIX_EPOCH,
+            Duration::from_secs(60),
+        )
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn err_if_ttl_is_too_large() {
+        let store = in_memory_store(Default::default());
+
+        let product_id = ProductId::new();
+
+        store
+            .set_stock(
+                ActiveTransaction::none().get(),
+                StockBuilder::new()
+                    .product_id(product_id)
+                    .on_hand(3)
+                    .build(),
+            )
+            .unwrap();
+
+        let err = execute(
+            ReserveStock {
+                product_id,
+                variant_id: None,
+                order_id: OrderId::new(),
+                line_item_id: LineItemId::new(),
+                quantity: 1,
+            },
+            ActiveTransaction::none(),
+            &store,
+            SystemTime::now(),
+            Duration::MAX,
+        )
+        .await
+        .err()
+        .unwrap();
+
+        assert_eq!(ErrorKind::Other, err.kind());
+    }
+}
@@ -0,0 +1,73 @@
+/*! Contains the `SetStockLevelCommand` type. */
+
+use crate::domain::{
+    Error,
+    error,
+    infra::*,
+    inventory::*,
+    products::*,
+};
+
+/**
+Input for a `SetStockLevelCommand`.
+
+Setting the stock level of a product starts tracking its stock.
+The stock of products with variants is set for each variant.
+*/
+#[derive(Clone, Serialize, Deserialize)]
+pub struct SetStockLevel {
+    pub product_id: ProductId,
+    #[serde(default)]
+    pub variant_id: Option<VariantId>,
+    pub on_hand: u32,
+}
+
+impl CommandArgs for SetStockLevel {
+    type Output = Result<(), Error>;
+}
+
+/** Default implementation for a `SetStockLevelCommand`. */
+async fn execute(
+    command: SetStockLevel,
+    transaction: ActiveTransaction,
+    store: impl StockStore,
+    product_query: impl Query<GetProduct>,
+) -> Result<(), Error> {
+    let stock = match store.get_stock(command.product_id, command.variant_id)? {
+        Some(mut stock) => {
+            stock.set_on_hand(command.on_hand);
+
+            stock
+        }
+        None => {
+            let product = product_query
+                .execute(GetProduct {
+                    id: command.product_id,
+                    include_archived: true,
+                    include_drafts: true,
+                })
+                .await?
+                .ok_or_else(|| error::not_found("product not found"))?;
+
+            Stock::new(&product, command.variant_id, command.on_hand)?
+        }
+    };
+
+    store.set_stock(transaction.get(), stock)?;
+
+    Ok(())
+}
+
+impl Resolver {
+    /** Set the stock on hand for a product. */
+    pub fn set_stock_level_command(&self) -> impl Command<SetStockLevel> {
+        self.command(|resolver, command: SetStockLevel| async move {
+            let store = resolver.stock_store();
+            let active_transaction = resolver.active_transaction();
+
+            let product_query = resolver.get_product_query();
+
+            execute(command, active_transaction, store, product_query).await
+        })
+    }
+}
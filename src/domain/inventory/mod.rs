@@ -0,0 +1,17 @@
+/*! Domain module for product inventory. */
+
+pub mod commands;
+pub mod model;
+pub mod queries;
+pub(in crate::domain) mod resolver;
+
+pub use self::{
+    commands::*,
+    model::*,
+    queries::*,
+};
+
+use self::model::store::{
+    StockStore,
+    StockStoreFilter,
+};
@@ -0,0 +1,265 @@
+/*! Contains the `Stock` entity. */
+
+use std::time::SystemTime;
+
+pub mod store;
+
+#[cfg(test)]
+pub mod test_data;
+
+use crate::domain::{
+    Error,
+    error,
+    infra::*,
+    orders::{
+        LineItemId,
+        OrderId,
+    },
+    products::{
+        Product,
+        ProductId,
+        VariantId,
+    },
+};
+
+pub type StockVersion = Version<StockData>;
+
+/**
+Stock set aside for a line item in an order.
+
+Reservations stop counting against the available stock once they expire.
+*/
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ReservationData {
+    pub order_id: OrderId,
+    pub line_item_id: LineItemId,
+    pub quantity: u32,
+    pub expires_at: SystemTime,
+}
+
+/** Data for the stock of a product or one of its variants. */
+#[derive(Clone, Serialize, Deserialize)]
+pub struct StockData {
+    pub product_id: ProductId,
+    #[serde(default)]
+    pub variant_id: Option<VariantId>,
+    pub version: StockVersion,
+    pub on_hand: u32,
+    pub reservations: Vec<ReservationData>,
+    _private: (),
+}
+
+/**
+The stock of a product.
+
+Stock is identified by the product it's for, and for products with variants, the variant.
+The stock available to order is the stock on hand, less any reservations that haven't expired.
+Products without any stock aren't tracked, so can be ordered in any quantity.
+*/
+pub struct Stock {
+    data: StockData,
+}
+
+impl Stock {
+    pub(self) fn from_data(data: StockData) -> Self {
+        Stock { data }
+    }
+
+    pub fn into_data(self) -> StockData {
+        self.data
+    }
+
+    pub fn to_data(&self) -> &StockData {
+        &self.data
+    }
+
+    /**
+    Start tracking the stock of a product.
+
+    The stock of products with variants is tracked separately for each variant.
+    */
+    pub fn new(
+        product: &Product,
+        variant_id: Option<VariantId>,
+        on_hand: u32,
+    ) -> Result<Self, Error> {
+        match variant_id {
+            Some(variant_id) if product.variant(variant_id).is_none() => {
+                return Err(error::bad_input("variant not found"));
+            }
+            None if !product.to_data().variants.is_empty() => {
+                return Err(error::bad_input(
+                    "stock is tracked for each variant of products with variants",
+                ));
+            }
+            _ => (),
+        }
+
+        Ok(Stock::from_data(StockData {
+            product_id: product.to_data().id,
+            variant_id,
+            version: StockVersion::default(),
+            on_hand,
+            reservations: Vec::new(),
+            _private: (),
+        }))
+    }
+
+    /**
+    Set the stock on hand.
+
+    The stock on hand may be set lower than the stock currently reserved.
+    In that case no more stock is available until enough reservations are released.
+    */
+    pub fn set_on_hand(&mut self, on_hand: u32) {
+        self.data.on_hand = on_hand;
+    }
+
+    /** The stock held by reservations that haven't expired. */
+    pub fn reserved(&self, now: SystemTime) -> u32 {
+        self.data
+            .reservations
+            .iter()
+            .filter(|reservation| reservation.expires_at > now)
+            .map(|reservation| reservation.quantity)
+            .fold(0, u32::saturating_add)
+    }
+
+    /** The stock that can still be reserved. */
+    pub fn available(&self, now: SystemTime) -> u32 {
+        self.data.on_hand.saturating_sub(self.reserved(now))
+    }
+
+    /**
+    Reserve stock for a line item.
+
+    If the line item already has a reservation then it's replaced, so only the difference in
+    quantity needs to be available.
+    */
+    pub fn reserve(
+        &mut self,
+        order_id: OrderId,
+        line_item_id: LineItemId,
+        quantity: u32,
+        expires_at: SystemTime,
+        now: SystemTime,
+    ) -> Result<(), Error> {
+        if quantity < 1 {
+            return Err(error::bad_input("quantity must be greater than 0"));
+        }
+
+        self.release_expired(now);
+        let previous = self.release(line_item_id);
+
+        let available = self.available(now);
+        if quantity > available {
+            if let Some(previous) = previous {
+                self.data.reservations.push(previous);
+            }
+
+            return Err(error::conflict(format_args!(
+                "only {} of the product are available",
+                available
+            )));
+        }
+
+        self.data.reservations.push(ReservationData {
+            order_id,
+            line_item_id,
+            quantity,
+            expires_at,
+        });
+
+        Ok(())
+    }
+
+    /** Release the reservation for a line item, returning it if there was one. */
+    pub fn release(&mut self, line_item_id: LineItemId) -> Option<ReservationData> {
+        let index = self
+            .data
+            .reservations
+            .iter()
+            .position(|reservation| reservation.line_item_id == line_item_id)?;
+
+        Some(self.data.reservations.remove(index))
+    }
+
+    /** Release any reservations that have expired, returning how many there were. */
+    pub fn release_expired(&mut self, now: SystemTime) -> usize {
+        let before = self.data.reservations.len();
+
+        self.data
+            .reservations
+            .retain(|reservation| reservation.expires_at > now);
+
+        before - self.data.reservations.len()
+    }
+}
+
+impl Entity for Stock {
+    type Id = (ProductId, Option<VariantId>);
+    type Version = StockVersion;
+    type Data = StockData;
+    type Error = Error;
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    use crate::domain::{
+        ErrorKind,
+        products::model::test_data::default_product,
+    };
+
+    #[test]
+    fn reserve_available_stock() {
+        let now = SystemTime::UNIX_EPOCH;
+        let expires_at = now + Duration::from_secs(60);
+
+        let mut stock = Stock::new(&default_product(), None, 5).unwrap();
+
+        let order_id = OrderId::new();
+        let first = LineItemId::new();
+        let second = LineItemId::new();
+
+        stock.reserve(order_id, first, 3, expires_at, now).unwrap();
+        assert_eq!(2, stock.available(now));
+
+        let err = stock
+            .reserve(order_id, second, 3, expires_at, now)
+            .err()
+            .unwrap();
+        assert_eq!(ErrorKind::Conflict, err.kind());
+
+        // Changing a reservation only needs the difference to be available
+        stock.reserve(order_id, first, 5, expires_at, now).unwrap();
+        assert_eq!(0, stock.available(now));
+
+        // A failed change keeps the original reservation
+        assert!(stock.reserve(order_id, first, 6, expires_at, now).is_err());
+        assert_eq!(5, stock.reserved(now));
+
+        stock.release(first).unwrap();
+        assert_eq!(5, stock.available(now));
+    }
+
+    #[test]
+    fn expired_reservations_are_not_counted() {
+        let now = SystemTime::UNIX_EPOCH;
+        let expires_at = now + Duration::from_secs(60);
+
+        let mut stock = Stock::new(&default_product(), None, 5).unwrap();
+
+        stock
+            .reserve(OrderId::new(), LineItemId::new(), 5, expires_at, now)
+            .unwrap();
+        assert_eq!(0, stock.available(now));
+        assert_eq!(5, stock.available(expires_at));
+
+        assert_eq!(1, stock.release_expired(expires_at));
+        assert!(stock.data.reservations.is_empty());
+    }
+}
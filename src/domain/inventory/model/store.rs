@@ -0,0 +1,160 @@
+/*! Persistent stock storage. */
+
+use crate::{
+    domain::{
+        Error,
+        inventory::*,
+        products::{
+            ProductId,
+            VariantId,
+        },
+    },
+    store::*,
+};
+
+/** A place to persist and fetch the stock of products and their variants. */
+#[auto_impl(&, Arc)]
+pub(in crate::domain) trait StockStore {
+    fn get_stock(
+        &self,
+        product_id: ProductId,
+        variant_id: Option<VariantId>,
+    ) -> Result<Option<Stock>, Error>;
+    fn set_stock(&self, transaction: &Transaction, stock: Stock) -> Result<(), Error>;
+}
+
+/**
+An additional store for fetching the stock of multiple products at a time.
+
+Like `ProductStoreFilter`, this trait is an implementation detail that will probably need to be
+refactored when we add a proper database.
+*/
+#[auto_impl(&, Arc)]
+pub(in crate::domain) trait StockStoreFilter {
+    fn filter<F>(&self, predicate: F) -> Result<Vec<Stock>, Error>
+    where
+        F: Fn(&StockData) -> bool;
+}
+
+/**
+A test in-memory stock store.
+
+Stock for a variant is stored under the variant's id, otherwise it's stored under the product's id.
+*/
+pub(in crate::domain) struct InMemoryStore(TransactionValueStore<StockData>);
+
+fn key(product_id: ProductId, variant_id: Option<VariantId>) -> Id {
+    match variant_id {
+        Some(variant_id) => variant_id.into(),
+        None => product_id.into(),
+    }
+}
+
+impl StockStore for InMemoryStore {
+    fn get_stock(
+        &self,
+        product_id: ProductId,
+        variant_id: Option<VariantId>,
+    ) -> Result<Option<Stock>, Error> {
+        if let Some((version, data)) = self.0.get(key(product_id, variant_id)) {
+            assert_eq!(version, data.version.into());
+
+            Ok(Some(Stock::from_data(data)))
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn set_stock(&self, transaction: &Transaction, stock: Stock) -> Result<(), Error> {
+        let mut data = stock.into_data();
+        let id = key(data.product_id, data.variant_id);
+
+        self.0.set(
+            transaction,
+            id,
+            Some(data.version),
+            data.version.next()?,
+            data,
+        )?;
+
+        Ok(())
+    }
+}
+
+impl StockStoreFilter for InMemoryStore {
+    fn filter<F>(&self, predicate: F) -> Result<Vec<Stock>, Error>
+    where
+        F: Fn(&StockData) -> bool,
+    {
+        Ok(self
+            .0
+            .get_all(predicate)
+            .map(|(_, data)| Stock::from_data(data))
+            .collect())
+    }
+}
+
+/**
+Create an in-memory stock store.
+
+The store will participate in transactions tracked by the given transaction store.
+*/
+pub(in crate::domain) fn in_memory_store(transaction_store: TransactionStore) -> InMemoryStore {
+    InMemoryStore(TransactionValueStore::new(transaction_store))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::domain::inventory::model::test_data::StockBuilder;
+
+    #[test]
+    fn variants_are_stored_separately() {
+        let store = in_memory_store(Default::default());
+
+        let product_id = ProductId::new();
+        let variant_id = VariantId::new();
+
+        store
+            .set_stock(
+                &Transaction::none(),
+                StockBuilder::new()
+                    .product_id(product_id)
+                    .variant_id(variant_id)
+                    .build(),
+            )
+            .unwrap();
+
+        assert!(store.get_stock(product_id, None).unwrap().is_none());
+        assert!(
+            store
+                .get_stock(product_id, Some(variant_id))
+                .unwrap()
+                .is_some()
+        );
+    }
+
+    #[test]
+    fn set_stock_twice_fails_concurrency_check() {
+        let store = in_memory_store(Default::default());
+
+        let product_id = ProductId::new();
+
+        store
+            .set_stock(
+                &Transaction::none(),
+                StockBuilder::new().product_id(product_id).build(),
+            )
+            .unwrap();
+
+        assert!(
+            store
+                .set_stock(
+                    &Transaction::none(),
+                    StockBuilder::new().product_id(product_id).build(),
+                )
+                .is_err()
+        );
+    }
+}
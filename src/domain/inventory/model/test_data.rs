@@ -0,0 +1,49 @@
+use crate::domain::{
+    inventory::*,
+    products::{
+        ProductId,
+        VariantId,
+        model::test_data::default_product,
+    },
+};
+
+pub fn default_stock() -> Stock {
+    Stock::new(&default_product(), None, 10).unwrap()
+}
+
+pub struct StockBuilder {
+    stock: Stock,
+}
+
+impl Default for StockBuilder {
+    fn default() -> Self {
+        StockBuilder {
+            stock: default_stock(),
+        }
+    }
+}
+
+impl StockBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn product_id(mut self, product_id: ProductId) -> Self {
+        self.stock.data.product_id = product_id;
+        self
+    }
+
+    pub fn variant_id(mut self, variant_id: VariantId) -> Self {
+        self.stock.data.variant_id = Some(variant_id);
+        self
+    }
+
+    pub fn on_hand(mut self, on_hand: u32) -> Self {
+        self.stock.set_on_hand(on_hand);
+        self
+    }
+
+    pub fn build(self) -> Stock {
+        self.stock
+    }
+}
@@ -0,0 +1,81 @@
+/*! Contains the `GetStockLevelQuery` type. */
+
+use crate::domain::{
+    Error,
+    infra::*,
+    inventory::*,
+    products::{
+        ProductId,
+        VariantId,
+    },
+};
+
+/** Input for a `GetStockLevelQuery`. */
+#[derive(Serialize, Deserialize)]
+pub struct GetStockLevel {
+    pub product_id: ProductId,
+    #[serde(default)]
+    pub variant_id: Option<VariantId>,
+}
+
+/**
+The stock level of a product.
+
+Only reservations that haven't expired count towards the reserved stock.
+*/
+#[derive(Serialize)]
+pub struct StockLevel {
+    pub product_id: ProductId,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub variant_id: Option<VariantId>,
+    pub version: StockVersion,
+    pub on_hand: u32,
+    pub reserved: u32,
+    pub available: u32,
+}
+
+impl QueryArgs for GetStockLevel {
+    type Output = Result<Option<StockLevel>, Error>;
+}
+
+/**
+Default implementation for a `GetStockLevelQuery`.
+
+Products that aren't tracked don't have a stock level.
+*/
+async fn execute(
+    query: GetStockLevel,
+    store: impl StockStore,
+    clock: impl Clock,
+) -> Result<Option<StockLevel>, Error> {
+    let now = clock.now();
+
+    Ok(store
+        .get_stock(query.product_id, query.variant_id)?
+        .map(|stock| {
+            let reserved = stock.reserved(now);
+            let available = stock.available(now);
+            let data = stock.into_data();
+
+            StockLevel {
+                product_id: data.product_id,
+                variant_id: data.variant_id,
+                version: data.version,
+                on_hand: data.on_hand,
+                reserved,
+                available,
+            }
+        }))
+}
+
+impl Resolver {
+    /** Get the stock level of a product. */
+    pub fn get_stock_level_query(&self) -> impl Query<GetStockLevel> {
+        self.query(|resolver, query: GetStockLevel| async move {
+            let store = resolver.stock_store();
+            let clock = resolver.clock();
+
+            execute(query, store, clock).await
+        })
+    }
+}
@@ -0,0 +1,5 @@
+/*! Queries for fetching inventory state. */
+
+mod get_stock_level;
+
+pub use self::get_stock_level::*;
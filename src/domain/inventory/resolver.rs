@@ -0,0 +1,67 @@
+/*! Contains the `InventoryResolver` type. */
+
+use std::{
+    sync::Arc,
+    time::Duration,
+};
+
+use crate::domain::{
+    infra::*,
+    inventory::model::store::{
+        self,
+        InMemoryStore,
+        StockStore,
+        StockStoreFilter,
+    },
+};
+
+/**
+The default length of time stock is reserved for a line item.
+
+Reservations are renewed whenever the line item's quantity changes.
+*/
+const DEFAULT_RESERVATION_TTL: Duration = Duration::from_secs(30 * 60);
+
+/**
+Resolver for inventory.
+
+The `InventoryResolver` type wraps private implementation details and exposes them as traits within the `inventory` module.
+*/
+#[derive(Clone)]
+pub(in crate::domain) struct InventoryResolver {
+    stock_store: Register<Arc<InMemoryStore>>,
+    reservation_ttl: Register<Duration>,
+}
+
+impl Default for InventoryResolver {
+    fn default() -> Self {
+        InventoryResolver {
+            stock_store: Register::per_tenant(|resolver| {
+                Arc::new(store::in_memory_store(resolver.transaction_store()))
+            }),
+            reservation_ttl: Register::once(|_| DEFAULT_RESERVATION_TTL),
+        }
+    }
+}
+
+impl AppBuilder {
+    /** Use a different length of time to reserve stock for. */
+    pub fn reservation_ttl(mut self, reservation_ttl: Register<Duration>) -> Self {
+        self.root_resolver.inventory_resolver.reservation_ttl = reservation_ttl;
+        self
+    }
+}
+
+impl Resolver {
+    pub(in crate::domain::inventory) fn stock_store(&self) -> impl StockStore {
+        self.resolve(&self.inventory_resolver.stock_store)
+    }
+
+    pub(in crate::domain::inventory) fn stock_store_filter(&self) -> impl StockStoreFilter {
+        self.resolve(&self.inventory_resolver.stock_store)
+    }
+
+    pub(in crate::domain::inventory) fn reservation_ttl(&self) -> Duration {
+        self.resolve(&self.inventory_resolver.reservation_ttl)
+    }
+}
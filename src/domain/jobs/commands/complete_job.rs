@@ -0,0 +1,98 @@
+/*! Contains the `CompleteJobCommand` type. */
+
+use crate::domain::{
+    Error,
+    error,
+    infra::*,
+    jobs::*,
+};
+
+/**
+Input for a `CompleteJobCommand`.
+
+The error is the message of the error the job failed with, if it failed.
+*/
+#[derive(Clone, Serialize, Deserialize)]
+pub struct CompleteJob {
+    pub id: JobId,
+    pub error: Option<String>,
+}
+
+impl CommandArgs for CompleteJob {
+    type Output = Result<(), Error>;
+}
+
+/** Default implementation for a `CompleteJobCommand`. */
+async fn execute(
+    command: CompleteJob,
+    transaction: ActiveTransaction,
+    store: impl JobStore,
+    clock: impl Clock,
+) -> Result<(), Error> {
+    let mut job = store
+        .get_job(command.id)?
+        .ok_or_else(|| error::not_found("job not found"))?;
+
+    job.complete(clock.now(), command.error)?;
+
+    store.set_job(transaction.get(), job)?;
+
+    Ok(())
+}
+
+impl Resolver {
+    /** Record the outcome of running a job. */
+    pub fn complete_job_command(&self) -> impl Command<CompleteJob> {
+        self.command(|resolver, command: CompleteJob| async move {
+            let store = resolver.job_store();
+            let active_transaction = resolver.active_transaction();
+            let clock = resolver.clock();
+
+            execute(command, active_transaction, store, clock).await
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::SystemTime;
+
+    use super::*;
+
+    use crate::domain::jobs::model::{
+        store::in_memory_store,
+        test_data::JobBuilder,
+    };
+
+    #[tokio::test]
+    async fn record_failure() {
+        let store = in_memory_store(Default::default());
+
+        let id = JobId::new();
+        let now = SystemTime::now();
+
+        store
+            .set_job(
+                ActiveTransaction::none().get(),
+                JobBuilder::new().id(id).build(now),
+            )
+            .unwrap();
+
+        execute(
+            CompleteJob {
+                id,
+                error: Some("failed".to_owned()),
+            },
+            ActiveTransaction::none(),
+            &store,
+            now,
+        )
+        .await
+        .unwrap();
+
+        let job = store.get_job(id).unwrap().unwrap().into_data();
+
+        assert_eq!(JobStatus::Failed, job.status);
+        assert_eq!(Some("failed"), job.last_error.as_deref());
+    }
+}
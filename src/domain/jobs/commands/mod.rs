@@ -0,0 +1,11 @@
+/*! Commands for modifying job state. */
+
+mod complete_job;
+mod run_job;
+mod schedule_job;
+
+pub use self::{
+    complete_job::*,
+    run_job::*,
+    schedule_job::*,
+};
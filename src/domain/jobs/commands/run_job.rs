@@ -0,0 +1,167 @@
+/*! Contains the `RunJobCommand` type. */
+
+use crate::domain::{
+    Error,
+    error,
+    infra::*,
+    jobs::*,
+};
+
+/**
+Input for a `RunJobCommand`.
+
+Running a job executes its handler, but doesn't record the outcome.
+Run jobs in their own transaction, and then complete them with a `CompleteJobCommand` in another,
+so any changes made by failed jobs can be discarded.
+*/
+#[derive(Clone, Serialize, Deserialize)]
+pub struct RunJob {
+    pub id: JobId,
+}
+
+impl CommandArgs for RunJob {
+    type Output = Result<(), Error>;
+}
+
+/** Default implementation for a `RunJobCommand`. */
+async fn execute(
+    command: RunJob,
+    store: impl JobStore,
+    clock: impl Clock,
+    handlers: impl Fn(&str) -> Option<JobHandler>,
+    resolver: Resolver,
+) -> Result<(), Error> {
+    let job = store
+        .get_job(command.id)?
+        .ok_or_else(|| error::not_found("job not found"))?;
+
+    if !job.is_due(clock.now()) {
+        return Err(error::conflict("job isn't due to run"));
+    }
+
+    let job = job.into_data();
+
+    let handler = handlers(&job.kind)
+        .ok_or_else(|| error::msg(format_args!("jobs of kind `{}` can't be run", job.kind)))?;
+
+    handler(resolver, job.args).await
+}
+
+impl Resolver {
+    /** Run a job that's due. */
+    pub fn run_job_command(&self) -> impl Command<RunJob> {
+        self.command(|resolver, command: RunJob| async move {
+            let store = resolver.job_store();
+            let clock = resolver.clock();
+            let handlers = resolver.job_handlers();
+
+            execute(command, store, clock, handlers, resolver.by_ref()).await
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::{
+            Arc,
+            atomic::{
+                AtomicUsize,
+                Ordering,
+            },
+        },
+        time::{
+            Duration,
+            SystemTime,
+        },
+    };
+
+    use futures::FutureExt;
+
+    use super::*;
+
+    use crate::domain::{
+        App,
+        ErrorKind,
+        jobs::model::{
+            store::in_memory_store,
+            test_data::JobBuilder,
+        },
+    };
+
+    #[tokio::test]
+    async fn run_handler_with_args() {
+        let store = in_memory_store(Default::default());
+
+        let id = JobId::new();
+        let now = SystemTime::now();
+
+        store
+            .set_job(
+                ActiveTransaction::none().get(),
+                JobBuilder::new()
+                    .id(id)
+                    .args(serde_json::json!(3))
+                    .build(now),
+            )
+            .unwrap();
+
+        let runs = Arc::new(AtomicUsize::new(0));
+        let handlers = {
+            let runs = runs.clone();
+
+            move |_: &str| {
+                let runs = runs.clone();
+
+                Some(Arc::new(move |_, args: serde_json::Value| {
+                    runs.fetch_add(args.as_u64().unwrap() as usize, Ordering::SeqCst);
+
+                    async { Ok(()) }.boxed()
+                }) as JobHandler)
+            }
+        };
+
+        execute(
+            RunJob { id },
+            &store,
+            now,
+            handlers,
+            App::new().root_resolver.by_ref(),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(3, runs.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn err_if_not_due() {
+        let store = in_memory_store(Default::default());
+
+        let id = JobId::new();
+        let now = SystemTime::now();
+
+        store
+            .set_job(
+                ActiveTransaction::none().get(),
+                JobBuilder::new()
+                    .id(id)
+                    .schedule(JobSchedule::Delay { seconds: 10 })
+                    .build(now),
+            )
+            .unwrap();
+
+        let err = execute(
+            RunJob { id },
+            &store,
+            now + Duration::from_secs(5),
+            |_: &str| None,
+            App::new().root_resolver.by_ref(),
+        )
+        .await
+        .err()
+        .unwrap();
+
+        assert_eq!(ErrorKind::Conflict, err.kind());
+    }
+}
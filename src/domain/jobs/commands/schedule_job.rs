@@ -0,0 +1,143 @@
+/*! Contains the `ScheduleJobCommand` type. */
+
+use crate::domain::{
+    Error,
+    ErrorKind,
+    error,
+    infra::*,
+    jobs::*,
+};
+
+/** Input for a `ScheduleJobCommand`. */
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ScheduleJob {
+    pub id: JobId,
+    pub kind: String,
+    pub args: serde_json::Value,
+    pub schedule: JobSchedule,
+}
+
+impl CommandArgs for ScheduleJob {
+    type Output = Result<(), Error>;
+}
+
+/** Default implementation for a `ScheduleJobCommand`. */
+async fn execute(
+    command: ScheduleJob,
+    transaction: ActiveTransaction,
+    store: impl JobStore,
+    clock: impl Clock,
+    handlers: impl Fn(&str) -> Option<JobHandler>,
+) -> Result<(), Error> {
+    if handlers(&command.kind).is_none() {
+        return Err(error::bad_input(format_args!(
+            "jobs of kind `{}` can't be run",
+            command.kind
+        )));
+    }
+
+    if store.get_job(command.id)?.is_some() {
+        return Err(
+            error::emit(emit::evt!("job {id: command.id} already exists"))
+                .with_kind(ErrorKind::Conflict),
+        );
+    }
+
+    let job = Job::new(
+        command.id,
+        command.kind,
+        command.args,
+        command.schedule,
+        clock.now(),
+    )?;
+
+    store.set_job(transaction.get(), job)?;
+
+    Ok(())
+}
+
+impl Resolver {
+    /** Schedule a job to run in the background. */
+    pub fn schedule_job_command(&self) -> impl Command<ScheduleJob> {
+        self.command(|resolver, command: ScheduleJob| async move {
+            let store = resolver.job_store();
+            let active_transaction = resolver.active_transaction();
+            let clock = resolver.clock();
+            let handlers = resolver.job_handlers();
+
+            execute(command, active_transaction, store, clock, handlers).await
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::Arc,
+        time::SystemTime,
+    };
+
+    use futures::FutureExt;
+
+    use super::*;
+
+    use crate::domain::jobs::model::store::in_memory_store;
+
+    fn handlers(kind: &str) -> Option<JobHandler> {
+        if kind == "test" {
+            Some(Arc::new(|_, _| async { Ok(()) }.boxed()))
+        } else {
+            None
+        }
+    }
+
+    #[tokio::test]
+    async fn schedule_job() {
+        let store = in_memory_store(Default::default());
+
+        let id = JobId::new();
+        let now = SystemTime::now();
+
+        execute(
+            ScheduleJob {
+                id,
+                kind: "test".to_owned(),
+                args: serde_json::Value::Null,
+                schedule: JobSchedule::Delay { seconds: 0 },
+            },
+            ActiveTransaction::none(),
+            &store,
+            now,
+            handlers,
+        )
+        .await
+        .unwrap();
+
+        let job = store.get_job(id).unwrap().unwrap();
+
+        assert!(job.is_due(now));
+    }
+
+    #[tokio::test]
+    async fn err_if_kind_has_no_handler() {
+        let store = in_memory_store(Default::default());
+
+        let err = execute(
+            ScheduleJob {
+                id: JobId::new(),
+                kind: "missing".to_owned(),
+                args: serde_json::Value::Null,
+                schedule: JobSchedule::Delay { seconds: 0 },
+            },
+            ActiveTransaction::none(),
+            &store,
+            SystemTime::now(),
+            handlers,
+        )
+        .await
+        .err()
+        .unwrap();
+
+        assert_eq!(ErrorKind::BadInput, err.kind());
+    }
+}
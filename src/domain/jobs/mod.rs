@@ -0,0 +1,22 @@
+/*!
+Domain module for background jobs.
+
+Jobs run a command registered for their kind after a delay, or repeatedly on an interval.
+Pending jobs are persisted in a store, so they can be picked up by whatever is hosting the app.
+*/
+
+pub mod commands;
+pub mod model;
+pub mod queries;
+pub(in crate::domain) mod resolver;
+
+use self::model::store::{
+    JobStore,
+    JobStoreFilter,
+};
+pub use self::{
+    commands::*,
+    model::*,
+    queries::*,
+    resolver::JobHandler,
+};
@@ -0,0 +1,221 @@
+/*! Contains the `Job` entity. */
+
+use std::time::{
+    Duration,
+    SystemTime,
+};
+
+pub mod store;
+
+#[cfg(test)]
+pub mod test_data;
+
+use crate::domain::{
+    Error,
+    error,
+    infra::*,
+};
+
+pub type JobId = Id<JobData>;
+pub type NextJobId = NextId<JobData>;
+pub type JobVersion = Version<JobData>;
+
+/**
+When a job runs.
+
+- `Delay` runs the job once, after the given number of seconds.
+- `Interval` runs the job repeatedly, waiting the given number of seconds between each run.
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobSchedule {
+    Delay { seconds: u64 },
+    Interval { seconds: u64 },
+}
+
+/**
+The status of a job.
+
+Jobs that run on an interval stay pending after each run.
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobStatus {
+    Pending,
+    Succeeded,
+    Failed,
+}
+
+/** Data for a job. */
+#[derive(Clone, Serialize, Deserialize)]
+pub struct JobData {
+    pub id: JobId,
+    pub version: JobVersion,
+    pub kind: String,
+    pub args: serde_json::Value,
+    pub schedule: JobSchedule,
+    pub status: JobStatus,
+    pub run_at: SystemTime,
+    pub runs: u32,
+    pub last_run_at: Option<SystemTime>,
+    pub last_error: Option<String>,
+    _private: (),
+}
+
+/**
+A job that runs a command in the background.
+
+The kind of a job determines which command it runs, and its arguments are passed to that command.
+*/
+pub struct Job {
+    data: JobData,
+}
+
+impl Job {
+    pub(self) fn from_data(data: JobData) -> Self {
+        Job { data }
+    }
+
+    pub fn into_data(self) -> JobData {
+        self.data
+    }
+
+    pub fn to_data(&self) -> &JobData {
+        &self.data
+    }
+
+    pub fn new(
+        id: impl IdProvider<JobData>,
+        kind: impl Into<String>,
+        args: serde_json::Value,
+        schedule: JobSchedule,
+        now: SystemTime,
+    ) -> Result<Self, Error> {
+        let id = id.get()?;
+
+        if let JobSchedule::Interval { seconds: 0 } = schedule {
+            return Err(error::bad_input(
+                "job intervals must be at least one second",
+            ));
+        }
+
+        Ok(Job::from_data(JobData {
+            id,
+            version: JobVersion::default(),
+            kind: kind.into(),
+            args,
+            schedule,
+            status: JobStatus::Pending,
+            run_at: next_run_at(schedule, now)?,
+            runs: 0,
+            last_run_at: None,
+            last_error: None,
+            _private: (),
+        }))
+    }
+
+    /** Whether the job is pending and its scheduled time has passed. */
+    pub fn is_due(&self, now: SystemTime) -> bool {
+        self.data.status == JobStatus::Pending && self.data.run_at <= now
+    }
+
+    /**
+    Record the outcome of running the job.
+
+    Jobs that run on an interval are scheduled to run again, whether or not they succeeded.
+    Other jobs are completed.
+    */
+    pub fn complete(&mut self, now: SystemTime, error: Option<String>) -> Result<(), Error> {
+        if self.data.status != JobStatus::Pending {
+            return Err(error::conflict("job has already completed"));
+        }
+
+        match self.data.schedule {
+            JobSchedule::Interval { .. } => {
+                self.data.run_at = next_run_at(self.data.schedule, now)?;
+            }
+            JobSchedule::Delay { .. } => {
+                self.data.status = if error.is_some() {
+                    JobStatus::Failed
+                } else {
+                    JobStatus::Succeeded
+                };
+            }
+        }
+
+        self.data.runs += 1;
+        self.data.last_run_at = Some(now);
+        self.data.last_error = error;
+
+        Ok(())
+    }
+}
+
+fn next_run_at(schedule: JobSchedule, now: SystemTime) -> Result<SystemTime, Error> {
+    let (JobSchedule::Delay { seconds } | JobSchedule::Interval { seconds }) = schedule;
+
+    now.checked_add(Duration::from_secs(seconds))
+        .ok_or_else(|| error::bad_input("job is scheduled too far in the future"))
+}
+
+impl Entity for Job {
+    type Id = JobId;
+    type Version = JobVersion;
+    type Data = JobData;
+    type Error = Error;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::domain::jobs::model::test_data::JobBuilder;
+
+    #[test]
+    fn interval_must_be_valid() {
+        assert!(
+            Job::new(
+                JobId::new(),
+                "test",
+                serde_json::Value::Null,
+                JobSchedule::Interval { seconds: 0 },
+                SystemTime::now(),
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn delayed_job_completes_once() {
+        let now = SystemTime::now();
+        let mut job = JobBuilder::new()
+            .schedule(JobSchedule::Delay { seconds: 10 })
+            .build(now);
+
+        assert!(!job.is_due(now));
+
+        let now = now + Duration::from_secs(10);
+        assert!(job.is_due(now));
+
+        job.complete(now, Some("failed".to_owned())).unwrap();
+
+        assert_eq!(JobStatus::Failed, job.data.status);
+        assert!(!job.is_due(now));
+        assert!(job.complete(now, None).is_err());
+    }
+
+    #[test]
+    fn interval_job_is_rescheduled() {
+        let now = SystemTime::now();
+        let mut job = JobBuilder::new()
+            .schedule(JobSchedule::Interval { seconds: 10 })
+            .build(now);
+
+        let now = now + Duration::from_secs(10);
+        job.complete(now, None).unwrap();
+
+        assert_eq!(JobStatus::Pending, job.data.status);
+        assert_eq!(1, job.data.runs);
+        assert_eq!(now + Duration::from_secs(10), job.data.run_at);
+    }
+}
@@ -0,0 +1,136 @@
+/*! Persistent storage for jobs. */
+
+use std::vec::IntoIter;
+
+use crate::{
+    domain::{
+        Error,
+        jobs::*,
+    },
+    store::*,
+};
+
+/* A place to persist and fetch job entities. */
+#[auto_impl(&, Arc)]
+pub(in crate::domain) trait JobStore {
+    fn get_job(&self, id: JobId) -> Result<Option<Job>, Error>;
+    fn set_job(&self, transaction: &Transaction, job: Job) -> Result<(), Error>;
+}
+
+/**
+An additional store for fetching multiple job records at a time.
+
+This is used to find jobs that are due to run.
+*/
+#[auto_impl(&, Arc)]
+pub(in crate::domain) trait JobStoreFilter {
+    fn filter<F>(&self, predicate: F) -> Result<Iter, Error>
+    where
+        F: Fn(&JobData) -> bool;
+}
+
+pub(in crate::domain) type Iter = IntoIter<JobData>;
+
+/** A test in-memory job store. */
+pub(in crate::domain) struct InMemoryStore(TransactionValueStore<JobData>);
+
+impl JobStore for InMemoryStore {
+    fn get_job(&self, id: JobId) -> Result<Option<Job>, Error> {
+        if let Some((version, data)) = self.0.get(id) {
+            assert_eq!(version, data.version.into());
+
+            Ok(Some(Job::from_data(data)))
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn set_job(&self, transaction: &Transaction, job: Job) -> Result<(), Error> {
+        let mut data = job.into_data();
+        let id = data.id;
+
+        self.0.set(
+            transaction,
+            id,
+            Some(data.version),
+            data.version.next()?,
+            data,
+        )?;
+
+        Ok(())
+    }
+}
+
+impl JobStoreFilter for InMemoryStore {
+    #[allow(clippy::needless_collect)]
+    fn filter<F>(&self, predicate: F) -> Result<Iter, Error>
+    where
+        F: Fn(&JobData) -> bool,
+    {
+        let jobs: Vec<_> = self.0.get_all(predicate).map(|(_, data)| data).collect();
+
+        Ok(jobs.into_iter())
+    }
+}
+
+/**
+Create an in-memory job store.
+
+The store will participate in transactions tracked by the given transaction store.
+*/
+pub(in crate::domain) fn in_memory_store(transaction_store: TransactionStore) -> InMemoryStore {
+    InMemoryStore(TransactionValueStore::new(transaction_store))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::SystemTime;
+
+    use super::*;
+
+    use crate::domain::{
+        ErrorKind,
+        jobs::model::test_data::JobBuilder,
+    };
+
+    #[test]
+    fn test_in_memory_store() {
+        let store = in_memory_store(Default::default());
+
+        let id = JobId::new();
+
+        store
+            .set_job(
+                &Transaction::none(),
+                JobBuilder::new().id(id).build(SystemTime::now()),
+            )
+            .unwrap();
+
+        let found = store.get_job(id).unwrap().unwrap();
+        assert_eq!(id, found.data.id);
+    }
+
+    #[test]
+    fn add_job_twice_fails_concurrency_check() {
+        let store = in_memory_store(Default::default());
+
+        let id = JobId::new();
+
+        store
+            .set_job(
+                &Transaction::none(),
+                JobBuilder::new().id(id).build(SystemTime::now()),
+            )
+            .unwrap();
+
+        let err = store
+            .set_job(
+                &Transaction::none(),
+                JobBuilder::new().id(id).build(SystemTime::now()),
+            )
+            .err()
+            .unwrap();
+
+        assert_eq!(ErrorKind::Conflict, err.kind());
+    }
+}
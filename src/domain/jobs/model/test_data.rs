@@ -0,0 +1,55 @@
+use std::time::SystemTime;
+
+use crate::domain::jobs::*;
+
+pub fn default_kind() -> String {
+    "test".to_owned()
+}
+
+pub struct JobBuilder {
+    id: JobId,
+    kind: String,
+    args: serde_json::Value,
+    schedule: JobSchedule,
+}
+
+impl Default for JobBuilder {
+    fn default() -> Self {
+        JobBuilder {
+            id: JobId::new(),
+            kind: default_kind(),
+            args: serde_json::Value::Null,
+            schedule: JobSchedule::Delay { seconds: 0 },
+        }
+    }
+}
+
+impl JobBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn id(mut self, id: JobId) -> Self {
+        self.id = id;
+        self
+    }
+
+    pub fn kind(mut self, kind: &str) -> Self {
+        self.kind = kind.to_owned();
+        self
+    }
+
+    pub fn args(mut self, args: serde_json::Value) -> Self {
+        self.args = args;
+        self
+    }
+
+    pub fn schedule(mut self, schedule: JobSchedule) -> Self {
+        self.schedule = schedule;
+        self
+    }
+
+    pub fn build(self, now: SystemTime) -> Job {
+        Job::new(self.id, self.kind, self.args, self.schedule, now).unwrap()
+    }
+}
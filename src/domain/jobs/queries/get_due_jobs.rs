@@ -0,0 +1,87 @@
+/*! Contains the `GetDueJobsQuery` type. */
+
+use crate::domain::{
+    Error,
+    infra::*,
+    jobs::*,
+};
+
+/** Input for a `GetDueJobsQuery`. */
+#[derive(Serialize, Deserialize)]
+pub struct GetDueJobs {}
+
+impl QueryArgs for GetDueJobs {
+    type Output = Result<Vec<JobId>, Error>;
+}
+
+/** Default implementation for a `GetDueJobsQuery`. */
+async fn execute(
+    _: GetDueJobs,
+    filter: impl JobStoreFilter,
+    clock: impl Clock,
+) -> Result<Vec<JobId>, Error> {
+    let now = clock.now();
+
+    let mut jobs: Vec<_> = filter
+        .filter(|job| job.status == JobStatus::Pending && job.run_at <= now)?
+        .collect();
+    jobs.sort_by_key(|job| job.run_at);
+
+    Ok(jobs.into_iter().map(|job| job.id).collect())
+}
+
+impl Resolver {
+    /** Get the ids of jobs that are due to run, in the order they were scheduled to run. */
+    pub fn get_due_jobs_query(&self) -> impl Query<GetDueJobs> {
+        self.query(|resolver, query: GetDueJobs| async move {
+            let filter = resolver.job_store_filter();
+            let clock = resolver.clock();
+
+            execute(query, filter, clock).await
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{
+        Duration,
+        SystemTime,
+    };
+
+    use super::*;
+
+    use crate::{
+        domain::jobs::model::{
+            store::in_memory_store,
+            test_data::JobBuilder,
+        },
+        store::Transaction,
+    };
+
+    #[tokio::test]
+    async fn get_due_jobs_in_order() {
+        let store = in_memory_store(Default::default());
+        let now = SystemTime::now();
+
+        let (later, sooner, not_due) = (JobId::new(), JobId::new(), JobId::new());
+
+        for (id, seconds) in [(later, 5), (sooner, 1), (not_due, 20)] {
+            store
+                .set_job(
+                    &Transaction::none(),
+                    JobBuilder::new()
+                        .id(id)
+                        .schedule(JobSchedule::Delay { seconds })
+                        .build(now),
+                )
+                .unwrap();
+        }
+
+        let due = execute(GetDueJobs {}, &store, now + Duration::from_secs(10))
+            .await
+            .unwrap();
+
+        assert_eq!(vec![sooner, later], due);
+    }
+}
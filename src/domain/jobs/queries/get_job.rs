@@ -0,0 +1,35 @@
+/*! Contains the `GetJobQuery` type. */
+
+use crate::domain::{
+    Error,
+    infra::*,
+    jobs::*,
+};
+
+/** Input for a `GetJobQuery`. */
+#[derive(Serialize, Deserialize)]
+pub struct GetJob {
+    pub id: JobId,
+}
+
+impl QueryArgs for GetJob {
+    type Output = Result<Option<Job>, Error>;
+}
+
+/** Default implementation for a `GetJobQuery`. */
+async fn execute(query: GetJob, store: impl JobStore) -> Result<Option<Job>, Error> {
+    let job = store.get_job(query.id)?;
+
+    Ok(job)
+}
+
+impl Resolver {
+    /** Get a job. */
+    pub fn get_job_query(&self) -> impl Query<GetJob> {
+        self.query(|resolver, query: GetJob| async move {
+            let store = resolver.job_store();
+
+            execute(query, store).await
+        })
+    }
+}
@@ -0,0 +1,34 @@
+/*! Contains the `GetJobsQuery` type. */
+
+use crate::domain::{
+    Error,
+    infra::*,
+    jobs::*,
+};
+
+/** Input for a `GetJobsQuery`. */
+#[derive(Serialize, Deserialize)]
+pub struct GetJobs {}
+
+impl QueryArgs for GetJobs {
+    type Output = Result<Vec<JobData>, Error>;
+}
+
+/** Default implementation for a `GetJobsQuery`. */
+async fn execute(_: GetJobs, filter: impl JobStoreFilter) -> Result<Vec<JobData>, Error> {
+    let mut jobs: Vec<_> = filter.filter(|_| true)?.collect();
+    jobs.sort_by_key(|job| job.run_at);
+
+    Ok(jobs)
+}
+
+impl Resolver {
+    /** Get all jobs, in the order they're scheduled to run. */
+    pub fn get_jobs_query(&self) -> impl Query<GetJobs> {
+        self.query(|resolver, query: GetJobs| async move {
+            let filter = resolver.job_store_filter();
+
+            execute(query, filter).await
+        })
+    }
+}
@@ -0,0 +1,11 @@
+/*! Queries for fetching job state. */
+
+mod get_due_jobs;
+mod get_job;
+mod get_jobs;
+
+pub use self::{
+    get_due_jobs::*,
+    get_job::*,
+    get_jobs::*,
+};
@@ -0,0 +1,145 @@
+/*! Contains the `JobsResolver` type. */
+
+use std::{
+    collections::HashMap,
+    future::Future,
+    sync::Arc,
+};
+
+use futures::{
+    FutureExt,
+    future::{
+        self,
+        BoxFuture,
+    },
+};
+use serde::de::DeserializeOwned;
+
+use crate::domain::{
+    Error,
+    error,
+    infra::*,
+    inventory::{
+        EXPIRE_RESERVATIONS_JOB,
+        ExpireReservations,
+    },
+    jobs::{
+        JobData,
+        model::store::{
+            self,
+            InMemoryStore,
+            JobStore,
+            JobStoreFilter,
+        },
+    },
+};
+
+/**
+A function that runs jobs of a given kind.
+
+Handlers are given a resolver that participates in the transaction the job is run in.
+*/
+pub type JobHandler =
+    Arc<dyn Fn(Resolver, serde_json::Value) -> BoxFuture<'static, Result<(), Error>> + Send + Sync>;
+
+/**
+Resolver for jobs.
+
+The `JobsResolver` type wraps private implementation details and exposes them as traits within the `jobs` module.
+*/
+#[derive(Clone)]
+pub(in crate::domain) struct JobsResolver {
+    job_store: Register<Arc<InMemoryStore>>,
+    job_id: Register<Arc<dyn IdProvider<JobData> + Send + Sync>>,
+    job_handlers: Arc<HashMap<String, JobHandler>>,
+}
+
+impl Default for JobsResolver {
+    fn default() -> Self {
+        JobsResolver {
+            job_store: Register::per_tenant(|resolver| {
+                Arc::new(store::in_memory_store(resolver.transaction_store()))
+            }),
+            job_id: Register::once(|_| {
+                Arc::new(NextId::<JobData>::new()) as Arc<dyn IdProvider<JobData> + Send + Sync>
+            }),
+            job_handlers: Arc::new(HashMap::from([(
+                EXPIRE_RESERVATIONS_JOB.to_owned(),
+                // Jobs scheduled without any arguments still use the defaults
+                job_handler(
+                    |resolver: Resolver, args: Option<ExpireReservations>| async move {
+                        resolver
+                            .expire_reservations_command()
+                            .execute(args.unwrap_or_default())
+                            .await?;
+
+                        Ok(())
+                    },
+                ),
+            )])),
+        }
+    }
+}
+
+/** Wrap a handler so it's called with the deserialized arguments of a job. */
+fn job_handler<TArgs, F, O>(handler: F) -> JobHandler
+where
+    TArgs: DeserializeOwned + Send + 'static,
+    F: Fn(Resolver, TArgs) -> O + Send + Sync + 'static,
+    O: Future<Output = Result<(), Error>> + Send + 'static,
+{
+    Arc::new(
+        move |resolver, args| match serde_json::from_value::<TArgs>(args) {
+            Ok(args) => handler(resolver, args).boxed(),
+            Err(err) => future::err(error::bad_input(err)).boxed(),
+        },
+    )
+}
+
+impl AppBuilder {
+    /** Use a different source of ids for new jobs. */
+    pub fn job_id(mut self, job_id: Register<Arc<dyn IdProvider<JobData> + Send + Sync>>) -> Self {
+        self.root_resolver.jobs_resolver.job_id = job_id;
+        self
+    }
+
+    /**
+    Run jobs of the given kind.
+
+    The arguments a job was scheduled with are deserialized and passed to the handler.
+    Jobs can only be scheduled for kinds that have a handler.
+    The only kind registered by default is `EXPIRE_RESERVATIONS_JOB`.
+    */
+    pub fn job<TArgs, F, O>(mut self, kind: impl Into<String>, handler: F) -> Self
+    where
+        TArgs: DeserializeOwned + Send + 'static,
+        F: Fn(Resolver, TArgs) -> O + Send + Sync + 'static,
+        O: Future<Output = Result<(), Error>> + Send + 'static,
+    {
+        Arc::make_mut(&mut self.root_resolver.jobs_resolver.job_handlers)
+            .insert(kind.into(), job_handler(handler));
+        self
+    }
+}
+
+impl Resolver {
+    pub fn job_id(&self) -> impl IdProvider<JobData> {
+        self.resolve(&self.jobs_resolver.job_id)
+    }
+
+    pub(in crate::domain::jobs) fn job_store(&self) -> impl JobStore {
+        self.resolve(&self.jobs_resolver.job_store)
+    }
+
+    pub(in crate::domain::jobs) fn job_store_filter(&self) -> impl JobStoreFilter {
+        self.resolve(&self.jobs_resolver.job_store)
+    }
+
+    pub(in crate::domain::jobs) fn job_handlers(
+        &self,
+    ) -> impl Fn(&str) -> Option<JobHandler> + Send + Sync + use<> {
+        let handlers = self.jobs_resolver.job_handlers.clone();
+
+        move |kind| handlers.get(kind).cloned()
+    }
+}
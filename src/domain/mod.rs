@@ -10,12 +10,18 @@ Cross-cutting concerns should either live in the most specific entity submodule,
 */
 
 #[macro_use]
-mod error;
+pub mod error;
 pub mod infra;
 
+pub mod categories;
 pub mod customers;
+pub mod exchange_rates;
+pub mod inventory;
+pub mod jobs;
 pub mod orders;
 pub mod products;
+pub mod promotions;
+pub mod taxes;
 
 pub use self::{
     error::{
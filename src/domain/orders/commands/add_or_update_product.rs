@@ -4,16 +4,28 @@ use crate::domain::{
     Error,
     error,
     infra::*,
+    inventory::*,
     orders::*,
     products::*,
 };
 
-/** Input for an `AddOrUpdateProductCommand`. */
+/**
+Input for an `AddOrUpdateProductCommand`.
+
+Products with variants are added as the given variant.
+Stock is reserved for the line item in the same transaction as the order change.
+If there isn't enough stock available then the order isn't changed.
+If a version is given then the order must still have that version for the product to be added or updated.
+*/
 #[derive(Clone, Serialize, Deserialize)]
 pub struct AddOrUpdateProduct {
     pub id: OrderId,
     pub product_id: ProductId,
+    #[serde(default)]
+    pub variant_id: Option<VariantId>,
     pub quantity: u32,
+    #[serde(default)]
+    pub version: Option<OrderVersion>,
 }
 
 impl CommandArgs for AddOrUpdateProduct {
@@ -26,13 +38,27 @@ async fn execute(
     store: impl OrderStore,
     id: impl IdProvider<LineItemData>,
     product_query: impl Query<GetProduct>,
+    reserve_command: impl Command<ReserveStock>,
 ) -> Result<LineItemId, Error> {
     if let Some(order) = store.get_order(command.id)? {
-        let id = match order.into_line_item_for_product(command.product_id) {
+        order.check_version(command.version)?;
+
+        let id = match order.into_line_item_for_product(command.product_id, command.variant_id) {
             IntoLineItem::InOrder(mut line_item) => {
                 let (_, &LineItemData { id, .. }) = line_item.to_data();
 
                 line_item.set_quantity(command.quantity)?;
+
+                reserve_command
+                    .execute(ReserveStock {
+                        product_id: command.product_id,
+                        variant_id: command.variant_id,
+                        order_id: command.id,
+                        line_item_id: id,
+                        quantity: command.quantity,
+                    })
+                    .await?;
+
                 store.set_line_item(transaction.get(), line_item)?;
 
                 id
@@ -42,11 +68,24 @@ async fn execute(
                 let product = product_query
                     .execute(GetProduct {
                         id: command.product_id,
+                        include_archived: false,
+                        include_drafts: true,
                     })
                     .await?
-                    .ok_or_else(|| error::bad_input("product not found"))?;
+                    .ok_or_else(|| error::not_found("product not found"))?;
+
+                order.add_product_variant(id, &product, command.variant_id, command.quantity)?;
+
+                reserve_command
+                    .execute(ReserveStock {
+                        product_id: command.product_id,
+                        variant_id: command.variant_id,
+                        order_id: command.id,
+                        line_item_id: id,
+                        quantity: command.quantity,
+                    })
+                    .await?;
 
-                order.add_product(id, &product, command.quantity)?;
                 store.set_order(transaction.get(), order)?;
 
                 id
@@ -55,7 +94,7 @@ async fn execute(
 
         Ok(id)
     } else {
-        Err(error::bad_input("not found"))
+        Err(error::not_found("order not found"))
     }
 }
 
@@ -69,8 +108,17 @@ impl Resolver {
             let id = resolver.line_item_id();
 
             let get_product = resolver.get_product_query();
+            let reserve_stock = resolver.reserve_stock_command();
 
-            execute(command, active_transaction, store, id, get_product).await
+            execute(
+                command,
+                active_transaction,
+                store,
+                id,
+                get_product,
+                reserve_stock,
+            )
+            .await
         })
     }
 }
@@ -80,6 +128,7 @@ mod tests {
     use super::*;
 
     use crate::domain::{
+        ErrorKind,
         orders::model::{
             store::in_memory_store,
             test_data::OrderBuilder,
@@ -106,12 +155,15 @@ mod tests {
             AddOrUpdateProduct {
                 id: order_id,
                 product_id,
+                variant_id: None,
                 quantity,
+                version: None,
             },
             ActiveTransaction::none(),
             &store,
             NextLineItemId::new(),
             |_| async { Ok(Some(ProductBuilder::new().id(product_id).build())) },
+            |_| async { Ok(()) },
         )
         .await
         .unwrap();
@@ -150,12 +202,15 @@ mod tests {
             AddOrUpdateProduct {
                 id: order_id,
                 product_id,
+                variant_id: None,
                 quantity,
+                version: None,
             },
             ActiveTransaction::none(),
             &store,
             NextLineItemId::new(),
             |_| async { Ok(Some(ProductBuilder::new().id(product_id).build())) },
+            |_| async { Ok(()) },
         )
         .await
         .unwrap();
@@ -169,4 +224,46 @@ mod tests {
         assert_eq!(line_item_id, updated_line_item_id);
         assert_eq!(quantity, line_item.quantity);
     }
+
+    #[tokio::test]
+    async fn err_if_stock_is_not_available() {
+        let store = in_memory_store(Default::default());
+
+        let order_id = OrderId::new();
+        let product_id = ProductId::new();
+
+        store
+            .set_order(
+                ActiveTransaction::none().get(),
+                OrderBuilder::new().id(order_id).build(),
+            )
+            .unwrap();
+
+        let err = execute(
+            AddOrUpdateProduct {
+                id: order_id,
+                product_id,
+                variant_id: None,
+                quantity: 3,
+                version: None,
+            },
+            ActiveTransaction::none(),
+            &store,
+            NextLineItemId::new(),
+            |_| async { Ok(Some(ProductBuilder::new().id(product_id).build())) },
+            |_| async { Err(error::conflict("only 2 of the product are available")) },
+        )
+        .await
+        .err()
+        .unwrap();
+
+        assert_eq!(ErrorKind::Conflict, err.kind());
+        assert!(
+            !store
+                .get_order(order_id)
+                .unwrap()
+                .unwrap()
+                .contains_product(product_id)
+        );
+    }
 }
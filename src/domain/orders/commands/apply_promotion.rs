@@ -0,0 +1,162 @@
+/*! Contains the `ApplyPromotionCommand` type. */
+
+use crate::domain::{
+    Error,
+    error,
+    infra::*,
+    orders::*,
+    promotions::*,
+};
+
+/**
+Input for an `ApplyPromotionCommand`.
+
+Applying a promotion redeems it, counting towards its usage limit.
+If a version is given then the order must still have that version for the promotion to be applied.
+*/
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ApplyPromotion {
+    pub id: OrderId,
+    pub code: String,
+    #[serde(default)]
+    pub version: Option<OrderVersion>,
+}
+
+impl CommandArgs for ApplyPromotion {
+    type Output = Result<DiscountLineId, Error>;
+}
+
+/** Default implementation for an `ApplyPromotionCommand`. */
+async fn execute(
+    command: ApplyPromotion,
+    transaction: ActiveTransaction,
+    store: impl OrderStore,
+    id: impl IdProvider<DiscountLineData>,
+    redeem_command: impl Command<RedeemPromotion>,
+    promotion_query: impl Query<GetPromotion>,
+) -> Result<DiscountLineId, Error> {
+    let Some(mut order) = store.get_order(command.id)? else {
+        return Err(error::not_found("order not found"));
+    };
+
+    order.check_version(command.version)?;
+
+    let promotion_id = redeem_command
+        .execute(RedeemPromotion { code: command.code })
+        .await?;
+
+    let promotion = promotion_query
+        .execute(GetPromotion { id: promotion_id })
+        .await?
+        .ok_or_else(|| error::not_found("promotion not found"))?;
+
+    let id = id.get()?;
+
+    order.apply_promotion(id, &promotion)?;
+    store.set_order(transaction.get(), order)?;
+
+    Ok(id)
+}
+
+impl Resolver {
+    /** Apply a promotion to an order using its code. */
+    pub fn apply_promotion_command(&self) -> impl Command<ApplyPromotion> {
+        self.command(|resolver, command: ApplyPromotion| async move {
+            let store = resolver.order_store();
+            let active_transaction = resolver.active_transaction();
+
+            let id = resolver.discount_line_id();
+
+            let redeem_promotion = resolver.redeem_promotion_command();
+            let get_promotion = resolver.get_promotion_query();
+
+            execute(
+                command,
+                active_transaction,
+                store,
+                id,
+                redeem_promotion,
+                get_promotion,
+            )
+            .await
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::domain::{
+        ErrorKind,
+        orders::model::{
+            store::in_memory_store,
+            test_data::OrderBuilder,
+        },
+        products::model::test_data::default_product,
+        promotions::model::test_data::PromotionBuilder,
+    };
+
+    #[tokio::test]
+    async fn apply_promotion() {
+        let store = in_memory_store(Default::default());
+
+        let order_id = OrderId::new();
+        let promotion_id = PromotionId::new();
+
+        store
+            .set_order(
+                ActiveTransaction::none().get(),
+                OrderBuilder::new()
+                    .id(order_id)
+                    .add_product(default_product(), |line_item| line_item)
+                    .build(),
+            )
+            .unwrap();
+
+        let discount_line_id = execute(
+            ApplyPromotion {
+                id: order_id,
+                code: "SAVE".to_owned(),
+                version: None,
+            },
+            ActiveTransaction::none(),
+            &store,
+            NextDiscountLineId::new(),
+            |_| async move { Ok(promotion_id) },
+            |_| async move { Ok(Some(PromotionBuilder::new().id(promotion_id).build())) },
+        )
+        .await
+        .unwrap();
+
+        let order = store.get_order(order_id).unwrap().unwrap();
+        let (_, _, discount_lines) = order.to_data();
+
+        assert_eq!(1, discount_lines.len());
+        assert_eq!(discount_line_id, discount_lines[0].id);
+        assert_eq!(promotion_id, discount_lines[0].promotion_id);
+    }
+
+    #[tokio::test]
+    async fn err_if_not_found() {
+        let store = in_memory_store(Default::default());
+
+        let err = execute(
+            ApplyPromotion {
+                id: OrderId::new(),
+                code: "SAVE".to_owned(),
+                version: None,
+            },
+            ActiveTransaction::none(),
+            &store,
+            NextDiscountLineId::new(),
+            |_| async { Ok(PromotionId::new()) },
+            |_| async { Ok(None) },
+        )
+        .await
+        .err()
+        .unwrap();
+
+        assert_eq!(ErrorKind::NotFound, err.kind());
+    }
+}
@@ -2,6 +2,7 @@
 
 use crate::domain::{
     Error,
+    ErrorKind,
     customers::*,
     error,
     infra::*,
@@ -16,7 +17,14 @@ pub struct CreateOrder {
 }
 
 impl CommandArgs for CreateOrder {
-    type Output = Result<(), Error>;
+    type Output = Result<OrderId, Error>;
+
+    fn idempotency_args(&self) -> Result<serde_json::Value, serde_json::Error> {
+        // The id is generated for each request, so retries won't have the same one
+        Ok(serde_json::json!({
+            "customer_id": self.customer_id,
+        }))
+    }
 }
 
 async fn execute(
@@ -24,16 +32,19 @@ async fn execute(
     transaction: ActiveTransaction,
     store: impl OrderStore,
     customer_query: impl Query<GetCustomer>,
-) -> Result<(), Error> {
+) -> Result<OrderId, Error> {
     let order = {
         if store.get_order(command.id)?.is_some() {
-            return Err(error::emit(emit::evt!(
-                "order {order_id: command.id} already exists"
-            )));
+            return Err(
+                error::emit(emit::evt!("order {order_id: command.id} already exists"))
+                    .with_kind(ErrorKind::Conflict),
+            );
         } else {
             let customer = customer_query
                 .execute(GetCustomer {
                     id: command.customer_id,
+                    // Archived customers are found so ordering for them is forbidden rather than missing
+                    include_archived: true,
                 })
                 .await?
                 .ok_or_else(|| error::bad_input("customer not found"))?;
@@ -44,7 +55,7 @@ async fn execute(
 
     store.set_order(transaction.get(), order)?;
 
-    Ok(())
+    Ok(command.id)
 }
 
 impl Resolver {
@@ -1,9 +1,15 @@
 /*! Commands for modifying order state. */
 
 mod add_or_update_product;
+mod apply_promotion;
 mod create_order;
+mod remove_product;
+mod set_order_tax_region;
 
 pub use self::{
     add_or_update_product::*,
+    apply_promotion::*,
     create_order::*,
+    remove_product::*,
+    set_order_tax_region::*,
 };
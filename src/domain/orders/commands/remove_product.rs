@@ -0,0 +1,133 @@
+/*! Contains the `RemoveProductCommand` type. */
+
+use crate::domain::{
+    Error,
+    error,
+    infra::*,
+    inventory::*,
+    orders::*,
+    products::*,
+};
+
+/**
+Input for a `RemoveProductCommand`.
+
+Any stock reserved for the product's line item is released in the same transaction as the order change.
+If a version is given then the order must still have that version for the product to be removed.
+*/
+#[derive(Clone, Serialize, Deserialize)]
+pub struct RemoveProduct {
+    pub id: OrderId,
+    pub product_id: ProductId,
+    #[serde(default)]
+    pub variant_id: Option<VariantId>,
+    #[serde(default)]
+    pub version: Option<OrderVersion>,
+}
+
+impl CommandArgs for RemoveProduct {
+    type Output = Result<(), Error>;
+}
+
+/** Default implementation for a `RemoveProductCommand`. */
+async fn execute(
+    command: RemoveProduct,
+    transaction: ActiveTransaction,
+    store: impl OrderStore,
+    release_command: impl Command<ReleaseStock>,
+) -> Result<(), Error> {
+    let Some(mut order) = store.get_order(command.id)? else {
+        return Err(error::not_found("order not found"));
+    };
+
+    order.check_version(command.version)?;
+
+    let line_item_id = order.remove_product(command.product_id, command.variant_id)?;
+
+    release_command
+        .execute(ReleaseStock {
+            product_id: command.product_id,
+            variant_id: command.variant_id,
+            line_item_id,
+        })
+        .await?;
+
+    store.set_order(transaction.get(), order)?;
+
+    Ok(())
+}
+
+impl Resolver {
+    /** Remove a product from an order. */
+    pub fn remove_product_command(&self) -> impl Command<RemoveProduct> {
+        self.command(|resolver, command: RemoveProduct| async move {
+            let store = resolver.order_store();
+            let active_transaction = resolver.active_transaction();
+
+            let release_stock = resolver.release_stock_command();
+
+            execute(command, active_transaction, store, release_stock).await
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::domain::{
+        orders::model::{
+            store::in_memory_store,
+            test_data::OrderBuilder,
+        },
+        products::model::test_data::ProductBuilder,
+    };
+
+    #[tokio::test]
+    async fn remove_product_and_release_stock() {
+        let store = in_memory_store(Default::default());
+
+        let order_id = OrderId::new();
+        let product_id = ProductId::new();
+        let line_item_id = LineItemId::new();
+
+        store
+            .set_order(
+                ActiveTransaction::none().get(),
+                OrderBuilder::new()
+                    .id(order_id)
+                    .add_product(
+                        ProductBuilder::new().id(product_id).build(),
+                        move |line_item| line_item.id(line_item_id),
+                    )
+                    .build(),
+            )
+            .unwrap();
+
+        execute(
+            RemoveProduct {
+                id: order_id,
+                product_id,
+                variant_id: None,
+                version: None,
+            },
+            ActiveTransaction::none(),
+            &store,
+            |release: ReleaseStock| async move {
+                assert_eq!(line_item_id, release.line_item_id);
+
+                Ok(())
+            },
+        )
+        .await
+        .unwrap();
+
+        assert!(
+            !store
+                .get_order(order_id)
+                .unwrap()
+                .unwrap()
+                .contains_product(product_id)
+        );
+    }
+}
@@ -0,0 +1,176 @@
+/*! Contains the `SetOrderTaxRegionCommand` type. */
+
+use crate::domain::{
+    Error,
+    error,
+    infra::*,
+    orders::*,
+};
+
+/**
+Input for a `SetOrderTaxRegionCommand`.
+
+If a version is given then the order must still have that version for the region to be set.
+*/
+#[derive(Clone, Serialize, Deserialize)]
+pub struct SetOrderTaxRegion {
+    pub id: OrderId,
+    pub region: String,
+    #[serde(default)]
+    pub version: Option<OrderVersion>,
+}
+
+impl CommandArgs for SetOrderTaxRegion {
+    type Output = Result<(), Error>;
+}
+
+/** Default implementation for a `SetOrderTaxRegionCommand`. */
+async fn execute(
+    command: SetOrderTaxRegion,
+    transaction: ActiveTransaction,
+    store: impl OrderStore,
+) -> Result<(), Error> {
+    let order = {
+        if let Some(mut order) = store.get_order(command.id)? {
+            order.check_version(command.version)?;
+
+            order.set_tax_region(command.region)?;
+
+            order
+        } else {
+            return Err(error::not_found("order not found"));
+        }
+    };
+
+    store.set_order(transaction.get(), order)?;
+
+    Ok(())
+}
+
+impl Resolver {
+    /** Set the region an order is taxed in. */
+    pub fn set_order_tax_region_command(&self) -> impl Command<SetOrderTaxRegion> {
+        self.command(|resolver, command: SetOrderTaxRegion| async move {
+            let store = resolver.order_store();
+            let active_transaction = resolver.active_transaction();
+
+            execute(command, active_transaction, store).await
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::domain::{
+        ErrorKind,
+        orders::model::{
+            store::in_memory_store,
+            test_data::OrderBuilder,
+        },
+    };
+
+    #[tokio::test]
+    async fn set_tax_region() {
+        let store = in_memory_store(Default::default());
+
+        let order_id = OrderId::new();
+
+        store
+            .set_order(
+                ActiveTransaction::none().get(),
+                OrderBuilder::new().id(order_id).build(),
+            )
+            .unwrap();
+
+        execute(
+            SetOrderTaxRegion {
+                id: order_id,
+                region: "us-ca".to_owned(),
+                version: None,
+            },
+            ActiveTransaction::none(),
+            &store,
+        )
+        .await
+        .unwrap();
+
+        let order = store.get_order(order_id).unwrap().unwrap();
+
+        assert_eq!(Some("US-CA"), order.to_data().0.tax_region.as_deref());
+    }
+
+    #[tokio::test]
+    async fn err_if_not_found() {
+        let store = in_memory_store(Default::default());
+
+        let err = execute(
+            SetOrderTaxRegion {
+                id: OrderId::new(),
+                region: "AU".to_owned(),
+                version: None,
+            },
+            ActiveTransaction::none(),
+            &store,
+        )
+        .await
+        .err()
+        .unwrap();
+
+        assert_eq!(ErrorKind::NotFound, err.kind());
+    }
+
+    #[tokio::test]
+    async fn err_if_version_changed() {
+        let store = in_memory_store(Default::default());
+
+        let order_id = OrderId::new();
+
+        store
+            .set_order(
+                ActiveTransaction::none().get(),
+                OrderBuilder::new().id(order_id).build(),
+            )
+            .unwrap();
+
+        let version = store
+            .get_order(order_id)
+            .unwrap()
+            .unwrap()
+            .to_data()
+            .0
+            .version;
+
+        execute(
+            SetOrderTaxRegion {
+                id: order_id,
+                region: "AU".to_owned(),
+                version: Some(version),
+            },
+            ActiveTransaction::none(),
+            &store,
+        )
+        .await
+        .unwrap();
+
+        let err = execute(
+            SetOrderTaxRegion {
+                id: order_id,
+                region: "NZ".to_owned(),
+                version: Some(version),
+            },
+            ActiveTransaction::none(),
+            &store,
+        )
+        .await
+        .err()
+        .unwrap();
+
+        assert_eq!(ErrorKind::Stale, err.kind());
+
+        let order = store.get_order(order_id).unwrap().unwrap();
+
+        assert_eq!(Some("AU"), order.to_data().0.tax_region.as_deref());
+    }
+}
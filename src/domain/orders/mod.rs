@@ -5,7 +5,7 @@ pub mod model;
 pub mod queries;
 pub(in crate::domain) mod resolver;
 
-pub(self) use self::model::store::{
+use self::model::store::{
     OrderStore,
     OrderStoreFilter,
 };
@@ -1,11 +1,13 @@
 /*!
 Contains the `Order` and `OrderLineItem` entities.
 
+Orders also keep a discount line for each promotion applied to them.
+
 The separation between `Order` and `OrderLineItem` is kind of arbitrary, and may end up being a bit of a nuisance.
 If this becomes the case then rather than coupling the two together even more, we should make sure they're separated.
 
-The main idea right now is that `OrderLineItem` is a _subset_ of `Order` for a single product.
-This kind of suggests it shouldn't have an id of its own, and instead should be a composite of `(OrderId, ProductId)`.
+The main idea right now is that `OrderLineItem` is a _subset_ of `Order` for a single product or product variant.
+This kind of suggests it shouldn't have an id of its own, and instead should be a composite of `(OrderId, ProductId, Option<VariantId>)`.
 We'll probably need to come back here one day to work this out properly.
 */
 
@@ -25,6 +27,8 @@ use crate::domain::{
     error,
     infra::*,
     products::*,
+    promotions::*,
+    taxes::*,
 };
 
 pub type OrderId = Id<OrderData>;
@@ -33,6 +37,9 @@ pub type OrderVersion = Version<OrderData>;
 pub type LineItemId = Id<LineItemData>;
 pub type NextLineItemId = NextId<LineItemData>;
 pub type LineItemVersion = Version<LineItemData>;
+pub type DiscountLineId = Id<DiscountLineData>;
+pub type NextDiscountLineId = NextId<DiscountLineData>;
+pub type DiscountLineVersion = Version<DiscountLineData>;
 
 /**
 An order item quantity.
@@ -46,7 +53,7 @@ impl TryFrom<u32> for Quantity {
 
     fn try_from(quantity: u32) -> Result<Self, Self::Error> {
         if quantity < 1 {
-            return Err(error::msg("quantity must be greater than 0"));
+            return Err(error::bad_input("quantity must be greater than 0"));
         }
 
         Ok(Quantity(quantity))
@@ -59,38 +66,130 @@ pub struct OrderData {
     pub id: OrderId,
     pub version: OrderVersion,
     pub customer_id: CustomerId,
+    pub tax_region: Option<String>,
     _private: (),
 }
 
-/** Data for a single order line item. */
+/**
+Data for a single order line item.
+
+Line items for products with variants also reference the variant that was ordered.
+*/
 #[derive(Clone, Serialize, Deserialize)]
 pub struct LineItemData {
     pub id: LineItemId,
     pub version: LineItemVersion,
     pub product_id: ProductId,
+    #[serde(default)]
+    pub variant_id: Option<VariantId>,
     pub price: Currency,
     pub quantity: u32,
+    pub tax_category: String,
+    _private: (),
+}
+
+impl LineItemData {
+    /**
+    The price of the line item multiplied by its quantity.
+
+    This method will return an error instead of overflowing.
+    */
+    pub fn total(&self) -> Result<Currency, Error> {
+        self.price.checked_mul(self.quantity)
+    }
+}
+
+/**
+Data for a single order discount line.
+
+A discount line records a promotion applied to the order.
+The discount itself is copied from the promotion when it's applied.
+*/
+#[derive(Clone, Serialize, Deserialize)]
+pub struct DiscountLineData {
+    pub id: DiscountLineId,
+    pub version: DiscountLineVersion,
+    pub promotion_id: PromotionId,
+    pub code: String,
+    pub discount: Discount,
     _private: (),
 }
 
+/**
+The discount calculated for an order.
+
+The discount is calculated for each discount line and then summed for the whole order.
+It's also spread across the line items it was taken off, so tax can be calculated on what's left.
+The total discount is never more than the order's subtotal.
+*/
+pub struct OrderDiscount {
+    pub discount_lines: Vec<(DiscountLineId, Currency)>,
+    pub line_items: Vec<(LineItemId, Currency)>,
+    pub total: Currency,
+}
+
+impl OrderDiscount {
+    /** Get the discount taken off a line item. */
+    pub fn line_item(&self, id: LineItemId) -> Option<Currency> {
+        self.line_items
+            .iter()
+            .find(|(line_item_id, _)| *line_item_id == id)
+            .map(|(_, discount)| *discount)
+    }
+
+    /** Get the discount calculated for a discount line. */
+    pub fn discount_line(&self, id: DiscountLineId) -> Option<Currency> {
+        self.discount_lines
+            .iter()
+            .find(|(discount_line_id, _)| *discount_line_id == id)
+            .map(|(_, discount)| *discount)
+    }
+}
+
+/**
+The tax calculated for an order.
+
+Tax is calculated for each line item and then summed for the whole order.
+*/
+pub struct OrderTax {
+    pub pricing: PricingMode,
+    pub line_items: Vec<(LineItemId, Currency)>,
+    pub total: Currency,
+}
+
+impl OrderTax {
+    /** Get the tax calculated for a line item. */
+    pub fn line_item(&self, id: LineItemId) -> Option<Currency> {
+        self.line_items
+            .iter()
+            .find(|(line_item_id, _)| *line_item_id == id)
+            .map(|(_, tax)| *tax)
+    }
+}
+
 /**
 An order and its line items.
 
 Products can be added to an order as a line item.
+All line items in an order share the same currency.
+Promotions can be applied to an order as a discount line.
 */
 pub struct Order {
     order: OrderData,
     line_items: Vec<LineItemData>,
+    discount_lines: Vec<DiscountLineData>,
 }
 
 /**
 An order and one of its line items.
 
 Properties on the line item can be updated.
+The order's other line items are kept so changes can be checked against the order's subtotal.
 */
 pub struct OrderLineItem {
     order: OrderData,
     line_item: LineItemData,
+    others: Vec<LineItemData>,
 }
 
 /**
@@ -105,8 +204,16 @@ pub enum IntoLineItem {
 }
 
 impl OrderLineItem {
-    pub(self) fn from_data(order: OrderData, line_item: LineItemData) -> Self {
-        OrderLineItem { order, line_item }
+    pub(self) fn from_data(
+        order: OrderData,
+        line_item: LineItemData,
+        others: impl IntoIterator<Item = LineItemData>,
+    ) -> Self {
+        OrderLineItem {
+            order,
+            line_item,
+            others: others.into_iter().collect(),
+        }
     }
 
     pub fn into_data(self) -> (OrderId, LineItemData) {
@@ -121,83 +228,528 @@ impl OrderLineItem {
     where
         TQuantity: TryInto<Quantity, Error = Error>,
     {
-        self.line_item.quantity = quantity.try_into()?.0;
+        let quantity = quantity.try_into()?.0;
+
+        // The new total must fit in the order's subtotal so the order can still be read
+        let total = self.line_item.price.checked_mul(quantity)?;
+        checked_subtotal(self.others.iter(), total)?;
+
+        self.line_item.quantity = quantity;
 
         Ok(())
     }
+
+    /**
+    Check the line item's order still has the version a command expects.
+
+    If no version is expected then any version is accepted.
+    */
+    pub fn check_version(&self, expected: Option<OrderVersion>) -> Result<(), Error> {
+        check_order_version(&self.order, expected)
+    }
+}
+
+fn check_order_version(order: &OrderData, expected: Option<OrderVersion>) -> Result<(), Error> {
+    match expected {
+        Some(expected) if expected != order.version => {
+            Err(error::stale("order has been changed since it was read"))
+        }
+        _ => Ok(()),
+    }
+}
+
+/**
+Add a line item total to the totals of other line items.
+
+This function will return an error instead of overflowing.
+*/
+fn checked_subtotal<'a>(
+    line_items: impl IntoIterator<Item = &'a LineItemData>,
+    total: Currency,
+) -> Result<Currency, Error> {
+    let totals = line_items
+        .into_iter()
+        .map(LineItemData::total)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Currency::checked_sum(total.code(), totals)?.checked_add(total)
 }
 
 impl Order {
-    pub(self) fn from_data<TItems>(order: OrderData, line_items: TItems) -> Self
+    pub(self) fn from_data<TItems, TDiscounts>(
+        order: OrderData,
+        line_items: TItems,
+        discount_lines: TDiscounts,
+    ) -> Self
     where
         TItems: IntoIterator<Item = LineItemData>,
+        TDiscounts: IntoIterator<Item = DiscountLineData>,
     {
         let line_items = line_items.into_iter().collect();
+        let discount_lines = discount_lines.into_iter().collect();
 
-        Order { order, line_items }
+        Order {
+            order,
+            line_items,
+            discount_lines,
+        }
     }
 
-    pub fn into_data(self) -> (OrderData, Vec<LineItemData>) {
-        (self.order, self.line_items)
+    pub fn into_data(self) -> (OrderData, Vec<LineItemData>, Vec<DiscountLineData>) {
+        (self.order, self.line_items, self.discount_lines)
     }
 
-    pub fn to_data(&self) -> (&OrderData, &[LineItemData]) {
-        (&self.order, &self.line_items)
+    pub fn to_data(&self) -> (&OrderData, &[LineItemData], &[DiscountLineData]) {
+        (&self.order, &self.line_items, &self.discount_lines)
     }
 
-    pub fn into_line_item_for_product(self, product_id: ProductId) -> IntoLineItem {
-        if !self.contains_product(product_id) {
-            IntoLineItem::NotInOrder(self)
-        } else {
-            let Order {
-                order, line_items, ..
-            } = self;
+    pub fn into_line_item_for_product(
+        self,
+        product_id: ProductId,
+        variant_id: Option<VariantId>,
+    ) -> IntoLineItem {
+        match self.line_item_position(product_id, variant_id) {
+            None => IntoLineItem::NotInOrder(self),
+            Some(index) => {
+                let Order {
+                    order,
+                    mut line_items,
+                    ..
+                } = self;
 
-            let item = line_items
-                .into_iter()
-                .find(|item| item.product_id == product_id)
-                .unwrap();
+                let item = line_items.swap_remove(index);
 
-            IntoLineItem::InOrder(OrderLineItem::from_data(order, item))
+                IntoLineItem::InOrder(OrderLineItem::from_data(order, item, line_items))
+            }
         }
     }
 
+    /**
+    Check the order still has the version a command expects.
+
+    If no version is expected then any version is accepted.
+    */
+    pub fn check_version(&self, expected: Option<OrderVersion>) -> Result<(), Error> {
+        check_order_version(&self.order, expected)
+    }
+
+    fn line_item_position(
+        &self,
+        product_id: ProductId,
+        variant_id: Option<VariantId>,
+    ) -> Option<usize> {
+        self.line_items
+            .iter()
+            .position(|item| item.product_id == product_id && item.variant_id == variant_id)
+    }
+
     pub fn new(id: impl IdProvider<OrderData>, customer: &Customer) -> Result<Self, Error> {
-        let id = id.get()?;
         let &CustomerData {
-            id: customer_id, ..
+            id: customer_id,
+            archived,
+            ..
         } = customer.to_data();
 
+        if archived {
+            return Err(error::forbidden("customer is archived"));
+        }
+
+        let id = id.get()?;
+
         let order_data = OrderData {
             id,
             version: OrderVersion::default(),
             customer_id,
+            tax_region: None,
             _private: (),
         };
 
-        Ok(Order::from_data(order_data, vec![]))
+        Ok(Order::from_data(order_data, vec![], vec![]))
     }
 
+    /** Whether the order contains the product, or any of its variants. */
     pub fn contains_product(&self, product_id: ProductId) -> bool {
         self.line_items
             .iter()
             .any(|item| item.product_id == product_id)
     }
 
+    /**
+    The currency used by the order's line items.
+
+    This will be `None` if the order doesn't have any line items yet.
+    */
+    pub fn currency(&self) -> Option<CurrencyCode> {
+        self.line_items.first().map(|item| item.price.code())
+    }
+
+    /**
+    The sum of the totals of each line item in the order.
+
+    This will be `None` if the order doesn't have any line items yet.
+    */
+    pub fn subtotal(&self) -> Result<Option<Currency>, Error> {
+        let Some(currency) = self.currency() else {
+            return Ok(None);
+        };
+
+        let totals = self
+            .line_items
+            .iter()
+            .map(LineItemData::total)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Some(Currency::checked_sum(currency, totals)?))
+    }
+
+    /** Set the region the order is taxed in. */
+    pub fn set_tax_region(
+        &mut self,
+        tax_region: impl TryInto<TaxRegion, Error = Error>,
+    ) -> Result<(), Error> {
+        self.order.tax_region = Some(tax_region.try_into()?.into_inner());
+
+        Ok(())
+    }
+
+    /**
+    Calculate the tax for the order using the rates for its tax region.
+
+    If a discount has been calculated for the order then tax is calculated on each line item
+    after its part of the discount is taken off.
+
+    This will be `None` if the order doesn't have any line items yet.
+    */
+    pub fn tax(
+        &self,
+        rates: &TaxRates,
+        discount: Option<&OrderDiscount>,
+        rounding: Rounding,
+    ) -> Result<Option<OrderTax>, Error> {
+        if self.order.tax_region.as_deref() != Some(&*rates.to_data().region) {
+            return Err(error::msg(format_args!(
+                "can't calculate tax for an order in {:?} using rates for {}",
+                self.order.tax_region,
+                rates.to_data().region
+            )));
+        }
+
+        let Some(currency) = self.currency() else {
+            return Ok(None);
+        };
+
+        let line_items = self
+            .line_items
+            .iter()
+            .map(|item| {
+                let total = item.total()?;
+                let discount = discount
+                    .and_then(|discount| discount.line_item(item.id))
+                    .map(|discount| discount.minor_units())
+                    .unwrap_or(0);
+
+                let tax = rates.calculate(
+                    &item.tax_category,
+                    Currency::from_minor_units(
+                        total.code(),
+                        total.minor_units().saturating_sub(discount),
+                    ),
+                    rounding,
+                )?;
+
+                Ok((item.id, tax))
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        let total = Currency::checked_sum(currency, line_items.iter().map(|(_, tax)| *tax))?;
+
+        Ok(Some(OrderTax {
+            pricing: rates.pricing(),
+            line_items,
+            total,
+        }))
+    }
+
+    /**
+    Apply a promotion to the order as a discount line.
+
+    A promotion can only be applied to an order once, and the order's subtotal must meet the promotion's
+    minimum spend when it's applied. Discounts in a fixed amount must use the same currency as the order.
+    */
+    pub fn apply_promotion(
+        &mut self,
+        id: impl IdProvider<DiscountLineData>,
+        promotion: &Promotion,
+    ) -> Result<(), Error> {
+        let PromotionData {
+            id: promotion_id,
+            ref code,
+            ref discount,
+            min_spend,
+            ..
+        } = *promotion.to_data();
+
+        if self
+            .discount_lines
+            .iter()
+            .any(|line| line.promotion_id == promotion_id)
+        {
+            return Err(error::conflict("promotion is already applied to order"));
+        }
+
+        let Some(subtotal) = self.subtotal()? else {
+            return Err(error::conflict(
+                "promotions can't be applied to an order without any line items",
+            ));
+        };
+
+        if let Discount::FixedAmount { amount } = discount
+            && amount.code() != subtotal.code()
+        {
+            return Err(error::conflict(format_args!(
+                "promotion is for {} but the order uses {}",
+                amount.code(),
+                subtotal.code()
+            )));
+        }
+
+        if let Some(min_spend) = min_spend {
+            if min_spend.code() != subtotal.code() {
+                return Err(error::conflict(format_args!(
+                    "promotion requires a minimum spend in {} but the order uses {}",
+                    min_spend.code(),
+                    subtotal.code()
+                )));
+            }
+
+            if subtotal.minor_units() < min_spend.minor_units() {
+                return Err(error::conflict(
+                    "order doesn't meet the minimum spend for the promotion",
+                ));
+            }
+        }
+
+        let id = id.get()?;
+        let discount_line = DiscountLineData {
+            id,
+            version: DiscountLineVersion::default(),
+            promotion_id,
+            code: code.clone(),
+            discount: discount.clone(),
+            _private: (),
+        };
+
+        self.discount_lines.push(discount_line);
+
+        Ok(())
+    }
+
+    /**
+    Calculate the discount for the order using its discount lines.
+
+    Discounts are calculated on the current line items, so changing quantities will change the discount.
+    Percentage discounts are rounded down to the minor units of the order's currency.
+    Percentage and fixed amount discounts are spread across line items in proportion to their totals.
+    Buy X get Y discounts count the units of every variant of their product, and make the cheapest
+    of them free.
+
+    This will be `None` if the order doesn't have any line items yet.
+    */
+    pub fn discount(&self) -> Result<Option<OrderDiscount>, Error> {
+        let Some(subtotal) = self.subtotal()? else {
+            return Ok(None);
+        };
+
+        let totals = self
+            .line_items
+            .iter()
+            .map(|item| Ok(item.total()?.minor_units()))
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        // What's left of each line item after the discounts taken off so far
+        let mut remaining = totals.clone();
+
+        let discount_lines = self
+            .discount_lines
+            .iter()
+            .map(|line| {
+                let shares = match line.discount {
+                    Discount::Percentage { percent } => spread(
+                        Rounding::Down.divide(
+                            u128::from(subtotal.minor_units()) * u128::from(percent),
+                            100,
+                        ) as u64,
+                        &remaining,
+                    ),
+                    Discount::FixedAmount { amount } => spread(amount.minor_units(), &remaining),
+                    Discount::BuyXGetY {
+                        product_id,
+                        buy,
+                        get,
+                    } => {
+                        let mut shares = vec![0; self.line_items.len()];
+
+                        // Units of every variant of the product count towards the promotion
+                        let mut items = self
+                            .line_items
+                            .iter()
+                            .enumerate()
+                            .filter(|(_, item)| item.product_id == product_id)
+                            .collect::<Vec<_>>();
+
+                        let quantity = items
+                            .iter()
+                            .map(|(_, item)| u64::from(item.quantity))
+                            .sum::<u64>();
+
+                        // The quantities are widened so a large `buy + get` can't overflow
+                        let mut free =
+                            quantity / (u64::from(buy) + u64::from(get)) * u64::from(get);
+
+                        // The cheapest units are the free ones
+                        items.sort_by_key(|(_, item)| item.price.minor_units());
+
+                        for (index, item) in items {
+                            let units = free.min(u64::from(item.quantity));
+                            free -= units;
+
+                            shares[index] =
+                                item.price.minor_units().checked_mul(units).ok_or_else(|| {
+                                    error::bad_input("currency value is too large")
+                                })?;
+                        }
+
+                        shares
+                    }
+                };
+
+                // The discount can't take a line item below zero
+                let mut units = 0;
+                for (remaining, share) in remaining.iter_mut().zip(shares) {
+                    let share = share.min(*remaining);
+
+                    *remaining -= share;
+                    units += share;
+                }
+
+                Ok((line.id, Currency::from_minor_units(subtotal.code(), units)))
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        let line_items = self
+            .line_items
+            .iter()
+            .zip(totals.iter().zip(&remaining))
+            .map(|(item, (total, remaining))| {
+                (
+                    item.id,
+                    Currency::from_minor_units(subtotal.code(), total - remaining),
+                )
+            })
+            .collect();
+
+        let total = Currency::from_minor_units(
+            subtotal.code(),
+            subtotal.minor_units() - remaining.iter().sum::<u64>(),
+        );
+
+        Ok(Some(OrderDiscount {
+            discount_lines,
+            line_items,
+            total,
+        }))
+    }
+
+    /**
+    The total amount payable for the order.
+
+    If a discount has been calculated for the order then it's taken off the subtotal.
+    If tax has been calculated for the order using `Exclusive` pricing then it's added to the subtotal.
+    Tax using `Inclusive` pricing is already part of the subtotal.
+
+    This will be `None` if the order doesn't have any line items yet.
+    */
+    pub fn total(
+        &self,
+        discount: Option<&OrderDiscount>,
+        tax: Option<&OrderTax>,
+    ) -> Result<Option<Currency>, Error> {
+        let Some(subtotal) = self.subtotal()? else {
+            return Ok(None);
+        };
+
+        let discounted = match discount {
+            Some(discount) => Currency::from_minor_units(
+                subtotal.code(),
+                subtotal
+                    .minor_units()
+                    .saturating_sub(discount.total.minor_units()),
+            ),
+            None => subtotal,
+        };
+
+        match tax {
+            Some(OrderTax {
+                pricing: PricingMode::Exclusive,
+                total,
+                ..
+            }) => Ok(Some(discounted.checked_add(*total)?)),
+            _ => Ok(Some(discounted)),
+        }
+    }
+
     pub fn add_product(
         &mut self,
         id: impl IdProvider<LineItemData>,
         product: &Product,
         quantity: impl TryInto<Quantity, Error = Error>,
+    ) -> Result<(), Error> {
+        self.add_product_variant(id, product, None, quantity)
+    }
+
+    /**
+    Add a product to the order as a specific variant.
+
+    Products with variants can only be added as one of their variants.
+    Each variant of a product gets its own line item.
+    */
+    pub fn add_product_variant(
+        &mut self,
+        id: impl IdProvider<LineItemData>,
+        product: &Product,
+        variant_id: Option<VariantId>,
+        quantity: impl TryInto<Quantity, Error = Error>,
     ) -> Result<(), Error> {
         let &ProductData {
             id: product_id,
-            price,
+            ref tax_category,
+            status,
+            archived,
             ..
         } = product.to_data();
 
-        if self.contains_product(product_id) {
-            return Err(error::msg("product is already in order"));
+        if archived {
+            return Err(error::bad_input("product is archived"));
+        }
+
+        if status != ProductStatus::Active {
+            return Err(error::bad_input(format_args!("product is {}", status)));
+        }
+
+        let price = product.price(variant_id)?;
+
+        if self.line_item_position(product_id, variant_id).is_some() {
+            return Err(error::conflict("product is already in order"));
+        }
+
+        if let Some(currency) = self.currency()
+            && currency != price.code()
+        {
+            return Err(error::conflict(format_args!(
+                "product is priced in {} but the order uses {}",
+                price.code(),
+                currency
+            )));
         }
 
         let id = id.get()?;
@@ -205,15 +757,64 @@ impl Order {
             id,
             version: LineItemVersion::default(),
             product_id,
+            variant_id,
             price,
             quantity: quantity.try_into()?.0,
+            tax_category: tax_category.clone(),
             _private: (),
         };
 
+        // The new total must fit in the order's subtotal so the order can still be read
+        checked_subtotal(&self.line_items, line_item.total()?)?;
+
         self.line_items.push(line_item);
 
         Ok(())
     }
+
+    /** Remove a product from the order, returning the id of the line item it was in. */
+    pub fn remove_product(
+        &mut self,
+        product_id: ProductId,
+        variant_id: Option<VariantId>,
+    ) -> Result<LineItemId, Error> {
+        let index = self
+            .line_item_position(product_id, variant_id)
+            .ok_or_else(|| error::not_found("product is not in order"))?;
+
+        Ok(self.line_items.remove(index).id)
+    }
+}
+
+/**
+Spread an amount across line items in proportion to what's left of each of them.
+
+Minor units lost to rounding go to the first line items with room for them.
+No line item gets more than what's left of it.
+*/
+fn spread(amount: u64, remaining: &[u64]) -> Vec<u64> {
+    // What's left of the line items is never more than the order's subtotal, so can't overflow
+    let total = remaining.iter().sum::<u64>();
+    if total == 0 {
+        return vec![0; remaining.len()];
+    }
+
+    let amount = amount.min(total);
+
+    let mut shares = remaining
+        .iter()
+        .map(|remaining| (u128::from(amount) * u128::from(*remaining) / u128::from(total)) as u64)
+        .collect::<Vec<_>>();
+
+    let mut left = amount - shares.iter().sum::<u64>();
+    for (share, remaining) in shares.iter_mut().zip(remaining) {
+        let extra = left.min(remaining - *share);
+
+        *share += extra;
+        left -= extra;
+    }
+
+    shares
 }
 
 impl Entity for Order {
@@ -230,27 +831,22 @@ impl Entity for OrderLineItem {
     type Error = Error;
 }
 
-impl Resolver {
-    pub fn order_id(&self) -> impl IdProvider<OrderData> {
-        NextId::<OrderData>::new()
-    }
-
-    pub fn line_item_id(&self) -> impl IdProvider<LineItemData> {
-        NextId::<LineItemData>::new()
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
 
     use crate::domain::{
-        customers::model::test_data::default_customer,
+        ErrorKind,
+        customers::model::test_data::{
+            CustomerBuilder,
+            default_customer,
+        },
         orders::model::test_data::default_order,
         products::model::test_data::{
             ProductBuilder,
             default_product,
         },
+        promotions::model::test_data::PromotionBuilder,
     };
 
     #[test]
@@ -279,12 +875,254 @@ mod tests {
 
         order.add_product(LineItemId::new(), &product, 1).unwrap();
 
-        let (order_data, mut line_item_data) = order.into_data();
-        let mut order = OrderLineItem::from_data(order_data, line_item_data.pop().unwrap());
+        let (order_data, mut line_item_data, _) = order.into_data();
+        let mut order =
+            OrderLineItem::from_data(order_data, line_item_data.pop().unwrap(), line_item_data);
 
         assert!(order.set_quantity(0).is_err());
     }
 
+    #[test]
+    fn add_product_err_if_subtotal_overflows() {
+        let mut order = default_order();
+
+        let product = ProductBuilder::new()
+            .price(Currency::usd(u64::MAX / 2 + 1))
+            .build();
+
+        assert!(order.add_product(LineItemId::new(), &product, 2).is_err());
+
+        order.add_product(LineItemId::new(), &product, 1).unwrap();
+
+        let other = ProductBuilder::new()
+            .id(ProductId::new())
+            .price(Currency::usd(u64::MAX / 2 + 1))
+            .build();
+
+        assert!(order.add_product(LineItemId::new(), &other, 1).is_err());
+
+        // The order can still be read
+        assert_eq!(1, order.line_items.len());
+        assert_eq!(
+            Some(Currency::usd(u64::MAX / 2 + 1)),
+            order.subtotal().unwrap()
+        );
+        assert!(order.total(None, None).unwrap().is_some());
+    }
+
+    #[test]
+    fn set_quantity_err_if_subtotal_overflows() {
+        let mut order = default_order();
+
+        let product = ProductBuilder::new()
+            .price(Currency::usd(u64::MAX / 4))
+            .build();
+        let other = ProductBuilder::new()
+            .id(ProductId::new())
+            .price(Currency::usd(u64::MAX / 4))
+            .build();
+
+        order.add_product(LineItemId::new(), &product, 1).unwrap();
+        order.add_product(LineItemId::new(), &other, 1).unwrap();
+
+        let IntoLineItem::InOrder(mut line_item) =
+            order.into_line_item_for_product(product.to_data().id, None)
+        else {
+            panic!("expected the product to be in the order");
+        };
+
+        // The line item fits on its own, but not alongside the other one
+        assert!(line_item.set_quantity(4).is_err());
+
+        line_item.set_quantity(2).unwrap();
+        assert_eq!(2, line_item.to_data().1.quantity);
+    }
+
+    #[test]
+    fn remove_product() {
+        let mut order = default_order();
+        let product = default_product();
+        let line_item_id = LineItemId::new();
+
+        order.add_product(line_item_id, &product, 1).unwrap();
+
+        assert_eq!(
+            line_item_id,
+            order.remove_product(product.to_data().id, None).unwrap()
+        );
+        assert!(!order.contains_product(product.to_data().id));
+
+        assert!(order.remove_product(product.to_data().id, None).is_err());
+    }
+
+    #[test]
+    fn add_product_variants() {
+        let mut order = default_order();
+
+        let small = VariantId::new();
+        let large = VariantId::new();
+        let product = ProductBuilder::new()
+            .variant(small, "SHIRT-S")
+            .variant(large, "SHIRT-L")
+            .build();
+
+        // A variant must be chosen for products with variants
+        assert!(order.add_product(LineItemId::new(), &product, 1).is_err());
+
+        order
+            .add_product_variant(LineItemId::new(), &product, Some(small), 1)
+            .unwrap();
+        order
+            .add_product_variant(LineItemId::new(), &product, Some(large), 1)
+            .unwrap();
+
+        assert!(
+            order
+                .add_product_variant(LineItemId::new(), &product, Some(small), 1)
+                .is_err()
+        );
+        assert!(
+            order
+                .add_product_variant(LineItemId::new(), &product, Some(VariantId::new()), 1)
+                .is_err()
+        );
+
+        let (_, line_items, _) = order.to_data();
+        assert_eq!(
+            vec![Some(small), Some(large)],
+            line_items
+                .iter()
+                .map(|item| item.variant_id)
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn line_items_must_share_currency() {
+        let mut order = default_order();
+
+        let usd = ProductBuilder::new().price(Currency::usd(100)).build();
+        let eur = ProductBuilder::new().price(Currency::eur(100)).build();
+
+        order.add_product(LineItemId::new(), &usd, 1).unwrap();
+
+        assert!(order.add_product(LineItemId::new(), &eur, 1).is_err());
+        assert_eq!(Some(CurrencyCode::USD), order.currency());
+    }
+
+    #[test]
+    fn order_totals() {
+        let mut order = default_order();
+
+        assert!(order.total(None, None).unwrap().is_none());
+
+        order
+            .add_product(
+                LineItemId::new(),
+                &ProductBuilder::new().price(Currency::usd(150)).build(),
+                2,
+            )
+            .unwrap();
+        order
+            .add_product(
+                LineItemId::new(),
+                &ProductBuilder::new().price(Currency::usd(25)).build(),
+                3,
+            )
+            .unwrap();
+
+        assert_eq!(Some(Currency::usd(375)), order.subtotal().unwrap());
+        assert_eq!(Some(Currency::usd(375)), order.total(None, None).unwrap());
+    }
+
+    #[test]
+    fn order_tax() {
+        let mut order = default_order();
+        order.set_tax_region("us-ny").unwrap();
+
+        let rates = TaxRates::new(
+            "US-NY",
+            PricingMode::Exclusive,
+            [
+                (TaxCategory::STANDARD.to_owned(), "8.875".parse().unwrap()),
+                ("food".to_owned(), TaxRate::ZERO),
+            ],
+        )
+        .unwrap();
+
+        assert!(order.tax(&rates, None, Rounding::HalfUp).unwrap().is_none());
+
+        let standard = LineItemId::new();
+        order
+            .add_product(
+                standard,
+                &ProductBuilder::new().price(Currency::usd(500)).build(),
+                2,
+            )
+            .unwrap();
+        order
+            .add_product(
+                LineItemId::new(),
+                &ProductBuilder::new()
+                    .price(Currency::usd(300))
+                    .tax_category("food")
+                    .build(),
+                1,
+            )
+            .unwrap();
+
+        let tax = order.tax(&rates, None, Rounding::HalfUp).unwrap().unwrap();
+
+        assert_eq!(Some(Currency::usd(89)), tax.line_item(standard));
+        assert_eq!(Currency::usd(89), tax.total);
+        assert_eq!(Some(Currency::usd(1300)), order.subtotal().unwrap());
+        assert_eq!(
+            Some(Currency::usd(1389)),
+            order.total(None, Some(&tax)).unwrap()
+        );
+    }
+
+    #[test]
+    fn order_tax_inclusive() {
+        let mut order = default_order();
+        order.set_tax_region("AU").unwrap();
+
+        let rates = TaxRates::new(
+            "AU",
+            PricingMode::Inclusive,
+            [(TaxCategory::STANDARD.to_owned(), "10".parse().unwrap())],
+        )
+        .unwrap();
+
+        order
+            .add_product(
+                LineItemId::new(),
+                &ProductBuilder::new().price(Currency::aud(1100)).build(),
+                1,
+            )
+            .unwrap();
+
+        let tax = order.tax(&rates, None, Rounding::HalfUp).unwrap().unwrap();
+
+        assert_eq!(Currency::aud(100), tax.total);
+        assert_eq!(
+            Some(Currency::aud(1100)),
+            order.total(None, Some(&tax)).unwrap()
+        );
+    }
+
+    #[test]
+    fn order_tax_uses_rates_for_region() {
+        let mut order = default_order();
+        let rates = TaxRates::new("AU", PricingMode::Inclusive, []).unwrap();
+
+        assert!(order.tax(&rates, None, Rounding::HalfUp).is_err());
+
+        order.set_tax_region("NZ").unwrap();
+
+        assert!(order.tax(&rates, None, Rounding::HalfUp).is_err());
+    }
+
     #[test]
     fn product_must_not_be_in_order_when_adding() {
         let mut order = default_order();
@@ -294,4 +1132,357 @@ mod tests {
 
         assert!(order.add_product(LineItemId::new(), &product, 1).is_err());
     }
+
+    #[test]
+    fn archived_entities_must_not_be_added_to_orders() {
+        let customer = CustomerBuilder::new().archived().build();
+
+        assert_eq!(
+            ErrorKind::Forbidden,
+            Order::new(OrderId::new(), &customer).err().unwrap().kind()
+        );
+
+        let mut order = default_order();
+        let product = ProductBuilder::new().archived().build();
+
+        assert!(order.add_product(LineItemId::new(), &product, 1).is_err());
+    }
+
+    #[test]
+    fn inactive_products_must_not_be_added_to_orders() {
+        let mut order = default_order();
+
+        for status in [ProductStatus::Draft, ProductStatus::Discontinued] {
+            let product = ProductBuilder::new().status(status).build();
+
+            assert!(order.add_product(LineItemId::new(), &product, 1).is_err());
+        }
+    }
+
+    #[test]
+    fn order_discounts() {
+        let mut order = default_order();
+
+        let product_id = ProductId::new();
+        order
+            .add_product(
+                LineItemId::new(),
+                &ProductBuilder::new()
+                    .id(product_id)
+                    .price(Currency::usd(200))
+                    .build(),
+                5,
+            )
+            .unwrap();
+
+        let percentage = DiscountLineId::new();
+        order
+            .apply_promotion(
+                percentage,
+                &PromotionBuilder::new()
+                    .code("TEN")
+                    .discount(Discount::Percentage { percent: 15 })
+                    .build(),
+            )
+            .unwrap();
+
+        let buy_x_get_y = DiscountLineId::new();
+        order
+            .apply_promotion(
+                buy_x_get_y,
+                &PromotionBuilder::new()
+                    .code("TWO-FOR-ONE")
+                    .discount(Discount::BuyXGetY {
+                        product_id,
+                        buy: 1,
+                        get: 1,
+                    })
+                    .build(),
+            )
+            .unwrap();
+
+        let discount = order.discount().unwrap().unwrap();
+
+        assert_eq!(Some(Currency::usd(150)), discount.discount_line(percentage));
+        assert_eq!(
+            Some(Currency::usd(400)),
+            discount.discount_line(buy_x_get_y)
+        );
+        assert_eq!(Currency::usd(550), discount.total);
+        assert_eq!(
+            Some(Currency::usd(450)),
+            order.total(Some(&discount), None).unwrap()
+        );
+    }
+
+    #[test]
+    fn buy_x_get_y_counts_every_variant() {
+        let mut order = default_order();
+
+        let small = VariantId::new();
+        let large = VariantId::new();
+
+        let mut product = ProductBuilder::new()
+            .price(Currency::usd(1000))
+            .variant(small, "shirt-s")
+            .build();
+        product
+            .add_variant(
+                large,
+                "shirt-l",
+                [("size".to_owned(), "L".to_owned())].into(),
+                Some(Currency::usd(1200)),
+            )
+            .unwrap();
+        let product_id = product.to_data().id;
+
+        let small_item = LineItemId::new();
+        order
+            .add_product_variant(small_item, &product, Some(small), 1)
+            .unwrap();
+
+        let large_item = LineItemId::new();
+        order
+            .add_product_variant(large_item, &product, Some(large), 3)
+            .unwrap();
+
+        order
+            .apply_promotion(
+                DiscountLineId::new(),
+                &PromotionBuilder::new()
+                    .discount(Discount::BuyXGetY {
+                        product_id,
+                        buy: 1,
+                        get: 1,
+                    })
+                    .build(),
+            )
+            .unwrap();
+
+        let discount = order.discount().unwrap().unwrap();
+
+        // Two of the four units are free, starting with the cheapest
+        assert_eq!(Some(Currency::usd(1000)), discount.line_item(small_item));
+        assert_eq!(Some(Currency::usd(1200)), discount.line_item(large_item));
+        assert_eq!(Currency::usd(2200), discount.total);
+    }
+
+    #[test]
+    fn order_discount_is_capped_at_subtotal() {
+        let mut order = default_order();
+
+        order
+            .add_product(
+                LineItemId::new(),
+                &ProductBuilder::new().price(Currency::usd(300)).build(),
+                1,
+            )
+            .unwrap();
+
+        order
+            .apply_promotion(
+                DiscountLineId::new(),
+                &PromotionBuilder::new()
+                    .discount(Discount::FixedAmount {
+                        amount: Currency::usd(500),
+                    })
+                    .build(),
+            )
+            .unwrap();
+
+        let discount = order.discount().unwrap().unwrap();
+
+        assert_eq!(Currency::usd(300), discount.total);
+        assert_eq!(
+            Some(Currency::usd(0)),
+            order.total(Some(&discount), None).unwrap()
+        );
+    }
+
+    #[test]
+    fn order_tax_is_calculated_after_discounts() {
+        let mut order = default_order();
+        order.set_tax_region("us-ny").unwrap();
+
+        let rates = TaxRates::new(
+            "US-NY",
+            PricingMode::Exclusive,
+            [
+                (TaxCategory::STANDARD.to_owned(), "10".parse().unwrap()),
+                ("food".to_owned(), TaxRate::ZERO),
+            ],
+        )
+        .unwrap();
+
+        let standard = LineItemId::new();
+        order
+            .add_product(
+                standard,
+                &ProductBuilder::new().price(Currency::usd(1000)).build(),
+                1,
+            )
+            .unwrap();
+
+        let food = LineItemId::new();
+        order
+            .add_product(
+                food,
+                &ProductBuilder::new()
+                    .price(Currency::usd(1000))
+                    .tax_category("food")
+                    .build(),
+                1,
+            )
+            .unwrap();
+
+        order
+            .apply_promotion(
+                DiscountLineId::new(),
+                &PromotionBuilder::new()
+                    .discount(Discount::FixedAmount {
+                        amount: Currency::usd(501),
+                    })
+                    .build(),
+            )
+            .unwrap();
+
+        let discount = order.discount().unwrap().unwrap();
+
+        // The discount is spread across line items, with the remainder on the first
+        assert_eq!(Some(Currency::usd(251)), discount.line_item(standard));
+        assert_eq!(Some(Currency::usd(250)), discount.line_item(food));
+
+        let tax = order
+            .tax(&rates, Some(&discount), Rounding::HalfUp)
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(Currency::usd(75), tax.total);
+        assert_eq!(
+            Some(Currency::usd(1574)),
+            order.total(Some(&discount), Some(&tax)).unwrap()
+        );
+    }
+
+    #[test]
+    fn order_tax_inclusive_is_calculated_after_discounts() {
+        let mut order = default_order();
+        order.set_tax_region("AU").unwrap();
+
+        let rates = TaxRates::new(
+            "AU",
+            PricingMode::Inclusive,
+            [(TaxCategory::STANDARD.to_owned(), "10".parse().unwrap())],
+        )
+        .unwrap();
+
+        order
+            .add_product(
+                LineItemId::new(),
+                &ProductBuilder::new().price(Currency::aud(1100)).build(),
+                2,
+            )
+            .unwrap();
+
+        order
+            .apply_promotion(
+                DiscountLineId::new(),
+                &PromotionBuilder::new()
+                    .code("HALF")
+                    .discount(Discount::Percentage { percent: 50 })
+                    .build(),
+            )
+            .unwrap();
+
+        let discount = order.discount().unwrap().unwrap();
+        let tax = order
+            .tax(&rates, Some(&discount), Rounding::HalfUp)
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(Currency::aud(100), tax.total);
+        assert_eq!(
+            Some(Currency::aud(1100)),
+            order.total(Some(&discount), Some(&tax)).unwrap()
+        );
+
+        // Nothing is left to tax once the whole order is discounted
+        order
+            .apply_promotion(
+                DiscountLineId::new(),
+                &PromotionBuilder::new()
+                    .code("FREE")
+                    .discount(Discount::Percentage { percent: 100 })
+                    .build(),
+            )
+            .unwrap();
+
+        let discount = order.discount().unwrap().unwrap();
+        let tax = order
+            .tax(&rates, Some(&discount), Rounding::HalfUp)
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(Currency::aud(0), tax.total);
+        assert_eq!(
+            Some(Currency::aud(0)),
+            order.total(Some(&discount), Some(&tax)).unwrap()
+        );
+    }
+
+    #[test]
+    fn promotion_must_be_applicable() {
+        let mut order = default_order();
+        let promotion = PromotionBuilder::new()
+            .min_spend(Currency::usd(1000))
+            .build();
+
+        // The order doesn't have any line items
+        assert!(
+            order
+                .apply_promotion(DiscountLineId::new(), &promotion)
+                .is_err()
+        );
+
+        order
+            .add_product(
+                LineItemId::new(),
+                &ProductBuilder::new().price(Currency::usd(500)).build(),
+                1,
+            )
+            .unwrap();
+
+        // The order doesn't meet the minimum spend
+        assert!(
+            order
+                .apply_promotion(DiscountLineId::new(), &promotion)
+                .is_err()
+        );
+
+        // The order uses a different currency
+        assert!(
+            order
+                .apply_promotion(
+                    DiscountLineId::new(),
+                    &PromotionBuilder::new()
+                        .discount(Discount::FixedAmount {
+                            amount: Currency::eur(100),
+                        })
+                        .build(),
+                )
+                .is_err()
+        );
+
+        // The promotion has already been applied
+        let promotion = PromotionBuilder::new().build();
+        order
+            .apply_promotion(DiscountLineId::new(), &promotion)
+            .unwrap();
+
+        assert!(
+            order
+                .apply_promotion(DiscountLineId::new(), &promotion)
+                .is_err()
+        );
+    }
 }
@@ -41,14 +41,17 @@ pub(in crate::domain) trait OrderStoreFilter {
     fn filter<F>(&self, predicate: F) -> Result<Iter, Error>
     where
         F: Fn(&OrderData) -> bool;
+
+    fn get_orders(&self, ids: &[OrderId]) -> Result<Vec<Order>, Error>;
 }
 
 pub(in crate::domain) type Iter = IntoIter<OrderData>;
 
 /** A test in-memory order store. */
 pub(in crate::domain) struct InMemoryStore {
-    orders: TransactionValueStore<(OrderData, HashSet<LineItemId>)>,
+    orders: TransactionValueStore<(OrderData, HashSet<LineItemId>, HashSet<DiscountLineId>)>,
     line_items: TransactionValueStore<LineItemData>,
+    discount_lines: TransactionValueStore<DiscountLineData>,
 }
 
 impl OrderStore for InMemoryStore {
@@ -57,49 +60,73 @@ impl OrderStore for InMemoryStore {
         id: OrderId,
         line_item_id: LineItemId,
     ) -> Result<Option<OrderLineItem>, Error> {
-        if let Some((version, (order_data, item_ids))) = self.orders.get(id) {
+        if let Some((version, (order_data, item_ids, _))) = self.orders.get(id) {
             assert_eq!(version, order_data.version.into());
 
             // Check that the line item is part of the order
             if !item_ids.contains(&line_item_id) {
-                return Err(error::msg("line item not found"));
+                return Err(error::not_found("line item not found"));
             }
 
             // Find the line item
             let (version, line_item_data) = self
                 .line_items
                 .get(line_item_id)
-                .ok_or_else(|| error::msg("line item not found"))?;
+                .ok_or_else(|| error::not_found("line item not found"))?;
 
             assert_eq!(version, line_item_data.version.into());
 
-            Ok(Some(OrderLineItem::from_data(order_data, line_item_data)))
+            // The other line items are needed to check changes against the order's subtotal
+            let others = self
+                .line_items
+                .get_all(|line_item| {
+                    line_item.id != line_item_id && item_ids.contains(&line_item.id)
+                })
+                .map(|(_, line_item_data)| line_item_data);
+
+            Ok(Some(OrderLineItem::from_data(
+                order_data,
+                line_item_data,
+                others,
+            )))
         } else {
             Ok(None)
         }
     }
 
     fn set_line_item(&self, transaction: &Transaction, order: OrderLineItem) -> Result<(), Error> {
-        let (order_id, mut order_item_data) = order.into_data();
+        let OrderLineItem {
+            order: mut order_data,
+            line_item: mut order_item_data,
+            ..
+        } = order;
+        let order_id = order_data.id;
         let line_item_id = order_item_data.id;
 
         // Check that the line item is part of the order
-        {
-            let (_, (_, item_ids)) = self
-                .orders
-                .get(order_id)
-                .ok_or_else(|| error::msg("order not found"))?;
+        let (_, (_, item_ids, discount_line_ids)) = self
+            .orders
+            .get(order_id)
+            .ok_or_else(|| error::not_found("order not found"))?;
 
-            if !item_ids.contains(&line_item_id) {
-                return Err(error::msg("line item not found"));
-            }
+        if !item_ids.contains(&line_item_id) {
+            return Err(error::not_found("line item not found"));
         }
 
+        // The order is the root of its line items, so changing one changes the order
+        self.orders.set(
+            transaction,
+            order_id,
+            Some(order_data.version),
+            order_data.version.next()?,
+            (order_data, item_ids, discount_line_ids),
+        )?;
+
         self.line_items.set(
             transaction,
             line_item_id,
             Some(order_item_data.version),
-            order_item_data.version.next(),
+            order_item_data.version.next()?,
             order_item_data,
         )?;
 
@@ -107,7 +134,7 @@ impl OrderStore for InMemoryStore {
     }
 
     fn get_order(&self, id: OrderId) -> Result<Option<Order>, Error> {
-        if let Some((version, (order_data, line_items))) = self.orders.get(id) {
+        if let Some((version, (order_data, line_items, discount_lines))) = self.orders.get(id) {
             assert_eq!(version, order_data.version.into());
 
             let items_data = self
@@ -119,24 +146,38 @@ impl OrderStore for InMemoryStore {
                     line_item_data
                 });
 
-            Ok(Some(Order::from_data(order_data, items_data)))
+            let discounts_data = self
+                .discount_lines
+                .get_all(|discount_line| discount_lines.contains(&discount_line.id))
+                .map(|(version, discount_line_data)| {
+                    assert_eq!(version, discount_line_data.version.into());
+
+                    discount_line_data
+                });
+
+            Ok(Some(Order::from_data(
+                order_data,
+                items_data,
+                discounts_data,
+            )))
         } else {
             Ok(None)
         }
     }
 
     fn set_order(&self, transaction: &Transaction, order: Order) -> Result<(), Error> {
-        let (mut order_data, line_items_data) = order.into_data();
+        let (mut order_data, line_items_data, discount_lines_data) = order.into_data();
         let id = order_data.id;
         let order_item_ids = line_items_data.iter().map(|item| item.id).collect();
+        let discount_line_ids = discount_lines_data.iter().map(|line| line.id).collect();
 
         // Update the order
         self.orders.set(
             transaction,
             id,
             Some(order_data.version),
-            order_data.version.next(),
-            (order_data, order_item_ids),
+            order_data.version.next()?,
+            (order_data, order_item_ids, discount_line_ids),
         )?;
 
         // Update each of its line items
@@ -147,11 +188,24 @@ impl OrderStore for InMemoryStore {
                 transaction,
                 id,
                 Some(line_item_data.version),
-                line_item_data.version.next(),
+                line_item_data.version.next()?,
                 line_item_data,
             )?;
         }
 
+        // Update each of its discount lines
+        for mut discount_line_data in discount_lines_data {
+            let id = discount_line_data.id;
+
+            self.discount_lines.set(
+                transaction,
+                id,
+                Some(discount_line_data.version),
+                discount_line_data.version.next()?,
+                discount_line_data,
+            )?;
+        }
+
         Ok(())
     }
 }
@@ -164,18 +218,72 @@ impl OrderStoreFilter for InMemoryStore {
     {
         let orders: Vec<_> = self
             .orders
-            .get_all(|(data, _)| predicate(data))
-            .map(|(_, (data, _))| data)
+            .get_all(|(data, _, _)| predicate(data))
+            .map(|(_, (data, _, _))| data)
             .collect();
 
         Ok(orders.into_iter())
     }
+
+    fn get_orders(&self, ids: &[OrderId]) -> Result<Vec<Order>, Error> {
+        let orders: Vec<_> = self
+            .orders
+            .get_all(|(data, _, _)| ids.contains(&data.id))
+            .map(|(_, order)| order)
+            .collect();
+
+        // Line items and discount lines for all orders are fetched together
+        let mut items_data: Vec<_> = self
+            .line_items
+            .get_all(|line_item| {
+                orders
+                    .iter()
+                    .any(|(_, line_items, _)| line_items.contains(&line_item.id))
+            })
+            .map(|(_, line_item_data)| line_item_data)
+            .collect();
+
+        let mut discounts_data: Vec<_> = self
+            .discount_lines
+            .get_all(|discount_line| {
+                orders
+                    .iter()
+                    .any(|(_, _, discount_lines)| discount_lines.contains(&discount_line.id))
+            })
+            .map(|(_, discount_line_data)| discount_line_data)
+            .collect();
+
+        let orders = orders
+            .into_iter()
+            .map(|(order_data, line_items, discount_lines)| {
+                let (order_items, rest): (Vec<_>, Vec<_>) = items_data
+                    .drain(..)
+                    .partition(|line_item| line_items.contains(&line_item.id));
+                items_data = rest;
+
+                let (order_discounts, rest): (Vec<_>, Vec<_>) = discounts_data
+                    .drain(..)
+                    .partition(|discount_line| discount_lines.contains(&discount_line.id));
+                discounts_data = rest;
+
+                Order::from_data(order_data, order_items, order_discounts)
+            })
+            .collect();
+
+        Ok(orders)
+    }
 }
 
+/**
+Create an in-memory order store.
+
+The store will participate in transactions tracked by the given transaction store.
+*/
 pub(in crate::domain) fn in_memory_store(transaction_store: TransactionStore) -> InMemoryStore {
     InMemoryStore {
         orders: TransactionValueStore::new(transaction_store.clone()),
-        line_items: TransactionValueStore::new(transaction_store),
+        line_items: TransactionValueStore::new(transaction_store.clone()),
+        discount_lines: TransactionValueStore::new(transaction_store),
     }
 }
 
@@ -221,12 +329,45 @@ mod tests {
             .unwrap();
 
         // Get the product with the order
-        let (_, line_items) = store.get_order(order_id).unwrap().unwrap().into_data();
+        let (_, line_items, _) = store.get_order(order_id).unwrap().unwrap().into_data();
 
         assert_eq!(1, line_items.len());
         assert_eq!(5, line_items[0].quantity);
     }
 
+    #[test]
+    fn get_orders_with_their_line_items() {
+        let store = in_memory_store(Default::default());
+
+        let ids = [OrderId::new(), OrderId::new(), OrderId::new()];
+
+        for (id, quantity) in ids.iter().zip([1, 2, 3]) {
+            store
+                .set_order(
+                    &Transaction::none(),
+                    OrderBuilder::new()
+                        .id(*id)
+                        .add_product(default_product(), move |line_item| {
+                            line_item.quantity(quantity)
+                        })
+                        .build(),
+                )
+                .unwrap();
+        }
+
+        let mut orders = store.get_orders(&ids[..2]).unwrap();
+        orders.sort_by_key(|order| order.to_data().1[0].quantity);
+
+        assert_eq!(2, orders.len());
+        for (order, (id, quantity)) in orders.iter().zip(ids.iter().zip([1, 2])) {
+            let (order, line_items, _) = order.to_data();
+
+            assert_eq!(*id, order.id);
+            assert_eq!(1, line_items.len());
+            assert_eq!(quantity, line_items[0].quantity);
+        }
+    }
+
     #[test]
     fn add_order_twice_fails_concurrency_check() {
         let store = in_memory_store(Default::default());
@@ -292,4 +433,40 @@ mod tests {
                 .is_err()
         );
     }
+
+    #[test]
+    fn set_order_item_changes_order_version() {
+        let store = in_memory_store(Default::default());
+
+        let order_id = OrderId::new();
+        let line_item_id = LineItemId::new();
+
+        let order = OrderBuilder::new()
+            .id(order_id)
+            .add_product(default_product(), move |line_item| {
+                line_item.id(line_item_id)
+            })
+            .build();
+
+        store.set_order(&Transaction::none(), order).unwrap();
+
+        let before = store.get_order(order_id).unwrap().unwrap();
+
+        let mut line_item = store
+            .get_line_item(order_id, line_item_id)
+            .unwrap()
+            .unwrap();
+        line_item.set_quantity(3).unwrap();
+
+        store
+            .set_line_item(&Transaction::none(), line_item)
+            .unwrap();
+
+        let after = store.get_order(order_id).unwrap().unwrap();
+
+        assert_ne!(before.to_data().0.version, after.to_data().0.version);
+
+        // Writing the order from before the line item changed fails optimistic concurrency check
+        assert!(store.set_order(&Transaction::none(), before).is_err());
+    }
 }
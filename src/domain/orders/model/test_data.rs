@@ -51,7 +51,11 @@ impl OrderBuilder {
             let line_item = self.order.line_items.pop().unwrap();
 
             let line_item = builder(OrderLineItemBuilder {
-                line_item: OrderLineItem::from_data(self.order.order.clone(), line_item),
+                line_item: OrderLineItem::from_data(
+                    self.order.order.clone(),
+                    line_item,
+                    self.order.line_items.clone(),
+                ),
             });
 
             self.order.line_items.push(line_item.build());
@@ -7,6 +7,7 @@ use crate::domain::{
     products::{
         GetProduct,
         ProductId,
+        VariantId,
     },
 };
 
@@ -17,11 +18,20 @@ pub struct GetLineItemWithProduct {
     pub line_item_id: LineItemId,
 }
 
+/**
+A line item with its associated product.
+
+The title, SKU and original price will be `None` if the product or variant no longer exists.
+*/
 #[derive(Serialize)]
 pub struct LineItemWithProduct {
     pub order_id: OrderId,
     pub line_item_id: LineItemId,
     pub product_id: ProductId,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub variant_id: Option<VariantId>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sku: Option<String>,
     pub title: Option<String>,
     pub original_price: Option<Currency>,
     pub price: Currency,
@@ -49,21 +59,29 @@ async fn execute(
     let product = product_query
         .execute(GetProduct {
             id: line_item.product_id,
+            include_archived: true,
+            include_drafts: true,
         })
         .await?;
 
-    let (title, original_price) = if let Some(product) = product {
-        let product = product.into_data();
+    let (title, sku, original_price) = if let Some(product) = product {
+        let sku = line_item
+            .variant_id
+            .and_then(|variant_id| product.variant(variant_id))
+            .map(|variant| variant.sku.clone());
+        let original_price = product.price(line_item.variant_id).ok();
 
-        (Some(product.title), Some(product.price))
+        (Some(product.into_data().title), sku, original_price)
     } else {
-        (None, None)
+        (None, None, None)
     };
 
     Ok(Some(LineItemWithProduct {
         order_id: query.id,
         line_item_id: query.line_item_id,
         product_id: line_item.product_id,
+        variant_id: line_item.variant_id,
+        sku,
         title,
         original_price,
         price: line_item.price,
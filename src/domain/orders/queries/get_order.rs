@@ -18,7 +18,7 @@ impl QueryArgs for GetOrder {
 
 /** Default implementation for a `GetOrderQuery`. */
 async fn execute(query: GetOrder, store: impl OrderStore) -> Result<Option<Order>, Error> {
-    Ok(store.get_order(query.id)?)
+    store.get_order(query.id)
 }
 
 impl Resolver {
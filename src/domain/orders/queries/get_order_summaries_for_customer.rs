@@ -1,10 +1,24 @@
 /*! Contains the `GetOrderSummariesForCustomerQuery` type. */
 
+use std::collections::{
+    BTreeSet,
+    HashMap,
+};
+
+use futures::future;
+
 use crate::domain::{
     Error,
     customers::*,
     infra::*,
-    orders::*,
+    orders::{
+        queries::{
+            calculate_tax_with_rates,
+            get_tax_rates,
+        },
+        *,
+    },
+    taxes::*,
 };
 
 /** Input for a `GetOrderSummariesForCustomerQuery`. */
@@ -13,24 +27,84 @@ pub struct GetOrderSummariesForCustomer {
     pub id: CustomerId,
 }
 
-/** An individual order summary. */
+/**
+An individual order summary.
+
+The subtotal, discount and total will be `None` if the order doesn't have any line items.
+The tax will be `None` if the order also doesn't have a tax region, or there are no tax rates for it.
+The total doesn't include tax when the tax is `None`.
+*/
 #[derive(Serialize)]
 pub struct OrderSummary {
     pub id: OrderId,
+    pub subtotal: Option<Currency>,
+    pub discount: Option<Currency>,
+    pub tax: Option<Currency>,
+    pub total: Option<Currency>,
 }
 
 impl QueryArgs for GetOrderSummariesForCustomer {
     type Output = Result<Vec<OrderSummary>, Error>;
 }
 
-/** Default implementation for a `GetOrderSummariesForCustomerQuery`. */
+/**
+Default implementation for a `GetOrderSummariesForCustomerQuery`.
+
+The customer's orders are fetched together, and tax rates are fetched once for each region they're taxed in.
+*/
 async fn execute(
     query: GetOrderSummariesForCustomer,
-    store: impl OrderStoreFilter,
+    filter: impl OrderStoreFilter,
+    tax_rates_query: impl Query<GetTaxRates>,
+    rounding: Rounding,
 ) -> Result<Vec<OrderSummary>, Error> {
-    store
+    let ids: Vec<_> = filter
         .filter(|o| o.customer_id == query.id)?
-        .map(|o| Ok(OrderSummary { id: o.id }))
+        .map(|o| o.id)
+        .collect();
+
+    let orders = filter.get_orders(&ids)?;
+
+    let regions: BTreeSet<_> = orders
+        .iter()
+        .filter_map(|order| order.to_data().0.tax_region.as_deref())
+        .collect();
+
+    let rates: HashMap<_, _> = future::try_join_all(regions.into_iter().map(|region| {
+        let tax_rates_query = &tax_rates_query;
+
+        async move {
+            let rates = get_tax_rates(region, tax_rates_query).await?;
+
+            Ok::<_, Error>((region, rates))
+        }
+    }))
+    .await?
+    .into_iter()
+    .collect();
+
+    orders
+        .iter()
+        .map(|order| {
+            let rates = order
+                .to_data()
+                .0
+                .tax_region
+                .as_deref()
+                .and_then(|region| rates.get(region))
+                .and_then(Option::as_ref);
+
+            let discount = order.discount()?;
+            let tax = calculate_tax_with_rates(order, discount.as_ref(), rates, rounding)?;
+
+            Ok(OrderSummary {
+                id: order.to_data().0.id,
+                subtotal: order.subtotal()?,
+                discount: discount.as_ref().map(|discount| discount.total),
+                tax: tax.as_ref().map(|tax| tax.total),
+                total: order.total(discount.as_ref(), tax.as_ref())?,
+            })
+        })
         .collect()
 }
 
@@ -40,9 +114,11 @@ impl Resolver {
         &self,
     ) -> impl Query<GetOrderSummariesForCustomer> {
         self.query(|resolver, query: GetOrderSummariesForCustomer| async move {
-            let store = resolver.order_store_filter();
+            let filter = resolver.order_store_filter();
+            let tax_rates_query = resolver.get_tax_rates_query();
+            let rounding = resolver.tax_rounding();
 
-            execute(query, store).await
+            execute(query, filter, tax_rates_query, rounding).await
         })
     }
 }
@@ -1,11 +1,18 @@
 /*! Contains the `GetOrderWithProductsQuery` type. */
 
+use futures::future;
+
 use crate::domain::{
     Error,
     error,
     infra::*,
-    orders::*,
+    orders::{
+        queries::calculate_tax,
+        *,
+    },
     products::*,
+    promotions::*,
+    taxes::*,
 };
 
 /** Input for a `GetOrderWithProductsQuery`. */
@@ -14,21 +21,60 @@ pub struct GetOrderWithProducts {
     pub id: OrderId,
 }
 
-/** An order with a product summary for each of its line items. */
+/**
+An order with a product summary for each of its line items.
+
+The subtotal, discount and total will be `None` if the order doesn't have any line items.
+The tax will be `None` if the order also doesn't have a tax region, or there are no tax rates for it.
+The total doesn't include tax when the tax is `None`.
+*/
 #[derive(Serialize)]
 pub struct OrderWithProducts {
     pub id: OrderId,
+    pub version: OrderVersion,
     pub line_items: Vec<ProductLineItem>,
+    pub discount_lines: Vec<OrderDiscountLine>,
+    pub tax_region: Option<String>,
+    pub tax_pricing: Option<PricingMode>,
+    pub subtotal: Option<Currency>,
+    pub discount: Option<Currency>,
+    pub tax: Option<Currency>,
+    pub total: Option<Currency>,
 }
 
-/** An individual line item with a product summary. */
+/**
+An individual line item with a product summary.
+
+The price is the one the product had when it was added to the order.
+The SKU will be `None` if the line item isn't for a variant, or the variant has since been removed.
+*/
 #[derive(Serialize)]
 pub struct ProductLineItem {
     pub line_item_id: LineItemId,
     pub product_id: ProductId,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub variant_id: Option<VariantId>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sku: Option<String>,
     pub title: String,
     pub price: Currency,
     pub quantity: u32,
+    pub total: Currency,
+    pub tax: Option<Currency>,
+}
+
+/**
+An individual discount line for a promotion applied to the order.
+
+The amount will be `None` if the order doesn't have any line items.
+*/
+#[derive(Serialize)]
+pub struct OrderDiscountLine {
+    pub discount_line_id: DiscountLineId,
+    pub promotion_id: PromotionId,
+    pub code: String,
+    pub discount: Discount,
+    pub amount: Option<Currency>,
 }
 
 impl QueryArgs for GetOrderWithProducts {
@@ -39,39 +85,84 @@ impl QueryArgs for GetOrderWithProducts {
 async fn execute(
     query: GetOrderWithProducts,
     store: impl OrderStore,
-    products_query: impl Query<GetProductSummaries>,
+    product_query: impl Query<GetProduct>,
+    tax_rates_query: impl Query<GetTaxRates>,
+    rounding: Rounding,
 ) -> Result<Option<OrderWithProducts>, Error> {
-    let (order, line_items) = match store.get_order(query.id)? {
-        Some(order) => order.into_data(),
-        None => return Ok(None),
+    let Some(order) = store.get_order(query.id)? else {
+        return Ok(None);
     };
 
-    let products = {
-        let product_ids = line_items.iter().map(|l| l.product_id).collect();
-        products_query.execute(GetProductSummaries { ids: product_ids })
-    }
+    let discount = order.discount()?;
+    let tax = calculate_tax(&order, discount.as_ref(), &tax_rates_query, rounding).await?;
+
+    let subtotal = order.subtotal()?;
+    let total = order.total(discount.as_ref(), tax.as_ref())?;
+
+    let (order, line_items, discount_lines) = order.into_data();
+
+    // Products are fetched concurrently so they're loaded in a single batch
+    // Archived products are still included so existing orders can resolve them
+    let products = future::try_join_all(line_items.iter().map(|line_item| {
+        product_query.execute(GetProduct {
+            id: line_item.product_id,
+            include_archived: true,
+            include_drafts: true,
+        })
+    }))
     .await?;
 
     let line_items = line_items
         .into_iter()
-        .map(|line_item| {
-            products
-                .iter()
-                .find(|p| p.id == line_item.product_id)
-                .map(|product| ProductLineItem {
-                    line_item_id: line_item.id,
-                    product_id: product.id,
-                    title: product.title.to_owned(),
-                    price: product.price,
-                    quantity: line_item.quantity,
-                })
-                .ok_or_else(|| error::bad_input("missing product for line item"))
+        .zip(products)
+        .map(|(line_item, product)| {
+            let product = product.ok_or_else(|| error::msg("missing product for line item"))?;
+
+            let sku = line_item
+                .variant_id
+                .and_then(|variant_id| product.variant(variant_id))
+                .map(|variant| variant.sku.clone());
+
+            let product = product.into_data();
+
+            Ok(ProductLineItem {
+                line_item_id: line_item.id,
+                product_id: product.id,
+                variant_id: line_item.variant_id,
+                sku,
+                title: product.title,
+                price: line_item.price,
+                quantity: line_item.quantity,
+                total: line_item.total()?,
+                tax: tax.as_ref().and_then(|tax| tax.line_item(line_item.id)),
+            })
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+
+    let discount_lines = discount_lines
+        .into_iter()
+        .map(|discount_line| OrderDiscountLine {
+            discount_line_id: discount_line.id,
+            promotion_id: discount_line.promotion_id,
+            code: discount_line.code,
+            discount: discount_line.discount,
+            amount: discount
+                .as_ref()
+                .and_then(|discount| discount.discount_line(discount_line.id)),
         })
-        .collect::<std::result::Result<Vec<_>, _>>()?;
+        .collect();
 
     Ok(Some(OrderWithProducts {
         id: order.id,
+        version: order.version,
         line_items,
+        discount_lines,
+        tax_region: order.tax_region,
+        tax_pricing: tax.as_ref().map(|tax| tax.pricing),
+        subtotal,
+        discount: discount.map(|discount| discount.total),
+        tax: tax.map(|tax| tax.total),
+        total,
     }))
 }
 
@@ -80,9 +171,11 @@ impl Resolver {
     pub fn get_order_with_products_query(&self) -> impl Query<GetOrderWithProducts> {
         self.query(|resolver, query: GetOrderWithProducts| async move {
             let store = resolver.order_store();
-            let products_query = resolver.get_product_summaries_query();
+            let product_query = resolver.get_product_query();
+            let tax_rates_query = resolver.get_tax_rates_query();
+            let rounding = resolver.tax_rounding();
 
-            execute(query, store, products_query).await
+            execute(query, store, product_query, tax_rates_query, rounding).await
         })
     }
 }
@@ -11,3 +11,78 @@ pub use self::{
     get_order_summaries_for_customer::*,
     get_order_with_products::*,
 };
+
+use crate::domain::{
+    Error,
+    ErrorKind,
+    infra::*,
+    orders::*,
+    taxes::*,
+};
+
+/**
+Calculate the tax for an order using the rates for its tax region.
+
+Tax is calculated after any discount is taken off.
+
+This will be `None` if the order doesn't have a tax region or any line items.
+It will also be `None` if there aren't any rates for the region or the categories of its products,
+so the order can still be read while the rates are missing.
+*/
+async fn calculate_tax(
+    order: &Order,
+    discount: Option<&OrderDiscount>,
+    tax_rates_query: &impl Query<GetTaxRates>,
+    rounding: Rounding,
+) -> Result<Option<OrderTax>, Error> {
+    let Some(region) = order.to_data().0.tax_region.as_deref() else {
+        return Ok(None);
+    };
+
+    let rates = get_tax_rates(region, tax_rates_query).await?;
+
+    calculate_tax_with_rates(order, discount, rates.as_ref(), rounding)
+}
+
+/** Get the tax rates for a region, or `None` if there aren't any. */
+async fn get_tax_rates(
+    region: &str,
+    tax_rates_query: &impl Query<GetTaxRates>,
+) -> Result<Option<TaxRates>, Error> {
+    let rates = tax_rates_query
+        .execute(GetTaxRates {
+            region: region.to_owned(),
+        })
+        .await?;
+
+    if rates.is_none() {
+        emit::warn!("there are no tax rates for {region}");
+    }
+
+    Ok(rates)
+}
+
+/**
+Calculate the tax for an order using rates that have already been fetched for its tax region.
+
+This lets queries over many orders fetch the rates for each region once.
+*/
+fn calculate_tax_with_rates(
+    order: &Order,
+    discount: Option<&OrderDiscount>,
+    rates: Option<&TaxRates>,
+    rounding: Rounding,
+) -> Result<Option<OrderTax>, Error> {
+    let (Some(region), Some(rates)) = (order.to_data().0.tax_region.as_deref(), rates) else {
+        return Ok(None);
+    };
+
+    match order.tax(rates, discount, rounding) {
+        Err(err) if err.kind() == ErrorKind::Unavailable => {
+            emit::warn!("failed to calculate tax in {region}: {reason: err.to_string()}");
+
+            Ok(None)
+        }
+        tax => tax,
+    }
+}
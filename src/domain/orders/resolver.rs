@@ -4,11 +4,16 @@ use std::sync::Arc;
 
 use crate::domain::{
     infra::*,
-    orders::model::store::{
-        self,
-        InMemoryStore,
-        OrderStore,
-        OrderStoreFilter,
+    orders::{
+        DiscountLineData,
+        LineItemData,
+        OrderData,
+        model::store::{
+            self,
+            InMemoryStore,
+            OrderStore,
+            OrderStoreFilter,
+        },
     },
 };
 
@@ -20,19 +25,74 @@ The `OrdersResolver` type wraps private implementation details and exposes them
 #[derive(Clone)]
 pub(in crate::domain) struct OrdersResolver {
     order_store: Register<Arc<InMemoryStore>>,
+    order_id: Register<Arc<dyn IdProvider<OrderData> + Send + Sync>>,
+    line_item_id: Register<Arc<dyn IdProvider<LineItemData> + Send + Sync>>,
+    discount_line_id: Register<Arc<dyn IdProvider<DiscountLineData> + Send + Sync>>,
 }
 
 impl Default for OrdersResolver {
     fn default() -> Self {
         OrdersResolver {
-            order_store: Register::once(|resolver| {
+            order_store: Register::per_tenant(|resolver| {
                 Arc::new(store::in_memory_store(resolver.transaction_store()))
             }),
+            order_id: Register::once(|_| {
+                Arc::new(NextId::<OrderData>::new()) as Arc<dyn IdProvider<OrderData> + Send + Sync>
+            }),
+            line_item_id: Register::once(|_| {
+                Arc::new(NextId::<LineItemData>::new())
+                    as Arc<dyn IdProvider<LineItemData> + Send + Sync>
+            }),
+            discount_line_id: Register::once(|_| {
+                Arc::new(NextId::<DiscountLineData>::new())
+                    as Arc<dyn IdProvider<DiscountLineData> + Send + Sync>
+            }),
         }
     }
 }
 
+impl AppBuilder {
+    /** Use a different source of ids for new orders. */
+    pub fn order_id(
+        mut self,
+        order_id: Register<Arc<dyn IdProvider<OrderData> + Send + Sync>>,
+    ) -> Self {
+        self.root_resolver.orders_resolver.order_id = order_id;
+        self
+    }
+
+    /** Use a different source of ids for new line items. */
+    pub fn line_item_id(
+        mut self,
+        line_item_id: Register<Arc<dyn IdProvider<LineItemData> + Send + Sync>>,
+    ) -> Self {
+        self.root_resolver.orders_resolver.line_item_id = line_item_id;
+        self
+    }
+
+    /** Use a different source of ids for new discount lines. */
+    pub fn discount_line_id(
+        mut self,
+        discount_line_id: Register<Arc<dyn IdProvider<DiscountLineData> + Send + Sync>>,
+    ) -> Self {
+        self.root_resolver.orders_resolver.discount_line_id = discount_line_id;
+        self
+    }
+}
+
 impl Resolver {
+    pub fn order_id(&self) -> impl IdProvider<OrderData> {
+        self.resolve(&self.orders_resolver.order_id)
+    }
+
+    pub fn line_item_id(&self) -> impl IdProvider<LineItemData> {
+        self.resolve(&self.orders_resolver.line_item_id)
+    }
+
+    pub fn discount_line_id(&self) -> impl IdProvider<DiscountLineData> {
+        self.resolve(&self.orders_resolver.discount_line_id)
+    }
+
     pub(in crate::domain::orders) fn order_store(&self) -> impl OrderStore {
         self.resolve(&self.orders_resolver.order_store)
     }
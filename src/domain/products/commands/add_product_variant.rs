@@ -0,0 +1,132 @@
+/*! Contains the `AddProductVariantCommand`. */
+
+use std::collections::BTreeMap;
+
+use crate::domain::{
+    Error,
+    error,
+    infra::*,
+    products::*,
+};
+
+/**
+Input for an `AddProductVariantCommand`.
+
+The variant's SKU must not be used by any other product.
+If a version is given then the product must still have that version for the variant to be added.
+*/
+#[derive(Clone, Serialize, Deserialize)]
+pub struct AddProductVariant {
+    pub id: ProductId,
+    pub variant_id: VariantId,
+    pub sku: String,
+    #[serde(default)]
+    pub options: BTreeMap<String, String>,
+    #[serde(default)]
+    pub price: Option<Currency>,
+    #[serde(default)]
+    pub version: Option<ProductVersion>,
+}
+
+impl CommandArgs for AddProductVariant {
+    type Output = Result<(), Error>;
+}
+
+/** Default implementation for an `AddProductVariantCommand`. */
+async fn execute(
+    command: AddProductVariant,
+    transaction: ActiveTransaction,
+    store: impl ProductStore,
+    filter: impl ProductStoreFilter,
+) -> Result<(), Error> {
+    let Some(mut product) = store.get_product(command.id)? else {
+        return Err(error::not_found("product not found"));
+    };
+
+    product.check_version(command.version)?;
+
+    let sku = Sku::try_from(command.sku)?.into_inner();
+
+    let mut existing = filter
+        .filter(|p| p.id != command.id && p.variants.iter().any(|variant| variant.sku == sku))?;
+    if existing.next().is_some() {
+        return Err(error::conflict(format_args!(
+            "SKU `{}` is already used by another product",
+            sku
+        )));
+    }
+
+    product.add_variant(command.variant_id, sku, command.options, command.price)?;
+
+    store.set_product(transaction.get(), product)?;
+
+    Ok(())
+}
+
+impl Resolver {
+    /** Add a variant to an existing product. */
+    pub fn add_product_variant_command(&self) -> impl Command<AddProductVariant> {
+        self.command(|resolver, command: AddProductVariant| async move {
+            let store = resolver.product_store();
+            let filter = resolver.product_store_filter();
+            let active_transaction = resolver.active_transaction();
+
+            execute(command, active_transaction, store, filter).await
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::domain::{
+        ErrorKind,
+        products::model::{
+            store::in_memory_store,
+            test_data::ProductBuilder,
+        },
+    };
+
+    #[tokio::test]
+    async fn err_if_sku_used_by_another_product() {
+        let store = in_memory_store(Default::default());
+
+        let id = ProductId::new();
+
+        store
+            .set_product(
+                ActiveTransaction::none().get(),
+                ProductBuilder::new()
+                    .id(ProductId::new())
+                    .variant(VariantId::new(), "SHIRT-S")
+                    .build(),
+            )
+            .unwrap();
+        store
+            .set_product(
+                ActiveTransaction::none().get(),
+                ProductBuilder::new().id(id).build(),
+            )
+            .unwrap();
+
+        let err = execute(
+            AddProductVariant {
+                id,
+                variant_id: VariantId::new(),
+                sku: "shirt-s".to_owned(),
+                options: Default::default(),
+                price: None,
+                version: None,
+            },
+            ActiveTransaction::none(),
+            &store,
+            &store,
+        )
+        .await
+        .err()
+        .unwrap();
+
+        assert_eq!(ErrorKind::Conflict, err.kind());
+    }
+}
@@ -0,0 +1,111 @@
+/*! Contains the `ArchiveProductCommand` type. */
+
+use crate::domain::{
+    Error,
+    error,
+    infra::*,
+    products::*,
+};
+
+/**
+Input for a `ArchiveProductCommand`.
+
+If a version is given then the product must still have that version to be archived.
+*/
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ArchiveProduct {
+    pub id: ProductId,
+    #[serde(default)]
+    pub version: Option<ProductVersion>,
+}
+
+impl CommandArgs for ArchiveProduct {
+    type Output = Result<(), Error>;
+}
+
+/** Default implementation for a `ArchiveProductCommand`. */
+async fn execute(
+    command: ArchiveProduct,
+    transaction: ActiveTransaction,
+    store: impl ProductStore,
+) -> Result<(), Error> {
+    let Some(mut product) = store.get_product(command.id)? else {
+        return Err(error::not_found("product not found"));
+    };
+
+    product.check_version(command.version)?;
+
+    product.archive();
+
+    store.set_product(transaction.get(), product)?;
+
+    Ok(())
+}
+
+impl Resolver {
+    /** Archive a product. */
+    pub fn archive_product_command(&self) -> impl Command<ArchiveProduct> {
+        self.command(|resolver, command: ArchiveProduct| async move {
+            let store = resolver.product_store();
+            let active_transaction = resolver.active_transaction();
+
+            execute(command, active_transaction, store).await
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::domain::{
+        ErrorKind,
+        products::model::{
+            store::in_memory_store,
+            test_data::ProductBuilder,
+        },
+    };
+
+    #[tokio::test]
+    async fn archive_product() {
+        let store = in_memory_store(Default::default());
+
+        let id = ProductId::new();
+
+        store
+            .set_product(
+                ActiveTransaction::none().get(),
+                ProductBuilder::new().id(id).build(),
+            )
+            .unwrap();
+
+        execute(
+            ArchiveProduct { id, version: None },
+            ActiveTransaction::none(),
+            &store,
+        )
+        .await
+        .unwrap();
+
+        assert!(store.get_product(id).unwrap().unwrap().to_data().archived);
+    }
+
+    #[tokio::test]
+    async fn err_if_not_found() {
+        let store = in_memory_store(Default::default());
+
+        let err = execute(
+            ArchiveProduct {
+                id: ProductId::new(),
+                version: None,
+            },
+            ActiveTransaction::none(),
+            &store,
+        )
+        .await
+        .err()
+        .unwrap();
+
+        assert_eq!(ErrorKind::NotFound, err.kind());
+    }
+}
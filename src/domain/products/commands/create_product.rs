@@ -2,17 +2,27 @@
 
 use crate::domain::{
     Error,
+    ErrorKind,
     error,
     infra::*,
     products::*,
 };
 
-/** Input for a `CreateProductCommand`. */
+/**
+Input for a `CreateProductCommand`.
+
+Products are in the standard tax category unless another one is given.
+Draft products need to be published before they can be ordered.
+*/
 #[derive(Clone, Serialize, Deserialize)]
 pub struct CreateProduct {
     pub id: ProductId,
     pub title: String,
     pub price: Currency,
+    #[serde(default)]
+    pub tax_category: Option<String>,
+    #[serde(default)]
+    pub draft: bool,
 }
 
 impl CommandArgs for CreateProduct {
@@ -24,14 +34,26 @@ async fn execute(
     command: CreateProduct,
     transaction: ActiveTransaction,
     store: impl ProductStore,
+    clock: impl Clock,
 ) -> Result<(), Error> {
     let product = {
         if store.get_product(command.id)?.is_some() {
-            return Err(error::emit(emit::evt!(
-                "product {id: command.id} already exists"
-            )));
+            return Err(
+                error::emit(emit::evt!("product {id: command.id} already exists"))
+                    .with_kind(ErrorKind::Conflict),
+            );
         } else {
-            Product::new(command.id, command.title, command.price)?
+            let mut product = if command.draft {
+                Product::new_draft(command.id, command.title, command.price, clock.now())?
+            } else {
+                Product::new(command.id, command.title, command.price, clock.now())?
+            };
+
+            if let Some(tax_category) = command.tax_category {
+                product.set_tax_category(tax_category)?;
+            }
+
+            product
         }
     };
 
@@ -46,14 +68,17 @@ impl Resolver {
         self.command(|resolver, command: CreateProduct| async move {
             let store = resolver.product_store();
             let active_transaction = resolver.active_transaction();
+            let clock = resolver.clock();
 
-            execute(command, active_transaction, store).await
+            execute(command, active_transaction, store, clock).await
         })
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::time::SystemTime;
+
     use super::*;
 
     use crate::domain::products::model::store::in_memory_store;
@@ -66,16 +91,24 @@ mod tests {
             id: ProductId::new(),
             title: "Test Product".into(),
             price: Currency::usd(100),
+            tax_category: None,
+            draft: false,
         };
 
-        execute(create.clone(), ActiveTransaction::none(), &store)
+        execute(
+            create.clone(),
+            ActiveTransaction::none(),
+            &store,
+            SystemTime::now(),
+        )
+        .await
+        .unwrap();
+
+        let err = execute(create, ActiveTransaction::none(), &store, SystemTime::now())
             .await
+            .err()
             .unwrap();
 
-        assert!(
-            execute(create, ActiveTransaction::none(), &store)
-                .await
-                .is_err()
-        );
+        assert_eq!(ErrorKind::Conflict, err.kind());
     }
 }
@@ -0,0 +1,115 @@
+/*! Contains the `DiscontinueProductCommand` type. */
+
+use crate::domain::{
+    Error,
+    error,
+    infra::*,
+    products::*,
+};
+
+/**
+Input for a `DiscontinueProductCommand`.
+
+Only active products can be discontinued.
+If a version is given then the product must still have that version to be discontinued.
+*/
+#[derive(Clone, Serialize, Deserialize)]
+pub struct DiscontinueProduct {
+    pub id: ProductId,
+    #[serde(default)]
+    pub version: Option<ProductVersion>,
+}
+
+impl CommandArgs for DiscontinueProduct {
+    type Output = Result<(), Error>;
+}
+
+/** Default implementation for a `DiscontinueProductCommand`. */
+async fn execute(
+    command: DiscontinueProduct,
+    transaction: ActiveTransaction,
+    store: impl ProductStore,
+) -> Result<(), Error> {
+    let Some(mut product) = store.get_product(command.id)? else {
+        return Err(error::not_found("product not found"));
+    };
+
+    product.check_version(command.version)?;
+
+    product.discontinue()?;
+
+    store.set_product(transaction.get(), product)?;
+
+    Ok(())
+}
+
+impl Resolver {
+    /** Discontinue a product so it can no longer be ordered. */
+    pub fn discontinue_product_command(&self) -> impl Command<DiscontinueProduct> {
+        self.command(|resolver, command: DiscontinueProduct| async move {
+            let store = resolver.product_store();
+            let active_transaction = resolver.active_transaction();
+
+            execute(command, active_transaction, store).await
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::domain::{
+        ErrorKind,
+        products::model::{
+            store::in_memory_store,
+            test_data::ProductBuilder,
+        },
+    };
+
+    #[tokio::test]
+    async fn discontinue_active_product() {
+        let store = in_memory_store(Default::default());
+
+        let id = ProductId::new();
+
+        store
+            .set_product(
+                ActiveTransaction::none().get(),
+                ProductBuilder::new().id(id).build(),
+            )
+            .unwrap();
+
+        execute(
+            DiscontinueProduct { id, version: None },
+            ActiveTransaction::none(),
+            &store,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            ProductStatus::Discontinued,
+            store.get_product(id).unwrap().unwrap().to_data().status
+        );
+    }
+
+    #[tokio::test]
+    async fn err_if_not_found() {
+        let store = in_memory_store(Default::default());
+
+        let err = execute(
+            DiscontinueProduct {
+                id: ProductId::new(),
+                version: None,
+            },
+            ActiveTransaction::none(),
+            &store,
+        )
+        .await
+        .err()
+        .unwrap();
+
+        assert_eq!(ErrorKind::NotFound, err.kind());
+    }
+}
@@ -0,0 +1,407 @@
+/*! Contains the `ImportProductsCommand` type. */
+
+use std::collections::HashSet;
+
+use uuid::Uuid;
+
+use crate::domain::{
+    Error,
+    categories::GetCategory,
+    error,
+    infra::*,
+    products::*,
+};
+
+/**
+Input for an `ImportProductsCommand`.
+
+Each record in the file either creates a new product or updates an existing one with the same id.
+Empty fields leave the product's current value unchanged.
+Valid records are applied in batches, each in its own transaction, so a large import doesn't need
+to hold a single transaction open. The batch size defaults to 100 records.
+Batches are committed as they're applied, even if the command itself runs in a transaction.
+That transaction only holds the command's entry in the audit log.
+*/
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ImportProducts {
+    pub format: ProductFileFormat,
+    pub data: String,
+    #[serde(default)]
+    pub batch_size: Option<usize>,
+}
+
+/**
+The outcome of an import.
+
+Records that weren't imported are reported along with the row they came from.
+*/
+#[derive(Serialize)]
+pub struct ImportReport {
+    pub imported: usize,
+    pub errors: Vec<ImportRowError>,
+}
+
+/** A record that couldn't be imported. */
+#[derive(Serialize)]
+pub struct ImportRowError {
+    pub row: usize,
+    pub message: String,
+}
+
+impl CommandArgs for ImportProducts {
+    type Output = Result<ImportReport, Error>;
+
+    // The file can be large, so the audit log only gets its size and a digest of its contents
+    fn audit_args(&self) -> Result<serde_json::Value, serde_json::Error> {
+        let rows = ProductRecord::read(self.format, &self.data)
+            .map(|records| records.len())
+            .ok();
+
+        Ok(serde_json::json!({
+            "format": self.format,
+            "batch_size": self.batch_size,
+            "rows": rows,
+            "digest": Uuid::new_v5(&NAMESPACE, self.data.as_bytes()),
+        }))
+    }
+}
+
+// The namespace used to derive digests of imported files
+const NAMESPACE: Uuid = Uuid::from_u128(0x2d7b_91c4_6e3a_4f08_b5d2_7a19_c0e4_6f31);
+
+const DEFAULT_BATCH_SIZE: usize = 100;
+
+/**
+Default implementation for an `ImportProductsCommand`.
+
+Each record in a batch is checked against its product before any of the batch is written.
+Records that can't be applied or saved are reported and the rest of the batch is committed.
+*/
+async fn execute(command: ImportProducts, resolver: &Resolver) -> Result<ImportReport, Error> {
+    let mut errors = Vec::new();
+
+    let mut valid = Vec::new();
+    let mut ids = HashSet::new();
+    for (row, record) in ProductRecord::read(command.format, &command.data)? {
+        let record = record.and_then(|record| {
+            record.validate()?;
+
+            if let Some(id) = record.id
+                && !ids.insert(id)
+            {
+                return Err(error::bad_input(
+                    "the product already appears in an earlier row",
+                ));
+            }
+
+            Ok(record)
+        });
+
+        match record {
+            Ok(record) => valid.push((row, record)),
+            Err(err) => errors.push(ImportRowError {
+                row,
+                message: err.to_string(),
+            }),
+        }
+    }
+
+    let batch_size = command.batch_size.unwrap_or(DEFAULT_BATCH_SIZE).max(1);
+
+    let mut imported = 0;
+    for batch in valid.chunks(batch_size) {
+        let (applied, batch_errors) = resolver
+            .transaction(|resolver| async move {
+                let store = resolver.product_store();
+                let active_transaction = resolver.active_transaction();
+
+                let mut products = Vec::new();
+                let mut errors = Vec::new();
+                for (row, record) in batch {
+                    match prepare(&resolver, record.clone()).await {
+                        Ok(product) => products.push((*row, product)),
+                        Err(err) => errors.push(ImportRowError {
+                            row: *row,
+                            message: err.to_string(),
+                        }),
+                    }
+                }
+
+                // A product can still fail to save, like if it was changed since it was read
+                // That doesn't stop the rest of the batch from being saved
+                let mut applied = 0;
+                for (row, product) in products {
+                    match store.set_product(active_transaction.get(), product) {
+                        Ok(()) => applied += 1,
+                        Err(err) => errors.push(ImportRowError {
+                            row,
+                            message: err.to_string(),
+                        }),
+                    }
+                }
+
+                Ok::<_, Error>((applied, errors))
+            })
+            .await?;
+
+        imported += applied;
+        errors.extend(batch_errors);
+    }
+
+    errors.sort_by_key(|err| err.row);
+
+    Ok(ImportReport { imported, errors })
+}
+
+/**
+Apply a single record to its product, without saving it.
+
+Changes to a product's status follow the same transitions as its commands.
+*/
+async fn prepare(resolver: &Resolver, record: ProductRecord) -> Result<Product, Error> {
+    let store = resolver.product_store();
+    let category_query = resolver.get_category_query();
+    let clock = resolver.clock();
+
+    let id = match record.id {
+        Some(id) => id,
+        None => resolver.product_id().get()?,
+    };
+
+    let now = clock.now();
+    let price = record.price();
+
+    let mut product = match store.get_product(id)? {
+        Some(mut product) => {
+            product.set_title(record.title)?;
+            product.set_price(price, now)?;
+
+            product
+        }
+        None if record.status == Some(ProductStatus::Draft) => {
+            Product::new_draft(id, record.title, price, now)?
+        }
+        None => Product::new(id, record.title, price, now)?,
+    };
+
+    if let Some(tax_category) = record.tax_category {
+        product.set_tax_category(tax_category)?;
+    }
+
+    if let Some(tags) = record.tags {
+        product.set_tags(tags)?;
+    }
+
+    if let Some(category_id) = record.category_id {
+        let category = category_query
+            .execute(GetCategory { id: category_id })
+            .await?
+            .ok_or_else(|| error::bad_input("category not found"))?;
+
+        product.set_category(Some(&category));
+    }
+
+    if let Some(status) = record.status
+        && status != product.to_data().status
+    {
+        match status {
+            ProductStatus::Active => product.publish()?,
+            ProductStatus::Discontinued => product.discontinue()?,
+            ProductStatus::Draft => {
+                return Err(error::bad_input(
+                    "a published product can't be made a draft again",
+                ));
+            }
+        }
+    }
+
+    match record.archived {
+        Some(true) => product.archive(),
+        Some(false) => product.restore(),
+        None => (),
+    }
+
+    Ok(product)
+}
+
+impl Resolver {
+    /** Import products in bulk from a file. */
+    pub fn import_products_command(&self) -> impl Command<ImportProducts> {
+        self.command(|resolver, command: ImportProducts| async move {
+            execute(command, &resolver).await
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::domain::{
+        App,
+        products::model::test_data::ProductBuilder,
+    };
+
+    async fn get_product(app: &App, id: ProductId) -> Product {
+        app.root_resolver
+            .get_product_query()
+            .execute(GetProduct {
+                id,
+                include_archived: true,
+                include_drafts: true,
+            })
+            .await
+            .unwrap()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn import_valid_rows() {
+        let app = App::new();
+        let resolver = &app.root_resolver;
+
+        let existing = ProductBuilder::new().price(Currency::usd(100)).build();
+        let existing_id = existing.to_data().id;
+        resolver
+            .transaction(|resolver| async move {
+                resolver
+                    .product_store()
+                    .set_product(resolver.active_transaction().get(), existing)
+            })
+            .await
+            .unwrap();
+
+        let new_id = ProductId::new();
+        let data = format!(
+            "id,title,currency,price,status,tags\n\
+             {},Updated,usd,150,,sale\n\
+             {},New draft,usd,200,draft,\n\
+             ,,usd,100,,\n\
+             ,Unknown status,usd,100,pending,\n\
+             ,Not a draft,usd,100,,\n",
+            existing_id, new_id
+        );
+
+        let report = execute(
+            ImportProducts {
+                format: ProductFileFormat::Csv,
+                data,
+                batch_size: Some(2),
+            },
+            resolver,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(3, report.imported);
+        assert_eq!(
+            vec![4, 5],
+            report.errors.iter().map(|err| err.row).collect::<Vec<_>>()
+        );
+
+        let existing = get_product(&app, existing_id).await;
+        assert_eq!("Updated", existing.to_data().title);
+        assert_eq!(Currency::usd(150), existing.to_data().price);
+        assert_eq!(vec!["sale"], existing.to_data().tags);
+        assert_eq!(2, existing.price_history().len());
+
+        let new = get_product(&app, new_id).await;
+        assert_eq!(ProductStatus::Draft, new.to_data().status);
+    }
+
+    #[tokio::test]
+    async fn report_rows_that_fail_to_save() {
+        let app = App::new();
+        let resolver = &app.root_resolver;
+
+        let existing = ProductBuilder::new().build();
+        let existing_id = existing.to_data().id;
+        resolver
+            .transaction(|resolver| async move {
+                resolver
+                    .product_store()
+                    .set_product(resolver.active_transaction().get(), existing)
+            })
+            .await
+            .unwrap();
+
+        // Another transaction changes the product while it's being imported
+        let transactions = resolver.transaction_store();
+        let concurrent = transactions.begin();
+
+        let mut changed = get_product(&app, existing_id).await;
+        changed.set_title("Changed").unwrap();
+        resolver
+            .product_store()
+            .set_product(&concurrent, changed)
+            .unwrap();
+
+        let report = execute(
+            ImportProducts {
+                format: ProductFileFormat::Csv,
+                data: format!(
+                    "id,title,currency,price\n{},Imported,usd,100\n,New,usd,100\n",
+                    existing_id
+                ),
+                batch_size: None,
+            },
+            resolver,
+        )
+        .await
+        .unwrap();
+
+        transactions.cancel(concurrent);
+
+        assert_eq!(1, report.imported);
+        assert_eq!(1, report.errors.len());
+        assert_eq!(2, report.errors[0].row);
+    }
+
+    #[test]
+    fn audit_a_summary_of_the_file() {
+        let command = ImportProducts {
+            format: ProductFileFormat::Csv,
+            data: "title,currency,price\nA shirt,usd,100\nA hat,usd,100\n".to_owned(),
+            batch_size: None,
+        };
+
+        let args = command.audit_args().unwrap();
+
+        assert_eq!(2, args["rows"]);
+        assert!(args["digest"].is_string());
+        assert!(args.get("data").is_none());
+    }
+
+    #[tokio::test]
+    async fn report_rows_that_fail_to_apply() {
+        let app = App::new();
+        let resolver = &app.root_resolver;
+
+        let id = ProductId::new();
+
+        // The second row's category doesn't exist, so only the first row is imported
+        let data = format!(
+            "{{\"id\":\"{}\",\"title\":\"Active\",\"currency\":\"USD\",\"price\":100}}\n\
+             {{\"title\":\"Missing category\",\"currency\":\"USD\",\"price\":100,\"category_id\":\"{}\"}}\n",
+            id,
+            ProductId::new()
+        );
+
+        let report = execute(
+            ImportProducts {
+                format: ProductFileFormat::JsonLines,
+                data,
+                batch_size: None,
+            },
+            resolver,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(1, report.imported);
+        assert_eq!(1, report.errors.len());
+        assert_eq!(2, report.errors[0].row);
+
+        get_product(&app, id).await;
+    }
+}
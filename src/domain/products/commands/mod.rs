@@ -1,9 +1,29 @@
 /*! Commands for modifying product state. */
 
+mod add_product_variant;
+mod archive_product;
 mod create_product;
+mod discontinue_product;
+mod import_products;
+mod publish_product;
+mod remove_product_variant;
+mod restore_product;
+mod set_product_category;
+mod set_product_price;
+mod set_product_tags;
 mod set_product_title;
 
 pub use self::{
+    add_product_variant::*,
+    archive_product::*,
     create_product::*,
+    discontinue_product::*,
+    import_products::*,
+    publish_product::*,
+    remove_product_variant::*,
+    restore_product::*,
+    set_product_category::*,
+    set_product_price::*,
+    set_product_tags::*,
     set_product_title::*,
 };
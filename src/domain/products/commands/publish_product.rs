@@ -0,0 +1,118 @@
+/*! Contains the `PublishProductCommand` type. */
+
+use crate::domain::{
+    Error,
+    error,
+    infra::*,
+    products::*,
+};
+
+/**
+Input for a `PublishProductCommand`.
+
+Draft and discontinued products can be published.
+If a version is given then the product must still have that version to be published.
+*/
+#[derive(Clone, Serialize, Deserialize)]
+pub struct PublishProduct {
+    pub id: ProductId,
+    #[serde(default)]
+    pub version: Option<ProductVersion>,
+}
+
+impl CommandArgs for PublishProduct {
+    type Output = Result<(), Error>;
+}
+
+/** Default implementation for a `PublishProductCommand`. */
+async fn execute(
+    command: PublishProduct,
+    transaction: ActiveTransaction,
+    store: impl ProductStore,
+) -> Result<(), Error> {
+    let Some(mut product) = store.get_product(command.id)? else {
+        return Err(error::not_found("product not found"));
+    };
+
+    product.check_version(command.version)?;
+
+    product.publish()?;
+
+    store.set_product(transaction.get(), product)?;
+
+    Ok(())
+}
+
+impl Resolver {
+    /** Publish a product so it can be ordered. */
+    pub fn publish_product_command(&self) -> impl Command<PublishProduct> {
+        self.command(|resolver, command: PublishProduct| async move {
+            let store = resolver.product_store();
+            let active_transaction = resolver.active_transaction();
+
+            execute(command, active_transaction, store).await
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::domain::{
+        ErrorKind,
+        products::model::{
+            store::in_memory_store,
+            test_data::ProductBuilder,
+        },
+    };
+
+    #[tokio::test]
+    async fn publish_draft_product() {
+        let store = in_memory_store(Default::default());
+
+        let id = ProductId::new();
+
+        store
+            .set_product(
+                ActiveTransaction::none().get(),
+                ProductBuilder::new()
+                    .id(id)
+                    .status(ProductStatus::Draft)
+                    .build(),
+            )
+            .unwrap();
+
+        execute(
+            PublishProduct { id, version: None },
+            ActiveTransaction::none(),
+            &store,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            ProductStatus::Active,
+            store.get_product(id).unwrap().unwrap().to_data().status
+        );
+    }
+
+    #[tokio::test]
+    async fn err_if_not_found() {
+        let store = in_memory_store(Default::default());
+
+        let err = execute(
+            PublishProduct {
+                id: ProductId::new(),
+                version: None,
+            },
+            ActiveTransaction::none(),
+            &store,
+        )
+        .await
+        .err()
+        .unwrap();
+
+        assert_eq!(ErrorKind::NotFound, err.kind());
+    }
+}
@@ -0,0 +1,56 @@
+/*! Contains the `RemoveProductVariantCommand`. */
+
+use crate::domain::{
+    Error,
+    error,
+    infra::*,
+    products::*,
+};
+
+/**
+Input for a `RemoveProductVariantCommand`.
+
+If a version is given then the product must still have that version for the variant to be removed.
+*/
+#[derive(Clone, Serialize, Deserialize)]
+pub struct RemoveProductVariant {
+    pub id: ProductId,
+    pub variant_id: VariantId,
+    #[serde(default)]
+    pub version: Option<ProductVersion>,
+}
+
+impl CommandArgs for RemoveProductVariant {
+    type Output = Result<(), Error>;
+}
+
+/** Default implementation for a `RemoveProductVariantCommand`. */
+async fn execute(
+    command: RemoveProductVariant,
+    transaction: ActiveTransaction,
+    store: impl ProductStore,
+) -> Result<(), Error> {
+    let Some(mut product) = store.get_product(command.id)? else {
+        return Err(error::not_found("product not found"));
+    };
+
+    product.check_version(command.version)?;
+
+    product.remove_variant(command.variant_id)?;
+
+    store.set_product(transaction.get(), product)?;
+
+    Ok(())
+}
+
+impl Resolver {
+    /** Remove a variant from an existing product. */
+    pub fn remove_product_variant_command(&self) -> impl Command<RemoveProductVariant> {
+        self.command(|resolver, command: RemoveProductVariant| async move {
+            let store = resolver.product_store();
+            let active_transaction = resolver.active_transaction();
+
+            execute(command, active_transaction, store).await
+        })
+    }
+}
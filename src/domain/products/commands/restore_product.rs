@@ -0,0 +1,55 @@
+/*! Contains the `RestoreProductCommand` type. */
+
+use crate::domain::{
+    Error,
+    error,
+    infra::*,
+    products::*,
+};
+
+/**
+Input for a `RestoreProductCommand`.
+
+If a version is given then the product must still have that version to be restored.
+*/
+#[derive(Clone, Serialize, Deserialize)]
+pub struct RestoreProduct {
+    pub id: ProductId,
+    #[serde(default)]
+    pub version: Option<ProductVersion>,
+}
+
+impl CommandArgs for RestoreProduct {
+    type Output = Result<(), Error>;
+}
+
+/** Default implementation for a `RestoreProductCommand`. */
+async fn execute(
+    command: RestoreProduct,
+    transaction: ActiveTransaction,
+    store: impl ProductStore,
+) -> Result<(), Error> {
+    let Some(mut product) = store.get_product(command.id)? else {
+        return Err(error::not_found("product not found"));
+    };
+
+    product.check_version(command.version)?;
+
+    product.restore();
+
+    store.set_product(transaction.get(), product)?;
+
+    Ok(())
+}
+
+impl Resolver {
+    /** Restore an archived product. */
+    pub fn restore_product_command(&self) -> impl Command<RestoreProduct> {
+        self.command(|resolver, command: RestoreProduct| async move {
+            let store = resolver.product_store();
+            let active_transaction = resolver.active_transaction();
+
+            execute(command, active_transaction, store).await
+        })
+    }
+}
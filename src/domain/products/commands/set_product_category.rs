@@ -0,0 +1,126 @@
+/*! Contains the `SetProductCategoryCommand`. */
+
+use crate::domain::{
+    Error,
+    categories::*,
+    error,
+    infra::*,
+    products::*,
+};
+
+/**
+Input for a `SetProductCategoryCommand`.
+
+If no category is given then the product is taken out of its current one.
+If a version is given then the product must still have that version for the category to be set.
+*/
+#[derive(Clone, Serialize, Deserialize)]
+pub struct SetProductCategory {
+    pub id: ProductId,
+    #[serde(default)]
+    pub category_id: Option<CategoryId>,
+    #[serde(default)]
+    pub version: Option<ProductVersion>,
+}
+
+impl CommandArgs for SetProductCategory {
+    type Output = Result<(), Error>;
+}
+
+/** Default implementation for a `SetProductCategoryCommand`. */
+async fn execute(
+    command: SetProductCategory,
+    transaction: ActiveTransaction,
+    store: impl ProductStore,
+    category_query: impl Query<GetCategory>,
+) -> Result<(), Error> {
+    let Some(mut product) = store.get_product(command.id)? else {
+        return Err(error::not_found("product not found"));
+    };
+
+    product.check_version(command.version)?;
+
+    let category = match command.category_id {
+        Some(id) => Some(
+            category_query
+                .execute(GetCategory { id })
+                .await?
+                .ok_or_else(|| error::bad_input("category not found"))?,
+        ),
+        None => None,
+    };
+
+    product.set_category(category.as_ref());
+
+    store.set_product(transaction.get(), product)?;
+
+    Ok(())
+}
+
+impl Resolver {
+    /** Put an existing product in a category. */
+    pub fn set_product_category_command(&self) -> impl Command<SetProductCategory> {
+        self.command(|resolver, command: SetProductCategory| async move {
+            let store = resolver.product_store();
+            let active_transaction = resolver.active_transaction();
+
+            let category_query = resolver.get_category_query();
+
+            execute(command, active_transaction, store, category_query).await
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::domain::{
+        ErrorKind,
+        products::model::{
+            store::in_memory_store,
+            test_data::ProductBuilder,
+        },
+    };
+
+    #[tokio::test]
+    async fn err_if_category_not_found() {
+        let store = in_memory_store(Default::default());
+
+        let id = ProductId::new();
+
+        store
+            .set_product(
+                ActiveTransaction::none().get(),
+                ProductBuilder::new().id(id).build(),
+            )
+            .unwrap();
+
+        let category_query = |_| async { Ok(None) };
+
+        let err = execute(
+            SetProductCategory {
+                id,
+                category_id: Some(CategoryId::new()),
+                version: None,
+            },
+            ActiveTransaction::none(),
+            &store,
+            &category_query,
+        )
+        .await
+        .err()
+        .unwrap();
+
+        assert_eq!(ErrorKind::BadInput, err.kind());
+        assert_eq!(
+            None,
+            store
+                .get_product(id)
+                .unwrap()
+                .unwrap()
+                .to_data()
+                .category_id
+        );
+    }
+}
@@ -0,0 +1,118 @@
+/*! Contains the `SetProductPriceCommand`. */
+
+use crate::domain::{
+    Error,
+    error,
+    infra::*,
+    products::*,
+};
+
+/**
+Input for a `SetProductPriceCommand`.
+
+If a version is given then the product must still have that version for the price to be set.
+*/
+#[derive(Clone, Serialize, Deserialize)]
+pub struct SetProductPrice {
+    pub id: ProductId,
+    pub price: Currency,
+    #[serde(default)]
+    pub version: Option<ProductVersion>,
+}
+
+impl CommandArgs for SetProductPrice {
+    type Output = Result<(), Error>;
+}
+
+/** Default implementation for a `SetProductPriceCommand`. */
+async fn execute(
+    command: SetProductPrice,
+    transaction: ActiveTransaction,
+    store: impl ProductStore,
+    clock: impl Clock,
+) -> Result<(), Error> {
+    let Some(mut product) = store.get_product(command.id)? else {
+        return Err(error::not_found("product not found"));
+    };
+
+    product.check_version(command.version)?;
+
+    product.set_price(command.price, clock.now())?;
+
+    store.set_product(transaction.get(), product)?;
+
+    Ok(())
+}
+
+impl Resolver {
+    /**
+    Set an existing product's price.
+
+    Orders that already contain the product keep the price it had when it was added.
+    */
+    pub fn set_product_price_command(&self) -> impl Command<SetProductPrice> {
+        self.command(|resolver, command: SetProductPrice| async move {
+            let store = resolver.product_store();
+            let active_transaction = resolver.active_transaction();
+            let clock = resolver.clock();
+
+            execute(command, active_transaction, store, clock).await
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{
+        Duration,
+        SystemTime,
+    };
+
+    use super::*;
+
+    use crate::domain::products::model::{
+        store::in_memory_store,
+        test_data::ProductBuilder,
+    };
+
+    #[tokio::test]
+    async fn set_price_records_history() {
+        let store = in_memory_store(Default::default());
+
+        let id = ProductId::new();
+        let created_at = SystemTime::UNIX_EPOCH;
+        let changed_at = created_at + Duration::from_secs(60);
+
+        store
+            .set_product(
+                ActiveTransaction::none().get(),
+                ProductBuilder::new()
+                    .id(id)
+                    .price(Currency::usd(100))
+                    .created_at(created_at)
+                    .build(),
+            )
+            .unwrap();
+
+        execute(
+            SetProductPrice {
+                id,
+                price: Currency::usd(200),
+                version: None,
+            },
+            ActiveTransaction::none(),
+            &store,
+            changed_at,
+        )
+        .await
+        .unwrap();
+
+        let product = store.get_product(id).unwrap().unwrap();
+        let history = product.price_history();
+
+        assert_eq!(Currency::usd(200), product.to_data().price);
+        assert_eq!(2, history.len());
+        assert_eq!(created_at, history[0].effective_from);
+        assert_eq!(Some(changed_at), history[0].effective_to);
+    }
+}
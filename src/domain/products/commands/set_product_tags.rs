@@ -0,0 +1,57 @@
+/*! Contains the `SetProductTagsCommand`. */
+
+use crate::domain::{
+    Error,
+    error,
+    infra::*,
+    products::*,
+};
+
+/**
+Input for a `SetProductTagsCommand`.
+
+The given tags replace any the product already has.
+If a version is given then the product must still have that version for the tags to be set.
+*/
+#[derive(Clone, Serialize, Deserialize)]
+pub struct SetProductTags {
+    pub id: ProductId,
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub version: Option<ProductVersion>,
+}
+
+impl CommandArgs for SetProductTags {
+    type Output = Result<(), Error>;
+}
+
+/** Default implementation for a `SetProductTagsCommand`. */
+async fn execute(
+    command: SetProductTags,
+    transaction: ActiveTransaction,
+    store: impl ProductStore,
+) -> Result<(), Error> {
+    let Some(mut product) = store.get_product(command.id)? else {
+        return Err(error::not_found("product not found"));
+    };
+
+    product.check_version(command.version)?;
+
+    product.set_tags(command.tags)?;
+
+    store.set_product(transaction.get(), product)?;
+
+    Ok(())
+}
+
+impl Resolver {
+    /** Replace an existing product's tags. */
+    pub fn set_product_tags_command(&self) -> impl Command<SetProductTags> {
+        self.command(|resolver, command: SetProductTags| async move {
+            let store = resolver.product_store();
+            let active_transaction = resolver.active_transaction();
+
+            execute(command, active_transaction, store).await
+        })
+    }
+}
@@ -7,11 +7,17 @@ use crate::domain::{
     products::*,
 };
 
-/** Input for a `SetProductTitleCommand`. */
+/**
+Input for a `SetProductTitleCommand`.
+
+If a version is given then the product must still have that version for the title to be set.
+*/
 #[derive(Clone, Serialize, Deserialize)]
 pub struct SetProductTitle {
     pub id: ProductId,
     pub title: String,
+    #[serde(default)]
+    pub version: Option<ProductVersion>,
 }
 
 impl CommandArgs for SetProductTitle {
@@ -26,11 +32,13 @@ async fn execute(
 ) -> Result<(), Error> {
     let product = {
         if let Some(mut product) = store.get_product(command.id)? {
+            product.check_version(command.version)?;
+
             product.set_title(command.title)?;
 
             product
         } else {
-            return Err(error::msg("not found"));
+            return Err(error::not_found("product not found"));
         }
     };
 
@@ -50,3 +58,63 @@ impl Resolver {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::domain::{
+        ErrorKind,
+        products::model::{
+            store::in_memory_store,
+            test_data::ProductBuilder,
+        },
+    };
+
+    #[tokio::test]
+    async fn err_if_version_changed() {
+        let store = in_memory_store(Default::default());
+
+        let id = ProductId::new();
+
+        store
+            .set_product(
+                ActiveTransaction::none().get(),
+                ProductBuilder::new().id(id).build(),
+            )
+            .unwrap();
+
+        let version = store.get_product(id).unwrap().unwrap().to_data().version;
+
+        execute(
+            SetProductTitle {
+                id,
+                title: "First".to_owned(),
+                version: Some(version),
+            },
+            ActiveTransaction::none(),
+            &store,
+        )
+        .await
+        .unwrap();
+
+        let err = execute(
+            SetProductTitle {
+                id,
+                title: "Second".to_owned(),
+                version: Some(version),
+            },
+            ActiveTransaction::none(),
+            &store,
+        )
+        .await
+        .err()
+        .unwrap();
+
+        assert_eq!(ErrorKind::Stale, err.kind());
+        assert_eq!(
+            "First",
+            store.get_product(id).unwrap().unwrap().to_data().title
+        );
+    }
+}
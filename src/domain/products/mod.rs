@@ -3,14 +3,17 @@
 pub mod commands;
 pub mod model;
 pub mod queries;
+pub mod records;
 pub(in crate::domain) mod resolver;
 
-pub(self) use self::model::store::{
+use self::model::store::{
     ProductStore,
     ProductStoreFilter,
+    ProductStoreSearch,
 };
 pub use self::{
     commands::*,
     model::*,
     queries::*,
+    records::*,
 };
@@ -1,8 +1,14 @@
 /*! Contains the `Product` entity. */
 
-use std::convert::{
-    TryFrom,
-    TryInto,
+use std::{
+    collections::BTreeMap,
+    convert::{
+        TryFrom,
+        TryInto,
+    },
+    fmt,
+    str::FromStr,
+    time::SystemTime,
 };
 
 pub mod store;
@@ -12,13 +18,20 @@ pub mod test_data;
 
 use crate::domain::{
     Error,
+    categories::{
+        Category,
+        CategoryId,
+    },
     error,
     infra::*,
+    taxes::TaxCategory,
 };
 
 pub type ProductId = Id<ProductData>;
 pub type NextProductId = NextId<ProductData>;
 pub type ProductVersion = Version<ProductData>;
+pub type VariantId = Id<VariantData>;
+pub type NextVariantId = NextId<VariantData>;
 
 /**
 A product title.
@@ -32,7 +45,7 @@ impl TryFrom<String> for Title {
 
     fn try_from(title: String) -> Result<Self, Self::Error> {
         if title.is_empty() {
-            return Err(error::msg("title must not be empty"));
+            return Err(error::bad_input("title must not be empty"));
         }
 
         Ok(Title(title))
@@ -62,6 +75,161 @@ impl TryFrom<Currency> for Price {
     }
 }
 
+/**
+A product tag.
+
+Tags are free-form, but must not be empty, longer than 32 characters, or contain control characters.
+Surrounding whitespace is trimmed and tags are compared without regard to case.
+*/
+pub struct Tag(String);
+
+impl Tag {
+    /** The maximum number of tags a single product can have. */
+    pub const MAX_PER_PRODUCT: usize = 20;
+
+    pub fn into_inner(self) -> String {
+        self.0
+    }
+}
+
+impl TryFrom<String> for Tag {
+    type Error = Error;
+
+    fn try_from(tag: String) -> Result<Self, Self::Error> {
+        let tag = tag.trim();
+
+        if tag.is_empty() || tag.chars().count() > 32 || tag.chars().any(char::is_control) {
+            return Err(error::bad_input(format_args!(
+                "`{}` is not a valid tag",
+                tag.escape_debug()
+            )));
+        }
+
+        Ok(Tag(tag.to_lowercase()))
+    }
+}
+
+impl<'a> TryFrom<&'a str> for Tag {
+    type Error = Error;
+
+    fn try_from(tag: &'a str) -> Result<Self, Self::Error> {
+        Self::try_from(tag.to_owned())
+    }
+}
+
+/**
+A stock keeping unit that identifies a product variant.
+
+SKUs must not be empty or longer than 64 characters, and may only contain ASCII letters, digits,
+`-`, `_` and `.`. They're compared without regard to case.
+*/
+pub struct Sku(String);
+
+impl Sku {
+    pub fn into_inner(self) -> String {
+        self.0
+    }
+}
+
+impl TryFrom<String> for Sku {
+    type Error = Error;
+
+    fn try_from(sku: String) -> Result<Self, Self::Error> {
+        if sku.is_empty()
+            || sku.len() > 64
+            || !sku
+                .bytes()
+                .all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_' || b == b'.')
+        {
+            return Err(error::bad_input(format_args!(
+                "`{}` is not a valid SKU",
+                sku.escape_debug()
+            )));
+        }
+
+        Ok(Sku(sku.to_ascii_uppercase()))
+    }
+}
+
+impl<'a> TryFrom<&'a str> for Sku {
+    type Error = Error;
+
+    fn try_from(sku: &'a str) -> Result<Self, Self::Error> {
+        Self::try_from(sku.to_owned())
+    }
+}
+
+/**
+Data for a variant of a product.
+
+Options describe what makes the variant different from others, like its size or colour.
+If the variant doesn't have its own price then it uses the product's.
+*/
+#[derive(Clone, Serialize, Deserialize)]
+pub struct VariantData {
+    pub id: VariantId,
+    pub sku: String,
+    pub options: BTreeMap<String, String>,
+    #[serde(default)]
+    pub price: Option<Currency>,
+    _private: (),
+}
+
+/**
+Where a product is in its lifecycle.
+
+Products start as drafts while they're being prepared, which are hidden from public queries.
+Only active products can be added to orders.
+Discontinued products can no longer be ordered, but stay visible so existing orders can resolve them.
+*/
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProductStatus {
+    Draft,
+    #[default]
+    Active,
+    Discontinued,
+}
+
+impl fmt::Display for ProductStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ProductStatus::Draft => "draft",
+            ProductStatus::Active => "active",
+            ProductStatus::Discontinued => "discontinued",
+        })
+    }
+}
+
+impl FromStr for ProductStatus {
+    type Err = Error;
+
+    fn from_str(status: &str) -> Result<Self, Self::Err> {
+        match status {
+            "draft" => Ok(ProductStatus::Draft),
+            "active" => Ok(ProductStatus::Active),
+            "discontinued" => Ok(ProductStatus::Discontinued),
+            _ => Err(error::bad_input(format_args!(
+                "`{}` is not a product status",
+                status
+            ))),
+        }
+    }
+}
+
+/**
+A price a product had, and when it had it.
+
+The product's current price doesn't have an end yet.
+*/
+#[derive(Clone, Serialize, Deserialize)]
+pub struct PriceHistoryData {
+    pub price: Currency,
+    pub effective_from: SystemTime,
+    #[serde(default)]
+    pub effective_to: Option<SystemTime>,
+}
+
 /** Data for a product. */
 #[derive(Clone, Serialize, Deserialize)]
 pub struct ProductData {
@@ -69,10 +237,25 @@ pub struct ProductData {
     pub version: ProductVersion,
     pub title: String,
     pub price: Currency,
+    pub tax_category: String,
+    pub created_at: SystemTime,
+    #[serde(default)]
+    pub status: ProductStatus,
+    #[serde(default)]
+    pub archived: bool,
+    #[serde(default)]
+    pub category_id: Option<CategoryId>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub variants: Vec<VariantData>,
+    #[serde(default)]
+    pub price_history: Vec<PriceHistoryData>,
     _private: (),
 }
 
 /** A product with some simple metadata. */
+#[derive(Clone)]
 pub struct Product {
     data: ProductData,
 }
@@ -94,23 +277,334 @@ impl Product {
         id: impl IdProvider<ProductData>,
         title: impl TryInto<Title, Error = Error>,
         price: impl TryInto<Price, Error = Error>,
+        now: SystemTime,
     ) -> Result<Self, Error> {
         let id = id.get()?;
+        let price = price.try_into()?.0;
 
         Ok(Product::from_data(ProductData {
             id,
             version: ProductVersion::default(),
             title: title.try_into()?.0,
-            price: price.try_into()?.0,
+            price,
+            tax_category: TaxCategory::STANDARD.to_owned(),
+            created_at: now,
+            status: ProductStatus::Active,
+            archived: false,
+            category_id: None,
+            tags: Vec::new(),
+            variants: Vec::new(),
+            price_history: vec![PriceHistoryData {
+                price,
+                effective_from: now,
+                effective_to: None,
+            }],
             _private: (),
         }))
     }
 
+    /**
+    Create a draft product.
+
+    The product needs to be published before it can be ordered.
+    */
+    pub fn new_draft(
+        id: impl IdProvider<ProductData>,
+        title: impl TryInto<Title, Error = Error>,
+        price: impl TryInto<Price, Error = Error>,
+        now: SystemTime,
+    ) -> Result<Self, Error> {
+        let mut product = Product::new(id, title, price, now)?;
+        product.data.status = ProductStatus::Draft;
+
+        Ok(product)
+    }
+
+    /**
+    Make the product available to order.
+
+    Draft products can be published, and discontinued products can be published again.
+    */
+    pub fn publish(&mut self) -> Result<(), Error> {
+        match self.data.status {
+            ProductStatus::Draft | ProductStatus::Discontinued => {
+                self.data.status = ProductStatus::Active;
+
+                Ok(())
+            }
+            ProductStatus::Active => Err(error::conflict("product is already active")),
+        }
+    }
+
+    /**
+    Stop the product from being ordered.
+
+    Only active products can be discontinued.
+    */
+    pub fn discontinue(&mut self) -> Result<(), Error> {
+        match self.data.status {
+            ProductStatus::Active => {
+                self.data.status = ProductStatus::Discontinued;
+
+                Ok(())
+            }
+            status => Err(error::conflict(format_args!(
+                "a {} product can't be discontinued",
+                status
+            ))),
+        }
+    }
+
     pub fn set_title(&mut self, title: impl TryInto<Title, Error = Error>) -> Result<(), Error> {
         self.data.title = title.try_into()?.0;
 
         Ok(())
     }
+
+    /**
+    Change the product's price.
+
+    The change is recorded in the product's price history.
+    Variants with their own price must keep using the same currency as the product.
+    Orders that already contain the product keep the price it had when it was added.
+    */
+    pub fn set_price(
+        &mut self,
+        price: impl TryInto<Price, Error = Error>,
+        now: SystemTime,
+    ) -> Result<(), Error> {
+        let price = price.try_into()?.0;
+
+        if price == self.data.price {
+            return Ok(());
+        }
+
+        if let Some((variant, variant_price)) = self
+            .data
+            .variants
+            .iter()
+            .filter_map(|variant| Some((variant, variant.price?)))
+            .find(|(_, variant_price)| variant_price.code() != price.code())
+        {
+            return Err(error::bad_input(format_args!(
+                "variant `{}` is priced in {}, so the product can't be priced in {}",
+                variant.sku,
+                variant_price.code(),
+                price.code()
+            )));
+        }
+
+        let mut history = self.price_history();
+        if let Some(current) = history.last_mut() {
+            current.effective_to = Some(now);
+        }
+        history.push(PriceHistoryData {
+            price,
+            effective_from: now,
+            effective_to: None,
+        });
+
+        self.data.price = price;
+        self.data.price_history = history;
+
+        Ok(())
+    }
+
+    /**
+    Get the prices the product has had, from oldest to newest.
+
+    Products created before price history was recorded start with their current price.
+    */
+    pub fn price_history(&self) -> Vec<PriceHistoryData> {
+        if self.data.price_history.is_empty() {
+            vec![PriceHistoryData {
+                price: self.data.price,
+                effective_from: self.data.created_at,
+                effective_to: None,
+            }]
+        } else {
+            self.data.price_history.clone()
+        }
+    }
+
+    pub fn set_tax_category(
+        &mut self,
+        tax_category: impl TryInto<TaxCategory, Error = Error>,
+    ) -> Result<(), Error> {
+        self.data.tax_category = tax_category.try_into()?.into_inner();
+
+        Ok(())
+    }
+
+    /** Put the product in a category, or take it out of its current one. */
+    pub fn set_category(&mut self, category: Option<&Category>) {
+        self.data.category_id = category.map(|category| category.to_data().id);
+    }
+
+    /**
+    Replace the product's tags.
+
+    Duplicate tags are removed, and the tags are kept in sorted order.
+    */
+    pub fn set_tags<T>(&mut self, tags: impl IntoIterator<Item = T>) -> Result<(), Error>
+    where
+        T: TryInto<Tag, Error = Error>,
+    {
+        let mut tags = tags
+            .into_iter()
+            .map(|tag| Ok(tag.try_into()?.into_inner()))
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        tags.sort();
+        tags.dedup();
+
+        if tags.len() > Tag::MAX_PER_PRODUCT {
+            return Err(error::bad_input(format_args!(
+                "a product can't have more than {} tags",
+                Tag::MAX_PER_PRODUCT
+            )));
+        }
+
+        self.data.tags = tags;
+
+        Ok(())
+    }
+
+    pub fn variant(&self, id: VariantId) -> Option<&VariantData> {
+        self.data.variants.iter().find(|variant| variant.id == id)
+    }
+
+    /**
+    Add a variant to the product.
+
+    Each variant must have a different SKU and set of options.
+    A variant's price must use the same currency as the product's.
+    */
+    pub fn add_variant(
+        &mut self,
+        id: impl IdProvider<VariantData>,
+        sku: impl TryInto<Sku, Error = Error>,
+        options: BTreeMap<String, String>,
+        price: Option<Currency>,
+    ) -> Result<VariantId, Error> {
+        let sku = sku.try_into()?.into_inner();
+
+        if options
+            .iter()
+            .any(|(name, value)| name.trim().is_empty() || value.trim().is_empty())
+        {
+            return Err(error::bad_input(
+                "variant option names and values must not be empty",
+            ));
+        }
+
+        if let Some(price) = price
+            && price.code() != self.data.price.code()
+        {
+            return Err(error::bad_input(format_args!(
+                "variant is priced in {} but the product uses {}",
+                price.code(),
+                self.data.price.code()
+            )));
+        }
+
+        if self.data.variants.iter().any(|variant| variant.sku == sku) {
+            return Err(error::conflict(format_args!(
+                "the product already has a variant with SKU `{}`",
+                sku
+            )));
+        }
+
+        if self
+            .data
+            .variants
+            .iter()
+            .any(|variant| variant.options == options)
+        {
+            return Err(error::conflict(
+                "the product already has a variant with the same options",
+            ));
+        }
+
+        let id = id.get()?;
+        self.data.variants.push(VariantData {
+            id,
+            sku,
+            options,
+            price,
+            _private: (),
+        });
+
+        Ok(id)
+    }
+
+    /**
+    Remove a variant from the product.
+
+    Orders that already contain the variant keep the price it had when it was added.
+    */
+    pub fn remove_variant(&mut self, id: VariantId) -> Result<(), Error> {
+        let index = self
+            .data
+            .variants
+            .iter()
+            .position(|variant| variant.id == id)
+            .ok_or_else(|| error::not_found("variant not found"))?;
+
+        self.data.variants.remove(index);
+
+        Ok(())
+    }
+
+    /**
+    Get the price to order the product at.
+
+    Products with variants can only be ordered as one of their variants.
+    */
+    pub fn price(&self, variant_id: Option<VariantId>) -> Result<Currency, Error> {
+        match variant_id {
+            Some(variant_id) => {
+                let variant = self
+                    .variant(variant_id)
+                    .ok_or_else(|| error::bad_input("variant not found"))?;
+
+                Ok(variant.price.unwrap_or(self.data.price))
+            }
+            None if self.data.variants.is_empty() => Ok(self.data.price),
+            None => Err(error::bad_input(
+                "a variant must be chosen for products with variants",
+            )),
+        }
+    }
+
+    /**
+    Archive the product.
+
+    Archived products are hidden from queries by default and can't be added to orders.
+    Orders that already contain the product can still resolve it.
+    */
+    pub fn archive(&mut self) {
+        self.data.archived = true;
+    }
+
+    /** Restore an archived product. */
+    pub fn restore(&mut self) {
+        self.data.archived = false;
+    }
+
+    /**
+    Check the product still has the version a command expects.
+
+    If no version is expected then any version is accepted.
+    */
+    pub fn check_version(&self, expected: Option<ProductVersion>) -> Result<(), Error> {
+        match expected {
+            Some(expected) if expected != self.data.version => {
+                Err(error::stale("product has been changed since it was read"))
+            }
+            _ => Ok(()),
+        }
+    }
 }
 
 impl Entity for Product {
@@ -120,22 +614,223 @@ impl Entity for Product {
     type Error = Error;
 }
 
-impl Resolver {
-    pub fn product_id(&self) -> impl IdProvider<ProductData> {
-        NextId::<ProductData>::new()
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn title_must_be_non_empty() {
-        assert!(Product::new(ProductId::new(), "", Currency::usd(100)).is_err());
+        assert!(Product::new(ProductId::new(), "", Currency::usd(100), SystemTime::now()).is_err());
 
-        let mut product = Product::new(ProductId::new(), "A title", Currency::usd(100)).unwrap();
+        let mut product = Product::new(
+            ProductId::new(),
+            "A title",
+            Currency::usd(100),
+            SystemTime::now(),
+        )
+        .unwrap();
 
         assert!(product.set_title("").is_err());
     }
+
+    #[test]
+    fn tax_category_defaults_to_standard() {
+        let mut product = Product::new(
+            ProductId::new(),
+            "A title",
+            Currency::usd(100),
+            SystemTime::now(),
+        )
+        .unwrap();
+
+        assert_eq!(TaxCategory::STANDARD, product.data.tax_category);
+
+        product.set_tax_category("Food").unwrap();
+        assert_eq!("food", product.data.tax_category);
+
+        assert!(product.set_tax_category("").is_err());
+    }
+
+    #[test]
+    fn tags_are_normalized() {
+        let mut product = Product::new(
+            ProductId::new(),
+            "A title",
+            Currency::usd(100),
+            SystemTime::now(),
+        )
+        .unwrap();
+
+        product
+            .set_tags(["Summer", " sale ", "summer", "Cotton blend"])
+            .unwrap();
+        assert_eq!(vec!["cotton blend", "sale", "summer"], product.data.tags);
+
+        assert!(product.set_tags([" "]).is_err());
+        assert!(product.set_tags(["a\ttag"]).is_err());
+        assert!(
+            product
+                .set_tags((0..=Tag::MAX_PER_PRODUCT).map(|i| i.to_string()))
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn add_variants() {
+        let mut product = Product::new(
+            ProductId::new(),
+            "A shirt",
+            Currency::usd(100),
+            SystemTime::now(),
+        )
+        .unwrap();
+
+        let options = |size: &str| BTreeMap::from([("size".to_owned(), size.to_owned())]);
+
+        let small = product
+            .add_variant(VariantId::new(), "shirt-s", options("S"), None)
+            .unwrap();
+        let large = product
+            .add_variant(
+                VariantId::new(),
+                "shirt-l",
+                options("L"),
+                Some(Currency::usd(120)),
+            )
+            .unwrap();
+
+        assert_eq!("SHIRT-S", product.variant(small).unwrap().sku);
+        assert_eq!(Currency::usd(100), product.price(Some(small)).unwrap());
+        assert_eq!(Currency::usd(120), product.price(Some(large)).unwrap());
+        assert!(product.price(None).is_err());
+
+        // SKUs and options must be unique, and prices must share the product's currency
+        assert!(
+            product
+                .add_variant(VariantId::new(), "SHIRT-S", options("M"), None)
+                .is_err()
+        );
+        assert!(
+            product
+                .add_variant(VariantId::new(), "shirt-m", options("L"), None)
+                .is_err()
+        );
+        assert!(
+            product
+                .add_variant(
+                    VariantId::new(),
+                    "shirt-m",
+                    options("M"),
+                    Some(Currency::eur(100))
+                )
+                .is_err()
+        );
+        assert!(
+            product
+                .add_variant(VariantId::new(), "shirt m", options("M"), None)
+                .is_err()
+        );
+
+        product.remove_variant(small).unwrap();
+        assert!(product.price(Some(small)).is_err());
+    }
+
+    #[test]
+    fn set_price_records_history() {
+        let created_at = SystemTime::UNIX_EPOCH;
+        let changed_at = created_at + std::time::Duration::from_secs(60);
+
+        let mut product =
+            Product::new(ProductId::new(), "A title", Currency::usd(100), created_at).unwrap();
+
+        product.set_price(Currency::usd(150), changed_at).unwrap();
+
+        // Setting the same price doesn't change the history
+        product.set_price(Currency::usd(150), changed_at).unwrap();
+
+        let history = product.price_history();
+
+        assert_eq!(Currency::usd(150), product.data.price);
+        assert_eq!(2, history.len());
+        assert_eq!(Currency::usd(100), history[0].price);
+        assert_eq!(Some(changed_at), history[0].effective_to);
+        assert_eq!(Currency::usd(150), history[1].price);
+        assert_eq!(changed_at, history[1].effective_from);
+        assert_eq!(None, history[1].effective_to);
+    }
+
+    #[test]
+    fn set_price_keeps_variant_currency() {
+        let mut product = Product::new(
+            ProductId::new(),
+            "A shirt",
+            Currency::usd(100),
+            SystemTime::now(),
+        )
+        .unwrap();
+
+        let variant_id = product
+            .add_variant(
+                VariantId::new(),
+                "shirt-l",
+                BTreeMap::from([("size".to_owned(), "L".to_owned())]),
+                Some(Currency::usd(120)),
+            )
+            .unwrap();
+
+        assert!(
+            product
+                .set_price(Currency::eur(100), SystemTime::now())
+                .is_err()
+        );
+
+        product
+            .set_price(Currency::usd(110), SystemTime::now())
+            .unwrap();
+        assert_eq!(Currency::usd(120), product.price(Some(variant_id)).unwrap());
+    }
+
+    #[test]
+    fn lifecycle_transitions() {
+        let mut product = Product::new_draft(
+            ProductId::new(),
+            "A title",
+            Currency::usd(100),
+            SystemTime::now(),
+        )
+        .unwrap();
+
+        assert_eq!(ProductStatus::Draft, product.data.status);
+        assert!(product.discontinue().is_err());
+
+        product.publish().unwrap();
+        assert_eq!(ProductStatus::Active, product.data.status);
+        assert!(product.publish().is_err());
+
+        product.discontinue().unwrap();
+        assert_eq!(ProductStatus::Discontinued, product.data.status);
+        assert!(product.discontinue().is_err());
+
+        product.publish().unwrap();
+        assert_eq!(ProductStatus::Active, product.data.status);
+    }
+
+    #[test]
+    fn archive_and_restore() {
+        let mut product = Product::new(
+            ProductId::new(),
+            "A title",
+            Currency::usd(100),
+            SystemTime::now(),
+        )
+        .unwrap();
+
+        assert!(!product.data.archived);
+
+        product.archive();
+        assert!(product.data.archived);
+
+        product.restore();
+        assert!(!product.data.archived);
+    }
 }
@@ -1,10 +1,20 @@
 /*! Persistent storage for products. */
 
-use std::vec::IntoIter;
+use std::{
+    sync::{
+        Arc,
+        RwLock,
+    },
+    vec::IntoIter,
+};
 
 use crate::{
     domain::{
         Error,
+        infra::{
+            SearchHit,
+            SearchIndex,
+        },
         products::*,
     },
     store::*,
@@ -30,12 +40,37 @@ pub(in crate::domain) trait ProductStoreFilter {
     fn filter<F>(&self, predicate: F) -> Result<Iter, Error>
     where
         F: Fn(&ProductData) -> bool;
+
+    fn get_products(&self, ids: &[ProductId]) -> Result<Vec<Product>, Error>;
 }
 
 pub(in crate::domain) type Iter = IntoIter<ProductData>;
 
-/** A test in-memory product store. */
-pub(in crate::domain) struct InMemoryStore(TransactionValueStore<ProductData>);
+/**
+An additional store for searching the text of products.
+
+Only committed changes to products are searchable.
+*/
+#[auto_impl(&, Arc)]
+pub(in crate::domain) trait ProductStoreSearch {
+    fn search(&self, query: &str, prefix: bool) -> Result<Vec<SearchHit<ProductId>>, Error>;
+}
+
+/** How much the title of a product counts towards its relevance in searches. */
+const TITLE_WEIGHT: f64 = 1.0;
+
+/** How much each tag on a product counts towards its relevance in searches. */
+const TAG_WEIGHT: f64 = 0.5;
+
+/**
+A test in-memory product store.
+
+Products are indexed for searching by their title and tags once the transaction that changed them commits.
+*/
+pub(in crate::domain) struct InMemoryStore(
+    TransactionValueStore<ProductData>,
+    Arc<RwLock<SearchIndex<ProductId, ProductVersion>>>,
+);
 
 impl ProductStore for InMemoryStore {
     fn get_product(&self, id: ProductId) -> Result<Option<Product>, Error> {
@@ -51,14 +86,23 @@ impl ProductStore for InMemoryStore {
     fn set_product(&self, transaction: &Transaction, product: Product) -> Result<(), Error> {
         let mut data = product.into_data();
         let id = data.id;
+        let title = data.title.clone();
+        let tags = data.tags.clone();
+
+        let old_version = data.version;
+        let new_version = data.version.next()?;
 
-        self.0.set(
-            transaction,
-            id,
-            Some(data.version),
-            data.version.next(),
-            data,
-        )?;
+        self.0
+            .set(transaction, id, Some(old_version), new_version, data)?;
+
+        let search = self.1.clone();
+        self.0.transactions().on_commit(transaction.id(), move || {
+            let fields = Some((title.as_str(), TITLE_WEIGHT))
+                .into_iter()
+                .chain(tags.iter().map(|tag| (tag.as_str(), TAG_WEIGHT)));
+
+            search.write().unwrap().insert(id, new_version, fields);
+        });
 
         Ok(())
     }
@@ -74,19 +118,44 @@ impl ProductStoreFilter for InMemoryStore {
 
         Ok(products.into_iter())
     }
+
+    fn get_products(&self, ids: &[ProductId]) -> Result<Vec<Product>, Error> {
+        let products = self
+            .0
+            .get_all(|p| ids.contains(&p.id))
+            .map(|(_, data)| Product::from_data(data))
+            .collect();
+
+        Ok(products)
+    }
 }
 
-pub(in crate::domain::products) fn in_memory_store(
-    transaction_store: TransactionStore,
-) -> InMemoryStore {
-    InMemoryStore(TransactionValueStore::new(transaction_store))
+impl ProductStoreSearch for InMemoryStore {
+    fn search(&self, query: &str, prefix: bool) -> Result<Vec<SearchHit<ProductId>>, Error> {
+        Ok(self.1.read().unwrap().search(query, prefix))
+    }
+}
+
+/**
+Create an in-memory product store.
+
+The store will participate in transactions tracked by the given transaction store.
+*/
+pub(in crate::domain) fn in_memory_store(transaction_store: TransactionStore) -> InMemoryStore {
+    InMemoryStore(
+        TransactionValueStore::new(transaction_store),
+        Default::default(),
+    )
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    use crate::domain::products::model::test_data;
+    use crate::domain::{
+        ErrorKind,
+        products::model::test_data,
+    };
 
     #[test]
     fn test_in_memory_store() {
@@ -118,13 +187,48 @@ mod tests {
             .unwrap();
 
         // Attempting to create a second time fails optimistic concurrency check
-        assert!(
+        let err = store
+            .set_product(
+                &Transaction::none(),
+                test_data::ProductBuilder::new().id(id).build(),
+            )
+            .err()
+            .unwrap();
+
+        assert_eq!(ErrorKind::Conflict, err.kind());
+    }
+
+    #[test]
+    fn search_committed_products() {
+        let transactions = TransactionStore::new();
+        let store = in_memory_store(transactions.clone());
+
+        let id = ProductId::new();
+
+        let transaction = transactions.begin();
+        store
+            .set_product(
+                &transaction,
+                test_data::ProductBuilder::new()
+                    .id(id)
+                    .title("Red shirt")
+                    .build(),
+            )
+            .unwrap();
+
+        // The product isn't searchable until its transaction commits
+        assert!(store.search("shirt", false).unwrap().is_empty());
+
+        transactions.commit(transaction);
+
+        assert_eq!(
+            vec![id],
             store
-                .set_product(
-                    &Transaction::none(),
-                    test_data::ProductBuilder::new().id(id).build()
-                )
-                .is_err()
+                .search("shirt", false)
+                .unwrap()
+                .into_iter()
+                .map(|hit| hit.key)
+                .collect::<Vec<_>>()
         );
     }
 }
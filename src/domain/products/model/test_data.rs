@@ -1,4 +1,7 @@
+use std::time::SystemTime;
+
 use crate::domain::{
+    categories::CategoryId,
     infra::*,
     products::*,
 };
@@ -12,7 +15,13 @@ pub fn default_price() -> Currency {
 }
 
 pub fn default_product() -> Product {
-    Product::new(NextProductId::new(), default_title(), default_price()).unwrap()
+    Product::new(
+        NextProductId::new(),
+        default_title(),
+        default_price(),
+        SystemTime::now(),
+    )
+    .unwrap()
 }
 
 pub struct ProductBuilder {
@@ -37,6 +46,59 @@ impl ProductBuilder {
         self
     }
 
+    pub fn title(mut self, title: &str) -> Self {
+        self.product.set_title(title).unwrap();
+        self
+    }
+
+    pub fn created_at(mut self, created_at: SystemTime) -> Self {
+        self.product.data.created_at = created_at;
+        self
+    }
+
+    pub fn price(mut self, price: Currency) -> Self {
+        self.product.data.price = price;
+        self.product.data.price_history.clear();
+        self
+    }
+
+    pub fn tax_category(mut self, tax_category: &str) -> Self {
+        self.product.set_tax_category(tax_category).unwrap();
+        self
+    }
+
+    pub fn category(mut self, category_id: CategoryId) -> Self {
+        self.product.data.category_id = Some(category_id);
+        self
+    }
+
+    pub fn tags(mut self, tags: &[&str]) -> Self {
+        self.product.set_tags(tags.iter().copied()).unwrap();
+        self
+    }
+
+    pub fn variant(mut self, id: VariantId, sku: &str) -> Self {
+        self.product
+            .add_variant(
+                id,
+                sku,
+                [("variant".to_owned(), sku.to_owned())].into(),
+                None,
+            )
+            .unwrap();
+        self
+    }
+
+    pub fn status(mut self, status: ProductStatus) -> Self {
+        self.product.data.status = status;
+        self
+    }
+
+    pub fn archived(mut self) -> Self {
+        self.product.archive();
+        self
+    }
+
     pub fn build(self) -> Product {
         self.product
     }
@@ -0,0 +1,99 @@
+/*! Contains the `ExportProductsQuery` type. */
+
+use crate::domain::{
+    Error,
+    infra::*,
+    products::*,
+};
+
+/**
+Input for an `ExportProductsQuery`.
+
+The export includes the full catalog, including drafts and archived products.
+*/
+#[derive(Serialize, Deserialize)]
+pub struct ExportProducts {
+    pub format: ProductFileFormat,
+}
+
+impl QueryArgs for ExportProducts {
+    type Output = Result<String, Error>;
+}
+
+/**
+Default implementation for an `ExportProductsQuery`.
+
+Products are exported in the order they were created, so repeated exports are easy to compare.
+The file can be imported again to update the same products.
+*/
+async fn execute(query: ExportProducts, store: impl ProductStoreFilter) -> Result<String, Error> {
+    let mut products: Vec<_> = store.filter(|_| true)?.collect();
+    products.sort_by_key(|product| (product.created_at, product.id));
+
+    ProductRecord::write(query.format, products.iter().map(ProductRecord::from_data))
+}
+
+impl Resolver {
+    /** Export every product to a file. */
+    pub fn export_products_query(&self) -> impl Query<ExportProducts> {
+        self.query(|resolver, query: ExportProducts| async move {
+            let store = resolver.product_store_filter();
+
+            execute(query, store).await
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{
+        Duration,
+        SystemTime,
+    };
+
+    use super::*;
+
+    use crate::domain::products::model::{
+        store::{
+            ProductStore,
+            in_memory_store,
+        },
+        test_data::ProductBuilder,
+    };
+
+    #[tokio::test]
+    async fn export_in_creation_order() {
+        let store = in_memory_store(Default::default());
+
+        let created_at = SystemTime::UNIX_EPOCH;
+        for (title, offset) in [("Second", 1), ("First", 0), ("Third", 2)] {
+            store
+                .set_product(
+                    ActiveTransaction::none().get(),
+                    ProductBuilder::new()
+                        .title(title)
+                        .created_at(created_at + Duration::from_secs(offset))
+                        .archived()
+                        .build(),
+                )
+                .unwrap();
+        }
+
+        let data = execute(
+            ExportProducts {
+                format: ProductFileFormat::JsonLines,
+            },
+            &store,
+        )
+        .await
+        .unwrap();
+
+        let titles: Vec<_> = ProductRecord::read(ProductFileFormat::JsonLines, &data)
+            .unwrap()
+            .into_iter()
+            .map(|(_, record)| record.unwrap().title)
+            .collect();
+
+        assert_eq!(vec!["First", "Second", "Third"], titles);
+    }
+}
@@ -6,30 +6,192 @@ use crate::domain::{
     products::*,
 };
 
-/** Input for a `GetProductQuery`. */
+/**
+Input for a `GetProductQuery`.
+
+Archived products aren't returned unless `include_archived` is set, and drafts aren't returned unless `include_drafts` is.
+*/
 #[derive(Serialize, Deserialize)]
 pub struct GetProduct {
     pub id: ProductId,
+    #[serde(default)]
+    pub include_archived: bool,
+    #[serde(default)]
+    pub include_drafts: bool,
 }
 
 impl QueryArgs for GetProduct {
     type Output = Result<Option<Product>, Error>;
 }
 
-/** Default implementation for a `GetProductQuery`. */
-async fn execute(query: GetProduct, store: impl ProductStore) -> Result<Option<Product>, Error> {
-    let product = store.get_product(query.id)?;
+/**
+Default implementation for a `GetProductQuery`.
+
+Products fetched concurrently within the same transaction are loaded in a single batch.
+*/
+async fn execute(
+    query: GetProduct,
+    loader: BatchLoader<ProductId, Product>,
+    store: impl ProductStoreFilter,
+) -> Result<Option<Product>, Error> {
+    let product = loader
+        .load(query.id, |ids| {
+            let products = store.get_products(ids)?;
+
+            Ok(products.into_iter().map(|p| (p.to_data().id, p)))
+        })
+        .await?;
+
+    Ok(product.filter(|p| {
+        let p = p.to_data();
 
-    Ok(product)
+        (query.include_archived || !p.archived)
+            && (query.include_drafts || p.status != ProductStatus::Draft)
+    }))
 }
 
 impl Resolver {
     /** Get a product. */
     pub fn get_product_query(&self) -> impl Query<GetProduct> {
         self.query(|resolver, query: GetProduct| async move {
-            let store = resolver.product_store();
+            let loader = resolver.batch_loader();
+            let store = resolver.product_store_filter();
 
-            execute(query, store).await
+            execute(query, loader, store).await
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::domain::products::model::{
+        store::{
+            ProductStore,
+            in_memory_store,
+        },
+        test_data::ProductBuilder,
+    };
+
+    #[tokio::test]
+    async fn exclude_archived_by_default() {
+        let store = in_memory_store(Default::default());
+
+        let id = ProductId::new();
+        store
+            .set_product(
+                ActiveTransaction::none().get(),
+                ProductBuilder::new().id(id).archived().build(),
+            )
+            .unwrap();
+
+        let product = execute(
+            GetProduct {
+                id,
+                include_archived: false,
+                include_drafts: false,
+            },
+            BatchLoader::default(),
+            &store,
+        )
+        .await
+        .unwrap();
+
+        assert!(product.is_none());
+
+        let product = execute(
+            GetProduct {
+                id,
+                include_archived: true,
+                include_drafts: true,
+            },
+            BatchLoader::default(),
+            &store,
+        )
+        .await
+        .unwrap();
+
+        assert!(product.is_some());
+    }
+
+    #[tokio::test]
+    async fn exclude_drafts_by_default() {
+        let store = in_memory_store(Default::default());
+
+        let id = ProductId::new();
+        store
+            .set_product(
+                ActiveTransaction::none().get(),
+                ProductBuilder::new()
+                    .id(id)
+                    .status(ProductStatus::Draft)
+                    .build(),
+            )
+            .unwrap();
+
+        let product = execute(
+            GetProduct {
+                id,
+                include_archived: false,
+                include_drafts: false,
+            },
+            BatchLoader::default(),
+            &store,
+        )
+        .await
+        .unwrap();
+
+        assert!(product.is_none());
+
+        let product = execute(
+            GetProduct {
+                id,
+                include_archived: false,
+                include_drafts: true,
+            },
+            BatchLoader::default(),
+            &store,
+        )
+        .await
+        .unwrap();
+
+        assert!(product.is_some());
+    }
+
+    #[tokio::test]
+    async fn see_changes_made_after_loading() {
+        let store = in_memory_store(Default::default());
+        let loader = BatchLoader::default();
+
+        let id = ProductId::new();
+        store
+            .set_product(
+                ActiveTransaction::none().get(),
+                ProductBuilder::new().id(id).title("First").build(),
+            )
+            .unwrap();
+
+        let get = || GetProduct {
+            id,
+            include_archived: false,
+            include_drafts: false,
+        };
+
+        let mut product = execute(get(), loader.clone(), &store)
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!("First", product.to_data().title);
+
+        product.set_title("Second").unwrap();
+        store
+            .set_product(ActiveTransaction::none().get(), product)
+            .unwrap();
+
+        let product = execute(get(), loader, &store).await.unwrap().unwrap();
+
+        assert_eq!("Second", product.to_data().title);
+    }
+}
@@ -0,0 +1,48 @@
+/*! Contains the `GetProductPriceHistoryQuery` type. */
+
+use crate::domain::{
+    Error,
+    infra::*,
+    products::*,
+};
+
+/**
+Input for a `GetProductPriceHistoryQuery`.
+
+The history of archived products is still returned.
+*/
+#[derive(Serialize, Deserialize)]
+pub struct GetProductPriceHistory {
+    pub id: ProductId,
+}
+
+impl QueryArgs for GetProductPriceHistory {
+    type Output = Result<Option<Vec<PriceHistoryData>>, Error>;
+}
+
+/** Default implementation for a `GetProductPriceHistoryQuery`. */
+async fn execute(
+    query: GetProductPriceHistory,
+    product_query: impl Query<GetProduct>,
+) -> Result<Option<Vec<PriceHistoryData>>, Error> {
+    let product = product_query
+        .execute(GetProduct {
+            id: query.id,
+            include_archived: true,
+            include_drafts: true,
+        })
+        .await?;
+
+    Ok(product.map(|product| product.price_history()))
+}
+
+impl Resolver {
+    /** Get the prices a product has had, from oldest to newest. */
+    pub fn get_product_price_history_query(&self) -> impl Query<GetProductPriceHistory> {
+        self.query(|resolver, query: GetProductPriceHistory| async move {
+            let product_query = resolver.get_product_query();
+
+            execute(query, product_query).await
+        })
+    }
+}
@@ -2,14 +2,24 @@
 
 use crate::domain::{
     Error,
+    exchange_rates::ConvertCurrency,
     infra::*,
     products::*,
 };
 
-/** Input for a `GetProductSummariesQuery`. */
+/**
+Input for a `GetProductSummariesQuery`.
+
+If a currency is given then each summary will also include its price converted into that currency.
+Archived products aren't summarized unless `include_archived` is set.
+*/
 #[derive(Serialize, Deserialize)]
 pub struct GetProductSummaries {
     pub ids: Vec<ProductId>,
+    #[serde(default)]
+    pub currency: Option<CurrencyCode>,
+    #[serde(default)]
+    pub include_archived: bool,
 }
 
 /** An individual product summary. */
@@ -18,6 +28,8 @@ pub struct ProductSummary {
     pub id: ProductId,
     pub title: String,
     pub price: Currency,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub converted_price: Option<Currency>,
 }
 
 impl QueryArgs for GetProductSummaries {
@@ -28,17 +40,34 @@ impl QueryArgs for GetProductSummaries {
 async fn execute(
     query: GetProductSummaries,
     store: impl ProductStoreFilter,
+    convert_query: impl Query<ConvertCurrency>,
 ) -> Result<Vec<ProductSummary>, Error> {
-    store
-        .filter(|p| query.ids.iter().any(|id| p.id == *id))?
-        .map(|p| {
-            Ok(ProductSummary {
-                id: p.id,
-                title: p.title,
-                price: p.price,
-            })
-        })
-        .collect()
+    let products =
+        store.filter(|p| query.ids.contains(&p.id) && (query.include_archived || !p.archived))?;
+
+    let mut summaries = Vec::new();
+    for p in products {
+        let converted_price = match query.currency {
+            Some(to) => Some(
+                convert_query
+                    .execute(ConvertCurrency {
+                        amount: p.price,
+                        to,
+                    })
+                    .await?,
+            ),
+            None => None,
+        };
+
+        summaries.push(ProductSummary {
+            id: p.id,
+            title: p.title,
+            price: p.price,
+            converted_price,
+        });
+    }
+
+    Ok(summaries)
 }
 
 impl Resolver {
@@ -46,8 +75,76 @@ impl Resolver {
     pub fn get_product_summaries_query(&self) -> impl Query<GetProductSummaries> {
         self.query(|resolver, query: GetProductSummaries| async move {
             let store = resolver.product_store_filter();
+            let convert_query = resolver.convert_currency_query();
 
-            execute(query, store).await
+            execute(query, store, convert_query).await
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::{
+        domain::products::model::{
+            store::{
+                ProductStore,
+                in_memory_store,
+            },
+            test_data,
+        },
+        store::Transaction,
+    };
+
+    async fn convert_to_eur(query: ConvertCurrency) -> Result<Currency, Error> {
+        assert_eq!(CurrencyCode::EUR, query.to);
+
+        Ok(Currency::eur(query.amount.minor_units() * 2))
+    }
+
+    #[tokio::test]
+    async fn get_summaries_with_converted_prices() {
+        let store = in_memory_store(Default::default());
+
+        let id = ProductId::new();
+        store
+            .set_product(
+                &Transaction::none(),
+                test_data::ProductBuilder::new()
+                    .id(id)
+                    .price(Currency::usd(100))
+                    .build(),
+            )
+            .unwrap();
+
+        let unconverted = execute(
+            GetProductSummaries {
+                ids: vec![id],
+                currency: None,
+                include_archived: false,
+            },
+            &store,
+            convert_to_eur,
+        )
+        .await
+        .unwrap();
+
+        assert!(unconverted[0].converted_price.is_none());
+
+        let converted = execute(
+            GetProductSummaries {
+                ids: vec![id],
+                currency: Some(CurrencyCode::EUR),
+                include_archived: false,
+            },
+            &store,
+            convert_to_eur,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(Currency::usd(100), converted[0].price);
+        assert_eq!(Some(Currency::eur(200)), converted[0].converted_price);
+    }
+}
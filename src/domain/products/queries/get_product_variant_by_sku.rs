@@ -0,0 +1,140 @@
+/*! Contains the `GetProductVariantBySkuQuery` type. */
+
+use crate::domain::{
+    Error,
+    infra::*,
+    products::*,
+};
+
+/**
+Input for a `GetProductVariantBySkuQuery`.
+
+SKUs are matched without regard to case.
+Variants of archived products aren't returned unless `include_archived` is set, and variants of drafts
+aren't returned unless `include_drafts` is.
+*/
+#[derive(Serialize, Deserialize)]
+pub struct GetProductVariantBySku {
+    pub sku: String,
+    #[serde(default)]
+    pub include_archived: bool,
+    #[serde(default)]
+    pub include_drafts: bool,
+}
+
+/** A variant along with the product it belongs to. */
+#[derive(Serialize)]
+pub struct ProductVariant {
+    pub product: ProductData,
+    pub variant: VariantData,
+}
+
+impl QueryArgs for GetProductVariantBySku {
+    type Output = Result<Option<ProductVariant>, Error>;
+}
+
+/** Default implementation for a `GetProductVariantBySkuQuery`. */
+async fn execute(
+    query: GetProductVariantBySku,
+    store: impl ProductStoreFilter,
+) -> Result<Option<ProductVariant>, Error> {
+    let Ok(sku) = Sku::try_from(query.sku) else {
+        return Ok(None);
+    };
+    let sku = sku.into_inner();
+
+    let product = store
+        .filter(|p| {
+            (query.include_archived || !p.archived)
+                && (query.include_drafts || p.status != ProductStatus::Draft)
+                && p.variants.iter().any(|variant| variant.sku == sku)
+        })?
+        .next();
+
+    Ok(product.and_then(|product| {
+        let variant = product
+            .variants
+            .iter()
+            .find(|variant| variant.sku == sku)
+            .cloned()?;
+
+        Some(ProductVariant { product, variant })
+    }))
+}
+
+impl Resolver {
+    /** Get a product variant by its SKU. */
+    pub fn get_product_variant_by_sku_query(&self) -> impl Query<GetProductVariantBySku> {
+        self.query(|resolver, query: GetProductVariantBySku| async move {
+            let store = resolver.product_store_filter();
+
+            execute(query, store).await
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::{
+        domain::products::model::{
+            store::{
+                ProductStore,
+                in_memory_store,
+            },
+            test_data::ProductBuilder,
+        },
+        store::Transaction,
+    };
+
+    #[tokio::test]
+    async fn get_variant_by_sku() {
+        let store = in_memory_store(Default::default());
+
+        let product_id = ProductId::new();
+        let variant_id = VariantId::new();
+
+        store
+            .set_product(
+                &Transaction::none(),
+                ProductBuilder::new()
+                    .id(product_id)
+                    .variant(variant_id, "SHIRT-S")
+                    .variant(VariantId::new(), "SHIRT-L")
+                    .build(),
+            )
+            .unwrap();
+
+        let found = execute(
+            GetProductVariantBySku {
+                sku: "shirt-s".to_owned(),
+                include_archived: false,
+                include_drafts: false,
+            },
+            &store,
+        )
+        .await
+        .unwrap()
+        .unwrap();
+
+        assert_eq!(product_id, found.product.id);
+        assert_eq!(variant_id, found.variant.id);
+
+        for sku in ["SHIRT-M", "not a sku"] {
+            assert!(
+                execute(
+                    GetProductVariantBySku {
+                        sku: sku.to_owned(),
+                        include_archived: false,
+                        include_drafts: false,
+                    },
+                    &store,
+                )
+                .await
+                .unwrap()
+                .is_none()
+            );
+        }
+    }
+}
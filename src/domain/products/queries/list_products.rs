@@ -0,0 +1,609 @@
+/*! Contains the `ListProductsQuery` type. */
+
+use std::{
+    convert::TryFrom,
+    fmt,
+    str::FromStr,
+    time::SystemTime,
+};
+
+use serde::{
+    de::{
+        self,
+        Deserialize,
+        Deserializer,
+    },
+    ser::{
+        Serialize,
+        Serializer,
+    },
+};
+
+use crate::domain::{
+    Error,
+    categories::*,
+    error,
+    infra::*,
+    products::*,
+};
+
+/** The default number of products returned in a single page. */
+const DEFAULT_LIMIT: usize = 20;
+
+/** The maximum number of products that can be returned in a single page. */
+const MAX_LIMIT: usize = 100;
+
+/** The field to sort a list of products by. */
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProductSort {
+    Title,
+    Price,
+    #[default]
+    Created,
+}
+
+impl FromStr for ProductSort {
+    type Err = Error;
+
+    fn from_str(sort: &str) -> Result<Self, Self::Err> {
+        match sort {
+            "title" => Ok(ProductSort::Title),
+            "price" => Ok(ProductSort::Price),
+            "created" => Ok(ProductSort::Created),
+            sort => Err(error::bad_input(format_args!(
+                "`{}` is not a valid product sort",
+                sort
+            ))),
+        }
+    }
+}
+
+/**
+The value a product is sorted by.
+
+Titles are sorted without regard to case.
+Prices are sorted by their currency first, then their amount.
+*/
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum SortKey {
+    Title(String),
+    Price(CurrencyCode, u64),
+    Created(SystemTime),
+}
+
+impl SortKey {
+    fn new(sort: ProductSort, product: &ProductData) -> Self {
+        match sort {
+            ProductSort::Title => SortKey::Title(product.title.to_lowercase()),
+            ProductSort::Price => SortKey::Price(product.price.code(), product.price.minor_units()),
+            ProductSort::Created => SortKey::Created(product.created_at),
+        }
+    }
+
+    fn sort(&self) -> ProductSort {
+        match self {
+            SortKey::Title(_) => ProductSort::Title,
+            SortKey::Price(..) => ProductSort::Price,
+            SortKey::Created(_) => ProductSort::Created,
+        }
+    }
+}
+
+/**
+A position in a list of products to continue from.
+
+Cursors are opaque to callers and only valid for the sort they were returned for.
+Products are paged by their sorted value rather than their offset, so products added or removed
+between pages don't cause others to be skipped or repeated.
+*/
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProductCursor {
+    key: SortKey,
+    id: ProductId,
+}
+
+impl fmt::Display for ProductCursor {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let json = serde_json::to_vec(&(&self.key, self.id)).map_err(|_| fmt::Error)?;
+
+        for b in json {
+            write!(f, "{:02x}", b)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> TryFrom<&'a str> for ProductCursor {
+    type Error = Error;
+
+    fn try_from(cursor: &'a str) -> Result<Self, Self::Error> {
+        let invalid = || error::bad_input("cursor is not valid");
+
+        if !cursor.len().is_multiple_of(2) || !cursor.is_ascii() {
+            return Err(invalid());
+        }
+
+        let json = (0..cursor.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&cursor[i..i + 2], 16))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| invalid())?;
+
+        let (key, id) = serde_json::from_slice(&json).map_err(|_| invalid())?;
+
+        Ok(ProductCursor { key, id })
+    }
+}
+
+impl FromStr for ProductCursor {
+    type Err = Error;
+
+    fn from_str(cursor: &str) -> Result<Self, Self::Err> {
+        Self::try_from(cursor)
+    }
+}
+
+impl Serialize for ProductCursor {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for ProductCursor {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let cursor = String::deserialize(deserializer)?;
+
+        ProductCursor::try_from(cursor.as_str()).map_err(de::Error::custom)
+    }
+}
+
+/**
+Input for a `ListProductsQuery`.
+
+Products can be filtered by a case-insensitive substring of their title, and by a price range.
+The price range includes both of its ends, and only matches products priced in the same currency.
+Filtering by a category also includes products in any of its descendants.
+Filtering by a tag only includes products that have it.
+Archived products aren't listed unless `include_archived` is set, and drafts aren't listed unless `include_drafts` is.
+
+To get the next page of products, pass the cursor returned with the previous page as `after`.
+*/
+#[derive(Default, Serialize, Deserialize)]
+pub struct ListProducts {
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub min_price: Option<Currency>,
+    #[serde(default)]
+    pub max_price: Option<Currency>,
+    #[serde(default)]
+    pub category_id: Option<CategoryId>,
+    #[serde(default)]
+    pub tag: Option<String>,
+    #[serde(default)]
+    pub sort: ProductSort,
+    #[serde(default)]
+    pub descending: bool,
+    #[serde(default)]
+    pub after: Option<ProductCursor>,
+    #[serde(default)]
+    pub limit: Option<usize>,
+    #[serde(default)]
+    pub include_archived: bool,
+    #[serde(default)]
+    pub include_drafts: bool,
+}
+
+/**
+A single page of products.
+
+The cursor for the next page will be `None` if there are no more products.
+*/
+pub struct ProductPage {
+    pub products: Vec<ProductData>,
+    pub next: Option<ProductCursor>,
+}
+
+impl QueryArgs for ListProducts {
+    type Output = Result<ProductPage, Error>;
+}
+
+/** Default implementation for a `ListProductsQuery`. */
+async fn execute(
+    query: ListProducts,
+    store: impl ProductStoreFilter,
+    category_query: impl Query<GetCategoryTree>,
+) -> Result<ProductPage, Error> {
+    if let (Some(min), Some(max)) = (query.min_price, query.max_price)
+        && min.code() != max.code()
+    {
+        return Err(error::bad_input(
+            "the minimum and maximum price must use the same currency",
+        ));
+    }
+
+    if let Some(ref after) = query.after
+        && after.key.sort() != query.sort
+    {
+        return Err(error::bad_input(
+            "the cursor was returned for a different sort",
+        ));
+    }
+
+    let limit = match query.limit {
+        Some(0) => return Err(error::bad_input("limit must be greater than zero")),
+        Some(limit) => limit.min(MAX_LIMIT),
+        None => DEFAULT_LIMIT,
+    };
+
+    let categories = match query.category_id {
+        Some(id) => {
+            let tree = category_query
+                .execute(GetCategoryTree { id: Some(id) })
+                .await?
+                .pop()
+                .ok_or_else(|| error::not_found("category not found"))?;
+
+            Some(tree.ids())
+        }
+        None => None,
+    };
+
+    let tag = match query.tag {
+        Some(tag) => Some(Tag::try_from(tag)?.into_inner()),
+        None => None,
+    };
+
+    let title = query.title.as_ref().map(|title| title.to_lowercase());
+    let in_range = |bound: Option<Currency>, price: Currency, ok: fn(u64, u64) -> bool| {
+        bound
+            .map(|bound| {
+                bound.code() == price.code() && ok(price.minor_units(), bound.minor_units())
+            })
+            .unwrap_or(true)
+    };
+
+    let mut products: Vec<_> = store
+        .filter(|p| {
+            (query.include_archived || !p.archived)
+                && (query.include_drafts || p.status != ProductStatus::Draft)
+                && title
+                    .as_ref()
+                    .map(|title| p.title.to_lowercase().contains(title))
+                    .unwrap_or(true)
+                && in_range(query.min_price, p.price, |price, min| price >= min)
+                && in_range(query.max_price, p.price, |price, max| price <= max)
+                && categories
+                    .as_ref()
+                    .map(|categories| {
+                        p.category_id
+                            .map(|id| categories.contains(&id))
+                            .unwrap_or(false)
+                    })
+                    .unwrap_or(true)
+                && tag.as_ref().map(|tag| p.tags.contains(tag)).unwrap_or(true)
+        })?
+        .map(|p| (SortKey::new(query.sort, &p), p))
+        .collect();
+
+    // Ids break ties between products with the same sorted value, so the order is stable across pages
+    products.sort_by(|(a_key, a), (b_key, b)| (a_key, a.id).cmp(&(b_key, b.id)));
+    if query.descending {
+        products.reverse();
+    }
+
+    let start = match query.after {
+        Some(after) => {
+            let after = (after.key, after.id);
+
+            products
+                .iter()
+                .position(|(key, p)| {
+                    let position = (key.clone(), p.id);
+
+                    if query.descending {
+                        position < after
+                    } else {
+                        position > after
+                    }
+                })
+                .unwrap_or(products.len())
+        }
+        None => 0,
+    };
+
+    let mut page: Vec<_> = products.into_iter().skip(start).take(limit + 1).collect();
+
+    let next = if page.len() > limit {
+        page.truncate(limit);
+        page.last().map(|(key, p)| ProductCursor {
+            key: key.clone(),
+            id: p.id,
+        })
+    } else {
+        None
+    };
+
+    Ok(ProductPage {
+        products: page.into_iter().map(|(_, p)| p).collect(),
+        next,
+    })
+}
+
+impl Resolver {
+    /** List products, one page at a time. */
+    pub fn list_products_query(&self) -> impl Query<ListProducts> {
+        self.query(|resolver, query: ListProducts| async move {
+            let store = resolver.product_store_filter();
+            let category_query = resolver.get_category_tree_query();
+
+            execute(query, store, category_query).await
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    use crate::{
+        domain::{
+            categories::model::test_data::CategoryBuilder,
+            products::model::{
+                store::{
+                    InMemoryStore,
+                    ProductStore,
+                    in_memory_store,
+                },
+                test_data::ProductBuilder,
+            },
+        },
+        store::Transaction,
+    };
+
+    fn store() -> InMemoryStore {
+        let store = in_memory_store(Default::default());
+
+        for (title, price, created) in [
+            ("Blue shirt", Currency::usd(300), 1),
+            ("red shirt", Currency::usd(100), 2),
+            ("Green hat", Currency::usd(200), 3),
+            ("Yellow shirt", Currency::eur(150), 4),
+        ] {
+            store
+                .set_product(
+                    &Transaction::none(),
+                    ProductBuilder::new()
+                        .id(ProductId::new())
+                        .title(title)
+                        .price(price)
+                        .created_at(SystemTime::UNIX_EPOCH + Duration::from_secs(created))
+                        .build(),
+                )
+                .unwrap();
+        }
+
+        store
+    }
+
+    async fn no_categories(_: GetCategoryTree) -> Result<Vec<CategoryTree>, Error> {
+        Ok(Vec::new())
+    }
+
+    fn titles(page: &ProductPage) -> Vec<&str> {
+        page.products.iter().map(|p| p.title.as_str()).collect()
+    }
+
+    #[tokio::test]
+    async fn filter_by_title_and_price() {
+        let store = store();
+
+        let page = execute(
+            ListProducts {
+                title: Some("SHIRT".to_owned()),
+                min_price: Some(Currency::usd(100)),
+                max_price: Some(Currency::usd(250)),
+                ..Default::default()
+            },
+            &store,
+            &no_categories,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(vec!["red shirt"], titles(&page));
+    }
+
+    #[tokio::test]
+    async fn sort_products() {
+        let store = store();
+
+        let by_title = execute(
+            ListProducts {
+                sort: ProductSort::Title,
+                ..Default::default()
+            },
+            &store,
+            &no_categories,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            vec!["Blue shirt", "Green hat", "red shirt", "Yellow shirt"],
+            titles(&by_title)
+        );
+
+        let by_price = execute(
+            ListProducts {
+                sort: ProductSort::Price,
+                descending: true,
+                ..Default::default()
+            },
+            &store,
+            &no_categories,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            vec!["Yellow shirt", "Blue shirt", "Green hat", "red shirt"],
+            titles(&by_price)
+        );
+    }
+
+    #[tokio::test]
+    async fn page_through_products() {
+        let store = store();
+
+        let mut pages = vec![];
+        let mut after = None;
+        loop {
+            let page = execute(
+                ListProducts {
+                    after,
+                    limit: Some(3),
+                    ..Default::default()
+                },
+                &store,
+                &no_categories,
+            )
+            .await
+            .unwrap();
+
+            pages.push(
+                titles(&page)
+                    .into_iter()
+                    .map(String::from)
+                    .collect::<Vec<_>>(),
+            );
+
+            match page.next {
+                Some(next) => after = Some(next.to_string().parse().unwrap()),
+                None => break,
+            }
+        }
+
+        assert_eq!(
+            vec![
+                vec!["Blue shirt", "red shirt", "Green hat"],
+                vec!["Yellow shirt"]
+            ],
+            pages
+        );
+    }
+
+    #[tokio::test]
+    async fn filter_by_category_and_tag() {
+        let store = in_memory_store(Default::default());
+
+        let clothing = CategoryId::new();
+        let shirts = CategoryId::new();
+
+        for (title, category, tags) in [
+            ("Blue shirt", Some(shirts), &["sale"][..]),
+            ("Green hat", Some(clothing), &["sale", "summer"][..]),
+            ("Novel", None, &["sale"][..]),
+            ("Red shirt", Some(shirts), &[][..]),
+        ] {
+            let mut product = ProductBuilder::new()
+                .id(ProductId::new())
+                .title(title)
+                .tags(tags);
+            if let Some(category) = category {
+                product = product.category(category);
+            }
+
+            store
+                .set_product(&Transaction::none(), product.build())
+                .unwrap();
+        }
+
+        // The `Clothing` category contains `Shirts`
+        let category_query = |query: GetCategoryTree| async move {
+            Ok(match query.id {
+                Some(id) if id == clothing => vec![CategoryTree {
+                    category: CategoryBuilder::new().id(clothing).build().into_data(),
+                    children: vec![CategoryTree {
+                        category: CategoryBuilder::new()
+                            .id(shirts)
+                            .parent(clothing)
+                            .build()
+                            .into_data(),
+                        children: vec![],
+                    }],
+                }],
+                _ => vec![],
+            })
+        };
+
+        let page = execute(
+            ListProducts {
+                category_id: Some(clothing),
+                tag: Some("Sale".to_owned()),
+                sort: ProductSort::Title,
+                ..Default::default()
+            },
+            &store,
+            &category_query,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(vec!["Blue shirt", "Green hat"], titles(&page));
+
+        assert!(
+            execute(
+                ListProducts {
+                    category_id: Some(CategoryId::new()),
+                    ..Default::default()
+                },
+                &store,
+                &category_query,
+            )
+            .await
+            .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn err_if_cursor_is_for_another_sort() {
+        let store = store();
+
+        let page = execute(
+            ListProducts {
+                limit: Some(1),
+                ..Default::default()
+            },
+            &store,
+            &no_categories,
+        )
+        .await
+        .unwrap();
+
+        assert!(
+            execute(
+                ListProducts {
+                    sort: ProductSort::Title,
+                    after: page.next,
+                    ..Default::default()
+                },
+                &store,
+                &no_categories,
+            )
+            .await
+            .is_err()
+        );
+    }
+}
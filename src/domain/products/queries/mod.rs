@@ -1,9 +1,19 @@
 /*! Queries for fetching product state. */
 
+mod export_products;
 mod get_product;
+mod get_product_price_history;
 mod get_product_summaries;
+mod get_product_variant_by_sku;
+mod list_products;
+mod search_products;
 
 pub use self::{
+    export_products::*,
     get_product::*,
+    get_product_price_history::*,
     get_product_summaries::*,
+    get_product_variant_by_sku::*,
+    list_products::*,
+    search_products::*,
 };
@@ -0,0 +1,168 @@
+/*! Contains the `SearchProductsQuery` type. */
+
+use crate::domain::{
+    Error,
+    error,
+    infra::*,
+    products::*,
+};
+
+/** The default number of products returned by a search. */
+const DEFAULT_LIMIT: usize = 20;
+
+/** The maximum number of products that can be returned by a search. */
+const MAX_LIMIT: usize = 100;
+
+/**
+Input for a `SearchProductsQuery`.
+
+If `prefix` is set then the last word in the query also matches words it's the start of, like `shi` for `shirt`.
+Archived products aren't returned unless `include_archived` is set, and drafts aren't returned unless `include_drafts` is.
+*/
+#[derive(Serialize, Deserialize)]
+pub struct SearchProducts {
+    pub query: String,
+    #[serde(default)]
+    pub prefix: bool,
+    #[serde(default)]
+    pub limit: Option<usize>,
+    #[serde(default)]
+    pub include_archived: bool,
+    #[serde(default)]
+    pub include_drafts: bool,
+}
+
+/** A product that matched a search, along with how relevant it is. */
+pub struct ProductSearchResult {
+    pub product: ProductData,
+    pub score: f64,
+}
+
+impl QueryArgs for SearchProducts {
+    type Output = Result<Vec<ProductSearchResult>, Error>;
+}
+
+/**
+Default implementation for a `SearchProductsQuery`.
+
+Results are returned with the most relevant first.
+*/
+async fn execute(
+    query: SearchProducts,
+    search: impl ProductStoreSearch,
+    store: impl ProductStoreFilter,
+) -> Result<Vec<ProductSearchResult>, Error> {
+    let limit = match query.limit {
+        Some(0) => return Err(error::bad_input("limit must be greater than zero")),
+        Some(limit) => limit.min(MAX_LIMIT),
+        None => DEFAULT_LIMIT,
+    };
+
+    let hits = search.search(&query.query, query.prefix)?;
+
+    // Archived and draft products are filtered out after searching, so hits are fetched in chunks until there are enough
+    let mut results = Vec::new();
+    for hits in hits.chunks(limit) {
+        let ids: Vec<_> = hits.iter().map(|hit| hit.key).collect();
+        let products = store.get_products(&ids)?;
+
+        for hit in hits {
+            let Some(product) = products.iter().find(|p| p.to_data().id == hit.key) else {
+                continue;
+            };
+
+            let product = product.to_data();
+            if (product.archived && !query.include_archived)
+                || (product.status == ProductStatus::Draft && !query.include_drafts)
+            {
+                continue;
+            }
+
+            results.push(ProductSearchResult {
+                product: product.clone(),
+                score: hit.score,
+            });
+        }
+
+        if results.len() >= limit {
+            break;
+        }
+    }
+
+    results.truncate(limit);
+
+    Ok(results)
+}
+
+impl Resolver {
+    /** Search for products by their text. */
+    pub fn search_products_query(&self) -> impl Query<SearchProducts> {
+        self.query(|resolver, query: SearchProducts| async move {
+            let search = resolver.product_store_search();
+            let store = resolver.product_store_filter();
+
+            execute(query, search, store).await
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::{
+        domain::products::model::{
+            store::{
+                ProductStore,
+                in_memory_store,
+            },
+            test_data::ProductBuilder,
+        },
+        store::Transaction,
+    };
+
+    #[tokio::test]
+    async fn search_products() {
+        let store = in_memory_store(Default::default());
+
+        for (title, archived) in [
+            ("Red shirt", false),
+            ("Blue shirts", false),
+            ("Green shirt", true),
+            ("Red hat", false),
+        ] {
+            let product = ProductBuilder::new().id(ProductId::new()).title(title);
+            let product = if archived {
+                product.archived()
+            } else {
+                product
+            };
+
+            store
+                .set_product(&Transaction::none(), product.build())
+                .unwrap();
+        }
+
+        let results = execute(
+            SearchProducts {
+                query: "red shi".to_owned(),
+                prefix: true,
+                limit: Some(2),
+                include_archived: false,
+                include_drafts: false,
+            },
+            &store,
+            &store,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            vec!["Red shirt", "Red hat"],
+            results
+                .iter()
+                .map(|result| result.product.title.as_str())
+                .collect::<Vec<_>>()
+        );
+    }
+}
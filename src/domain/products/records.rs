@@ -0,0 +1,397 @@
+/*!
+Product records for bulk import and export.
+
+Records are a flat representation of a product that can be kept in a spreadsheet.
+They use the same fields whether they're in CSV or JSON Lines, so a file exported in one format can
+be converted to the other without losing anything.
+*/
+
+use std::{
+    convert::TryFrom,
+    str::FromStr,
+};
+
+use crate::domain::{
+    Error,
+    categories::CategoryId,
+    error,
+    infra::{
+        csv,
+        *,
+    },
+    products::*,
+    taxes::TaxCategory,
+};
+
+/** A format that product records can be imported from or exported to. */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProductFileFormat {
+    /**
+    Comma-separated values with a header row.
+
+    Columns can be in any order, and tags are separated by `;`.
+    */
+    Csv,
+    /** One JSON object per line. */
+    JsonLines,
+}
+
+impl FromStr for ProductFileFormat {
+    type Err = Error;
+
+    fn from_str(format: &str) -> Result<Self, Self::Err> {
+        match format {
+            "csv" => Ok(ProductFileFormat::Csv),
+            "jsonl" | "jsonlines" => Ok(ProductFileFormat::JsonLines),
+            _ => Err(error::bad_input(format_args!(
+                "`{}` is not a supported file format",
+                format
+            ))),
+        }
+    }
+}
+
+/**
+A single product in an import or export file.
+
+The price is given in minor units of the currency, like cents.
+Only the title, currency and price are required when importing.
+Records without an id, or with an id that doesn't exist yet, create a new product.
+*/
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProductRecord {
+    #[serde(default)]
+    pub id: Option<ProductId>,
+    pub title: String,
+    pub currency: CurrencyCode,
+    pub price: u64,
+    #[serde(default)]
+    pub tax_category: Option<String>,
+    #[serde(default)]
+    pub status: Option<ProductStatus>,
+    #[serde(default)]
+    pub archived: Option<bool>,
+    #[serde(default)]
+    pub category_id: Option<CategoryId>,
+    #[serde(default)]
+    pub tags: Option<Vec<String>>,
+}
+
+/** The columns of a CSV file, in the order they're exported. */
+const COLUMNS: [&str; 9] = [
+    "id",
+    "title",
+    "currency",
+    "price",
+    "tax_category",
+    "status",
+    "archived",
+    "category_id",
+    "tags",
+];
+
+/** The columns a CSV file must have to be imported. */
+const REQUIRED_COLUMNS: [&str; 3] = ["title", "currency", "price"];
+
+impl ProductRecord {
+    pub fn from_data(product: &ProductData) -> Self {
+        ProductRecord {
+            id: Some(product.id),
+            title: product.title.clone(),
+            currency: product.price.code(),
+            price: product.price.minor_units(),
+            tax_category: Some(product.tax_category.clone()),
+            status: Some(product.status),
+            archived: Some(product.archived),
+            category_id: product.category_id,
+            tags: Some(product.tags.clone()),
+        }
+    }
+
+    pub fn price(&self) -> Currency {
+        Currency::from_minor_units(self.currency, self.price)
+    }
+
+    /**
+    Check the record against the rules for products.
+
+    This uses the same rules as the product itself, so a valid record can be applied to a product.
+    */
+    pub fn validate(&self) -> Result<(), Error> {
+        Title::try_from(self.title.as_str())?;
+        Price::try_from(self.price())?;
+
+        if let Some(tax_category) = &self.tax_category {
+            TaxCategory::try_from(tax_category.clone())?;
+        }
+
+        if let Some(tags) = &self.tags {
+            for tag in tags {
+                Tag::try_from(tag.as_str())?;
+            }
+
+            if tags.len() > Tag::MAX_PER_PRODUCT {
+                return Err(error::bad_input(format_args!(
+                    "a product can't have more than {} tags",
+                    Tag::MAX_PER_PRODUCT
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    /**
+    Read records from a file.
+
+    Each record is returned with the row it came from, counting from 1.
+    Rows are lines in the file, so the first record in a CSV file with a header is row 2.
+    A record that can't be read is returned as an error, but the file as a whole is only an error
+    if it can't be read at all.
+    */
+    pub fn read(
+        format: ProductFileFormat,
+        data: &str,
+    ) -> Result<Vec<(usize, Result<ProductRecord, Error>)>, Error> {
+        match format {
+            ProductFileFormat::Csv => read_csv(data),
+            ProductFileFormat::JsonLines => Ok(data
+                .lines()
+                .enumerate()
+                .filter(|(_, line)| !line.trim().is_empty())
+                .map(|(i, line)| (i + 1, serde_json::from_str(line).map_err(error::bad_input)))
+                .collect()),
+        }
+    }
+
+    /** Write records to a file. */
+    pub fn write(
+        format: ProductFileFormat,
+        records: impl IntoIterator<Item = ProductRecord>,
+    ) -> Result<String, Error> {
+        let mut out = String::new();
+
+        match format {
+            ProductFileFormat::Csv => {
+                csv::write(&mut out, COLUMNS);
+
+                for record in records {
+                    csv::write(&mut out, record.to_csv());
+                }
+            }
+            ProductFileFormat::JsonLines => {
+                for record in records {
+                    out.push_str(&serde_json::to_string(&record).map_err(error::msg)?);
+                    out.push('\n');
+                }
+            }
+        }
+
+        Ok(out)
+    }
+
+    fn to_csv(&self) -> [String; 9] {
+        [
+            self.id.map(|id| id.to_string()).unwrap_or_default(),
+            self.title.clone(),
+            self.currency.to_string(),
+            self.price.to_string(),
+            self.tax_category.clone().unwrap_or_default(),
+            self.status
+                .map(|status| status.to_string())
+                .unwrap_or_default(),
+            self.archived
+                .map(|archived| archived.to_string())
+                .unwrap_or_default(),
+            self.category_id
+                .map(|id| id.to_string())
+                .unwrap_or_default(),
+            self.tags
+                .as_ref()
+                .map(|tags| tags.join(";"))
+                .unwrap_or_default(),
+        ]
+    }
+
+    fn from_csv(header: &[String], fields: Vec<String>) -> Result<Self, Error> {
+        if fields.len() != header.len() {
+            return Err(error::bad_input(format_args!(
+                "expected {} fields but found {}",
+                header.len(),
+                fields.len()
+            )));
+        }
+
+        let mut id = None;
+        let mut title = String::new();
+        let mut currency = None;
+        let mut price = None;
+        let mut tax_category = None;
+        let mut status = None;
+        let mut archived = None;
+        let mut category_id = None;
+        let mut tags = None;
+
+        for (column, field) in header.iter().zip(fields) {
+            let value = field.trim();
+
+            match column.as_str() {
+                "title" => title = field,
+                // Other empty fields are treated as missing
+                _ if value.is_empty() => (),
+                "id" => id = Some(ProductId::try_from(value)?),
+                "currency" => currency = Some(value.parse()?),
+                "price" => {
+                    price = Some(value.parse().map_err(|_| {
+                        error::bad_input(format_args!("`{}` is not a valid price", value))
+                    })?)
+                }
+                "tax_category" => tax_category = Some(value.to_owned()),
+                "status" => status = Some(value.parse()?),
+                "archived" => {
+                    archived = Some(value.parse().map_err(|_| {
+                        error::bad_input(format_args!("`{}` is not `true` or `false`", value))
+                    })?)
+                }
+                "category_id" => category_id = Some(CategoryId::try_from(value)?),
+                "tags" => {
+                    tags = Some(
+                        value
+                            .split(';')
+                            .filter(|tag| !tag.trim().is_empty())
+                            .map(|tag| tag.to_owned())
+                            .collect(),
+                    )
+                }
+                _ => (),
+            }
+        }
+
+        Ok(ProductRecord {
+            id,
+            title,
+            currency: currency.ok_or_else(|| error::bad_input("a currency is required"))?,
+            price: price.ok_or_else(|| error::bad_input("a price is required"))?,
+            tax_category,
+            status,
+            archived,
+            category_id,
+            tags,
+        })
+    }
+}
+
+fn read_csv(data: &str) -> Result<Vec<(usize, Result<ProductRecord, Error>)>, Error> {
+    let mut records = csv::read(data)?.into_iter();
+
+    let Some((_, header)) = records.next() else {
+        return Ok(Vec::new());
+    };
+
+    let header: Vec<_> = header
+        .into_iter()
+        .map(|column| column.trim().to_lowercase())
+        .collect();
+
+    for column in &header {
+        if !COLUMNS.contains(&column.as_str()) {
+            return Err(error::bad_input(format_args!(
+                "`{}` is not a known column",
+                column
+            )));
+        }
+    }
+
+    for column in REQUIRED_COLUMNS {
+        if !header.iter().any(|c| c == column) {
+            return Err(error::bad_input(format_args!(
+                "the `{}` column is required",
+                column
+            )));
+        }
+    }
+
+    Ok(records
+        .map(|(row, fields)| (row, ProductRecord::from_csv(&header, fields)))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::domain::products::model::test_data::ProductBuilder;
+
+    #[test]
+    fn read_csv_records() {
+        let records = ProductRecord::read(
+            ProductFileFormat::Csv,
+            "price,title,currency,tags\n100,\"Shirt, blue\",usd,summer;cotton\nabc,Hat,usd,\n100,Scarf,,\n",
+        )
+        .unwrap();
+
+        assert_eq!(3, records.len());
+
+        let (row, record) = &records[0];
+        let record = record.as_ref().unwrap();
+
+        assert_eq!(2, *row);
+        assert_eq!("Shirt, blue", record.title);
+        assert_eq!(Currency::usd(100), record.price());
+        assert_eq!(
+            Some(vec!["summer".to_owned(), "cotton".to_owned()]),
+            record.tags
+        );
+
+        assert_eq!(3, records[1].0);
+        assert!(records[1].1.is_err());
+
+        // Required fields can't be left empty
+        assert!(records[2].1.is_err());
+    }
+
+    #[test]
+    fn err_if_csv_columns_are_invalid() {
+        assert!(ProductRecord::read(ProductFileFormat::Csv, "title,price\n").is_err());
+        assert!(
+            ProductRecord::read(ProductFileFormat::Csv, "title,currency,price,colour\n").is_err()
+        );
+    }
+
+    #[test]
+    fn write_and_read_round_trips() {
+        let product = ProductBuilder::new()
+            .title("A \"quoted\" title")
+            .tags(&["summer", "cotton"])
+            .build()
+            .into_data();
+
+        for format in [ProductFileFormat::Csv, ProductFileFormat::JsonLines] {
+            let data = ProductRecord::write(format, [ProductRecord::from_data(&product)]).unwrap();
+
+            let records = ProductRecord::read(format, &data).unwrap();
+            let record = records[0].1.as_ref().unwrap();
+
+            assert_eq!(1, records.len());
+            assert_eq!(Some(product.id), record.id);
+            assert_eq!(product.title, record.title);
+            assert_eq!(product.price, record.price());
+            assert_eq!(Some(product.status), record.status);
+            assert_eq!(Some(&product.tags), record.tags.as_ref());
+        }
+    }
+
+    #[test]
+    fn validate_with_product_rules() {
+        let mut record = ProductRecord::from_data(&ProductBuilder::new().build().into_data());
+        record.validate().unwrap();
+
+        record.title = String::new();
+        assert!(record.validate().is_err());
+
+        let mut record = ProductRecord::from_data(&ProductBuilder::new().build().into_data());
+        record.tax_category = Some("not valid".to_owned());
+        assert!(record.validate().is_err());
+    }
+}
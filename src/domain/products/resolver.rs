@@ -4,11 +4,16 @@ use std::sync::Arc;
 
 use crate::domain::{
     infra::*,
-    products::model::store::{
-        self,
-        InMemoryStore,
-        ProductStore,
-        ProductStoreFilter,
+    products::{
+        ProductData,
+        VariantData,
+        model::store::{
+            self,
+            InMemoryStore,
+            ProductStore,
+            ProductStoreFilter,
+            ProductStoreSearch,
+        },
     },
 };
 
@@ -20,19 +25,57 @@ The `ProductsResolver` type wraps private implementation details and exposes the
 #[derive(Clone)]
 pub(in crate::domain) struct ProductsResolver {
     product_store: Register<Arc<InMemoryStore>>,
+    product_id: Register<Arc<dyn IdProvider<ProductData> + Send + Sync>>,
+    variant_id: Register<Arc<dyn IdProvider<VariantData> + Send + Sync>>,
 }
 
 impl Default for ProductsResolver {
     fn default() -> Self {
         ProductsResolver {
-            product_store: Register::once(|resolver| {
+            product_store: Register::per_tenant(|resolver| {
                 Arc::new(store::in_memory_store(resolver.transaction_store()))
             }),
+            product_id: Register::once(|_| {
+                Arc::new(NextId::<ProductData>::new())
+                    as Arc<dyn IdProvider<ProductData> + Send + Sync>
+            }),
+            variant_id: Register::once(|_| {
+                Arc::new(NextId::<VariantData>::new())
+                    as Arc<dyn IdProvider<VariantData> + Send + Sync>
+            }),
         }
     }
 }
 
+impl AppBuilder {
+    /** Use a different source of ids for new products. */
+    pub fn product_id(
+        mut self,
+        product_id: Register<Arc<dyn IdProvider<ProductData> + Send + Sync>>,
+    ) -> Self {
+        self.root_resolver.products_resolver.product_id = product_id;
+        self
+    }
+
+    /** Use a different source of ids for new product variants. */
+    pub fn variant_id(
+        mut self,
+        variant_id: Register<Arc<dyn IdProvider<VariantData> + Send + Sync>>,
+    ) -> Self {
+        self.root_resolver.products_resolver.variant_id = variant_id;
+        self
+    }
+}
+
 impl Resolver {
+    pub fn product_id(&self) -> impl IdProvider<ProductData> {
+        self.resolve(&self.products_resolver.product_id)
+    }
+
+    pub fn variant_id(&self) -> impl IdProvider<VariantData> {
+        self.resolve(&self.products_resolver.variant_id)
+    }
+
     pub(in crate::domain::products) fn product_store(&self) -> impl ProductStore {
         self.resolve(&self.products_resolver.product_store)
     }
@@ -40,4 +83,8 @@ impl Resolver {
     pub(in crate::domain::products) fn product_store_filter(&self) -> impl ProductStoreFilter {
         self.resolve(&self.products_resolver.product_store)
     }
+
+    pub(in crate::domain::products) fn product_store_search(&self) -> impl ProductStoreSearch {
+        self.resolve(&self.products_resolver.product_store)
+    }
 }
@@ -0,0 +1,129 @@
+/*! Contains the `CreatePromotionCommand` type. */
+
+use std::{
+    convert::TryFrom,
+    time::SystemTime,
+};
+
+use crate::domain::{
+    Error,
+    ErrorKind,
+    error,
+    infra::*,
+    promotions::*,
+};
+
+/** Input for a `CreatePromotionCommand`. */
+#[derive(Clone, Serialize, Deserialize)]
+pub struct CreatePromotion {
+    pub id: PromotionId,
+    pub code: String,
+    pub discount: Discount,
+    pub min_spend: Option<Currency>,
+    pub expires_at: Option<SystemTime>,
+    pub usage_limit: Option<u32>,
+}
+
+impl CommandArgs for CreatePromotion {
+    type Output = Result<(), Error>;
+}
+
+/** Default implementation for a `CreatePromotionCommand`. */
+async fn execute(
+    command: CreatePromotion,
+    transaction: ActiveTransaction,
+    store: impl PromotionStore,
+    filter: impl PromotionStoreFilter,
+) -> Result<(), Error> {
+    let code = PromotionCode::try_from(command.code)?.into_inner();
+
+    if store.get_promotion(command.id)?.is_some() {
+        return Err(
+            error::emit(emit::evt!("promotion {id: command.id} already exists"))
+                .with_kind(ErrorKind::Conflict),
+        );
+    }
+
+    // Codes are how shoppers find promotions, so they need to be unique
+    if filter.filter(|p| p.code == code)?.next().is_some() {
+        return Err(error::conflict(format_args!(
+            "a promotion with the code {} already exists",
+            code
+        )));
+    }
+
+    let mut promotion = Promotion::new(command.id, code, command.discount)?;
+
+    promotion.set_min_spend(command.min_spend)?;
+    promotion.set_expires_at(command.expires_at);
+    promotion.set_usage_limit(command.usage_limit);
+
+    store.set_promotion(transaction.get(), promotion)?;
+
+    Ok(())
+}
+
+impl Resolver {
+    /** Create a promotion. */
+    pub fn create_promotion_command(&self) -> impl Command<CreatePromotion> {
+        self.command(|resolver, command: CreatePromotion| async move {
+            let store = resolver.promotion_store();
+            let filter = resolver.promotion_store_filter();
+            let active_transaction = resolver.active_transaction();
+
+            execute(command, active_transaction, store, filter).await
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::domain::promotions::model::store::in_memory_store;
+
+    fn create(code: &str) -> CreatePromotion {
+        CreatePromotion {
+            id: PromotionId::new(),
+            code: code.to_owned(),
+            discount: Discount::Percentage { percent: 10 },
+            min_spend: None,
+            expires_at: None,
+            usage_limit: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn err_if_already_exists() {
+        let store = in_memory_store(Default::default());
+
+        let create = create("A");
+
+        execute(create.clone(), ActiveTransaction::none(), &store, &store)
+            .await
+            .unwrap();
+
+        let err = execute(create, ActiveTransaction::none(), &store, &store)
+            .await
+            .err()
+            .unwrap();
+
+        assert_eq!(ErrorKind::Conflict, err.kind());
+    }
+
+    #[tokio::test]
+    async fn err_if_code_already_exists() {
+        let store = in_memory_store(Default::default());
+
+        execute(create("a"), ActiveTransaction::none(), &store, &store)
+            .await
+            .unwrap();
+
+        let err = execute(create("A"), ActiveTransaction::none(), &store, &store)
+            .await
+            .err()
+            .unwrap();
+
+        assert_eq!(ErrorKind::Conflict, err.kind());
+    }
+}
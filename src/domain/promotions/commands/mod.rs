@@ -0,0 +1,9 @@
+/*! Commands for modifying promotion state. */
+
+mod create_promotion;
+mod redeem_promotion;
+
+pub use self::{
+    create_promotion::*,
+    redeem_promotion::*,
+};
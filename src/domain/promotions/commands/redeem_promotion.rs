@@ -0,0 +1,127 @@
+/*! Contains the `RedeemPromotionCommand` type. */
+
+use std::convert::TryFrom;
+
+use crate::domain::{
+    Error,
+    error,
+    infra::*,
+    promotions::*,
+};
+
+/**
+Input for a `RedeemPromotionCommand`.
+
+Redeeming a promotion counts towards its usage limit.
+The id of the redeemed promotion is returned.
+*/
+#[derive(Clone, Serialize, Deserialize)]
+pub struct RedeemPromotion {
+    pub code: String,
+}
+
+impl CommandArgs for RedeemPromotion {
+    type Output = Result<PromotionId, Error>;
+}
+
+/** Default implementation for a `RedeemPromotionCommand`. */
+async fn execute(
+    command: RedeemPromotion,
+    transaction: ActiveTransaction,
+    store: impl PromotionStore,
+    filter: impl PromotionStoreFilter,
+    clock: impl Clock,
+) -> Result<PromotionId, Error> {
+    let code = PromotionCode::try_from(command.code)?.into_inner();
+
+    let Some(data) = filter.filter(|p| p.code == code)?.next() else {
+        return Err(error::not_found("promotion not found"));
+    };
+
+    let mut promotion = store
+        .get_promotion(data.id)?
+        .ok_or_else(|| error::not_found("promotion not found"))?;
+
+    promotion.redeem(clock.now())?;
+
+    store.set_promotion(transaction.get(), promotion)?;
+
+    Ok(data.id)
+}
+
+impl Resolver {
+    /** Redeem a promotion by its code. */
+    pub fn redeem_promotion_command(&self) -> impl Command<RedeemPromotion> {
+        self.command(|resolver, command: RedeemPromotion| async move {
+            let store = resolver.promotion_store();
+            let filter = resolver.promotion_store_filter();
+            let active_transaction = resolver.active_transaction();
+            let clock = resolver.clock();
+
+            execute(command, active_transaction, store, filter, clock).await
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::SystemTime;
+
+    use super::*;
+
+    use crate::domain::{
+        ErrorKind,
+        promotions::model::{
+            store::in_memory_store,
+            test_data::PromotionBuilder,
+        },
+    };
+
+    #[tokio::test]
+    async fn redeem_counts_uses() {
+        let store = in_memory_store(Default::default());
+
+        let id = PromotionId::new();
+        store
+            .set_promotion(
+                ActiveTransaction::none().get(),
+                PromotionBuilder::new().id(id).code("SAVE").build(),
+            )
+            .unwrap();
+
+        let redeemed = execute(
+            RedeemPromotion {
+                code: "save".to_owned(),
+            },
+            ActiveTransaction::none(),
+            &store,
+            &store,
+            SystemTime::now(),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(id, redeemed);
+        assert_eq!(1, store.get_promotion(id).unwrap().unwrap().to_data().uses);
+    }
+
+    #[tokio::test]
+    async fn err_if_not_found() {
+        let store = in_memory_store(Default::default());
+
+        let err = execute(
+            RedeemPromotion {
+                code: "SAVE".to_owned(),
+            },
+            ActiveTransaction::none(),
+            &store,
+            &store,
+            SystemTime::now(),
+        )
+        .await
+        .err()
+        .unwrap();
+
+        assert_eq!(ErrorKind::NotFound, err.kind());
+    }
+}
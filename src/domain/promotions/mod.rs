@@ -0,0 +1,16 @@
+/*! Domain module for promotions and discount codes. */
+
+pub mod commands;
+pub mod model;
+pub mod queries;
+pub(in crate::domain) mod resolver;
+
+use self::model::store::{
+    PromotionStore,
+    PromotionStoreFilter,
+};
+pub use self::{
+    commands::*,
+    model::*,
+    queries::*,
+};
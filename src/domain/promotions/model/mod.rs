@@ -0,0 +1,326 @@
+/*! Contains the `Promotion` entity. */
+
+use std::{
+    convert::{
+        TryFrom,
+        TryInto,
+    },
+    time::SystemTime,
+};
+
+pub mod store;
+
+#[cfg(test)]
+pub mod test_data;
+
+use crate::domain::{
+    Error,
+    error,
+    infra::*,
+    products::ProductId,
+};
+
+pub type PromotionId = Id<PromotionData>;
+pub type NextPromotionId = NextId<PromotionData>;
+pub type PromotionVersion = Version<PromotionData>;
+
+/**
+A discount code that shoppers enter to redeem a promotion, like `SUMMER-10`.
+
+Codes are between 1 and 32 ASCII letters, digits, dashes or underscores.
+They're case-insensitive and normalized to uppercase.
+*/
+pub struct PromotionCode(String);
+
+impl PromotionCode {
+    pub fn into_inner(self) -> String {
+        self.0
+    }
+}
+
+impl TryFrom<String> for PromotionCode {
+    type Error = Error;
+
+    fn try_from(code: String) -> Result<Self, Self::Error> {
+        if code.is_empty()
+            || code.len() > 32
+            || !code
+                .bytes()
+                .all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_')
+        {
+            return Err(error::bad_input(format_args!(
+                "`{}` is not a valid promotion code",
+                code
+            )));
+        }
+
+        Ok(PromotionCode(code.to_ascii_uppercase()))
+    }
+}
+
+impl<'a> TryFrom<&'a str> for PromotionCode {
+    type Error = Error;
+
+    fn try_from(code: &'a str) -> Result<Self, Self::Error> {
+        Self::try_from(code.to_owned())
+    }
+}
+
+/**
+The discount a promotion gives.
+
+- `Percentage` takes a whole percentage off the order's subtotal.
+- `FixedAmount` takes a fixed amount off the order's subtotal.
+- `BuyXGetY` makes `get` of every `buy + get` units of a product free.
+  Units of all the product's variants are counted together, and the cheapest units are free.
+*/
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Discount {
+    Percentage {
+        percent: u32,
+    },
+    FixedAmount {
+        amount: Currency,
+    },
+    BuyXGetY {
+        product_id: ProductId,
+        buy: u32,
+        get: u32,
+    },
+}
+
+impl Discount {
+    fn validate(&self) -> Result<(), Error> {
+        match *self {
+            Discount::Percentage { percent } if percent == 0 || percent > 100 => Err(
+                error::bad_input("percentage discounts must be between 1 and 100 percent"),
+            ),
+            Discount::FixedAmount { amount } if amount.minor_units() == 0 => Err(error::bad_input(
+                "fixed amount discounts must be greater than zero",
+            )),
+            Discount::BuyXGetY { buy, get, .. } if buy == 0 || get == 0 => Err(error::bad_input(
+                "buy X get Y discounts must buy and get at least one item",
+            )),
+            Discount::BuyXGetY { buy, get, .. } if buy.checked_add(get).is_none() => Err(
+                error::bad_input("buy X get Y discounts must buy and get fewer items"),
+            ),
+            _ => Ok(()),
+        }
+    }
+}
+
+/** Data for a promotion. */
+#[derive(Clone, Serialize, Deserialize)]
+pub struct PromotionData {
+    pub id: PromotionId,
+    pub version: PromotionVersion,
+    pub code: String,
+    pub discount: Discount,
+    pub min_spend: Option<Currency>,
+    pub expires_at: Option<SystemTime>,
+    pub usage_limit: Option<u32>,
+    pub uses: u32,
+    _private: (),
+}
+
+/**
+A promotion that can be applied to orders using its code.
+
+Promotions can require a minimum spend, expire at a point in time, and limit the number of
+times they can be redeemed.
+*/
+pub struct Promotion {
+    data: PromotionData,
+}
+
+impl Promotion {
+    pub(self) fn from_data(data: PromotionData) -> Self {
+        Promotion { data }
+    }
+
+    pub fn into_data(self) -> PromotionData {
+        self.data
+    }
+
+    pub fn to_data(&self) -> &PromotionData {
+        &self.data
+    }
+
+    pub fn new(
+        id: impl IdProvider<PromotionData>,
+        code: impl TryInto<PromotionCode, Error = Error>,
+        discount: Discount,
+    ) -> Result<Self, Error> {
+        let id = id.get()?;
+
+        discount.validate()?;
+
+        Ok(Promotion::from_data(PromotionData {
+            id,
+            version: PromotionVersion::default(),
+            code: code.try_into()?.0,
+            discount,
+            min_spend: None,
+            expires_at: None,
+            usage_limit: None,
+            uses: 0,
+            _private: (),
+        }))
+    }
+
+    /** Require orders to have a minimum subtotal before the promotion can be applied. */
+    pub fn set_min_spend(&mut self, min_spend: Option<Currency>) -> Result<(), Error> {
+        if let (
+            Some(min_spend),
+            Discount::FixedAmount {
+                amount: discount, ..
+            },
+        ) = (min_spend, &self.data.discount)
+            && min_spend.code() != discount.code()
+        {
+            return Err(error::bad_input(
+                "the minimum spend must use the same currency as the discount",
+            ));
+        }
+
+        self.data.min_spend = min_spend;
+
+        Ok(())
+    }
+
+    /** Stop the promotion from being redeemed after a point in time. */
+    pub fn set_expires_at(&mut self, expires_at: Option<SystemTime>) {
+        self.data.expires_at = expires_at;
+    }
+
+    /** Limit the number of times the promotion can be redeemed. */
+    pub fn set_usage_limit(&mut self, usage_limit: Option<u32>) {
+        self.data.usage_limit = usage_limit;
+    }
+
+    /**
+    Redeem the promotion, counting it towards its usage limit.
+
+    Promotions can't be redeemed after they expire or once they reach their usage limit.
+    */
+    pub fn redeem(&mut self, now: SystemTime) -> Result<(), Error> {
+        if let Some(expires_at) = self.data.expires_at
+            && expires_at <= now
+        {
+            return Err(error::conflict("promotion has expired"));
+        }
+
+        if let Some(usage_limit) = self.data.usage_limit
+            && self.data.uses >= usage_limit
+        {
+            return Err(error::conflict("promotion has reached its usage limit"));
+        }
+
+        self.data.uses += 1;
+
+        Ok(())
+    }
+}
+
+impl Entity for Promotion {
+    type Id = PromotionId;
+    type Version = PromotionVersion;
+    type Data = PromotionData;
+    type Error = Error;
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    use crate::domain::promotions::model::test_data::default_promotion;
+
+    #[test]
+    fn discount_must_be_valid() {
+        for discount in [
+            Discount::Percentage { percent: 0 },
+            Discount::Percentage { percent: 101 },
+            Discount::FixedAmount {
+                amount: Currency::usd(0),
+            },
+            Discount::BuyXGetY {
+                product_id: ProductId::new(),
+                buy: 0,
+                get: 1,
+            },
+            Discount::BuyXGetY {
+                product_id: ProductId::new(),
+                buy: u32::MAX,
+                get: 1,
+            },
+        ] {
+            assert!(Promotion::new(PromotionId::new(), "CODE", discount).is_err());
+        }
+    }
+
+    #[test]
+    fn code_is_normalized() {
+        let promotion = Promotion::new(
+            PromotionId::new(),
+            "summer-10",
+            Discount::Percentage { percent: 10 },
+        )
+        .unwrap();
+
+        assert_eq!("SUMMER-10", promotion.data.code);
+
+        assert!(
+            Promotion::new(
+                PromotionId::new(),
+                "not a code",
+                Discount::Percentage { percent: 10 }
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn redeem_until_usage_limit() {
+        let mut promotion = default_promotion();
+        promotion.set_usage_limit(Some(2));
+
+        let now = SystemTime::now();
+
+        promotion.redeem(now).unwrap();
+        promotion.redeem(now).unwrap();
+
+        assert!(promotion.redeem(now).is_err());
+        assert_eq!(2, promotion.data.uses);
+    }
+
+    #[test]
+    fn redeem_until_expired() {
+        let mut promotion = default_promotion();
+
+        let now = SystemTime::now();
+        promotion.set_expires_at(Some(now));
+
+        promotion.redeem(now - Duration::from_secs(1)).unwrap();
+
+        assert!(promotion.redeem(now).is_err());
+    }
+
+    #[test]
+    fn min_spend_must_match_fixed_amount_currency() {
+        let mut promotion = Promotion::new(
+            PromotionId::new(),
+            "CODE",
+            Discount::FixedAmount {
+                amount: Currency::usd(500),
+            },
+        )
+        .unwrap();
+
+        assert!(promotion.set_min_spend(Some(Currency::eur(1000))).is_err());
+
+        promotion.set_min_spend(Some(Currency::usd(1000))).unwrap();
+    }
+}
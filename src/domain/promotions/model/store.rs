@@ -0,0 +1,131 @@
+/*! Persistent storage for promotions. */
+
+use std::vec::IntoIter;
+
+use crate::{
+    domain::{
+        Error,
+        promotions::*,
+    },
+    store::*,
+};
+
+/* A place to persist and fetch promotion entities. */
+#[auto_impl(&, Arc)]
+pub(in crate::domain) trait PromotionStore {
+    fn get_promotion(&self, id: PromotionId) -> Result<Option<Promotion>, Error>;
+    fn set_promotion(&self, transaction: &Transaction, promotion: Promotion) -> Result<(), Error>;
+}
+
+/**
+An additional store for fetching multiple promotion records at a time.
+
+This is used to find promotions by their code.
+*/
+#[auto_impl(&, Arc)]
+pub(in crate::domain) trait PromotionStoreFilter {
+    fn filter<F>(&self, predicate: F) -> Result<Iter, Error>
+    where
+        F: Fn(&PromotionData) -> bool;
+}
+
+pub(in crate::domain) type Iter = IntoIter<PromotionData>;
+
+/** A test in-memory promotion store. */
+pub(in crate::domain) struct InMemoryStore(TransactionValueStore<PromotionData>);
+
+impl PromotionStore for InMemoryStore {
+    fn get_promotion(&self, id: PromotionId) -> Result<Option<Promotion>, Error> {
+        if let Some((version, data)) = self.0.get(id) {
+            assert_eq!(version, data.version.into());
+
+            Ok(Some(Promotion::from_data(data)))
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn set_promotion(&self, transaction: &Transaction, promotion: Promotion) -> Result<(), Error> {
+        let mut data = promotion.into_data();
+        let id = data.id;
+
+        self.0.set(
+            transaction,
+            id,
+            Some(data.version),
+            data.version.next()?,
+            data,
+        )?;
+
+        Ok(())
+    }
+}
+
+impl PromotionStoreFilter for InMemoryStore {
+    #[allow(clippy::needless_collect)]
+    fn filter<F>(&self, predicate: F) -> Result<Iter, Error>
+    where
+        F: Fn(&PromotionData) -> bool,
+    {
+        let promotions: Vec<_> = self.0.get_all(predicate).map(|(_, data)| data).collect();
+
+        Ok(promotions.into_iter())
+    }
+}
+
+/**
+Create an in-memory promotion store.
+
+The store will participate in transactions tracked by the given transaction store.
+*/
+pub(in crate::domain) fn in_memory_store(transaction_store: TransactionStore) -> InMemoryStore {
+    InMemoryStore(TransactionValueStore::new(transaction_store))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::domain::{
+        ErrorKind,
+        promotions::model::test_data::PromotionBuilder,
+    };
+
+    #[test]
+    fn test_in_memory_store() {
+        let store = in_memory_store(Default::default());
+
+        let id = PromotionId::new();
+
+        store
+            .set_promotion(
+                &Transaction::none(),
+                PromotionBuilder::new().id(id).code("A").build(),
+            )
+            .unwrap();
+
+        let found = store.get_promotion(id).unwrap().unwrap();
+        assert_eq!(id, found.data.id);
+
+        let mut by_code = store.filter(|p| p.code == "A").unwrap();
+        assert_eq!(id, by_code.next().unwrap().id);
+    }
+
+    #[test]
+    fn add_promotion_twice_fails_concurrency_check() {
+        let store = in_memory_store(Default::default());
+
+        let id = PromotionId::new();
+
+        store
+            .set_promotion(&Transaction::none(), PromotionBuilder::new().id(id).build())
+            .unwrap();
+
+        let err = store
+            .set_promotion(&Transaction::none(), PromotionBuilder::new().id(id).build())
+            .err()
+            .unwrap();
+
+        assert_eq!(ErrorKind::Conflict, err.kind());
+    }
+}
@@ -0,0 +1,59 @@
+use crate::domain::{
+    infra::*,
+    promotions::*,
+};
+
+pub fn default_code() -> String {
+    "TEST-PROMOTION".to_owned()
+}
+
+pub fn default_promotion() -> Promotion {
+    Promotion::new(
+        NextPromotionId::new(),
+        default_code(),
+        Discount::Percentage { percent: 10 },
+    )
+    .unwrap()
+}
+
+pub struct PromotionBuilder {
+    promotion: Promotion,
+}
+
+impl Default for PromotionBuilder {
+    fn default() -> Self {
+        PromotionBuilder {
+            promotion: default_promotion(),
+        }
+    }
+}
+
+impl PromotionBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn id(mut self, id: PromotionId) -> Self {
+        self.promotion.data.id = id;
+        self
+    }
+
+    pub fn code(mut self, code: &str) -> Self {
+        self.promotion.data.code = PromotionCode::try_from(code).unwrap().into_inner();
+        self
+    }
+
+    pub fn discount(mut self, discount: Discount) -> Self {
+        self.promotion.data.discount = discount;
+        self
+    }
+
+    pub fn min_spend(mut self, min_spend: Currency) -> Self {
+        self.promotion.set_min_spend(Some(min_spend)).unwrap();
+        self
+    }
+
+    pub fn build(self) -> Promotion {
+        self.promotion
+    }
+}
@@ -0,0 +1,38 @@
+/*! Contains the `GetPromotionQuery` type. */
+
+use crate::domain::{
+    Error,
+    infra::*,
+    promotions::*,
+};
+
+/** Input for a `GetPromotionQuery`. */
+#[derive(Serialize, Deserialize)]
+pub struct GetPromotion {
+    pub id: PromotionId,
+}
+
+impl QueryArgs for GetPromotion {
+    type Output = Result<Option<Promotion>, Error>;
+}
+
+/** Default implementation for a `GetPromotionQuery`. */
+async fn execute(
+    query: GetPromotion,
+    store: impl PromotionStore,
+) -> Result<Option<Promotion>, Error> {
+    let promotion = store.get_promotion(query.id)?;
+
+    Ok(promotion)
+}
+
+impl Resolver {
+    /** Get a promotion. */
+    pub fn get_promotion_query(&self) -> impl Query<GetPromotion> {
+        self.query(|resolver, query: GetPromotion| async move {
+            let store = resolver.promotion_store();
+
+            execute(query, store).await
+        })
+    }
+}
@@ -0,0 +1,5 @@
+/*! Queries for fetching promotion state. */
+
+mod get_promotion;
+
+pub use self::get_promotion::*;
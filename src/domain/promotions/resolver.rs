@@ -0,0 +1,68 @@
+/*! Contains the `PromotionsResolver` type. */
+
+use std::sync::Arc;
+
+use crate::domain::{
+    infra::*,
+    promotions::{
+        PromotionData,
+        model::store::{
+            self,
+            InMemoryStore,
+            PromotionStore,
+            PromotionStoreFilter,
+        },
+    },
+};
+
+/**
+Resolver for promotions.
+
+The `PromotionsResolver` type wraps private implementation details and exposes them as traits within the `promotions` module.
+*/
+#[derive(Clone)]
+pub(in crate::domain) struct PromotionsResolver {
+    promotion_store: Register<Arc<InMemoryStore>>,
+    promotion_id: Register<Arc<dyn IdProvider<PromotionData> + Send + Sync>>,
+}
+
+impl Default for PromotionsResolver {
+    fn default() -> Self {
+        PromotionsResolver {
+            promotion_store: Register::per_tenant(|resolver| {
+                Arc::new(store::in_memory_store(resolver.transaction_store()))
+            }),
+            promotion_id: Register::once(|_| {
+                Arc::new(NextId::<PromotionData>::new())
+                    as Arc<dyn IdProvider<PromotionData> + Send + Sync>
+            }),
+        }
+    }
+}
+
+impl AppBuilder {
+    /** Use a different source of ids for new promotions. */
+    pub fn promotion_id(
+        mut self,
+        promotion_id: Register<Arc<dyn IdProvider<PromotionData> + Send + Sync>>,
+    ) -> Self {
+        self.root_resolver.promotions_resolver.promotion_id = promotion_id;
+        self
+    }
+}
+
+impl Resolver {
+    pub fn promotion_id(&self) -> impl IdProvider<PromotionData> {
+        self.resolve(&self.promotions_resolver.promotion_id)
+    }
+
+    pub(in crate::domain::promotions) fn promotion_store(&self) -> impl PromotionStore {
+        self.resolve(&self.promotions_resolver.promotion_store)
+    }
+
+    pub(in crate::domain::promotions) fn promotion_store_filter(
+        &self,
+    ) -> impl PromotionStoreFilter {
+        self.resolve(&self.promotions_resolver.promotion_store)
+    }
+}
@@ -0,0 +1,5 @@
+/*! Commands for modifying tax state. */
+
+mod set_tax_rates;
+
+pub use self::set_tax_rates::*;
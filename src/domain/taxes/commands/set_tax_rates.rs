@@ -0,0 +1,93 @@
+/*! Contains the `SetTaxRatesCommand` type. */
+
+use std::{
+    collections::BTreeMap,
+    convert::TryFrom,
+};
+
+use crate::domain::{
+    Error,
+    infra::*,
+    taxes::*,
+};
+
+/**
+Input for a `SetTaxRatesCommand`.
+
+Rates are keyed by tax category.
+*/
+#[derive(Clone, Serialize, Deserialize)]
+pub struct SetTaxRates {
+    pub region: String,
+    pub pricing: PricingMode,
+    pub rates: BTreeMap<String, TaxRate>,
+}
+
+impl CommandArgs for SetTaxRates {
+    type Output = Result<(), Error>;
+}
+
+/** Default implementation for a `SetTaxRatesCommand`. */
+async fn execute(
+    command: SetTaxRates,
+    transaction: ActiveTransaction,
+    store: impl TaxRatesStore,
+) -> Result<(), Error> {
+    let region = TaxRegion::try_from(command.region)?.into_inner();
+
+    let tax_rates = {
+        if let Some(mut tax_rates) = store.get_tax_rates(&region)? {
+            tax_rates.set_rates(command.pricing, command.rates)?;
+
+            tax_rates
+        } else {
+            TaxRates::new(region, command.pricing, command.rates)?
+        }
+    };
+
+    store.set_tax_rates(transaction.get(), tax_rates)?;
+
+    Ok(())
+}
+
+impl Resolver {
+    /** Replace the tax rates for a region. */
+    pub fn set_tax_rates_command(&self) -> impl Command<SetTaxRates> {
+        self.command(|resolver, command: SetTaxRates| async move {
+            let store = resolver.tax_rates_store();
+            let active_transaction = resolver.active_transaction();
+
+            execute(command, active_transaction, store).await
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::domain::taxes::model::store::in_memory_store;
+
+    #[tokio::test]
+    async fn set_tax_rates() {
+        let store = in_memory_store(Default::default());
+
+        for pricing in [PricingMode::Exclusive, PricingMode::Inclusive] {
+            execute(
+                SetTaxRates {
+                    region: "au".to_owned(),
+                    pricing,
+                    rates: BTreeMap::new(),
+                },
+                ActiveTransaction::none(),
+                &store,
+            )
+            .await
+            .unwrap();
+        }
+
+        let tax_rates = store.get_tax_rates("AU").unwrap().unwrap();
+
+        assert_eq!(PricingMode::Inclusive, tax_rates.pricing());
+    }
+}
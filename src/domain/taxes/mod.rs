@@ -0,0 +1,13 @@
+/*! Domain module for taxes. */
+
+pub mod commands;
+pub mod model;
+pub mod queries;
+pub(in crate::domain) mod resolver;
+
+use self::model::store::TaxRatesStore;
+pub use self::{
+    commands::*,
+    model::*,
+    queries::*,
+};
@@ -0,0 +1,469 @@
+/*!
+Contains the `TaxRates` entity.
+
+Tax is calculated for each line of an order and then summed, rather than calculated once on the
+order's subtotal. That way each line's tax is a whole number of minor units and the order's tax
+always equals the sum of its lines.
+*/
+
+use std::{
+    collections::BTreeMap,
+    convert::{
+        TryFrom,
+        TryInto,
+    },
+    fmt,
+    str::FromStr,
+};
+
+use serde::{
+    de::{
+        self,
+        Deserialize,
+        Deserializer,
+    },
+    ser::{
+        Serialize,
+        Serializer,
+    },
+};
+
+pub mod store;
+
+use crate::domain::{
+    Error,
+    error,
+    infra::*,
+};
+
+pub type TaxRatesVersion = Version<TaxRatesData>;
+
+/**
+A region that has its own tax rates, like `AU` or `US-CA`.
+
+Regions are between 1 and 16 ASCII letters, digits or dashes.
+They're case-insensitive and normalized to uppercase.
+*/
+pub struct TaxRegion(String);
+
+impl TryFrom<String> for TaxRegion {
+    type Error = Error;
+
+    fn try_from(region: String) -> Result<Self, Self::Error> {
+        if region.is_empty()
+            || region.len() > 16
+            || !region
+                .bytes()
+                .all(|b| b.is_ascii_alphanumeric() || b == b'-')
+        {
+            return Err(error::bad_input(format_args!(
+                "`{}` is not a valid tax region",
+                region
+            )));
+        }
+
+        Ok(TaxRegion(region.to_ascii_uppercase()))
+    }
+}
+
+impl<'a> TryFrom<&'a str> for TaxRegion {
+    type Error = Error;
+
+    fn try_from(region: &'a str) -> Result<Self, Self::Error> {
+        Self::try_from(region.to_owned())
+    }
+}
+
+impl TaxRegion {
+    pub fn into_inner(self) -> String {
+        self.0
+    }
+}
+
+/**
+A category of product that may be taxed differently, like `standard` or `food`.
+
+Categories are between 1 and 32 ASCII letters, digits, dashes or underscores.
+They're case-insensitive and normalized to lowercase.
+*/
+pub struct TaxCategory(String);
+
+impl TaxCategory {
+    /** The category products belong to unless they're given a different one. */
+    pub const STANDARD: &'static str = "standard";
+
+    pub fn into_inner(self) -> String {
+        self.0
+    }
+}
+
+impl TryFrom<String> for TaxCategory {
+    type Error = Error;
+
+    fn try_from(category: String) -> Result<Self, Self::Error> {
+        if category.is_empty()
+            || category.len() > 32
+            || !category
+                .bytes()
+                .all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_')
+        {
+            return Err(error::bad_input(format_args!(
+                "`{}` is not a valid tax category",
+                category
+            )));
+        }
+
+        Ok(TaxCategory(category.to_ascii_lowercase()))
+    }
+}
+
+impl<'a> TryFrom<&'a str> for TaxCategory {
+    type Error = Error;
+
+    fn try_from(category: &'a str) -> Result<Self, Self::Error> {
+        Self::try_from(category.to_owned())
+    }
+}
+
+// The number of millionths in a whole rate
+const ONE: u32 = 1_000_000;
+
+/**
+A tax rate.
+
+Rates serialize as a percentage string, like `"8.875"`, with up to 4 decimal places.
+Internally they're stored in millionths so tax can be calculated exactly.
+A rate must be between 0 and 100 percent.
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TaxRate {
+    millionths: u32,
+}
+
+impl TaxRate {
+    pub const ZERO: TaxRate = TaxRate { millionths: 0 };
+
+    /**
+    Create a rate from a percentage in hundredths of a percent.
+
+    `TaxRate::from_basis_points(1000)` is a rate of `10%`.
+    */
+    pub fn from_basis_points(basis_points: u32) -> Result<Self, Error> {
+        basis_points
+            .checked_mul(100)
+            .filter(|millionths| *millionths <= ONE)
+            .map(|millionths| TaxRate { millionths })
+            .ok_or_else(|| error::bad_input("tax rates can't be more than 100 percent"))
+    }
+}
+
+impl fmt::Display for TaxRate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // Percentages are the rate in ten-thousandths of a percent
+        let whole = self.millionths / 10_000;
+        let fraction = self.millionths % 10_000;
+
+        if fraction == 0 {
+            write!(f, "{}", whole)
+        } else {
+            let fraction = format!("{:04}", fraction);
+
+            write!(f, "{}.{}", whole, fraction.trim_end_matches('0'))
+        }
+    }
+}
+
+impl FromStr for TaxRate {
+    type Err = Error;
+
+    fn from_str(rate: &str) -> Result<Self, Self::Err> {
+        let invalid = || error::bad_input(format_args!("`{}` is not a valid tax rate", rate));
+
+        let (whole, fraction) = match rate.split_once('.') {
+            Some((_, "")) => return Err(invalid()),
+            Some(parts) => parts,
+            None => (rate, ""),
+        };
+
+        if whole.is_empty()
+            || fraction.len() > 4
+            || !whole.bytes().all(|b| b.is_ascii_digit())
+            || !fraction.bytes().all(|b| b.is_ascii_digit())
+        {
+            return Err(invalid());
+        }
+
+        let whole: u32 = whole.parse().map_err(|_| invalid())?;
+        let fraction: u32 = format!("{:0<4}", fraction).parse().map_err(|_| invalid())?;
+
+        let millionths = whole
+            .checked_mul(10_000)
+            .and_then(|whole| whole.checked_add(fraction))
+            .filter(|millionths| *millionths <= ONE)
+            .ok_or_else(|| error::bad_input("tax rates can't be more than 100 percent"))?;
+
+        Ok(TaxRate { millionths })
+    }
+}
+
+impl<'a> TryFrom<&'a str> for TaxRate {
+    type Error = Error;
+
+    fn try_from(rate: &'a str) -> Result<Self, Self::Error> {
+        rate.parse()
+    }
+}
+
+impl Serialize for TaxRate {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for TaxRate {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let rate = String::deserialize(deserializer)?;
+
+        rate.parse().map_err(de::Error::custom)
+    }
+}
+
+/**
+Whether prices in a region already include tax.
+
+When prices are `Exclusive` tax is added on top of them.
+When prices are `Inclusive` tax is the portion of the price that's already tax.
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PricingMode {
+    Exclusive,
+    Inclusive,
+}
+
+/** Data for the tax rates of a region. */
+#[derive(Clone, Serialize, Deserialize)]
+pub struct TaxRatesData {
+    pub region: String,
+    pub version: TaxRatesVersion,
+    pub pricing: PricingMode,
+    pub rates: BTreeMap<String, TaxRate>,
+    _private: (),
+}
+
+/**
+The tax rates for a region.
+
+Each tax category has its own rate.
+Categories that aren't in the region can't be taxed.
+*/
+pub struct TaxRates {
+    data: TaxRatesData,
+}
+
+impl TaxRates {
+    pub(self) fn from_data(data: TaxRatesData) -> Self {
+        TaxRates { data }
+    }
+
+    pub fn into_data(self) -> TaxRatesData {
+        self.data
+    }
+
+    pub fn to_data(&self) -> &TaxRatesData {
+        &self.data
+    }
+
+    pub fn new(
+        region: impl TryInto<TaxRegion, Error = Error>,
+        pricing: PricingMode,
+        rates: impl IntoIterator<Item = (String, TaxRate)>,
+    ) -> Result<Self, Error> {
+        let mut tax_rates = TaxRates::from_data(TaxRatesData {
+            region: region.try_into()?.0,
+            version: TaxRatesVersion::default(),
+            pricing,
+            rates: BTreeMap::new(),
+            _private: (),
+        });
+
+        tax_rates.set_rates(pricing, rates)?;
+
+        Ok(tax_rates)
+    }
+
+    /** Replace the pricing mode and all rates for the region. */
+    pub fn set_rates(
+        &mut self,
+        pricing: PricingMode,
+        rates: impl IntoIterator<Item = (String, TaxRate)>,
+    ) -> Result<(), Error> {
+        let rates = rates
+            .into_iter()
+            .map(|(category, rate)| Ok((TaxCategory::try_from(category)?.0, rate)))
+            .collect::<Result<_, Error>>()?;
+
+        self.data.pricing = pricing;
+        self.data.rates = rates;
+
+        Ok(())
+    }
+
+    pub fn pricing(&self) -> PricingMode {
+        self.data.pricing
+    }
+
+    /**
+    Calculate the tax on an amount in a given category.
+
+    The tax is rounded to a whole number of minor units using the given rounding rule.
+    For `Exclusive` pricing the result is the tax to add to the amount.
+    For `Inclusive` pricing the result is the part of the amount that's tax.
+    */
+    pub fn calculate(
+        &self,
+        category: &str,
+        amount: Currency,
+        rounding: Rounding,
+    ) -> Result<Currency, Error> {
+        let Some(rate) = self.data.rates.get(category) else {
+            return Err(error::unavailable(format_args!(
+                "there's no tax rate for `{}` in {}",
+                category, self.data.region
+            )));
+        };
+
+        let numerator = u128::from(amount.minor_units()) * u128::from(rate.millionths);
+
+        let denominator = match self.data.pricing {
+            PricingMode::Exclusive => u128::from(ONE),
+            PricingMode::Inclusive => u128::from(ONE) + u128::from(rate.millionths),
+        };
+
+        // The tax is never more than the amount, so it can't overflow
+        let tax = rounding.divide(numerator, denominator) as u64;
+
+        Ok(Currency::from_minor_units(amount.code(), tax))
+    }
+}
+
+impl Entity for TaxRates {
+    type Id = TaxRegion;
+    type Version = TaxRatesVersion;
+    type Data = TaxRatesData;
+    type Error = Error;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::domain::ErrorKind;
+
+    fn rate(rate: &str) -> TaxRate {
+        rate.parse().unwrap()
+    }
+
+    #[test]
+    fn parse_rate() {
+        for (input, basis_points) in [("10", 1000), ("0.25", 25), ("0", 0), ("100", 10000)] {
+            let parsed = rate(input);
+
+            assert_eq!(TaxRate::from_basis_points(basis_points).unwrap(), parsed);
+            assert_eq!(input, parsed.to_string());
+        }
+
+        assert_eq!("8.875", rate("8.875").to_string());
+
+        for input in ["", "-1", "100.01", "1.", ".5", "1.23456", "ten"] {
+            assert!(input.parse::<TaxRate>().is_err(), "{}", input);
+        }
+    }
+
+    #[test]
+    fn region_and_category_are_normalized() {
+        assert_eq!("US-CA", TaxRegion::try_from("us-ca").unwrap().into_inner());
+        assert_eq!("food", TaxCategory::try_from("Food").unwrap().into_inner());
+
+        assert!(TaxRegion::try_from("").is_err());
+        assert!(TaxRegion::try_from("US CA").is_err());
+        assert!(TaxCategory::try_from("a/b").is_err());
+    }
+
+    #[test]
+    fn calculate_exclusive() {
+        let rates = TaxRates::new(
+            "US-NY",
+            PricingMode::Exclusive,
+            [
+                (TaxCategory::STANDARD.to_owned(), rate("8.875")),
+                ("food".to_owned(), TaxRate::ZERO),
+            ],
+        )
+        .unwrap();
+
+        // 8.875% of $10.00 is exactly 88.75c
+        assert_eq!(
+            Currency::usd(89),
+            rates
+                .calculate(TaxCategory::STANDARD, Currency::usd(1000), Rounding::HalfUp)
+                .unwrap()
+        );
+        assert_eq!(
+            Currency::usd(88),
+            rates
+                .calculate(TaxCategory::STANDARD, Currency::usd(1000), Rounding::Down)
+                .unwrap()
+        );
+        assert_eq!(
+            Currency::usd(0),
+            rates
+                .calculate("food", Currency::usd(1000), Rounding::HalfUp)
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn calculate_inclusive() {
+        let rates = TaxRates::new(
+            "AU",
+            PricingMode::Inclusive,
+            [(TaxCategory::STANDARD.to_owned(), rate("10"))],
+        )
+        .unwrap();
+
+        // $11.00 including 10% GST contains $1.00 of tax
+        assert_eq!(
+            Currency::aud(100),
+            rates
+                .calculate(TaxCategory::STANDARD, Currency::aud(1100), Rounding::HalfUp)
+                .unwrap()
+        );
+
+        // $10.00 including 10% GST contains 90.9c of tax
+        assert_eq!(
+            Currency::aud(91),
+            rates
+                .calculate(TaxCategory::STANDARD, Currency::aud(1000), Rounding::HalfUp)
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn calculate_missing_category() {
+        let rates = TaxRates::new("AU", PricingMode::Inclusive, []).unwrap();
+
+        let err = rates
+            .calculate(TaxCategory::STANDARD, Currency::aud(100), Rounding::HalfUp)
+            .err()
+            .unwrap();
+
+        assert_eq!(ErrorKind::Unavailable, err.kind());
+    }
+}
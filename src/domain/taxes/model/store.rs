@@ -0,0 +1,115 @@
+/*! Persistent storage for tax rates. */
+
+use uuid::Uuid;
+
+use crate::{
+    domain::{
+        Error,
+        taxes::*,
+    },
+    store::{
+        self,
+        Transaction,
+        TransactionStore,
+        TransactionValueStore,
+    },
+};
+
+/* A place to persist and fetch tax rates. */
+#[auto_impl(&, Arc)]
+pub(in crate::domain) trait TaxRatesStore {
+    fn get_tax_rates(&self, region: &str) -> Result<Option<TaxRates>, Error>;
+    fn set_tax_rates(&self, transaction: &Transaction, tax_rates: TaxRates) -> Result<(), Error>;
+}
+
+/** A test in-memory tax rate store. */
+pub(in crate::domain) struct InMemoryStore(TransactionValueStore<TaxRatesData>);
+
+// The namespace used to derive storage ids from tax regions
+const NAMESPACE: Uuid = Uuid::from_u128(0x2f4d_9a17_c3e8_4b06_8d5a_71e0_b9c4_3f62);
+
+fn region_id(region: &str) -> store::Id {
+    store::Id::from_raw(Uuid::new_v5(&NAMESPACE, region.as_bytes()))
+}
+
+impl TaxRatesStore for InMemoryStore {
+    fn get_tax_rates(&self, region: &str) -> Result<Option<TaxRates>, Error> {
+        if let Some((version, data)) = self.0.get(region_id(region)) {
+            assert_eq!(version, data.version.into());
+
+            Ok(Some(TaxRates::from_data(data)))
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn set_tax_rates(&self, transaction: &Transaction, tax_rates: TaxRates) -> Result<(), Error> {
+        let mut data = tax_rates.into_data();
+        let id = region_id(&data.region);
+
+        self.0.set(
+            transaction,
+            id,
+            Some(data.version),
+            data.version.next()?,
+            data,
+        )?;
+
+        Ok(())
+    }
+}
+
+/**
+Create an in-memory tax rate store.
+
+The store will participate in transactions tracked by the given transaction store.
+*/
+pub(in crate::domain) fn in_memory_store(transaction_store: TransactionStore) -> InMemoryStore {
+    InMemoryStore(TransactionValueStore::new(transaction_store))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::domain::ErrorKind;
+
+    #[test]
+    fn test_in_memory_store() {
+        let store = in_memory_store(Default::default());
+
+        store
+            .set_tax_rates(
+                &Transaction::none(),
+                TaxRates::new("AU", PricingMode::Inclusive, []).unwrap(),
+            )
+            .unwrap();
+
+        let found = store.get_tax_rates("AU").unwrap().unwrap();
+        assert_eq!(PricingMode::Inclusive, found.pricing());
+
+        assert!(store.get_tax_rates("NZ").unwrap().is_none());
+    }
+
+    #[test]
+    fn add_region_twice_fails_concurrency_check() {
+        let store = in_memory_store(Default::default());
+
+        store
+            .set_tax_rates(
+                &Transaction::none(),
+                TaxRates::new("AU", PricingMode::Inclusive, []).unwrap(),
+            )
+            .unwrap();
+
+        let err = store
+            .set_tax_rates(
+                &Transaction::none(),
+                TaxRates::new("AU", PricingMode::Exclusive, []).unwrap(),
+            )
+            .err()
+            .unwrap();
+
+        assert_eq!(ErrorKind::Conflict, err.kind());
+    }
+}
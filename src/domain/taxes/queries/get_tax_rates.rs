@@ -0,0 +1,37 @@
+/*! Contains the `GetTaxRatesQuery` type. */
+
+use std::convert::TryFrom;
+
+use crate::domain::{
+    Error,
+    infra::*,
+    taxes::*,
+};
+
+/** Input for a `GetTaxRatesQuery`. */
+#[derive(Serialize, Deserialize)]
+pub struct GetTaxRates {
+    pub region: String,
+}
+
+impl QueryArgs for GetTaxRates {
+    type Output = Result<Option<TaxRates>, Error>;
+}
+
+/** Default implementation for a `GetTaxRatesQuery`. */
+async fn execute(query: GetTaxRates, store: impl TaxRatesStore) -> Result<Option<TaxRates>, Error> {
+    let region = TaxRegion::try_from(query.region)?.into_inner();
+
+    store.get_tax_rates(&region)
+}
+
+impl Resolver {
+    /** Get the tax rates for a region. */
+    pub fn get_tax_rates_query(&self) -> impl Query<GetTaxRates> {
+        self.query(|resolver, query: GetTaxRates| async move {
+            let store = resolver.tax_rates_store();
+
+            execute(query, store).await
+        })
+    }
+}
@@ -0,0 +1,5 @@
+/*! Queries for fetching tax state. */
+
+mod get_tax_rates;
+
+pub use self::get_tax_rates::*;
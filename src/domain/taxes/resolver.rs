@@ -0,0 +1,59 @@
+/*! Contains the `TaxesResolver` type. */
+
+use std::sync::Arc;
+
+use crate::domain::{
+    infra::*,
+    taxes::model::store::{
+        self,
+        InMemoryStore,
+        TaxRatesStore,
+    },
+};
+
+/**
+Resolver for taxes.
+
+The `TaxesResolver` type wraps private implementation details and exposes them as traits within the `taxes` module.
+*/
+#[derive(Clone)]
+pub(in crate::domain) struct TaxesResolver {
+    tax_rates_store: Register<Arc<InMemoryStore>>,
+    rounding: Register<Rounding>,
+}
+
+impl Default for TaxesResolver {
+    fn default() -> Self {
+        TaxesResolver {
+            tax_rates_store: Register::per_tenant(|resolver| {
+                Arc::new(store::in_memory_store(resolver.transaction_store()))
+            }),
+            rounding: Register::once(|_| Rounding::HalfUp),
+        }
+    }
+}
+
+impl AppBuilder {
+    /**
+    Use a different rule for rounding calculated tax.
+
+    Tax is rounded half up by default.
+    */
+    pub fn tax_rounding(mut self, rounding: Register<Rounding>) -> Self {
+        self.root_resolver.taxes_resolver.rounding = rounding;
+        self
+    }
+}
+
+impl Resolver {
+    pub fn tax_rounding(&self) -> Rounding {
+        match self.tenant_config().and_then(|config| config.tax_rounding) {
+            Some(rounding) => rounding,
+            None => self.resolve(&self.taxes_resolver.rounding),
+        }
+    }
+
+    pub(in crate::domain::taxes) fn tax_rates_store(&self) -> impl TaxRatesStore {
+        self.resolve(&self.taxes_resolver.tax_rates_store)
+    }
+}
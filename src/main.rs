@@ -13,7 +13,7 @@ async fn main() -> ExitCode {
 
     emit::info!("starting up");
 
-    let exit = match shop::api::init().ignite().await {
+    let exit = match shop::api::init(shop::domain::App::new()).ignite().await {
         Ok(rocket) => {
             let listen = format!("{}:{}", rocket.config().address, rocket.config().port);
 
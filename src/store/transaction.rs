@@ -4,6 +4,10 @@ use std::{
     sync::{
         Arc,
         Mutex,
+        atomic::{
+            AtomicU64,
+            Ordering,
+        },
     },
 };
 
@@ -23,6 +27,8 @@ pub struct TransactionId(Uuid);
 
 struct TransactionEntry {
     status: TransactionStatus,
+    changed: bool,
+    on_commit: Vec<Box<dyn FnOnce() + Send>>,
 }
 
 enum TransactionStatus {
@@ -82,6 +88,7 @@ of transactions.
 #[derive(Clone)]
 pub struct TransactionStore {
     active: Arc<Mutex<HashMap<TransactionId, TransactionEntry>>>,
+    generation: Arc<AtomicU64>,
 }
 
 impl Default for TransactionStore {
@@ -100,6 +107,7 @@ impl TransactionStore {
     pub fn new() -> Self {
         TransactionStore {
             active: Arc::new(Mutex::new(HashMap::new())),
+            generation: Arc::new(AtomicU64::new(0)),
         }
     }
 
@@ -117,6 +125,8 @@ impl TransactionStore {
             TransactionId(id),
             TransactionEntry {
                 status: TransactionStatus::Active,
+                changed: false,
+                on_commit: Vec::new(),
             },
         );
 
@@ -131,6 +141,7 @@ impl TransactionStore {
 
                     if let Some(transaction) = transactions.get_mut(&id) {
                         transaction.status = TransactionStatus::Cancelled;
+                        transaction.on_commit.clear();
                     }
                 }))
             },
@@ -143,13 +154,28 @@ impl TransactionStore {
     pub fn commit(&self, mut transaction: Transaction) {
         drop(transaction.complete_guard.take());
 
-        let mut transactions = self.active.lock().unwrap();
+        let on_commit = {
+            let mut transactions = self.active.lock().unwrap();
+
+            // NOTE: Only removing transactions when they commit means we'll eventually run out of
+            // space if they fail. In a degenerate scenario where everything fails this might not
+            // take very long. We could avoid this by tracking whether or not transactions are still
+            // reachable and whether or not their ids appear in any data stores.
+            match transactions.remove(&transaction.id) {
+                Some(entry) => {
+                    if entry.changed {
+                        self.generation.fetch_add(1, Ordering::SeqCst);
+                    }
 
-        // NOTE: Only removing transactions when they commit means we'll eventually run out of
-        // space if they fail. In a degenerate scenario where everything fails this might not
-        // take very long. We could avoid this by tracking whether or not transactions are still
-        // reachable and whether or not their ids appear in any data stores.
-        let _ = transactions.remove(&transaction.id);
+                    entry.on_commit
+                }
+                None => Vec::new(),
+            }
+        };
+
+        for f in on_commit {
+            f();
+        }
     }
 
     /**
@@ -162,9 +188,32 @@ impl TransactionStore {
 
         if let Some(transaction) = transactions.get_mut(&transaction.id) {
             transaction.status = TransactionStatus::Cancelled;
+            transaction.on_commit.clear();
         }
     }
 
+    /**
+    Run a function once the changes made in a given transaction become observable.
+
+    If the transaction isn't active then its changes are already observable, so the function is run immediately.
+    If the transaction is cancelled then the function is never run.
+    */
+    pub fn on_commit(&self, id: TransactionId, f: impl FnOnce() + Send + 'static) {
+        {
+            let mut transactions = self.active.lock().unwrap();
+
+            if let Some(transaction) = transactions.get_mut(&id) {
+                if let TransactionStatus::Active = transaction.status {
+                    transaction.on_commit.push(Box::new(f));
+                }
+
+                return;
+            }
+        }
+
+        f();
+    }
+
     /**
     Whether or not a given transaction was committed.
     */
@@ -175,6 +224,32 @@ impl TransactionStore {
         !transactions.contains_key(&id)
     }
 
+    /**
+    Record that a value was changed in a given transaction.
+
+    If the transaction isn't active then the change is already observable.
+    */
+    pub(crate) fn changed(&self, id: TransactionId) {
+        let mut transactions = self.active.lock().unwrap();
+
+        match transactions.get_mut(&id) {
+            Some(transaction) => transaction.changed = true,
+            None => {
+                self.generation.fetch_add(1, Ordering::SeqCst);
+            }
+        }
+    }
+
+    /**
+    A counter that increases each time changes to values become observable.
+
+    Callers can compare generations to tell whether any data has changed since they last looked.
+    Changes made by active or cancelled transactions don't affect the generation.
+    */
+    pub fn generation(&self) -> u64 {
+        self.generation.load(Ordering::SeqCst)
+    }
+
     /**
     Whether or not a given transaction was cancelled.
     */
@@ -243,4 +318,73 @@ mod tests {
 
         assert!(store.is_committed(id));
     }
+
+    #[test]
+    fn generation_increases_when_changes_are_committed() {
+        let store = TransactionStore::new();
+
+        let unchanged = store.begin();
+        store.commit(unchanged);
+
+        assert_eq!(0, store.generation());
+
+        let changed = store.begin();
+        store.changed(changed.id());
+
+        assert_eq!(0, store.generation());
+
+        store.commit(changed);
+
+        assert_eq!(1, store.generation());
+
+        let cancelled = store.begin();
+        store.changed(cancelled.id());
+        store.cancel(cancelled);
+
+        assert_eq!(1, store.generation());
+
+        store.changed(Transaction::none().id());
+
+        assert_eq!(2, store.generation());
+    }
+
+    #[test]
+    fn on_commit_runs_when_transaction_commits() {
+        let store = TransactionStore::new();
+        let runs = Arc::new(AtomicU64::new(0));
+
+        let committed = store.begin();
+        store.on_commit(committed.id(), {
+            let runs = runs.clone();
+            move || {
+                runs.fetch_add(1, Ordering::SeqCst);
+            }
+        });
+
+        assert_eq!(0, runs.load(Ordering::SeqCst));
+
+        store.commit(committed);
+
+        assert_eq!(1, runs.load(Ordering::SeqCst));
+
+        let cancelled = store.begin();
+        store.on_commit(cancelled.id(), {
+            let runs = runs.clone();
+            move || {
+                runs.fetch_add(1, Ordering::SeqCst);
+            }
+        });
+        store.cancel(cancelled);
+
+        assert_eq!(1, runs.load(Ordering::SeqCst));
+
+        store.on_commit(Transaction::none().id(), {
+            let runs = runs.clone();
+            move || {
+                runs.fetch_add(1, Ordering::SeqCst);
+            }
+        });
+
+        assert_eq!(2, runs.load(Ordering::SeqCst));
+    }
 }
@@ -18,6 +18,15 @@ use crate::store::{
     },
 };
 
+/**
+An error setting a value with a version that doesn't match its current one.
+
+This means the value was changed by some other caller since it was read.
+*/
+#[derive(Error, Debug)]
+#[error("version mismatch")]
+pub struct VersionMismatch;
+
 /**
 An identifier for a transactional value.
 */
@@ -30,6 +39,12 @@ impl fmt::Display for Id {
     }
 }
 
+impl Default for Id {
+    fn default() -> Self {
+        Id::new()
+    }
+}
+
 impl Id {
     pub fn new() -> Self {
         Id(Uuid::new_v4())
@@ -53,6 +68,12 @@ of a value to its previous one.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Version(Uuid);
 
+impl Default for Version {
+    fn default() -> Self {
+        Version::new()
+    }
+}
+
 impl Version {
     pub fn new() -> Self {
         Version(Uuid::new_v4())
@@ -61,10 +82,6 @@ impl Version {
     pub(crate) fn from_raw(version: Uuid) -> Version {
         Version(version)
     }
-
-    pub(crate) fn into_raw(self) -> Uuid {
-        self.0
-    }
 }
 
 struct TransactionalValue<T> {
@@ -153,19 +170,18 @@ where
         transactions: &TransactionStore,
         data: &'a HashMap<Id, TransactionalValue<T>>,
     ) -> Option<(Version, &'a T)> {
-        if let Some(existing) = data.get(&id) {
-            if let Some((existing_transaction, existing_version, ref existing_value)) =
+        if let Some(existing) = data.get(&id)
+            && let Some((existing_transaction, existing_version, ref existing_value)) =
                 existing.current
-            {
-                if transactions.is_committed(existing_transaction) {
-                    return Some((existing_version, existing_value));
-                }
+        {
+            if transactions.is_committed(existing_transaction) {
+                return Some((existing_version, existing_value));
+            }
 
-                if let Some((prior_transaction, prior_version, ref prior_value)) = existing.prior {
-                    assert!(transactions.is_committed(prior_transaction));
+            if let Some((prior_transaction, prior_version, ref prior_value)) = existing.prior {
+                assert!(transactions.is_committed(prior_transaction));
 
-                    return Some((prior_version, prior_value));
-                }
+                return Some((prior_version, prior_value));
             }
         }
 
@@ -250,7 +266,7 @@ where
                             };
 
                         if old_version != version_to_check {
-                            return Err(Error::from("version mismatch"));
+                            return Err(VersionMismatch.into());
                         }
 
                         // Now, we're going to set the value
@@ -288,6 +304,8 @@ where
             }
         }
 
+        self.transactions.changed(transaction.id());
+
         Ok(())
     }
 }
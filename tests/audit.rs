@@ -0,0 +1,157 @@
+#[macro_use]
+extern crate rocket;
+
+#[macro_use]
+extern crate serde_json;
+
+use rocket::{
+    http::{
+        Header,
+        Status,
+    },
+    local::asynchronous::Client,
+};
+
+use shop::domain::App;
+
+#[async_test]
+async fn get_entries_for_entity() {
+    let app = Client::untracked(
+        shop::api::init(App::new())
+            .configure(rocket::Config::figment().merge(("actor_header", true))),
+    )
+    .await
+    .expect("invalid app");
+
+    let put = app
+        .put("/products")
+        .header(Header::new("X-Actor", "jo@example.com"))
+        .json(&json!({
+            "title": "A new product",
+            "price": {
+                "usd": {
+                    "cents": 123
+                }
+            }
+        }))
+        .dispatch()
+        .await;
+
+    assert_eq!(Status::Created, put.status());
+    let id: String = serde_json::from_str(&put.into_string().await.expect("missing body"))
+        .expect("invalid value");
+
+    let post = app
+        .post(format!("/products/{}/title/A%20new%20title", id))
+        .dispatch()
+        .await;
+    assert_eq!(Status::Ok, post.status());
+
+    let get = app
+        .get(format!("/admin/audit?entity={}", id))
+        .dispatch()
+        .await;
+
+    assert_eq!(Status::Ok, get.status());
+    let entries: serde_json::Value =
+        serde_json::from_str(&get.into_string().await.expect("missing body"))
+            .expect("invalid value");
+    let entries = entries.as_array().expect("invalid entries");
+
+    assert_eq!(2, entries.len());
+
+    assert_eq!("CreateProduct", entries[0]["command"]);
+    assert_eq!("jo@example.com", entries[0]["actor"]);
+    assert_eq!(json!({ "status": "succeeded" }), entries[0]["outcome"]);
+
+    assert_eq!("SetProductTitle", entries[1]["command"]);
+    assert_eq!("anonymous", entries[1]["actor"]);
+    assert_eq!("A new title", entries[1]["args"]["title"]);
+
+    // Entries outside of the time range aren't returned
+    let get = app
+        .get(format!(
+            "/admin/audit?entity={}&from=2100-01-01T00:00:00Z",
+            id
+        ))
+        .dispatch()
+        .await;
+
+    assert_eq!(Status::Ok, get.status());
+    let entries: serde_json::Value =
+        serde_json::from_str(&get.into_string().await.expect("missing body"))
+            .expect("invalid value");
+
+    assert_eq!(0, entries.as_array().expect("invalid entries").len());
+}
+
+#[async_test]
+async fn failed_commands_are_recorded() {
+    let app = Client::untracked(shop::api::init(App::new()))
+        .await
+        .expect("invalid app");
+
+    let id = "6f0c1c6e-6b7a-4a6e-9a4b-2a3b6e5d7c11";
+
+    let post = app
+        .post(format!("/products/{}/title/A%20new%20title", id))
+        .dispatch()
+        .await;
+    assert_eq!(Status::NotFound, post.status());
+
+    // The entry outlives the transaction the command failed in
+    let get = app
+        .get(format!("/admin/audit?entity={}", id))
+        .dispatch()
+        .await;
+
+    assert_eq!(Status::Ok, get.status());
+    let entries: serde_json::Value =
+        serde_json::from_str(&get.into_string().await.expect("missing body"))
+            .expect("invalid value");
+
+    let entries = entries.as_array().expect("invalid entries");
+
+    assert_eq!(1, entries.len());
+
+    assert_eq!("SetProductTitle", entries[0]["command"]);
+    assert_eq!("failed", entries[0]["outcome"]["status"]);
+    assert_eq!("product not found", entries[0]["outcome"]["reason"]);
+}
+
+#[async_test]
+async fn ignore_actor_header_unless_enabled() {
+    let app = Client::untracked(shop::api::init(App::new()))
+        .await
+        .expect("invalid app");
+
+    let put = app
+        .put("/products")
+        .header(Header::new("X-Actor", "jo@example.com"))
+        .json(&json!({
+            "title": "A new product",
+            "price": {
+                "usd": {
+                    "cents": 123
+                }
+            }
+        }))
+        .dispatch()
+        .await;
+
+    assert_eq!(Status::Created, put.status());
+    let id: String = serde_json::from_str(&put.into_string().await.expect("missing body"))
+        .expect("invalid value");
+
+    let get = app
+        .get(format!("/admin/audit?entity={}", id))
+        .dispatch()
+        .await;
+
+    assert_eq!(Status::Ok, get.status());
+    let entries: serde_json::Value =
+        serde_json::from_str(&get.into_string().await.expect("missing body"))
+            .expect("invalid value");
+
+    assert_eq!("anonymous", entries[0]["actor"]);
+}
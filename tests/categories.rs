@@ -0,0 +1,156 @@
+#[macro_use]
+extern crate rocket;
+
+#[macro_use]
+extern crate serde_json;
+
+use rocket::{
+    http::Status,
+    local::asynchronous::Client,
+};
+
+use shop::domain::App;
+
+async fn create_category(app: &Client, name: &str, parent_id: Option<&str>) -> String {
+    let put = app
+        .put("/categories")
+        .json(&json!({
+            "name": name,
+            "parent_id": parent_id
+        }))
+        .dispatch()
+        .await;
+
+    assert_eq!(Status::Created, put.status());
+    serde_json::from_str(&put.into_string().await.expect("missing body")).expect("invalid value")
+}
+
+async fn create_product(app: &Client, title: &str) -> String {
+    let put = app
+        .put("/products")
+        .json(&json!({
+            "title": title,
+            "price": {
+                "usd": {
+                    "cents": 100
+                }
+            }
+        }))
+        .dispatch()
+        .await;
+
+    assert_eq!(Status::Created, put.status());
+    serde_json::from_str(&put.into_string().await.expect("missing body")).expect("invalid value")
+}
+
+async fn list_titles(app: &Client, query: &str) -> Vec<String> {
+    let get = app
+        .get(format!("/products?sort=title&{}", query))
+        .dispatch()
+        .await;
+
+    assert_eq!(Status::Ok, get.status());
+    let page: serde_json::Value =
+        serde_json::from_str(&get.into_string().await.expect("missing body"))
+            .expect("invalid value");
+
+    page["products"]
+        .as_array()
+        .expect("invalid products")
+        .iter()
+        .map(|product| product["title"].as_str().expect("invalid title").to_owned())
+        .collect()
+}
+
+#[async_test]
+async fn browse_category() {
+    let app = Client::untracked(shop::api::init(App::new()))
+        .await
+        .expect("invalid app");
+
+    let clothing = create_category(&app, "Clothing", None).await;
+    let shirts = create_category(&app, "Shirts", Some(&clothing)).await;
+
+    let shirt = create_product(&app, "Red shirt").await;
+    let hat = create_product(&app, "Green hat").await;
+    create_product(&app, "Novel").await;
+
+    for (product, category) in [(&shirt, &shirts), (&hat, &clothing)] {
+        let put = app
+            .put(format!("/products/{}/category/{}", product, category))
+            .dispatch()
+            .await;
+
+        assert_eq!(Status::Ok, put.status());
+    }
+
+    // Browsing a category includes products in its descendants
+    assert_eq!(
+        vec!["Green hat", "Red shirt"],
+        list_titles(&app, &format!("category={}", clothing)).await
+    );
+    assert_eq!(
+        vec!["Red shirt"],
+        list_titles(&app, &format!("category={}", shirts)).await
+    );
+
+    // A category can't be moved beneath its own descendants
+    let post = app
+        .post(format!("/categories/{}/move", clothing))
+        .json(&json!({ "parent_id": shirts }))
+        .dispatch()
+        .await;
+
+    assert_eq!(Status::BadRequest, post.status());
+
+    // Moving a category takes its products with it
+    let post = app
+        .post(format!("/categories/{}/move", shirts))
+        .json(&json!({ "parent_id": null }))
+        .dispatch()
+        .await;
+
+    assert_eq!(Status::Ok, post.status());
+    assert_eq!(
+        vec!["Green hat"],
+        list_titles(&app, &format!("category={}", clothing)).await
+    );
+
+    let get = app.get("/categories").dispatch().await;
+
+    assert_eq!(Status::Ok, get.status());
+    let trees: serde_json::Value =
+        serde_json::from_str(&get.into_string().await.expect("missing body"))
+            .expect("invalid value");
+
+    assert_eq!(2, trees.as_array().expect("invalid trees").len());
+}
+
+#[async_test]
+async fn filter_by_tag() {
+    let app = Client::untracked(shop::api::init(App::new()))
+        .await
+        .expect("invalid app");
+
+    let shirt = create_product(&app, "Red shirt").await;
+    create_product(&app, "Green hat").await;
+
+    let put = app
+        .put(format!("/products/{}/tags", shirt))
+        .json(&json!(["Summer", "sale"]))
+        .dispatch()
+        .await;
+
+    assert_eq!(Status::Ok, put.status());
+
+    let get = app.get(format!("/products/{}", shirt)).dispatch().await;
+
+    assert_eq!(Status::Ok, get.status());
+    let product: serde_json::Value =
+        serde_json::from_str(&get.into_string().await.expect("missing body"))
+            .expect("invalid value");
+
+    assert_eq!(json!(["sale", "summer"]), product["tags"]);
+
+    assert_eq!(vec!["Red shirt"], list_titles(&app, "tag=summer").await);
+}
@@ -5,13 +5,18 @@ extern crate rocket;
 extern crate serde_json;
 
 use rocket::{
-    http::Status,
+    http::{
+        Header,
+        Status,
+    },
     local::asynchronous::Client,
 };
 
+use shop::domain::App;
+
 #[async_test]
 async fn set_get() {
-    let app = Client::untracked(shop::api::init())
+    let app = Client::untracked(shop::api::init(App::new()))
         .await
         .expect("invalid app");
 
@@ -24,3 +29,69 @@ async fn set_get() {
     let get = app.get(format!("/customers/{}", id)).dispatch().await;
     assert_eq!(Status::Ok, get.status());
 }
+
+#[async_test]
+async fn set_get_with_etag() {
+    let app = Client::untracked(shop::api::init(App::new()))
+        .await
+        .expect("invalid app");
+
+    let put = app.put("/customers").json(&json!({})).dispatch().await;
+
+    let id: String = serde_json::from_str(&put.into_string().await.expect("missing body"))
+        .expect("invalid value");
+
+    let get = app.get(format!("/customers/{}", id)).dispatch().await;
+
+    assert_eq!(Status::Ok, get.status());
+    let etag = get
+        .headers()
+        .get_one("ETag")
+        .expect("missing etag")
+        .to_owned();
+    let customer: serde_json::Value =
+        serde_json::from_str(&get.into_string().await.expect("missing body"))
+            .expect("invalid value");
+
+    assert_eq!(
+        format!("\"{}\"", customer["version"].as_str().unwrap()),
+        etag
+    );
+
+    // The customer hasn't changed
+    let get = app
+        .get(format!("/customers/{}", id))
+        .header(Header::new("If-None-Match", etag.clone()))
+        .dispatch()
+        .await;
+
+    assert_eq!(Status::NotModified, get.status());
+
+    // Archive the customer using its current version
+    let post = app
+        .post(format!("/customers/{}/archive", id))
+        .header(Header::new("If-Match", etag.clone()))
+        .dispatch()
+        .await;
+
+    assert_eq!(Status::Ok, post.status());
+
+    // The version is now stale
+    let post = app
+        .post(format!("/customers/{}/restore", id))
+        .header(Header::new("If-Match", etag.clone()))
+        .dispatch()
+        .await;
+
+    assert_eq!(Status::PreconditionFailed, post.status());
+
+    let get = app
+        .get(format!("/customers/{}?archived=true", id))
+        .dispatch()
+        .await;
+    let customer: serde_json::Value =
+        serde_json::from_str(&get.into_string().await.expect("missing body"))
+            .expect("invalid value");
+
+    assert_eq!(true, customer["archived"]);
+}
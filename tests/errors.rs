@@ -0,0 +1,69 @@
+#[macro_use]
+extern crate rocket;
+
+#[macro_use]
+extern crate serde_json;
+
+use rocket::{
+    http::Status,
+    local::asynchronous::Client,
+};
+
+use shop::domain::App;
+
+#[async_test]
+async fn uncaught_errors_have_codes() {
+    let app = Client::untracked(shop::api::init(App::new()))
+        .await
+        .expect("invalid app");
+
+    let get = app.get("/not-a-route").dispatch().await;
+
+    assert_eq!(Status::NotFound, get.status());
+    let err: serde_json::Value =
+        serde_json::from_str(&get.into_string().await.expect("missing body"))
+            .expect("invalid value");
+
+    assert_eq!("not_found", err["code"]);
+
+    let put = app.put("/products").json(&json!({})).dispatch().await;
+
+    assert_eq!(Status::UnprocessableEntity, put.status());
+    let err: serde_json::Value =
+        serde_json::from_str(&put.into_string().await.expect("missing body"))
+            .expect("invalid value");
+
+    assert_eq!("bad_request", err["code"]);
+}
+
+#[async_test]
+async fn forbidden_errors_have_codes() {
+    let app = Client::untracked(shop::api::init(App::new()))
+        .await
+        .expect("invalid app");
+
+    let put = app.put("/customers").json(&json!({})).dispatch().await;
+
+    let customer_id: String = serde_json::from_str(&put.into_string().await.expect("missing body"))
+        .expect("invalid value");
+
+    let post = app
+        .post(format!("/customers/{}/archive", customer_id))
+        .dispatch()
+        .await;
+    assert_eq!(Status::Ok, post.status());
+
+    // Archived customers can't place new orders
+    let put = app
+        .put("/orders")
+        .json(&json!({ "customer": customer_id }))
+        .dispatch()
+        .await;
+
+    assert_eq!(Status::Forbidden, put.status());
+    let err: serde_json::Value =
+        serde_json::from_str(&put.into_string().await.expect("missing body"))
+            .expect("invalid value");
+
+    assert_eq!("forbidden", err["code"]);
+}
@@ -0,0 +1,144 @@
+#[macro_use]
+extern crate rocket;
+
+#[macro_use]
+extern crate serde_json;
+
+use rocket::{
+    http::Status,
+    local::asynchronous::Client,
+};
+
+use shop::domain::App;
+
+#[async_test]
+async fn set_get() {
+    let app = Client::untracked(shop::api::init(App::new()))
+        .await
+        .expect("invalid app");
+
+    let get = app.get("/admin/exchange-rates").dispatch().await;
+
+    assert_eq!(Status::NotFound, get.status());
+
+    let put = app
+        .put("/admin/exchange-rates")
+        .json(&json!({
+            "base": "USD",
+            "rates": {
+                "EUR": "0.9215"
+            }
+        }))
+        .dispatch()
+        .await;
+
+    assert_eq!(Status::Ok, put.status());
+
+    let get = app.get("/admin/exchange-rates").dispatch().await;
+
+    assert_eq!(Status::Ok, get.status());
+    let rates: serde_json::Value =
+        serde_json::from_str(&get.into_string().await.expect("missing body"))
+            .expect("invalid value");
+
+    assert_eq!(
+        json!({
+            "base": "USD",
+            "rates": {
+                "USD": "1",
+                "EUR": "0.9215"
+            }
+        }),
+        rates
+    );
+}
+
+#[async_test]
+async fn set_invalid_rate() {
+    let app = Client::untracked(shop::api::init(App::new()))
+        .await
+        .expect("invalid app");
+
+    let put = app
+        .put("/admin/exchange-rates")
+        .json(&json!({
+            "base": "USD",
+            "rates": {
+                "EUR": "-1"
+            }
+        }))
+        .dispatch()
+        .await;
+
+    assert_eq!(Status::UnprocessableEntity, put.status());
+}
+
+#[async_test]
+async fn get_product_in_currency() {
+    let app = Client::untracked(shop::api::init(App::new()))
+        .await
+        .expect("invalid app");
+
+    let put = app
+        .put("/products")
+        .json(&json!({
+            "title": "A new product",
+            "price": {
+                "usd": {
+                    "cents": 1000
+                }
+            }
+        }))
+        .dispatch()
+        .await;
+
+    assert_eq!(Status::Created, put.status());
+    let id: String = serde_json::from_str(&put.into_string().await.expect("missing body"))
+        .expect("invalid value");
+
+    // Converting without any exchange rates fails
+    let get = app
+        .get(format!("/products/{}?currency=EUR", id))
+        .dispatch()
+        .await;
+
+    assert_eq!(Status::ServiceUnavailable, get.status());
+
+    app.put("/admin/exchange-rates")
+        .json(&json!({
+            "base": "USD",
+            "rates": {
+                "EUR": "0.9215"
+            }
+        }))
+        .dispatch()
+        .await;
+
+    let get = app
+        .get(format!("/products/{}?currency=eur", id))
+        .dispatch()
+        .await;
+
+    assert_eq!(Status::Ok, get.status());
+    let product: serde_json::Value =
+        serde_json::from_str(&get.into_string().await.expect("missing body"))
+            .expect("invalid value");
+
+    assert_eq!(json!({"usd": {"cents": 1000}}), product["price"]);
+    assert_eq!(json!({"eur": {"cents": 922}}), product["converted_price"]);
+}
+
+#[async_test]
+async fn invalid_exchange_rates_config() {
+    let rocket = shop::api::init(App::new())
+        .configure(rocket::Config::figment().merge(("exchange_rates", 42)));
+
+    let Err(err) = Client::untracked(rocket).await else {
+        panic!("expected the app to fail to start");
+    };
+
+    assert!(matches!(
+        err.kind(),
+        rocket::error::ErrorKind::FailedFairings(_)
+    ));
+}
@@ -0,0 +1,96 @@
+#[macro_use]
+extern crate rocket;
+
+#[macro_use]
+extern crate serde_json;
+
+use rocket::{
+    http::Status,
+    local::asynchronous::Client,
+};
+
+use shop::domain::App;
+
+async fn create(app: &Client, path: &str, body: serde_json::Value) -> String {
+    let put = app.put(path).json(&body).dispatch().await;
+
+    assert_eq!(Status::Created, put.status());
+    serde_json::from_str(&put.into_string().await.expect("missing body")).expect("invalid value")
+}
+
+async fn available(app: &Client, product_id: &str) -> u64 {
+    let get = app
+        .get(format!("/inventory/{}", product_id))
+        .dispatch()
+        .await;
+
+    assert_eq!(Status::Ok, get.status());
+    let stock: serde_json::Value =
+        serde_json::from_str(&get.into_string().await.expect("missing body"))
+            .expect("invalid value");
+
+    stock["available"].as_u64().expect("invalid stock")
+}
+
+#[async_test]
+async fn reserve_stock() {
+    let app = Client::untracked(shop::api::init(App::new()))
+        .await
+        .expect("invalid app");
+
+    let product_id = create(
+        &app,
+        "/products",
+        json!({
+            "title": "A product",
+            "price": {
+                "usd": {
+                    "cents": 100
+                }
+            }
+        }),
+    )
+    .await;
+
+    // Products aren't tracked until their stock level is set
+    let get = app
+        .get(format!("/inventory/{}", product_id))
+        .dispatch()
+        .await;
+    assert_eq!(Status::NotFound, get.status());
+
+    let put = app
+        .put(format!("/inventory/{}", product_id))
+        .json(&json!({ "on_hand": 3 }))
+        .dispatch()
+        .await;
+    assert_eq!(Status::Ok, put.status());
+
+    let customer_id = create(&app, "/customers", json!({})).await;
+    let first = create(&app, "/orders", json!({ "customer": customer_id })).await;
+    let second = create(&app, "/orders", json!({ "customer": customer_id })).await;
+
+    let add = |order_id: &str, quantity: u32| {
+        app.post(format!("/orders/{}/products/{}", order_id, product_id))
+            .json(&json!({ "quantity": quantity }))
+    };
+
+    assert_eq!(Status::Ok, add(&first, 2).dispatch().await.status());
+    assert_eq!(1, available(&app, &product_id).await);
+
+    // Orders can't exceed the available stock
+    assert_eq!(Status::Conflict, add(&first, 4).dispatch().await.status());
+    assert_eq!(Status::Conflict, add(&second, 2).dispatch().await.status());
+    assert_eq!(1, available(&app, &product_id).await);
+
+    // Removing the product from an order releases its stock
+    let delete = app
+        .delete(format!("/orders/{}/products/{}", first, product_id))
+        .dispatch()
+        .await;
+    assert_eq!(Status::Ok, delete.status());
+    assert_eq!(3, available(&app, &product_id).await);
+
+    assert_eq!(Status::Ok, add(&second, 2).dispatch().await.status());
+    assert_eq!(1, available(&app, &product_id).await);
+}
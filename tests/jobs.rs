@@ -0,0 +1,223 @@
+#[macro_use]
+extern crate rocket;
+
+#[macro_use]
+extern crate serde_json;
+
+use rocket::{
+    http::Status,
+    local::asynchronous::Client,
+};
+
+use shop::domain::{
+    App,
+    customers::{
+        CreateCustomer,
+        CustomerId,
+    },
+    infra::*,
+    jobs::{
+        CompleteJob,
+        GetJobs,
+    },
+};
+
+async fn init() -> Client {
+    let app = App::builder()
+        .job("create-customer", |app, args: CreateCustomer| async move {
+            app.create_customer_command().execute(args).await
+        })
+        .build();
+
+    Client::untracked(shop::api::init(app))
+        .await
+        .expect("invalid app")
+}
+
+async fn schedule(app: &Client, customer_id: CustomerId) -> String {
+    let put = app
+        .put("/admin/jobs")
+        .json(&json!({
+            "kind": "create-customer",
+            "args": {
+                "id": customer_id
+            },
+            "schedule": {
+                "delay": {
+                    "seconds": 0
+                }
+            }
+        }))
+        .dispatch()
+        .await;
+
+    assert_eq!(Status::Created, put.status());
+    serde_json::from_str(&put.into_string().await.expect("missing body")).expect("invalid value")
+}
+
+async fn get(app: &Client, id: &str) -> serde_json::Value {
+    let get = app.get(format!("/admin/jobs/{}", id)).dispatch().await;
+
+    assert_eq!(Status::Ok, get.status());
+    serde_json::from_str(&get.into_string().await.expect("missing body")).expect("invalid value")
+}
+
+async fn run_due_jobs(app: &Client) -> usize {
+    shop::api::jobs::run_due_jobs(app.rocket().state::<App>().expect("missing app"))
+        .await
+        .expect("failed to run jobs")
+}
+
+#[async_test]
+async fn schedule_run() {
+    let app = init().await;
+
+    let customer_id = CustomerId::new();
+    let id = schedule(&app, customer_id).await;
+
+    assert_eq!("pending", get(&app, &id).await["status"]);
+
+    assert_eq!(1, run_due_jobs(&app).await);
+
+    let job = get(&app, &id).await;
+    assert_eq!("succeeded", job["status"]);
+    assert_eq!(1, job["runs"]);
+
+    let customer = app
+        .get(format!("/customers/{}", customer_id))
+        .dispatch()
+        .await;
+    assert_eq!(Status::Ok, customer.status());
+
+    // Completed jobs aren't run again
+    assert_eq!(0, run_due_jobs(&app).await);
+}
+
+#[async_test]
+async fn failed_job() {
+    let app = init().await;
+
+    let customer_id = CustomerId::new();
+    let first = schedule(&app, customer_id).await;
+    let second = schedule(&app, customer_id).await;
+
+    assert_eq!(2, run_due_jobs(&app).await);
+
+    assert_eq!("succeeded", get(&app, &first).await["status"]);
+
+    let job = get(&app, &second).await;
+    assert_eq!("failed", job["status"]);
+    assert!(job["last_error"].is_string());
+}
+
+#[async_test]
+async fn schedule_unknown_kind() {
+    let app = init().await;
+
+    let put = app
+        .put("/admin/jobs")
+        .json(&json!({
+            "kind": "missing",
+            "schedule": {
+                "interval": {
+                    "seconds": 60
+                }
+            }
+        }))
+        .dispatch()
+        .await;
+
+    assert_eq!(Status::BadRequest, put.status());
+}
+
+#[async_test]
+async fn expire_reservations_by_default() {
+    let app = init().await;
+
+    // Reservations are expired on an interval without scheduling anything
+    let list = app.get("/admin/jobs").dispatch().await;
+
+    assert_eq!(Status::Ok, list.status());
+    let jobs: serde_json::Value =
+        serde_json::from_str(&list.into_string().await.expect("missing body"))
+            .expect("invalid value");
+
+    let jobs = jobs.as_array().expect("invalid jobs");
+
+    assert_eq!(1, jobs.len());
+    assert_eq!("expire-reservations", jobs[0]["kind"]);
+    assert_eq!(
+        json!({ "interval": { "seconds": 60 } }),
+        jobs[0]["schedule"]
+    );
+
+    // The kind can also be scheduled to run sooner
+    let put = app
+        .put("/admin/jobs")
+        .json(&json!({
+            "kind": "expire-reservations",
+            "schedule": {
+                "delay": {
+                    "seconds": 0
+                }
+            }
+        }))
+        .dispatch()
+        .await;
+
+    assert_eq!(Status::Created, put.status());
+    let id: String = serde_json::from_str(&put.into_string().await.expect("missing body"))
+        .expect("invalid value");
+
+    assert_eq!(1, run_due_jobs(&app).await);
+    assert_eq!("succeeded", get(&app, &id).await["status"]);
+}
+
+#[async_test]
+async fn continue_after_failing_to_complete() {
+    let app = App::builder()
+        // Completing its own job means the job can't be completed again after it runs
+        .job("complete-self", |app, _: serde_json::Value| async move {
+            for job in app.get_jobs_query().execute(GetJobs {}).await? {
+                if job.kind == "complete-self" {
+                    app.complete_job_command()
+                        .execute(CompleteJob {
+                            id: job.id,
+                            error: None,
+                        })
+                        .await?;
+                }
+            }
+
+            Ok(())
+        })
+        .job("create-customer", |app, args: CreateCustomer| async move {
+            app.create_customer_command().execute(args).await
+        })
+        .build();
+
+    let app = Client::untracked(shop::api::init(app))
+        .await
+        .expect("invalid app");
+
+    let put = app
+        .put("/admin/jobs")
+        .json(&json!({
+            "kind": "complete-self",
+            "schedule": {
+                "delay": {
+                    "seconds": 0
+                }
+            }
+        }))
+        .dispatch()
+        .await;
+    assert_eq!(Status::Created, put.status());
+
+    let customer_id = CustomerId::new();
+    let id = schedule(&app, customer_id).await;
+
+    assert_eq!(2, run_due_jobs(&app).await);
+
+    assert_eq!("succeeded", get(&app, &id).await["status"]);
+}
@@ -5,13 +5,32 @@ extern crate rocket;
 extern crate serde_json;
 
 use rocket::{
-    http::Status,
+    http::{
+        Header,
+        Status,
+    },
     local::asynchronous::Client,
 };
 
+use std::sync::Arc;
+
+use shop::domain::{
+    App,
+    infra::{
+        IdProvider,
+        Register,
+        SequentialId,
+        TimeOrderedId,
+    },
+    orders::{
+        LineItemData,
+        OrderData,
+    },
+};
+
 #[async_test]
 async fn set_get() {
-    let app = Client::untracked(shop::api::init())
+    let app = Client::untracked(shop::api::init(App::new()))
         .await
         .expect("invalid app");
 
@@ -71,4 +90,599 @@ async fn set_get() {
             .expect("invalid order")
             .len()
     );
+    assert_eq!(
+        json!({ "usd": { "cents": 492 } }),
+        order.as_object().expect("invalid order")["total"]
+    );
+}
+
+#[async_test]
+async fn set_get_with_etag() {
+    let app = Client::untracked(shop::api::init(App::new()))
+        .await
+        .expect("invalid app");
+
+    let product_id: String = {
+        let get = app
+            .put("/products")
+            .json(&json!({
+                "title": "A new product",
+                "price": {
+                    "usd": {
+                        "cents": 123
+                    }
+                }
+            }))
+            .dispatch()
+            .await;
+
+        serde_json::from_str(&get.into_string().await.expect("missing body"))
+            .expect("invalid value")
+    };
+
+    let customer_id: String = {
+        let get = app.put("/customers").json(&json!({})).dispatch().await;
+
+        serde_json::from_str(&get.into_string().await.expect("missing body"))
+            .expect("invalid value")
+    };
+
+    let order_id: String = {
+        let put = app
+            .put("/orders")
+            .json(&json!({ "customer": customer_id }))
+            .dispatch()
+            .await;
+
+        serde_json::from_str(&put.into_string().await.expect("missing body"))
+            .expect("invalid value")
+    };
+
+    let get_etag = |response: &rocket::local::asynchronous::LocalResponse<'_>| {
+        response
+            .headers()
+            .get_one("ETag")
+            .expect("missing etag")
+            .to_owned()
+    };
+
+    let get = app.get(format!("/orders/{}", order_id)).dispatch().await;
+
+    assert_eq!(Status::Ok, get.status());
+    let etag = get_etag(&get);
+    let order: serde_json::Value =
+        serde_json::from_str(&get.into_string().await.expect("missing body"))
+            .expect("invalid value");
+
+    assert_eq!(format!("\"{}\"", order["version"].as_str().unwrap()), etag);
+
+    // The order hasn't changed
+    let get = app
+        .get(format!("/orders/{}", order_id))
+        .header(Header::new("If-None-Match", etag.clone()))
+        .dispatch()
+        .await;
+
+    assert_eq!(Status::NotModified, get.status());
+
+    // Add a product using the order's current version
+    let post = app
+        .post(format!("/orders/{}/products/{}", order_id, product_id))
+        .header(Header::new("If-Match", etag.clone()))
+        .json(&json!({
+            "quantity": 4
+        }))
+        .dispatch()
+        .await;
+
+    assert_eq!(Status::Ok, post.status());
+
+    // The version is now stale
+    let post = app
+        .post(format!("/orders/{}/tax-region/AU", order_id))
+        .header(Header::new("If-Match", etag.clone()))
+        .dispatch()
+        .await;
+
+    assert_eq!(Status::PreconditionFailed, post.status());
+
+    let get = app
+        .get(format!("/orders/{}", order_id))
+        .header(Header::new("If-None-Match", etag.clone()))
+        .dispatch()
+        .await;
+
+    assert_eq!(Status::Ok, get.status());
+    let etag = get_etag(&get);
+
+    // Updating a line item's quantity also changes the order's version
+    let post = app
+        .post(format!("/orders/{}/products/{}", order_id, product_id))
+        .header(Header::new("If-Match", etag.clone()))
+        .json(&json!({
+            "quantity": 2
+        }))
+        .dispatch()
+        .await;
+
+    assert_eq!(Status::Ok, post.status());
+
+    let delete = app
+        .delete(format!("/orders/{}/products/{}", order_id, product_id))
+        .header(Header::new("If-Match", etag.clone()))
+        .dispatch()
+        .await;
+
+    assert_eq!(Status::PreconditionFailed, delete.status());
+}
+
+#[async_test]
+async fn create_with_idempotency_key() {
+    let app = Client::untracked(shop::api::init(App::new()))
+        .await
+        .expect("invalid app");
+
+    let customer_id: String = {
+        let get = app.put("/customers").json(&json!({})).dispatch().await;
+
+        serde_json::from_str(&get.into_string().await.expect("missing body"))
+            .expect("invalid value")
+    };
+
+    let mut order_ids = vec![];
+    for _ in 0..2 {
+        let put = app
+            .put("/orders")
+            .header(Header::new("Idempotency-Key", "create-order-1"))
+            .json(&json!({ "customer": customer_id }))
+            .dispatch()
+            .await;
+
+        assert_eq!(Status::Created, put.status());
+        let order_id: String =
+            serde_json::from_str(&put.into_string().await.expect("missing body"))
+                .expect("invalid value");
+
+        order_ids.push(order_id);
+    }
+
+    assert_eq!(order_ids[0], order_ids[1]);
+
+    // The key can't be reused for a different order
+    let put = app
+        .put("/orders")
+        .header(Header::new("Idempotency-Key", "create-order-1"))
+        .json(&json!({ "customer": "3a0f2c4e-5d6b-4c8a-9e1f-2b3c4d5e6f70" }))
+        .dispatch()
+        .await;
+
+    assert_eq!(Status::Conflict, put.status());
+
+    let get = app
+        .get(format!("/customers/{}", customer_id))
+        .dispatch()
+        .await;
+    let customer: serde_json::Value =
+        serde_json::from_str(&get.into_string().await.expect("missing body"))
+            .expect("invalid value");
+
+    assert_eq!(
+        1,
+        customer.as_object().expect("invalid customer")["orders"]
+            .as_array()
+            .expect("invalid customer")
+            .len()
+    );
+}
+
+#[async_test]
+async fn add_product_to_missing_order() {
+    let app = Client::untracked(shop::api::init(App::new()))
+        .await
+        .expect("invalid app");
+
+    let post = app
+        .post(format!(
+            "/orders/{}/products/{}",
+            "3a0f2c4e-5d6b-4c8a-9e1f-2b3c4d5e6f70", "7b8c9d0e-1f2a-4b3c-8d4e-5f6a7b8c9d0e"
+        ))
+        .json(&json!({
+            "quantity": 1
+        }))
+        .dispatch()
+        .await;
+
+    assert_eq!(Status::NotFound, post.status());
+    let err: serde_json::Value =
+        serde_json::from_str(&post.into_string().await.expect("missing body"))
+            .expect("invalid value");
+
+    assert_eq!("not_found", err.as_object().expect("invalid error")["code"]);
+}
+
+#[async_test]
+async fn set_get_with_tax() {
+    let app = Client::untracked(shop::api::init(App::new()))
+        .await
+        .expect("invalid app");
+
+    let put = app
+        .put("/admin/tax-rates/us-ny")
+        .json(&json!({
+            "pricing": "exclusive",
+            "rates": {
+                "standard": "8.875",
+                "food": "0"
+            }
+        }))
+        .dispatch()
+        .await;
+
+    assert_eq!(Status::Ok, put.status());
+
+    let mut product_ids = vec![];
+    for (cents, tax_category) in [(1000, "standard"), (300, "food")] {
+        let put = app
+            .put("/products")
+            .json(&json!({
+                "title": "A new product",
+                "price": {
+                    "usd": {
+                        "cents": cents
+                    }
+                },
+                "tax_category": tax_category
+            }))
+            .dispatch()
+            .await;
+
+        let product_id: String =
+            serde_json::from_str(&put.into_string().await.expect("missing body"))
+                .expect("invalid value");
+
+        product_ids.push(product_id);
+    }
+
+    let customer_id: String = {
+        let get = app.put("/customers").json(&json!({})).dispatch().await;
+
+        serde_json::from_str(&get.into_string().await.expect("missing body"))
+            .expect("invalid value")
+    };
+
+    let put = app
+        .put("/orders")
+        .json(&json!({ "customer": customer_id }))
+        .dispatch()
+        .await;
+
+    let order_id: String = serde_json::from_str(&put.into_string().await.expect("missing body"))
+        .expect("invalid value");
+
+    for product_id in &product_ids {
+        app.post(format!("/orders/{}/products/{}", order_id, product_id))
+            .json(&json!({
+                "quantity": 1
+            }))
+            .dispatch()
+            .await;
+    }
+
+    let post = app
+        .post(format!("/orders/{}/tax-region/US-NY", order_id))
+        .dispatch()
+        .await;
+
+    assert_eq!(Status::Ok, post.status());
+
+    let get = app.get(format!("/orders/{}", order_id)).dispatch().await;
+
+    assert_eq!(Status::Ok, get.status());
+    let order: serde_json::Value =
+        serde_json::from_str(&get.into_string().await.expect("missing body"))
+            .expect("invalid value");
+
+    assert_eq!("exclusive", order["tax_pricing"]);
+    assert_eq!(json!({ "usd": { "cents": 1300 } }), order["subtotal"]);
+    assert_eq!(json!({ "usd": { "cents": 89 } }), order["tax"]);
+    assert_eq!(json!({ "usd": { "cents": 1389 } }), order["total"]);
+}
+
+#[async_test]
+async fn get_with_missing_tax_rates() {
+    let app = Client::untracked(shop::api::init(App::new()))
+        .await
+        .expect("invalid app");
+
+    let customer_id: String = {
+        let get = app.put("/customers").json(&json!({})).dispatch().await;
+
+        serde_json::from_str(&get.into_string().await.expect("missing body"))
+            .expect("invalid value")
+    };
+
+    let put = app
+        .put("/orders")
+        .json(&json!({ "customer": customer_id }))
+        .dispatch()
+        .await;
+
+    let order_id: String = serde_json::from_str(&put.into_string().await.expect("missing body"))
+        .expect("invalid value");
+
+    app.post(format!("/orders/{}/tax-region/NZ", order_id))
+        .dispatch()
+        .await;
+
+    let get = app.get(format!("/orders/{}", order_id)).dispatch().await;
+
+    // The order can still be read, but its tax can't be calculated
+    assert_eq!(Status::Ok, get.status());
+    let order: serde_json::Value =
+        serde_json::from_str(&get.into_string().await.expect("missing body"))
+            .expect("invalid value");
+
+    assert_eq!("NZ", order["tax_region"]);
+    assert_eq!(serde_json::Value::Null, order["tax"]);
+
+    let get = app
+        .get(format!("/customers/{}", customer_id))
+        .dispatch()
+        .await;
+
+    assert_eq!(Status::Ok, get.status());
+}
+
+#[async_test]
+async fn create_with_id_providers() {
+    let app = App::builder()
+        .order_id(Register::once(|_| {
+            Arc::new(SequentialId::<OrderData>::new())
+                as Arc<dyn IdProvider<OrderData> + Send + Sync>
+        }))
+        .line_item_id(Register::once(|_| {
+            Arc::new(TimeOrderedId::<LineItemData>::new())
+                as Arc<dyn IdProvider<LineItemData> + Send + Sync>
+        }))
+        .build();
+
+    let app = Client::untracked(shop::api::init(app))
+        .await
+        .expect("invalid app");
+
+    let customer_id: String = {
+        let get = app.put("/customers").json(&json!({})).dispatch().await;
+
+        serde_json::from_str(&get.into_string().await.expect("missing body"))
+            .expect("invalid value")
+    };
+
+    let mut order_ids = vec![];
+    for _ in 0..2 {
+        let put = app
+            .put("/orders")
+            .json(&json!({ "customer": customer_id }))
+            .dispatch()
+            .await;
+
+        let order_id: String =
+            serde_json::from_str(&put.into_string().await.expect("missing body"))
+                .expect("invalid value");
+
+        order_ids.push(order_id);
+    }
+
+    assert_eq!(
+        vec![
+            "00000000-0000-0000-0000-000000000001",
+            "00000000-0000-0000-0000-000000000002"
+        ],
+        order_ids
+    );
+
+    let mut line_item_ids = vec![];
+    for _ in 0..3 {
+        let product_id: String = {
+            let put = app
+                .put("/products")
+                .json(&json!({
+                    "title": "A new product",
+                    "price": {
+                        "usd": {
+                            "cents": 123
+                        }
+                    }
+                }))
+                .dispatch()
+                .await;
+
+            serde_json::from_str(&put.into_string().await.expect("missing body"))
+                .expect("invalid value")
+        };
+
+        let post = app
+            .post(format!("/orders/{}/products/{}", order_ids[0], product_id))
+            .json(&json!({
+                "quantity": 1
+            }))
+            .dispatch()
+            .await;
+
+        let line_item_id: String =
+            serde_json::from_str(&post.into_string().await.expect("missing body"))
+                .expect("invalid value");
+
+        line_item_ids.push(line_item_id);
+    }
+
+    let mut sorted = line_item_ids.clone();
+    sorted.sort();
+
+    assert_eq!(sorted, line_item_ids);
+}
+
+#[async_test]
+async fn get_with_archived_product() {
+    let app = Client::untracked(shop::api::init(App::new()))
+        .await
+        .expect("invalid app");
+
+    let product_id: String = {
+        let get = app
+            .put("/products")
+            .json(&json!({
+                "title": "A new product",
+                "price": {
+                    "usd": {
+                        "cents": 123
+                    }
+                }
+            }))
+            .dispatch()
+            .await;
+
+        serde_json::from_str(&get.into_string().await.expect("missing body"))
+            .expect("invalid value")
+    };
+
+    let customer_id: String = {
+        let get = app.put("/customers").json(&json!({})).dispatch().await;
+
+        serde_json::from_str(&get.into_string().await.expect("missing body"))
+            .expect("invalid value")
+    };
+
+    let order_id: String = {
+        let put = app
+            .put("/orders")
+            .json(&json!({ "customer": customer_id }))
+            .dispatch()
+            .await;
+
+        serde_json::from_str(&put.into_string().await.expect("missing body"))
+            .expect("invalid value")
+    };
+
+    app.post(format!("/orders/{}/products/{}", order_id, product_id))
+        .json(&json!({
+            "quantity": 1
+        }))
+        .dispatch()
+        .await;
+
+    let archive = app
+        .post(format!("/products/{}/archive", product_id))
+        .dispatch()
+        .await;
+    assert_eq!(Status::Ok, archive.status());
+
+    // Archived products are hidden from shoppers, but can still be fetched by admins
+    let get = app
+        .get(format!("/products/{}", product_id))
+        .dispatch()
+        .await;
+    assert_eq!(Status::NotFound, get.status());
+
+    let get = app
+        .get(format!("/admin/products/{}", product_id))
+        .dispatch()
+        .await;
+    assert_eq!(Status::Ok, get.status());
+
+    // Existing orders still resolve the archived product
+    let get = app.get(format!("/orders/{}", order_id)).dispatch().await;
+    assert_eq!(Status::Ok, get.status());
+
+    // Archived products can't be added to new orders
+    let order_id: String = {
+        let put = app
+            .put("/orders")
+            .json(&json!({ "customer": customer_id }))
+            .dispatch()
+            .await;
+
+        serde_json::from_str(&put.into_string().await.expect("missing body"))
+            .expect("invalid value")
+    };
+
+    let post = app
+        .post(format!("/orders/{}/products/{}", order_id, product_id))
+        .json(&json!({
+            "quantity": 1
+        }))
+        .dispatch()
+        .await;
+    assert_eq!(Status::NotFound, post.status());
+
+    let restore = app
+        .post(format!("/products/{}/restore", product_id))
+        .dispatch()
+        .await;
+    assert_eq!(Status::Ok, restore.status());
+
+    let get = app
+        .get(format!("/products/{}", product_id))
+        .dispatch()
+        .await;
+    assert_eq!(Status::Ok, get.status());
+}
+
+#[async_test]
+async fn reject_line_items_that_overflow() {
+    let app = Client::untracked(shop::api::init(App::new()))
+        .await
+        .expect("invalid app");
+
+    let put = app
+        .put("/products")
+        .json(&json!({
+            "title": "A very expensive product",
+            "price": {
+                "usd": {
+                    "cents": u64::MAX / 2 + 1
+                }
+            }
+        }))
+        .dispatch()
+        .await;
+
+    assert_eq!(Status::Created, put.status());
+    let product_id: String = serde_json::from_str(&put.into_string().await.expect("missing body"))
+        .expect("invalid value");
+
+    let customer_id: String = {
+        let get = app.put("/customers").json(&json!({})).dispatch().await;
+
+        serde_json::from_str(&get.into_string().await.expect("missing body"))
+            .expect("invalid value")
+    };
+
+    let put = app
+        .put("/orders")
+        .json(&json!({ "customer": customer_id }))
+        .dispatch()
+        .await;
+
+    let order_id: String = serde_json::from_str(&put.into_string().await.expect("missing body"))
+        .expect("invalid value");
+
+    let post = app
+        .post(format!("/orders/{}/products/{}", order_id, product_id))
+        .json(&json!({
+            "quantity": 2
+        }))
+        .dispatch()
+        .await;
+
+    assert_eq!(Status::BadRequest, post.status());
+
+    // The order and its customer can still be read
+    let get = app.get(format!("/orders/{}", order_id)).dispatch().await;
+    assert_eq!(Status::Ok, get.status());
+
+    let get = app
+        .get(format!("/customers/{}", customer_id))
+        .dispatch()
+        .await;
+    assert_eq!(Status::Ok, get.status());
 }
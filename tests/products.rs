@@ -5,13 +5,30 @@ extern crate rocket;
 extern crate serde_json;
 
 use rocket::{
-    http::Status,
+    http::{
+        ContentType,
+        Header,
+        Status,
+    },
     local::asynchronous::Client,
 };
 
+use shop::domain::{
+    App,
+    infra::{
+        IdProvider,
+        Register,
+    },
+    products::{
+        ProductData,
+        ProductId,
+    },
+};
+use std::sync::Arc;
+
 #[async_test]
 async fn set_get() {
-    let app = Client::untracked(shop::api::init())
+    let app = Client::untracked(shop::api::init(App::new()))
         .await
         .expect("invalid app");
 
@@ -44,3 +61,579 @@ async fn set_get() {
         product.as_object().expect("invalid product")["title"]
     );
 }
+
+#[async_test]
+async fn set_get_with_builder() {
+    let id = ProductId::new();
+
+    let app = App::builder()
+        .product_id(Register::once(move |_| {
+            Arc::new(id) as Arc<dyn IdProvider<ProductData> + Send + Sync>
+        }))
+        .build();
+
+    let app = Client::untracked(shop::api::init(app))
+        .await
+        .expect("invalid app");
+
+    let put = app
+        .put("/products")
+        .json(&json!({
+            "title": "A new product",
+            "price": {
+                "usd": {
+                    "cents": 123
+                }
+            }
+        }))
+        .dispatch()
+        .await;
+
+    assert_eq!(Status::Created, put.status());
+    let created: ProductId = serde_json::from_str(&put.into_string().await.expect("missing body"))
+        .expect("invalid value");
+
+    assert_eq!(id, created);
+}
+
+#[async_test]
+async fn set_get_with_etag() {
+    let app = Client::untracked(shop::api::init(App::new()))
+        .await
+        .expect("invalid app");
+
+    let put = app
+        .put("/products")
+        .json(&json!({
+            "title": "A new product",
+            "price": {
+                "usd": {
+                    "cents": 123
+                }
+            }
+        }))
+        .dispatch()
+        .await;
+
+    let id: String = serde_json::from_str(&put.into_string().await.expect("missing body"))
+        .expect("invalid value");
+
+    let get = app.get(format!("/products/{}", id)).dispatch().await;
+
+    assert_eq!(Status::Ok, get.status());
+    let etag = get
+        .headers()
+        .get_one("ETag")
+        .expect("missing etag")
+        .to_owned();
+    let product: serde_json::Value =
+        serde_json::from_str(&get.into_string().await.expect("missing body"))
+            .expect("invalid value");
+
+    assert_eq!(
+        format!("\"{}\"", product["version"].as_str().unwrap()),
+        etag
+    );
+
+    // The product hasn't changed
+    let get = app
+        .get(format!("/products/{}", id))
+        .header(Header::new("If-None-Match", etag.clone()))
+        .dispatch()
+        .await;
+
+    assert_eq!(Status::NotModified, get.status());
+
+    // Change the product using its current version
+    let post = app
+        .post(format!("/products/{}/title/Updated", id))
+        .header(Header::new("If-Match", etag.clone()))
+        .dispatch()
+        .await;
+
+    assert_eq!(Status::Ok, post.status());
+
+    // The version is now stale
+    let post = app
+        .post(format!("/products/{}/title/Stale", id))
+        .header(Header::new("If-Match", etag.clone()))
+        .dispatch()
+        .await;
+
+    assert_eq!(Status::PreconditionFailed, post.status());
+
+    let get = app
+        .get(format!("/products/{}", id))
+        .header(Header::new("If-None-Match", etag.clone()))
+        .dispatch()
+        .await;
+
+    assert_eq!(Status::Ok, get.status());
+    let new_etag = get
+        .headers()
+        .get_one("ETag")
+        .expect("missing etag")
+        .to_owned();
+    let product: serde_json::Value =
+        serde_json::from_str(&get.into_string().await.expect("missing body"))
+            .expect("invalid value");
+
+    assert!(new_etag > etag);
+    assert_eq!("Updated", product["title"]);
+}
+
+#[async_test]
+async fn get_cached() {
+    let app = Client::untracked(shop::api::init(App::new()))
+        .await
+        .expect("invalid app");
+
+    let put = app
+        .put("/products")
+        .json(&json!({
+            "title": "A new product",
+            "price": {
+                "usd": {
+                    "cents": 123
+                }
+            }
+        }))
+        .dispatch()
+        .await;
+
+    let id: String = serde_json::from_str(&put.into_string().await.expect("missing body"))
+        .expect("invalid value");
+
+    for _ in 0..2 {
+        app.get(format!("/products/{}", id)).dispatch().await;
+    }
+
+    // Changing the product invalidates the cached result
+    app.post(format!("/products/{}/title/Updated", id))
+        .dispatch()
+        .await;
+
+    let get = app.get(format!("/products/{}", id)).dispatch().await;
+    let product: serde_json::Value =
+        serde_json::from_str(&get.into_string().await.expect("missing body"))
+            .expect("invalid value");
+
+    assert_eq!("Updated", product["title"]);
+
+    let get = app.get("/admin/query-cache/metrics").dispatch().await;
+
+    assert_eq!(Status::Ok, get.status());
+    let metrics: serde_json::Value =
+        serde_json::from_str(&get.into_string().await.expect("missing body"))
+            .expect("invalid value");
+
+    let metrics = metrics
+        .as_array()
+        .expect("invalid metrics")
+        .iter()
+        .find(|metrics| {
+            metrics["query"]
+                .as_str()
+                .expect("invalid metrics")
+                .ends_with("GetProduct")
+        })
+        .expect("missing metrics");
+
+    assert_eq!(1, metrics["hits"]);
+    assert_eq!(2, metrics["misses"]);
+}
+
+#[async_test]
+async fn list() {
+    let app = Client::untracked(shop::api::init(App::new()))
+        .await
+        .expect("invalid app");
+
+    for (title, cents) in [("Blue shirt", 300), ("Red shirt", 100), ("Green hat", 200)] {
+        let put = app
+            .put("/products")
+            .json(&json!({
+                "title": title,
+                "price": {
+                    "usd": {
+                        "cents": cents
+                    }
+                }
+            }))
+            .dispatch()
+            .await;
+
+        assert_eq!(Status::Created, put.status());
+    }
+
+    let titles = |page: &serde_json::Value| {
+        page["products"]
+            .as_array()
+            .expect("invalid products")
+            .iter()
+            .map(|product| product["title"].as_str().expect("invalid title").to_owned())
+            .collect::<Vec<_>>()
+    };
+
+    // Page through products sorted by price
+    let get = app.get("/products?sort=price&limit=2").dispatch().await;
+
+    assert_eq!(Status::Ok, get.status());
+    let page: serde_json::Value =
+        serde_json::from_str(&get.into_string().await.expect("missing body"))
+            .expect("invalid value");
+
+    assert_eq!(vec!["Red shirt", "Green hat"], titles(&page));
+
+    let get = app
+        .get(format!(
+            "/products?sort=price&limit=2&after={}",
+            page["next"].as_str().expect("missing cursor")
+        ))
+        .dispatch()
+        .await;
+
+    assert_eq!(Status::Ok, get.status());
+    let page: serde_json::Value =
+        serde_json::from_str(&get.into_string().await.expect("missing body"))
+            .expect("invalid value");
+
+    assert_eq!(vec!["Blue shirt"], titles(&page));
+    assert!(page.get("next").is_none());
+
+    // Filter products by title and price
+    let get = app
+        .get("/products?title=shirt&currency=usd&min_price=200")
+        .dispatch()
+        .await;
+
+    assert_eq!(Status::Ok, get.status());
+    let page: serde_json::Value =
+        serde_json::from_str(&get.into_string().await.expect("missing body"))
+            .expect("invalid value");
+
+    assert_eq!(vec!["Blue shirt"], titles(&page));
+
+    // Prices can't be filtered without a currency
+    let get = app.get("/products?min_price=200").dispatch().await;
+
+    assert_eq!(Status::BadRequest, get.status());
+}
+
+#[async_test]
+async fn search() {
+    let app = Client::untracked(shop::api::init(App::new()))
+        .await
+        .expect("invalid app");
+
+    for title in ["Red running shirt", "Blue shirts", "Red hat"] {
+        let put = app
+            .put("/products")
+            .json(&json!({
+                "title": title,
+                "price": {
+                    "usd": {
+                        "cents": 100
+                    }
+                }
+            }))
+            .dispatch()
+            .await;
+
+        assert_eq!(Status::Created, put.status());
+    }
+
+    let titles = |results: &serde_json::Value| {
+        results
+            .as_array()
+            .expect("invalid results")
+            .iter()
+            .map(|result| result["title"].as_str().expect("invalid title").to_owned())
+            .collect::<Vec<_>>()
+    };
+
+    let get = app.get("/products/search?q=shirt").dispatch().await;
+
+    assert_eq!(Status::Ok, get.status());
+    let results: serde_json::Value =
+        serde_json::from_str(&get.into_string().await.expect("missing body"))
+            .expect("invalid value");
+
+    assert_eq!(vec!["Blue shirts", "Red running shirt"], titles(&results));
+
+    let get = app
+        .get("/products/search?q=red%20sh&prefix=true")
+        .dispatch()
+        .await;
+
+    assert_eq!(Status::Ok, get.status());
+    let results: serde_json::Value =
+        serde_json::from_str(&get.into_string().await.expect("missing body"))
+            .expect("invalid value");
+
+    assert_eq!(
+        vec!["Red running shirt", "Red hat", "Blue shirts"],
+        titles(&results)
+    );
+}
+
+#[async_test]
+async fn set_price() {
+    let app = Client::untracked(shop::api::init(App::new()))
+        .await
+        .expect("invalid app");
+
+    let put = app
+        .put("/products")
+        .json(&json!({
+            "title": "A product",
+            "price": {
+                "usd": {
+                    "cents": 100
+                }
+            }
+        }))
+        .dispatch()
+        .await;
+
+    assert_eq!(Status::Created, put.status());
+    let id: String = serde_json::from_str(&put.into_string().await.expect("missing body"))
+        .expect("invalid value");
+
+    let put = app.put("/customers").json(&json!({})).dispatch().await;
+
+    assert_eq!(Status::Created, put.status());
+    let customer_id: String = serde_json::from_str(&put.into_string().await.expect("missing body"))
+        .expect("invalid value");
+
+    let put = app
+        .put("/orders")
+        .json(&json!({ "customer": customer_id }))
+        .dispatch()
+        .await;
+
+    assert_eq!(Status::Created, put.status());
+    let order_id: String = serde_json::from_str(&put.into_string().await.expect("missing body"))
+        .expect("invalid value");
+
+    let post = app
+        .post(format!("/orders/{}/products/{}", order_id, id))
+        .json(&json!({ "quantity": 1 }))
+        .dispatch()
+        .await;
+
+    assert_eq!(Status::Ok, post.status());
+
+    let put = app
+        .put(format!("/products/{}/price", id))
+        .json(&json!({
+            "usd": {
+                "cents": 250
+            }
+        }))
+        .dispatch()
+        .await;
+
+    assert_eq!(Status::Ok, put.status());
+
+    let get = app.get(format!("/products/{}/prices", id)).dispatch().await;
+
+    assert_eq!(Status::Ok, get.status());
+    let history: serde_json::Value =
+        serde_json::from_str(&get.into_string().await.expect("missing body"))
+            .expect("invalid value");
+
+    assert_eq!(2, history.as_array().expect("invalid history").len());
+    assert_eq!(100, history[0]["price"]["usd"]["cents"]);
+    assert_eq!(history[0]["effective_to"], history[1]["effective_from"]);
+    assert_eq!(250, history[1]["price"]["usd"]["cents"]);
+    assert!(history[1].get("effective_to").is_none());
+
+    // Orders keep the price the product had when it was added
+    let get = app.get(format!("/orders/{}", order_id)).dispatch().await;
+
+    assert_eq!(Status::Ok, get.status());
+    let order: serde_json::Value =
+        serde_json::from_str(&get.into_string().await.expect("missing body"))
+            .expect("invalid value");
+
+    assert_eq!(100, order["line_items"][0]["price"]["usd"]["cents"]);
+}
+
+#[async_test]
+async fn lifecycle() {
+    let app = Client::untracked(shop::api::init(App::new()))
+        .await
+        .expect("invalid app");
+
+    let put = app
+        .put("/products")
+        .json(&json!({
+            "title": "A draft product",
+            "price": {
+                "usd": {
+                    "cents": 100
+                }
+            },
+            "draft": true
+        }))
+        .dispatch()
+        .await;
+
+    assert_eq!(Status::Created, put.status());
+    let id: String = serde_json::from_str(&put.into_string().await.expect("missing body"))
+        .expect("invalid value");
+
+    // Drafts are hidden from shoppers, but can still be fetched by admins
+    let get = app.get(format!("/products/{}", id)).dispatch().await;
+    assert_eq!(Status::NotFound, get.status());
+
+    let get = app
+        .get(format!("/products/{}?drafts=true", id))
+        .dispatch()
+        .await;
+    assert_eq!(Status::NotFound, get.status());
+
+    let get = app.get(format!("/admin/products/{}", id)).dispatch().await;
+    assert_eq!(Status::Ok, get.status());
+
+    let put = app.put("/customers").json(&json!({})).dispatch().await;
+
+    assert_eq!(Status::Created, put.status());
+    let customer_id: String = serde_json::from_str(&put.into_string().await.expect("missing body"))
+        .expect("invalid value");
+
+    let put = app
+        .put("/orders")
+        .json(&json!({ "customer": customer_id }))
+        .dispatch()
+        .await;
+
+    assert_eq!(Status::Created, put.status());
+    let order_id: String = serde_json::from_str(&put.into_string().await.expect("missing body"))
+        .expect("invalid value");
+
+    let add = |order_id: &str| {
+        app.post(format!("/orders/{}/products/{}", order_id, id))
+            .json(&json!({ "quantity": 1 }))
+    };
+
+    // Only active products can be ordered
+    assert_eq!(Status::BadRequest, add(&order_id).dispatch().await.status());
+
+    let post = app
+        .post(format!("/products/{}/publish", id))
+        .dispatch()
+        .await;
+    assert_eq!(Status::Ok, post.status());
+
+    assert_eq!(Status::Ok, add(&order_id).dispatch().await.status());
+
+    let post = app
+        .post(format!("/products/{}/discontinue", id))
+        .dispatch()
+        .await;
+    assert_eq!(Status::Ok, post.status());
+
+    // Discontinued products stay visible, but can't be ordered
+    let get = app.get(format!("/products/{}", id)).dispatch().await;
+
+    assert_eq!(Status::Ok, get.status());
+    let product: serde_json::Value =
+        serde_json::from_str(&get.into_string().await.expect("missing body"))
+            .expect("invalid value");
+
+    assert_eq!("discontinued", product["status"]);
+
+    let put = app
+        .put("/orders")
+        .json(&json!({ "customer": customer_id }))
+        .dispatch()
+        .await;
+
+    assert_eq!(Status::Created, put.status());
+    let order_id: String = serde_json::from_str(&put.into_string().await.expect("missing body"))
+        .expect("invalid value");
+
+    assert_eq!(Status::BadRequest, add(&order_id).dispatch().await.status());
+
+    // Discontinued products can't be discontinued again
+    let post = app
+        .post(format!("/products/{}/discontinue", id))
+        .dispatch()
+        .await;
+    assert_eq!(Status::Conflict, post.status());
+}
+
+#[async_test]
+async fn import_export() {
+    let app = Client::untracked(shop::api::init(App::new()))
+        .await
+        .expect("invalid app");
+
+    let post = app
+        .post("/admin/products/import?format=csv")
+        .body("title,currency,price,tags\nA shirt,usd,1000,summer;cotton\n,usd,100,\nA hat,usd,500,\n")
+        .dispatch()
+        .await;
+
+    assert_eq!(Status::Ok, post.status());
+    let report: serde_json::Value =
+        serde_json::from_str(&post.into_string().await.expect("missing body"))
+            .expect("invalid value");
+
+    assert_eq!(2, report["imported"]);
+    assert_eq!(3, report["errors"][0]["row"]);
+
+    let get = app
+        .get("/admin/products/export?format=csv")
+        .dispatch()
+        .await;
+
+    assert_eq!(Status::Ok, get.status());
+    assert_eq!(Some(ContentType::CSV), get.content_type());
+    let exported = get.into_string().await.expect("missing body");
+
+    assert_eq!(3, exported.lines().count());
+    assert!(exported.contains("A shirt"));
+    assert!(exported.contains("cotton;summer"));
+
+    // Imports and exports aren't available on the public routes
+    let get = app.get("/products/export?format=csv").dispatch().await;
+    assert_ne!(Status::Ok, get.status());
+
+    let post = app
+        .post("/products/import?format=csv")
+        .body("title,currency,price,tags\nA scarf,usd,800,\n")
+        .dispatch()
+        .await;
+    assert_eq!(Status::NotFound, post.status());
+
+    // Importing an export updates the same products
+    let post = app
+        .post("/admin/products/import?format=csv")
+        .body(exported)
+        .dispatch()
+        .await;
+
+    assert_eq!(Status::Ok, post.status());
+    let report: serde_json::Value =
+        serde_json::from_str(&post.into_string().await.expect("missing body"))
+            .expect("invalid value");
+
+    assert_eq!(2, report["imported"]);
+
+    let get = app.get("/products").dispatch().await;
+    let list: serde_json::Value =
+        serde_json::from_str(&get.into_string().await.expect("missing body"))
+            .expect("invalid value");
+
+    assert_eq!(2, list["products"].as_array().expect("invalid list").len());
+
+    let post = app
+        .post("/admin/products/import?format=xml")
+        .body("")
+        .dispatch()
+        .await;
+    assert_ne!(Status::Ok, post.status());
+}
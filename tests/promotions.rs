@@ -0,0 +1,214 @@
+#[macro_use]
+extern crate rocket;
+
+#[macro_use]
+extern crate serde_json;
+
+use rocket::{
+    http::Status,
+    local::asynchronous::Client,
+};
+
+use shop::domain::App;
+
+async fn create_order(app: &Client, cents: u64, quantity: u32) -> String {
+    let product_id: String = {
+        let put = app
+            .put("/products")
+            .json(&json!({
+                "title": "A new product",
+                "price": {
+                    "usd": {
+                        "cents": cents
+                    }
+                }
+            }))
+            .dispatch()
+            .await;
+
+        serde_json::from_str(&put.into_string().await.expect("missing body"))
+            .expect("invalid value")
+    };
+
+    let customer_id: String = {
+        let put = app.put("/customers").json(&json!({})).dispatch().await;
+
+        serde_json::from_str(&put.into_string().await.expect("missing body"))
+            .expect("invalid value")
+    };
+
+    let put = app
+        .put("/orders")
+        .json(&json!({ "customer": customer_id }))
+        .dispatch()
+        .await;
+
+    let order_id: String = serde_json::from_str(&put.into_string().await.expect("missing body"))
+        .expect("invalid value");
+
+    app.post(format!("/orders/{}/products/{}", order_id, product_id))
+        .json(&json!({
+            "quantity": quantity
+        }))
+        .dispatch()
+        .await;
+
+    order_id
+}
+
+#[async_test]
+async fn create_get() {
+    let app = Client::untracked(shop::api::init(App::new()))
+        .await
+        .expect("invalid app");
+
+    let put = app
+        .put("/admin/promotions")
+        .json(&json!({
+            "code": "summer-10",
+            "discount": {
+                "percentage": {
+                    "percent": 10
+                }
+            },
+            "expires_at": "2030-01-01T00:00:00Z",
+            "usage_limit": 100
+        }))
+        .dispatch()
+        .await;
+
+    assert_eq!(Status::Created, put.status());
+    let promotion_id: String =
+        serde_json::from_str(&put.into_string().await.expect("missing body"))
+            .expect("invalid value");
+
+    let get = app
+        .get(format!("/admin/promotions/{}", promotion_id))
+        .dispatch()
+        .await;
+
+    assert_eq!(Status::Ok, get.status());
+    let promotion: serde_json::Value =
+        serde_json::from_str(&get.into_string().await.expect("missing body"))
+            .expect("invalid value");
+
+    assert_eq!("SUMMER-10", promotion["code"]);
+    assert_eq!("2030-01-01T00:00:00.000000000Z", promotion["expires_at"]);
+    assert_eq!(0, promotion["uses"]);
+}
+
+#[async_test]
+async fn apply_to_order() {
+    let app = Client::untracked(shop::api::init(App::new()))
+        .await
+        .expect("invalid app");
+
+    app.put("/admin/promotions")
+        .json(&json!({
+            "code": "FIVE-OFF",
+            "discount": {
+                "fixed_amount": {
+                    "amount": {
+                        "usd": {
+                            "cents": 500
+                        }
+                    }
+                }
+            },
+            "min_spend": {
+                "usd": {
+                    "cents": 2000
+                }
+            },
+            "usage_limit": 1
+        }))
+        .dispatch()
+        .await;
+
+    // The order doesn't meet the minimum spend
+    let small_order_id = create_order(&app, 1000, 1).await;
+
+    let post = app
+        .post(format!("/orders/{}/promotions/five-off", small_order_id))
+        .dispatch()
+        .await;
+
+    assert_eq!(Status::Conflict, post.status());
+
+    // The order meets the minimum spend
+    let order_id = create_order(&app, 1000, 3).await;
+
+    let post = app
+        .post(format!("/orders/{}/promotions/five-off", order_id))
+        .dispatch()
+        .await;
+
+    assert_eq!(Status::Ok, post.status());
+
+    let get = app.get(format!("/orders/{}", order_id)).dispatch().await;
+
+    assert_eq!(Status::Ok, get.status());
+    let order: serde_json::Value =
+        serde_json::from_str(&get.into_string().await.expect("missing body"))
+            .expect("invalid value");
+
+    assert_eq!("FIVE-OFF", order["discount_lines"][0]["code"]);
+    assert_eq!(json!({ "usd": { "cents": 3000 } }), order["subtotal"]);
+    assert_eq!(json!({ "usd": { "cents": 500 } }), order["discount"]);
+    assert_eq!(json!({ "usd": { "cents": 2500 } }), order["total"]);
+
+    // The promotion has reached its usage limit
+    let other_order_id = create_order(&app, 1000, 3).await;
+
+    let post = app
+        .post(format!("/orders/{}/promotions/five-off", other_order_id))
+        .dispatch()
+        .await;
+
+    assert_eq!(Status::Conflict, post.status());
+}
+
+#[async_test]
+async fn apply_missing_promotion() {
+    let app = Client::untracked(shop::api::init(App::new()))
+        .await
+        .expect("invalid app");
+
+    let order_id = create_order(&app, 1000, 1).await;
+
+    let post = app
+        .post(format!("/orders/{}/promotions/MISSING", order_id))
+        .dispatch()
+        .await;
+
+    assert_eq!(Status::NotFound, post.status());
+}
+
+#[async_test]
+async fn create_invalid_expiry() {
+    let app = Client::untracked(shop::api::init(App::new()))
+        .await
+        .expect("invalid app");
+
+    for expires_at in [
+        "2030-01-01",
+        "2030-01-01T00:00:00+10:00",
+        "2030-01-01T00:00:00.Z",
+    ] {
+        let put = app
+            .put("/admin/promotions")
+            .json(&json!({
+                "code": "SUMMER-10",
+                "discount": {
+                    "percentage": {
+                        "percent": 10
+                    }
+                },
+                "expires_at": expires_at
+            }))
+            .dispatch()
+            .await;
+
+        assert_eq!(Status::UnprocessableEntity, put.status(), "{}", expires_at);
+    }
+}
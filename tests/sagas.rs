@@ -0,0 +1,64 @@
+#[macro_use]
+extern crate rocket;
+
+use rocket::{
+    http::Status,
+    local::asynchronous::Client,
+};
+
+use shop::domain::{
+    App,
+    customers::{
+        CreateCustomer,
+        CustomerId,
+    },
+    error,
+    infra::*,
+};
+
+#[async_test]
+async fn start_get() {
+    let app = App::builder()
+        .saga(
+            SagaDefinition::<CustomerId>::new("create-customer")
+                .step("create", |app, id| async move {
+                    app.create_customer_command()
+                        .execute(CreateCustomer { id })
+                        .await
+                })
+                .step("fail", |_, _| async move {
+                    Err(error::msg("the last step failed"))
+                }),
+        )
+        .build();
+
+    let customer_id = CustomerId::new();
+    let id = SagaId::new();
+
+    let status = app
+        .start_saga(id, "create-customer", customer_id)
+        .await
+        .expect("failed to run saga");
+    assert_eq!(SagaStatus::Compensated, status);
+
+    let app = Client::untracked(shop::api::init(app))
+        .await
+        .expect("invalid app");
+
+    let get = app.get(format!("/admin/sagas/{}", id)).dispatch().await;
+
+    assert_eq!(Status::Ok, get.status());
+    let saga: serde_json::Value =
+        serde_json::from_str(&get.into_string().await.expect("missing body"))
+            .expect("invalid value");
+
+    assert_eq!("compensated", saga["status"]);
+    assert_eq!("the last step failed", saga["error"]);
+
+    // The first step didn't have a compensation, so its changes are kept
+    let get = app
+        .get(format!("/customers/{}", customer_id))
+        .dispatch()
+        .await;
+    assert_eq!(Status::Ok, get.status());
+}
@@ -0,0 +1,226 @@
+#[macro_use]
+extern crate rocket;
+
+#[macro_use]
+extern crate serde_json;
+
+use std::convert::TryFrom;
+
+use rocket::{
+    http::{
+        Header,
+        Status,
+    },
+    local::asynchronous::Client,
+};
+
+use shop::domain::{
+    App,
+    infra::*,
+};
+
+async fn init() -> Client {
+    let app = App::builder()
+        .tenant(
+            TenantId::try_from("a").expect("invalid tenant"),
+            TenantConfig::new().host("a.example.com"),
+        )
+        .tenant(
+            TenantId::try_from("b").expect("invalid tenant"),
+            TenantConfig::new().host("b.example.com"),
+        )
+        .build();
+
+    Client::untracked(
+        shop::api::init(app).configure(rocket::Config::figment().merge(("tenant_header", true))),
+    )
+    .await
+    .expect("invalid app")
+}
+
+#[async_test]
+async fn isolate_tenants() {
+    let app = init().await;
+
+    let put = app
+        .put("/products")
+        .header(Header::new("X-Tenant", "a"))
+        .json(&json!({
+            "title": "A new product",
+            "price": {
+                "usd": {
+                    "cents": 123
+                }
+            }
+        }))
+        .dispatch()
+        .await;
+
+    assert_eq!(Status::Created, put.status());
+    let id: String = serde_json::from_str(&put.into_string().await.expect("missing body"))
+        .expect("invalid value");
+
+    // The tenant can be found by its header or its host
+    let get = app
+        .get(format!("/products/{}", id))
+        .header(Header::new("X-Tenant", "a"))
+        .dispatch()
+        .await;
+    assert_eq!(Status::Ok, get.status());
+
+    let get = app
+        .get(format!("/products/{}", id))
+        .header(Header::new("Host", "a.example.com"))
+        .dispatch()
+        .await;
+    assert_eq!(Status::Ok, get.status());
+
+    // Other tenants can't see the product
+    let get = app
+        .get(format!("/products/{}", id))
+        .header(Header::new("Host", "b.example.com"))
+        .dispatch()
+        .await;
+    assert_eq!(Status::NotFound, get.status());
+
+    // Hosts that aren't configured for any tenant aren't served
+    let get = app.get(format!("/products/{}", id)).dispatch().await;
+    assert_eq!(Status::NotFound, get.status());
+}
+
+#[async_test]
+async fn unknown_tenant() {
+    let app = init().await;
+
+    let get = app
+        .get("/admin/query-cache/metrics")
+        .header(Header::new("X-Tenant", "c"))
+        .dispatch()
+        .await;
+    assert_eq!(Status::NotFound, get.status());
+
+    let get = app
+        .get("/admin/query-cache/metrics")
+        .header(Header::new("X-Tenant", "not a tenant"))
+        .dispatch()
+        .await;
+    assert_eq!(Status::BadRequest, get.status());
+
+    let get = app
+        .get("/admin/query-cache/metrics")
+        .header(Header::new("Host", "c.example.com"))
+        .dispatch()
+        .await;
+    assert_eq!(Status::NotFound, get.status());
+}
+
+#[async_test]
+async fn load_exchange_rates_for_each_tenant() {
+    let path = std::env::temp_dir().join(format!("exchange-rates-{}.json", std::process::id()));
+    std::fs::write(
+        &path,
+        json!({
+            "base": "USD",
+            "rates": {
+                "EUR": "0.9215"
+            }
+        })
+        .to_string(),
+    )
+    .expect("failed to write rates");
+
+    let app = App::builder()
+        .tenant(
+            TenantId::default_tenant(),
+            TenantConfig::new().host("localhost"),
+        )
+        .tenant(
+            TenantId::try_from("a").expect("invalid tenant"),
+            TenantConfig::new().host("a.example.com"),
+        )
+        .build();
+
+    let rocket = shop::api::init(app).configure(
+        rocket::Config::figment().merge(("exchange_rates", path.to_string_lossy().as_ref())),
+    );
+    let app = Client::untracked(rocket).await.expect("invalid app");
+
+    std::fs::remove_file(&path).expect("failed to remove rates");
+
+    for host in ["a.example.com", "localhost"] {
+        let get = app
+            .get("/admin/exchange-rates")
+            .header(Header::new("Host", host))
+            .dispatch()
+            .await;
+        assert_eq!(Status::Ok, get.status(), "{}", host);
+    }
+}
+
+#[async_test]
+async fn ignore_tenant_header_unless_enabled() {
+    let app = App::builder()
+        .tenant(
+            TenantId::default_tenant(),
+            TenantConfig::new().host("localhost"),
+        )
+        .tenant(
+            TenantId::try_from("a").expect("invalid tenant"),
+            TenantConfig::new().host("a.example.com"),
+        )
+        .build();
+
+    let app = Client::untracked(shop::api::init(app))
+        .await
+        .expect("invalid app");
+
+    let put = app
+        .put("/products")
+        .header(Header::new("Host", "localhost"))
+        .header(Header::new("X-Tenant", "a"))
+        .json(&json!({
+            "title": "A new product",
+            "price": {
+                "usd": {
+                    "cents": 123
+                }
+            }
+        }))
+        .dispatch()
+        .await;
+
+    assert_eq!(Status::Created, put.status());
+    let id: String = serde_json::from_str(&put.into_string().await.expect("missing body"))
+        .expect("invalid value");
+
+    // The product was created in the default tenant, not the one in the header
+    let get = app
+        .get(format!("/products/{}", id))
+        .header(Header::new("Host", "a.example.com"))
+        .dispatch()
+        .await;
+    assert_eq!(Status::NotFound, get.status());
+
+    let get = app
+        .get(format!("/products/{}", id))
+        .header(Header::new("Host", "localhost"))
+        .dispatch()
+        .await;
+    assert_eq!(Status::Ok, get.status());
+}
+
+#[async_test]
+async fn serve_any_host_for_only_the_default_tenant() {
+    let app = Client::untracked(shop::api::init(App::new()))
+        .await
+        .expect("invalid app");
+
+    for host in ["shop.example.com", "localhost"] {
+        let get = app
+            .get("/admin/query-cache/metrics")
+            .header(Header::new("Host", host))
+            .dispatch()
+            .await;
+        assert_eq!(Status::Ok, get.status(), "{}", host);
+    }
+}
@@ -0,0 +1,133 @@
+#[macro_use]
+extern crate rocket;
+
+#[macro_use]
+extern crate serde_json;
+
+use rocket::{
+    http::Status,
+    local::asynchronous::Client,
+};
+
+use shop::domain::App;
+
+async fn create(app: &Client, path: &str, body: serde_json::Value) -> String {
+    let put = app.put(path).json(&body).dispatch().await;
+
+    assert_eq!(Status::Created, put.status());
+    serde_json::from_str(&put.into_string().await.expect("missing body")).expect("invalid value")
+}
+
+async fn get(app: &Client, path: &str) -> serde_json::Value {
+    let get = app.get(path).dispatch().await;
+
+    assert_eq!(Status::Ok, get.status());
+    serde_json::from_str(&get.into_string().await.expect("missing body")).expect("invalid value")
+}
+
+#[async_test]
+async fn order_variant() {
+    let app = Client::untracked(shop::api::init(App::new()))
+        .await
+        .expect("invalid app");
+
+    let product_id = create(
+        &app,
+        "/products",
+        json!({
+            "title": "A shirt",
+            "price": {
+                "usd": {
+                    "cents": 1000
+                }
+            }
+        }),
+    )
+    .await;
+
+    let small = create(
+        &app,
+        &format!("/products/{}/variants", product_id),
+        json!({
+            "sku": "shirt-s",
+            "options": { "size": "S" }
+        }),
+    )
+    .await;
+
+    let large = create(
+        &app,
+        &format!("/products/{}/variants", product_id),
+        json!({
+            "sku": "shirt-l",
+            "options": { "size": "L" },
+            "price": {
+                "usd": {
+                    "cents": 1200
+                }
+            }
+        }),
+    )
+    .await;
+
+    // SKUs must be unique
+    let duplicate = app
+        .put(format!("/products/{}/variants", product_id))
+        .json(&json!({
+            "sku": "SHIRT-S",
+            "options": { "size": "M" }
+        }))
+        .dispatch()
+        .await;
+    assert_eq!(Status::Conflict, duplicate.status());
+
+    let found = get(&app, "/products/skus/shirt-l").await;
+    assert_eq!(product_id, found["product"]["id"]);
+    assert_eq!(large, found["variant"]["id"]);
+    assert_eq!("SHIRT-L", found["variant"]["sku"]);
+
+    // Stock is tracked for each variant
+    let put = app
+        .put(format!("/inventory/{}?variant={}", product_id, large))
+        .json(&json!({ "on_hand": 1 }))
+        .dispatch()
+        .await;
+    assert_eq!(Status::Ok, put.status());
+
+    let customer_id = create(&app, "/customers", json!({})).await;
+    let order_id = create(&app, "/orders", json!({ "customer": customer_id })).await;
+
+    let add = |variant_id: Option<&str>, quantity: u32| {
+        app.post(format!("/orders/{}/products/{}", order_id, product_id))
+            .json(&json!({ "variant_id": variant_id, "quantity": quantity }))
+    };
+
+    // A variant must be chosen for products with variants
+    assert_eq!(Status::BadRequest, add(None, 1).dispatch().await.status());
+
+    assert_eq!(Status::Ok, add(Some(&small), 3).dispatch().await.status());
+    assert_eq!(
+        Status::Conflict,
+        add(Some(&large), 2).dispatch().await.status()
+    );
+    assert_eq!(Status::Ok, add(Some(&large), 1).dispatch().await.status());
+
+    let stock = get(
+        &app,
+        &format!("/inventory/{}?variant={}", product_id, large),
+    )
+    .await;
+    assert_eq!(0, stock["available"]);
+
+    let order = get(&app, &format!("/orders/{}", order_id)).await;
+    let line_items = order["line_items"].as_array().expect("invalid order");
+
+    assert_eq!(2, line_items.len());
+
+    let line_item = line_items
+        .iter()
+        .find(|line_item| line_item["variant_id"] == large.as_str())
+        .expect("missing line item");
+    assert_eq!("SHIRT-L", line_item["sku"]);
+    assert_eq!(1200, line_item["price"]["usd"]["cents"]);
+}